@@ -3,8 +3,21 @@ use std::path::Path;
 use cairo_vm::{
     cairo_run,
     hint_processor::builtin_hint_processor::builtin_hint_processor_definition::BuiltinHintProcessor,
+    math_utils::{
+        ec_add, ec_double,
+        stark_curve::{AffinePoint, ProjectivePoint, StarkFieldElement},
+    },
+    types::{program::Program, relocatable::Relocatable},
+    vm::{
+        runners::cairo_runner::CairoRunner,
+        vm_core::VirtualMachine,
+        vm_memory::{memory::Memory, memory_segments::MemorySegmentManager},
+    },
 };
-use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+use felt::{felt_str, Felt, FeltOps, NewFelt};
+use num_bigint::BigInt;
+use num_traits::Num;
 
 const BENCH_NAMES: &[&str] = &[
     "compare_arrays_200000",
@@ -44,6 +57,171 @@ pub fn criterion_benchmarks(c: &mut Criterion) {
     }
 }
 
+// Counts down from 10000 to 0, writing a new cell on each iteration:
+//   [ap] = 10000, ap++
+//   loop:
+//   [ap] = [ap - 1] - 1, ap++
+//   jmp loop if [ap - 1] != 0
+//   ret
+const COUNTDOWN_PROGRAM: &str = r#"{
+    "prime": "0x800000000000011000000000000000000000000000000000000000000000001",
+    "builtins": [],
+    "data": [
+        "0x480680017fff8000",
+        "0x2710",
+        "0x482480017fff8000",
+        "0x800000000000011000000000000000000000000000000000000000000000000",
+        "0x20680017fff7fff",
+        "0x800000000000010ffffffffffffffffffffffffffffffffffffffffffffffff",
+        "0x208b7fff7fff7ffe"
+    ],
+    "identifiers": {
+        "__main__.main": {"decorators": [], "pc": 0, "type": "function"}
+    },
+    "hints": {},
+    "reference_manager": {"references": []},
+    "attributes": [],
+    "debug_info": null
+}"#;
+
+const MEMORY_CELLS: usize = 100_000;
+// Distance between the cells written by the sparse benchmark
+const SPARSE_STRIDE: usize = 1 << 14;
+const SPARSE_CELLS: usize = 64;
+
+fn memory_with_segment() -> (Memory, Relocatable) {
+    let mut memory = Memory::new();
+    let base = MemorySegmentManager::new().add(&mut memory);
+    (memory, base)
+}
+
+fn fill_segment(memory: &mut Memory, base: &Relocatable, cells: usize, stride: usize) {
+    for i in 0..cells {
+        memory
+            .insert_value(&(base + i * stride), Felt::new(i))
+            .unwrap();
+    }
+}
+
+pub fn memory_benchmarks(c: &mut Criterion) {
+    c.bench_function("memory_insert_dense", |b| {
+        b.iter_batched(
+            memory_with_segment,
+            |(mut memory, base)| {
+                fill_segment(&mut memory, &base, MEMORY_CELLS, 1);
+                memory
+            },
+            BatchSize::SmallInput,
+        )
+    });
+    c.bench_function("memory_get_dense", |b| {
+        let (mut memory, base) = memory_with_segment();
+        fill_segment(&mut memory, &base, MEMORY_CELLS, 1);
+        b.iter(|| {
+            for i in 0..MEMORY_CELLS {
+                black_box(memory.get_integer(&(base + i)).unwrap());
+            }
+        })
+    });
+    c.bench_function("memory_get_ref_dense", |b| {
+        let (mut memory, base) = memory_with_segment();
+        fill_segment(&mut memory, &base, MEMORY_CELLS, 1);
+        b.iter(|| {
+            for i in 0..MEMORY_CELLS {
+                black_box(memory.get_ref(&(base + i)).unwrap());
+            }
+        })
+    });
+    let program = Program::from_reader(COUNTDOWN_PROGRAM.as_bytes(), Some("main")).unwrap();
+    let mut hint_executor = BuiltinHintProcessor::new_empty();
+    c.bench_function("memory_countdown_run", |b| {
+        b.iter(|| {
+            let mut runner = CairoRunner::new(&program, "all", false).unwrap();
+            let mut vm = VirtualMachine::new(true);
+            let end = runner.initialize(&mut vm).unwrap();
+            runner
+                .run_until_pc(end, &mut vm, &mut hint_executor)
+                .unwrap();
+            runner.relocate(&mut vm).unwrap();
+            runner
+        })
+    });
+    c.bench_function("memory_insert_sparse", |b| {
+        b.iter_batched(
+            memory_with_segment,
+            |(mut memory, base)| {
+                fill_segment(&mut memory, &base, SPARSE_CELLS, SPARSE_STRIDE);
+                memory
+            },
+            BatchSize::SmallInput,
+        )
+    });
+}
+
+// The scalar height used by the ec_op builtin
+const EC_OP_HEIGHT: u32 = 256;
+
+fn ec_op_inputs() -> ((Felt, Felt), (Felt, Felt), Felt) {
+    (
+        (
+            felt_str!(
+                "3139037544796708144595053687182055617920475701120786241351436619796497072089"
+            ),
+            felt_str!(
+                "2119589567875935397690285099786081818522144748339117565577200220779667999801"
+            ),
+        ),
+        (
+            felt_str!(
+                "874739451078007766457464989774322083649278607533249481151382481072868806602"
+            ),
+            felt_str!(
+                "152666792071518830868575557812948353041420400780739481342941381225525861407"
+            ),
+        ),
+        felt_str!("1809251394333065553493296640760748560207343510400633813116524750123642650623"),
+    )
+}
+
+pub fn ec_op_benchmarks(c: &mut Criterion) {
+    let (partial_sum, doubled_point, m) = ec_op_inputs();
+    let m = m.to_bigint();
+    c.bench_function("ec_op_jacobian", |b| {
+        let alpha = StarkFieldElement::ONE;
+        b.iter(|| {
+            let mut partial_sum =
+                ProjectivePoint::from(AffinePoint::from_felts(&partial_sum.0, &partial_sum.1));
+            let mut doubled_point =
+                ProjectivePoint::from(AffinePoint::from_felts(&doubled_point.0, &doubled_point.1));
+            for i in 0..EC_OP_HEIGHT as u64 {
+                black_box(doubled_point.has_same_x(&partial_sum));
+                if m.bit(i) {
+                    partial_sum = partial_sum.add(&doubled_point);
+                }
+                doubled_point = doubled_point.double(&alpha);
+            }
+            partial_sum.to_affine()
+        })
+    });
+    // The affine helpers of math_utils, which need an inversion at every step
+    c.bench_function("ec_op_affine", |b| {
+        let prime = BigInt::from_str_radix(&felt::PRIME_STR[2..], 16).unwrap();
+        let alpha = BigInt::from(1);
+        b.iter(|| {
+            let mut partial_sum = (partial_sum.0.to_bigint(), partial_sum.1.to_bigint());
+            let mut doubled_point = (doubled_point.0.to_bigint(), doubled_point.1.to_bigint());
+            for i in 0..EC_OP_HEIGHT as u64 {
+                black_box(doubled_point.0 == partial_sum.0);
+                if m.bit(i) {
+                    partial_sum = ec_add(partial_sum, doubled_point.clone(), &prime);
+                }
+                doubled_point = ec_double(doubled_point, &alpha, &prime);
+            }
+            partial_sum
+        })
+    });
+}
+
 fn build_bench_strings() -> Vec<(String, String)> {
     let mut full_string = Vec::<(String, String)>::new();
 
@@ -59,5 +237,10 @@ fn build_bench_strings() -> Vec<(String, String)> {
     full_string
 }
 
-criterion_group!(benches, criterion_benchmarks);
+criterion_group!(
+    benches,
+    criterion_benchmarks,
+    memory_benchmarks,
+    ec_op_benchmarks
+);
 criterion_main!(benches);
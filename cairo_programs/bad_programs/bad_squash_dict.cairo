@@ -0,0 +1,42 @@
+%builtins range_check
+
+from starkware.cairo.common.alloc import alloc
+from starkware.cairo.common.dict_access import DictAccess
+
+// Runs the squash_dict hint over an empty accesses array.
+// The common library returns early in this case, so the hint never sees it there.
+func squash_no_accesses{range_check_ptr}(dict_accesses: DictAccess*) {
+    alloc_locals;
+    local ptr_diff = 0;
+    local n_accesses = 0;
+    local big_keys;
+    local first_key;
+    %{
+        dict_access_size = ids.DictAccess.SIZE
+        address = ids.dict_accesses.address_
+        assert ids.ptr_diff % dict_access_size == 0, \
+            'Accesses array size must be divisible by DictAccess.SIZE'
+        n_accesses = ids.n_accesses
+        if '__squash_dict_max_size' in globals():
+            assert n_accesses <= __squash_dict_max_size, \
+                f'squash_dict() can only be used with n_accesses<={__squash_dict_max_size}. ' \
+                f'Got: n_accesses={n_accesses}.'
+        # A map from key to the list of indices accessing it.
+        access_indices = {}
+        for i in range(n_accesses):
+            key = memory[address + dict_access_size * i]
+            access_indices.setdefault(key, []).append(i)
+        # Descending list of keys.
+        keys = sorted(access_indices.keys(), reverse=True)
+        # Are the keys used bigger than range_check bound.
+        ids.big_keys = 1 if keys[0] >= range_check_builtin.bound else 0
+        ids.first_key = key = keys.pop()
+    %}
+    return ();
+}
+
+func main{range_check_ptr}() {
+    let (dict_accesses: DictAccess*) = alloc();
+    squash_no_accesses(dict_accesses);
+    return ();
+}
@@ -0,0 +1,32 @@
+from starkware.cairo.common.dict import dict_new, dict_read, dict_write
+from starkware.cairo.common.dict_access import DictAccess
+
+struct Point {
+    x: felt,
+    y: felt,
+}
+
+// The initial dict of my_dict is supplied by the host: {1: 10, 2: (3, 4), 3: [5, [6, 7]]}
+func main() {
+    alloc_locals;
+    let (local my_dict: DictAccess*) = dict_new();
+    let (local val1: felt) = dict_read{dict_ptr=my_dict}(key=1);
+    assert val1 = 10;
+
+    let (local point_ptr: felt) = dict_read{dict_ptr=my_dict}(key=2);
+    let point = cast(point_ptr, Point*);
+    assert point.x = 3;
+    assert point.y = 4;
+
+    let (local list_ptr: felt) = dict_read{dict_ptr=my_dict}(key=3);
+    let list = cast(list_ptr, felt*);
+    assert list[0] = 5;
+    let nested_list = cast(list[1], felt*);
+    assert nested_list[0] = 6;
+    assert nested_list[1] = 7;
+
+    dict_write{dict_ptr=my_dict}(key=1, new_value=11);
+    let (local val2: felt) = dict_read{dict_ptr=my_dict}(key=1);
+    assert val2 = 11;
+    return ();
+}
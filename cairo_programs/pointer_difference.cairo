@@ -0,0 +1,11 @@
+from starkware.cairo.common.alloc import alloc
+
+func main() {
+    alloc_locals;
+    let (local ptr: felt*) = alloc();
+    local end: felt* = ptr + 5;
+    // Deducing this value subtracts a bigger offset from a smaller one.
+    tempvar diff = ptr - end;
+    assert diff = -5;
+    return ();
+}
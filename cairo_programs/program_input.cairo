@@ -0,0 +1,11 @@
+%builtins output
+
+from starkware.cairo.common.serialize import serialize_word
+
+func main{output_ptr: felt*}() {
+    alloc_locals;
+    local n;
+    %{ ids.n = program_input['n'] %}
+    serialize_word(n);
+    return ();
+}
@@ -0,0 +1,36 @@
+%builtins range_check
+
+from starkware.cairo.common.cairo_secp.bigint import BigInt3
+from starkware.cairo.common.cairo_secp.ec import EcPoint
+from starkware.cairo.common.secp256r1.ec import ec_add, ec_double
+
+func assert_points_equal(point0: EcPoint, point1: EcPoint) {
+    assert point0.x.d0 = point1.x.d0;
+    assert point0.x.d1 = point1.x.d1;
+    assert point0.x.d2 = point1.x.d2;
+    assert point0.y.d0 = point1.y.d0;
+    assert point0.y.d1 = point1.y.d1;
+    assert point0.y.d2 = point1.y.d2;
+    return ();
+}
+
+func main{range_check_ptr}() {
+    alloc_locals;
+    // The generator of secp256r1.
+    let gen_pt = EcPoint(
+        BigInt3(0x2b33a0f4a13945d898c296, 0x1b958e9103c9dc0df604b7, 0x6b17d1f2e12c4247f8bce),
+        BigInt3(0x315ececbb6406837bf51f5, 0x2d29f03e7858af38cd5dac, 0x4fe342e2fe1a7f9b8ee7e),
+    );
+
+    let (local double_gen_pt: EcPoint) = ec_double(gen_pt);
+    let (sum_pt: EcPoint) = ec_add(gen_pt, gen_pt);
+    assert_points_equal(double_gen_pt, sum_pt);
+
+    // 4 * G, computed as 2 * (2 * G) and as (2 * G + G) + G.
+    let (quadruple_gen_pt: EcPoint) = ec_double(double_gen_pt);
+    let (triple_gen_pt: EcPoint) = ec_add(double_gen_pt, gen_pt);
+    let (sum_pt: EcPoint) = ec_add(triple_gen_pt, gen_pt);
+    assert_points_equal(quadruple_gen_pt, sum_pt);
+
+    return ();
+}
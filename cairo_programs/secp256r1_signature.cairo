@@ -0,0 +1,147 @@
+%builtins range_check
+
+from starkware.cairo.common.cairo_secp.bigint import (
+    BigInt3,
+    UnreducedBigInt5,
+    bigint_mul,
+    nondet_bigint3,
+)
+from starkware.cairo.common.cairo_secp.constants import BASE
+from starkware.cairo.common.cairo_secp.ec import EcPoint
+from starkware.cairo.common.secp256r1.ec import ec_add, ec_double, ec_negate
+
+// The order of the secp256r1 group, SECP256R1_N = N0 + N1 * BASE + N2 * BASE**2.
+const N0 = 0x179e84f3b9cac2fc632551;
+const N1 = 0x3ffffffffffef39beab69c;
+const N2 = 0xffffffff00000000fffff;
+
+func assert_points_equal(point0: EcPoint, point1: EcPoint) {
+    assert point0.x.d0 = point1.x.d0;
+    assert point0.x.d1 = point1.x.d1;
+    assert point0.x.d2 = point1.x.d2;
+    assert point0.y.d0 = point1.y.d0;
+    assert point0.y.d1 = point1.y.d1;
+    assert point0.y.d2 = point1.y.d2;
+    return ();
+}
+
+// Computes a * b^(-1) modulo SECP256R1_N.
+func div_mod_n{range_check_ptr}(a: BigInt3, b: BigInt3) -> (res: BigInt3) {
+    alloc_locals;
+    %{ from starkware.cairo.common.cairo_secp.secp256r1_utils import SECP256R1_N as N %}
+    %{
+        from starkware.cairo.common.cairo_secp.secp_utils import pack
+        from starkware.python.math_utils import div_mod, safe_div
+
+        a = pack(ids.a, PRIME)
+        b = pack(ids.b, PRIME)
+        value = res = div_mod(a, b, N)
+    %}
+    let (local res: BigInt3) = nondet_bigint3();
+
+    %{ value = k = safe_div(res * b - a, N) %}
+    let (local k: BigInt3) = nondet_bigint3();
+
+    let (local res_b: UnreducedBigInt5) = bigint_mul(res, b);
+    let (k_n: UnreducedBigInt5) = bigint_mul(k, BigInt3(d0=N0, d1=N1, d2=N2));
+
+    // res * b = k * N + a, handling the carries of the unreduced limbs.
+    tempvar carry1 = (res_b.d0 - k_n.d0 - a.d0) / BASE;
+    assert [range_check_ptr + 0] = carry1 + 2 ** 127;
+
+    tempvar carry2 = (res_b.d1 - k_n.d1 - a.d1 + carry1) / BASE;
+    assert [range_check_ptr + 1] = carry2 + 2 ** 127;
+
+    tempvar carry3 = (res_b.d2 - k_n.d2 - a.d2 + carry2) / BASE;
+    assert [range_check_ptr + 2] = carry3 + 2 ** 127;
+
+    tempvar carry4 = (res_b.d3 - k_n.d3 + carry3) / BASE;
+    assert [range_check_ptr + 3] = carry4 + 2 ** 127;
+
+    assert res_b.d4 - k_n.d4 + carry4 = 0;
+
+    let range_check_ptr = range_check_ptr + 4;
+    return (res=res);
+}
+
+// Returns (2**m * point, scalar * point), where scalar must be in the range [0, 2**m).
+func ec_mul_inner{range_check_ptr}(point: EcPoint, scalar: felt, m: felt) -> (
+    pow2: EcPoint, res: EcPoint
+) {
+    if (m == 0) {
+        assert scalar = 0;
+        let zero_point = EcPoint(BigInt3(0, 0, 0), BigInt3(0, 0, 0));
+        return (pow2=point, res=zero_point);
+    }
+
+    alloc_locals;
+    let (double_point: EcPoint) = ec_double(point);
+    %{ memory[ap] = (ids.scalar % PRIME) % 2 %}
+    jmp odd if [ap] != 0, ap++;
+    return ec_mul_inner(point=double_point, scalar=scalar / 2, m=m - 1);
+
+    odd:
+    let (local inner_pow2: EcPoint, inner_res: EcPoint) = ec_mul_inner(
+        point=double_point, scalar=(scalar - 1) / 2, m=m - 1
+    );
+    let (res: EcPoint) = ec_add(point, inner_res);
+    return (pow2=inner_pow2, res=res);
+}
+
+// Returns scalar * point.
+func ec_mul{range_check_ptr}(point: EcPoint, scalar: BigInt3) -> (res: EcPoint) {
+    alloc_locals;
+    let (pow2_0: EcPoint, local res0: EcPoint) = ec_mul_inner(point, scalar.d0, 86);
+    let (pow2_1: EcPoint, local res1: EcPoint) = ec_mul_inner(pow2_0, scalar.d1, 86);
+    let (_, local res2: EcPoint) = ec_mul_inner(pow2_1, scalar.d2, 84);
+    let (res: EcPoint) = ec_add(res0, res1);
+    let (res: EcPoint) = ec_add(res, res2);
+    return (res=res);
+}
+
+// Verifies that (r, s) is an ECDSA signature of msg_hash by public_key, and returns the point
+// whose x coordinate is compared to r.
+func verify_signature{range_check_ptr}(
+    public_key: EcPoint, msg_hash: BigInt3, r: BigInt3, s: BigInt3
+) -> (r_point: EcPoint) {
+    alloc_locals;
+    let gen_pt = EcPoint(
+        BigInt3(0x2b33a0f4a13945d898c296, 0x1b958e9103c9dc0df604b7, 0x6b17d1f2e12c4247f8bce),
+        BigInt3(0x315ececbb6406837bf51f5, 0x2d29f03e7858af38cd5dac, 0x4fe342e2fe1a7f9b8ee7e),
+    );
+
+    let (local u1: BigInt3) = div_mod_n(msg_hash, s);
+    let (local u2: BigInt3) = div_mod_n(r, s);
+    let (local gen_u1: EcPoint) = ec_mul(gen_pt, u1);
+    let (public_key_u2: EcPoint) = ec_mul(public_key, u2);
+    let (r_point: EcPoint) = ec_add(gen_u1, public_key_u2);
+
+    // r_point.x is reduced and smaller than SECP256R1_N, so it must be equal to r.
+    assert r_point.x.d0 = r.d0;
+    assert r_point.x.d1 = r.d1;
+    assert r_point.x.d2 = r.d2;
+    return (r_point=r_point);
+}
+
+func main{range_check_ptr}() {
+    alloc_locals;
+    // The P-256 test vector with SHA-256 and the message "sample" from RFC 6979, A.2.5.
+    let public_key = EcPoint(
+        BigInt3(0x21fa6ce669622e60f29fb6, 0x2dd318d5b5a30126e248ed, 0x60fed4ba255a9d31c961e),
+        BigInt3(0x3e9f5177a3c294d4462299, 0x27a558a2f193cbc6c830b5, 0x7903fe1008b8bc99a41ae),
+    );
+    let msg_hash = BigInt3(
+        0x29891562113d8a62add1bf, 0x75a53d07f1c6a0c7409a3, 0xaf2bdbe1aa9b6ec1e2ade
+    );
+    let r = BigInt3(0x2af991c34d0ea84eaf3716, 0x3673517a075a74b21ded5a, 0xefd48b2aacb6a8fd1140d);
+    let s = BigInt3(0x2ff4064dc4ab2f843acda8, 0x1e86db8a7d97cfa4036ee6, 0xf7cb1c942d657c41d436c);
+
+    let (local r_point: EcPoint) = verify_signature(public_key, msg_hash, r, s);
+
+    // r_point + (-r_point) is the point at infinity.
+    let (minus_r_point: EcPoint) = ec_negate(r_point);
+    let (zero_point: EcPoint) = ec_add(r_point, minus_r_point);
+    assert_points_equal(zero_point, EcPoint(BigInt3(0, 0, 0), BigInt3(0, 0, 0)));
+
+    return ();
+}
@@ -1,20 +1,34 @@
 use crate::{
-    hint_processor::hint_processor_definition::HintProcessor,
-    types::program::Program,
+    any_box,
+    hint_processor::{
+        builtin_hint_processor::bootloader::objects::{FactTopology, SimpleBootloaderInput},
+        hint_processor_definition::HintProcessor,
+    },
+    types::{
+        errors::program_errors::ProgramError,
+        program::Program,
+        relocatable::{relocate_address, relocate_value},
+    },
     vm::{
         errors::{
-            cairo_run_errors::CairoRunError, runner_errors::RunnerError, vm_exception::VmException,
+            cairo_run_errors::CairoRunError, runner_errors::RunnerError, trace_errors::TraceError,
+            vm_exception::VmException,
         },
-        runners::cairo_runner::CairoRunner,
-        trace::trace_entry::RelocatedTraceEntry,
+        runners::{
+            builtin_runner::OutputBuiltinAdditionalData,
+            cairo_runner::{CairoRunner, RelocationTable},
+        },
+        trace::trace_entry::{relocate_trace_register, RelocatedTraceEntry},
         vm_core::VirtualMachine,
     },
 };
 use felt::{Felt, FeltOps};
 use std::{
+    any::Any,
+    collections::HashMap,
     fs::File,
-    io::{self, BufWriter, Error, ErrorKind, Write},
-    path::Path,
+    io::{self, BufReader, BufWriter, Error, ErrorKind, Write},
+    path::{Path, PathBuf},
 };
 
 pub fn cairo_run(
@@ -30,9 +44,145 @@ pub fn cairo_run(
         Ok(program) => program,
         Err(error) => return Err(CairoRunError::Program(error)),
     };
+    let config = CairoRunConfig {
+        trace_enabled,
+        print_output,
+        layout,
+        proof_mode,
+        ..Default::default()
+    };
+    cairo_run_with_initial_scope(&program, &config, hint_executor, HashMap::new())
+}
+
+/// The options of `cairo_run_with_initial_scope` and `cairo_run_streaming`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CairoRunConfig<'a> {
+    /// Ignored by `cairo_run_streaming`, which traces the run if it is given a trace file.
+    pub trace_enabled: bool,
+    pub print_output: bool,
+    pub layout: &'a str,
+    pub proof_mode: bool,
+    /// Records the hint stats, left in the `hint_stats` field of the runner.
+    pub hint_stats: bool,
+}
+
+impl<'a> Default for CairoRunConfig<'a> {
+    fn default() -> Self {
+        CairoRunConfig {
+            trace_enabled: false,
+            print_output: false,
+            layout: "plain",
+            proof_mode: false,
+            hint_stats: false,
+        }
+    }
+}
+
+/// Same as `cairo_run`, but runs an already loaded program, and adds `main_scope_variables` to
+/// the main execution scope before running it, so that hints can read them (ie: `program_input`).
+pub fn cairo_run_with_initial_scope(
+    program: &Program,
+    config: &CairoRunConfig,
+    hint_executor: &mut dyn HintProcessor,
+    main_scope_variables: HashMap<String, Box<dyn Any>>,
+) -> Result<CairoRunner, CairoRunError> {
+    let mut vm = VirtualMachine::new(config.trace_enabled);
+    if config.hint_stats {
+        vm.enable_hint_stats();
+    }
+    let (mut cairo_runner, mut vm) = run_program(
+        program,
+        config.layout,
+        config.proof_mode,
+        hint_executor,
+        main_scope_variables,
+        vm,
+    )?;
+    cairo_runner.relocate(&mut vm)?;
 
-    let mut cairo_runner = CairoRunner::new(&program, layout, proof_mode)?;
-    let mut vm = VirtualMachine::new(trace_enabled);
+    if config.print_output {
+        write_output(&mut cairo_runner, &mut vm)?;
+    }
+
+    Ok(cairo_runner)
+}
+
+/// The default amount of trace entries kept in memory by `cairo_run_streaming` before they are
+/// written to disk.
+pub const DEFAULT_TRACE_CHUNK_SIZE: usize = 1 << 16;
+
+/// The files written by `cairo_run_streaming`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StreamingOutput {
+    pub trace_file: Option<PathBuf>,
+    pub memory_file: Option<PathBuf>,
+    /// The amount of trace entries kept in memory before they are written to disk.
+    pub trace_chunk_size: usize,
+}
+
+/// Same as `cairo_run_with_initial_scope`, but the trace is written to disk in chunks while the
+/// program runs, and is relocated when `output.trace_file` is written at the end of the run.
+/// The memory is written to `output.memory_file` one segment at a time.
+/// Neither `relocated_trace` nor `relocated_memory` are filled in the returned runner.
+pub fn cairo_run_streaming(
+    program: &Program,
+    config: &CairoRunConfig,
+    hint_executor: &mut dyn HintProcessor,
+    main_scope_variables: HashMap<String, Box<dyn Any>>,
+    output: &StreamingOutput,
+) -> Result<CairoRunner, CairoRunError> {
+    let mut vm = VirtualMachine::new(output.trace_file.is_some());
+    let unrelocated_trace_file = output.trace_file.as_ref().map(|trace_file| {
+        let mut unrelocated_trace_file = trace_file.clone().into_os_string();
+        unrelocated_trace_file.push(".unrelocated");
+        PathBuf::from(unrelocated_trace_file)
+    });
+    if let Some(unrelocated_trace_file) = &unrelocated_trace_file {
+        vm.enable_streaming_trace(unrelocated_trace_file, output.trace_chunk_size)?;
+    }
+    if config.hint_stats {
+        vm.enable_hint_stats();
+    }
+    let (mut cairo_runner, mut vm) = run_program(
+        program,
+        config.layout,
+        config.proof_mode,
+        hint_executor,
+        main_scope_variables,
+        vm,
+    )?;
+
+    if config.print_output {
+        write_output(&mut cairo_runner, &mut vm)?;
+    }
+
+    vm.segments.compute_effective_sizes(&vm.memory);
+    let relocation_table = vm.segments.relocate_segments()?;
+    cairo_runner.relocation_table = Some(cairo_runner.get_relocation_table(&vm)?);
+    if let Some(trace_file) = &output.trace_file {
+        write_streamed_binary_trace(&mut vm, &relocation_table, trace_file)?;
+    }
+    if let Some(memory_file) = &output.memory_file {
+        write_binary_memory_by_segment(&vm, &relocation_table, memory_file)?;
+    }
+
+    Ok(cairo_runner)
+}
+
+// Runs the program until its end, and finalizes its segments in proof mode.
+fn run_program(
+    program: &Program,
+    layout: &str,
+    proof_mode: bool,
+    hint_executor: &mut dyn HintProcessor,
+    main_scope_variables: HashMap<String, Box<dyn Any>>,
+    mut vm: VirtualMachine,
+) -> Result<(CairoRunner, VirtualMachine), CairoRunError> {
+    let mut cairo_runner = CairoRunner::new(program, layout, proof_mode)?;
+    if proof_mode {
+        cairo_runner.validate_proof_mode_program()?;
+    }
+    cairo_runner.initialize_main_scope(main_scope_variables);
     let end = cairo_runner.initialize(&mut vm)?;
 
     cairo_runner
@@ -45,13 +195,50 @@ pub fn cairo_run(
         cairo_runner.read_return_values(&vm)?;
         cairo_runner.finalize_segments(&mut vm)?;
     }
-    cairo_runner.relocate(&mut vm)?;
+    Ok((cairo_runner, vm))
+}
 
-    if print_output {
-        write_output(&mut cairo_runner, &mut vm)?;
-    }
+/// The result of running a list of tasks under the simple bootloader.
+pub struct SimpleBootloaderRun {
+    pub cairo_runner: CairoRunner,
+    pub vm: VirtualMachine,
+    /// The fact topology of each task, in the order they were run.
+    pub fact_topologies: Vec<FactTopology>,
+    /// The pages and attributes of the output builtin, used to compute the fact of the run.
+    pub output_builtin_additional_data: OutputBuiltinAdditionalData,
+}
 
-    Ok(cairo_runner)
+/// Runs the tasks in `input` under the simple bootloader, in proof mode.
+/// `bootloader_program` should be the simple bootloader of cairo-lang, compiled with
+/// `--proof_mode`.
+pub fn cairo_run_simple_bootloader(
+    bootloader_program: &Program,
+    input: SimpleBootloaderInput,
+    layout: &str,
+    trace_enabled: bool,
+    hint_executor: &mut dyn HintProcessor,
+) -> Result<SimpleBootloaderRun, CairoRunError> {
+    let vm = VirtualMachine::new(trace_enabled);
+    let (mut cairo_runner, mut vm) = run_program(
+        bootloader_program,
+        layout,
+        true,
+        hint_executor,
+        HashMap::from([("simple_bootloader_input".to_string(), any_box!(input))]),
+        vm,
+    )?;
+    cairo_runner.relocate(&mut vm)?;
+
+    let fact_topologies = cairo_runner
+        .exec_scopes
+        .get_list::<FactTopology>("fact_topologies")?;
+    let output_builtin_additional_data = vm.get_output_builtin()?.get_additional_data();
+    Ok(SimpleBootloaderRun {
+        cairo_runner,
+        vm,
+        fact_topologies,
+        output_builtin_additional_data,
+    })
 }
 
 pub fn write_output(
@@ -67,6 +254,34 @@ pub fn write_output(
         .map_err(|_| CairoRunError::Runner(RunnerError::WriteFail))
 }
 
+/// Reads a JSON file to be used as the program input.
+pub fn read_program_input(path: &Path) -> Result<serde_json::Value, ProgramError> {
+    let file = File::open(path)?;
+    Ok(serde_json::from_reader(BufReader::new(file))?)
+}
+
+/// Prints the final contents of the dictionaries created during the run.
+pub fn write_dicts(cairo_runner: &CairoRunner) -> Result<(), CairoRunError> {
+    let mut buffer = BufWriter::new(io::stdout());
+    writeln!(&mut buffer, "Dictionaries: ")
+        .map_err(|_| CairoRunError::Runner(RunnerError::WriteFail))?;
+    cairo_runner.write_dicts(&mut buffer)?;
+    buffer
+        .flush()
+        .map_err(|_| CairoRunError::Runner(RunnerError::WriteFail))
+}
+
+/// Writes the relocation table of a run as a JSON file, see `RelocationTable`.
+pub fn write_relocation_table(
+    relocation_table: &RelocationTable,
+    relocation_table_file: &Path,
+) -> io::Result<()> {
+    let file = File::create(relocation_table_file)?;
+    let mut buffer = BufWriter::new(file);
+    serde_json::to_writer(&mut buffer, relocation_table)?;
+    buffer.flush()
+}
+
 /// Writes a trace as a binary file. Bincode encodes to little endian by default and each trace
 /// entry is composed of 3 usize values that are padded to always reach 64 bit size.
 pub fn write_binary_trace(
@@ -88,6 +303,71 @@ pub fn write_binary_trace(
     buffer.flush()
 }
 
+/// Writes the trace streamed to disk during the run (see `VirtualMachine::enable_streaming_trace`)
+/// as a binary file, in the same format as `write_binary_trace`, relocating each entry as it is
+/// written.
+pub fn write_streamed_binary_trace(
+    vm: &mut VirtualMachine,
+    relocation_table: &Vec<usize>,
+    trace_file: &Path,
+) -> Result<(), CairoRunError> {
+    let mut streaming_trace = vm
+        .streaming_trace
+        .take()
+        .ok_or(TraceError::TraceNotEnabled)?;
+    // Write the entries that are still in memory
+    let trace = vm.trace.as_mut().ok_or(TraceError::TraceNotEnabled)?;
+    streaming_trace.flush(trace, &vm.memory)?;
+
+    let file = File::create(trace_file).map_err(|e| TraceError::WriteFail(e.to_string()))?;
+    let mut buffer = BufWriter::new(file);
+    for (i, entry) in streaming_trace.read_entries()?.enumerate() {
+        let entry = entry?;
+        let relocated_entry = RelocatedTraceEntry {
+            pc: relocate_trace_register(&entry.pc, relocation_table)?,
+            ap: relocate_trace_register(&entry.ap, relocation_table)?,
+            fp: relocate_trace_register(&entry.fp, relocation_table)?,
+        };
+        bincode::serialize_into(&mut buffer, &relocated_entry).map_err(|e| {
+            TraceError::WriteFail(format!(
+                "Failed to dump trace at position {i}, serialize error: {e}"
+            ))
+        })?;
+    }
+    buffer
+        .flush()
+        .map_err(|e| TraceError::WriteFail(e.to_string()).into())
+}
+
+/// Writes the memory of the VM as a binary file, in the same format as `write_binary_memory`,
+/// relocating it one segment at a time instead of building the whole relocated memory.
+pub fn write_binary_memory_by_segment(
+    vm: &VirtualMachine,
+    relocation_table: &Vec<usize>,
+    memory_file: &Path,
+) -> Result<(), CairoRunError> {
+    let file =
+        File::create(memory_file).map_err(|_| CairoRunError::Runner(RunnerError::WriteFail))?;
+    let mut buffer = BufWriter::new(file);
+    let mut memory_bytes: Vec<u8> = Vec::new();
+
+    for (segment_index, segment) in vm.memory.data.iter().enumerate() {
+        for (offset, value) in segment.iter_written() {
+            let addr = relocate_address((segment_index as isize, offset).into(), relocation_table)?;
+            let value = relocate_value(value, relocation_table)?;
+            encode_relocated_memory(&mut memory_bytes, addr, &value);
+        }
+        buffer
+            .write_all(&memory_bytes)
+            .map_err(|_| CairoRunError::Runner(RunnerError::WriteFail))?;
+        memory_bytes.clear();
+    }
+
+    buffer
+        .flush()
+        .map_err(|_| CairoRunError::Runner(RunnerError::WriteFail))
+}
+
 /*
    Writes a binary memory file with the relocated memory as input.
    The memory pairs (address, value) are encoded and concatenated in the file
@@ -309,6 +589,64 @@ mod tests {
         assert!(compare_files(cairo_rs_memory_path, expected_memory_path).is_ok());
     }
 
+    #[test]
+    fn write_streamed_binary_trace_file() {
+        let program_path = Path::new("cairo_programs/struct.json");
+        let expected_trace_path = Path::new("cairo_programs/trace_memory/cairo_trace_struct");
+        let cairo_rs_trace_path =
+            Path::new("cairo_programs/trace_memory/struct_cairo_rs_streamed.trace");
+        let unrelocated_trace_path =
+            Path::new("cairo_programs/trace_memory/struct_cairo_rs_streamed.trace.unrelocated");
+
+        // run test program until the end, writing the trace to disk every two steps
+        let program = Program::from_file(program_path, Some("main")).unwrap();
+        let mut hint_processor = BuiltinHintProcessor::new_empty();
+        let mut cairo_runner = cairo_runner!(program);
+        let mut vm = vm!(true);
+        assert_eq!(vm.enable_streaming_trace(unrelocated_trace_path, 2), Ok(()));
+        let end = cairo_runner.initialize(&mut vm).unwrap();
+        assert!(cairo_runner
+            .run_until_pc(end, &mut vm, &mut hint_processor)
+            .is_ok());
+        assert!(vm.trace.as_ref().unwrap().len() < 2);
+
+        // the streamed trace can only be relocated while it is written
+        assert_eq!(
+            cairo_runner.relocate(&mut vm).err(),
+            Some(TraceError::StreamedTrace)
+        );
+        let relocation_table = vm.segments.relocate_segments().unwrap();
+        assert!(
+            write_streamed_binary_trace(&mut vm, &relocation_table, cairo_rs_trace_path).is_ok()
+        );
+        assert!(!unrelocated_trace_path.exists());
+
+        // compare that the original cairo vm trace file and cairo_rs vm trace files are equal
+        assert!(compare_files(cairo_rs_trace_path, expected_trace_path).is_ok());
+    }
+
+    #[test]
+    fn write_binary_memory_by_segment_file() {
+        let program_path = Path::new("cairo_programs/struct.json");
+        let expected_memory_path = Path::new("cairo_programs/trace_memory/cairo_memory_struct");
+        let cairo_rs_memory_path =
+            Path::new("cairo_programs/trace_memory/struct_cairo_rs_by_segment.memory");
+
+        // run test program until the end
+        let mut hint_processor = BuiltinHintProcessor::new_empty();
+        let cairo_runner_result = run_test_program(program_path, &mut hint_processor);
+        let (_, mut vm) = cairo_runner_result.unwrap();
+
+        vm.segments.compute_effective_sizes(&vm.memory);
+        let relocation_table = vm.segments.relocate_segments().unwrap();
+        assert!(
+            write_binary_memory_by_segment(&vm, &relocation_table, cairo_rs_memory_path).is_ok()
+        );
+
+        // compare that the original cairo vm memory file and cairo_rs vm memory files are equal
+        assert!(compare_files(cairo_rs_memory_path, expected_memory_path).is_ok());
+    }
+
     #[test]
     fn run_with_no_trace() {
         let program_path = Path::new("cairo_programs/struct.json");
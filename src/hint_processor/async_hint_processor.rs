@@ -0,0 +1,225 @@
+use crate::{
+    hint_processor::{
+        builtin_hint_processor::builtin_hint_processor_definition::BuiltinHintProcessor,
+        hint_processor_definition::HintProcessor,
+    },
+    types::exec_scope::ExecutionScopes,
+    vm::{errors::hint_errors::HintError, vm_core::VirtualMachine},
+};
+use felt::Felt;
+use std::{any::Any, collections::HashMap, future::Future, pin::Pin};
+
+/// The future returned by `AsyncHintProcessor::execute_hint_async`.
+/// It borrows the VM for as long as the hint runs, so it isn't `Send`.
+pub type HintFuture<'a> = Pin<Box<dyn Future<Output = Result<(), HintError>> + 'a>>;
+
+/// A HintProcessor whose hints can wait on external I/O without blocking the thread, used by
+/// `CairoRunner::run_until_pc_async`.
+/// Hints are compiled with `HintProcessor::compile_hint`. By default, hints are executed with the
+/// synchronous `HintProcessor::execute_hint`, so processors only need to implement
+/// `execute_hint_async` for the hints that await something.
+pub trait AsyncHintProcessor: HintProcessor {
+    fn execute_hint_async<'a>(
+        &'a mut self,
+        vm: &'a mut VirtualMachine,
+        exec_scopes: &'a mut ExecutionScopes,
+        hint_data: &'a Box<dyn Any>,
+        constants: &'a HashMap<String, Felt>,
+    ) -> HintFuture<'a> {
+        Box::pin(std::future::ready(self.execute_hint(
+            vm,
+            exec_scopes,
+            hint_data,
+            constants,
+        )))
+    }
+}
+
+impl AsyncHintProcessor for BuiltinHintProcessor {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        hint_processor::builtin_hint_processor::builtin_hint_processor_definition::HintProcessorData,
+        serde::deserialize_program::{ApTracking, FlowTrackingData, HintParams},
+        types::{program::Program, relocatable::MaybeRelocatable},
+        utils::test_utils::*,
+        vm::{errors::vm_errors::VirtualMachineError, runners::cairo_runner::CairoRunner},
+    };
+    use felt::NewFelt;
+    use std::{
+        sync::Arc,
+        task::{Context, Poll, Wake, Waker},
+    };
+
+    const ORACLE_HINT: &str = "memory[ap] = oracle.fetch()";
+
+    // Completes on its second poll, like a future waiting on I/O
+    struct YieldOnce(bool);
+
+    impl Future for YieldOnce {
+        type Output = ();
+
+        fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+            if self.0 {
+                return Poll::Ready(());
+            }
+            self.0 = true;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    }
+
+    fn block_on<F: Future>(future: F) -> F::Output {
+        struct NoopWaker;
+        impl Wake for NoopWaker {
+            fn wake(self: Arc<Self>) {}
+        }
+        let waker = Waker::from(Arc::new(NoopWaker));
+        let mut cx = Context::from_waker(&waker);
+        let mut future = Box::pin(future);
+        loop {
+            if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+                return output;
+            }
+        }
+    }
+
+    // Executes ORACLE_HINT asynchronously, and the builtin hints synchronously
+    struct OracleHintProcessor {
+        builtin_hint_processor: BuiltinHintProcessor,
+        fetched: usize,
+    }
+
+    impl HintProcessor for OracleHintProcessor {
+        fn execute_hint(
+            &mut self,
+            vm: &mut VirtualMachine,
+            exec_scopes: &mut ExecutionScopes,
+            hint_data: &Box<dyn Any>,
+            constants: &HashMap<String, Felt>,
+        ) -> Result<(), HintError> {
+            self.builtin_hint_processor
+                .execute_hint(vm, exec_scopes, hint_data, constants)
+        }
+    }
+
+    impl AsyncHintProcessor for OracleHintProcessor {
+        fn execute_hint_async<'a>(
+            &'a mut self,
+            vm: &'a mut VirtualMachine,
+            exec_scopes: &'a mut ExecutionScopes,
+            hint_data: &'a Box<dyn Any>,
+            constants: &'a HashMap<String, Felt>,
+        ) -> HintFuture<'a> {
+            match hint_data.downcast_ref::<HintProcessorData>() {
+                Some(data) if data.code == ORACLE_HINT => Box::pin(async move {
+                    YieldOnce(false).await;
+                    self.fetched += 1;
+                    vm.insert_value(&vm.get_ap(), Felt::new(42))
+                        .map_err(HintError::Internal)
+                }),
+                _ => Box::pin(std::future::ready(self.execute_hint(
+                    vm,
+                    exec_scopes,
+                    hint_data,
+                    constants,
+                ))),
+            }
+        }
+    }
+
+    fn hint(code: &str) -> HintParams {
+        HintParams {
+            code: code.to_string(),
+            accessible_scopes: Vec::new(),
+            flow_tracking_data: FlowTrackingData {
+                ap_tracking: ApTracking::new(),
+                reference_ids: HashMap::new(),
+            },
+        }
+    }
+
+    #[test]
+    fn run_until_pc_async_awaits_hints() {
+        // [ap] = 42, ap++ with the oracle hint; ret with an add_segment hint
+        let program = program!(
+            data = vec_data!((5189976364521848832_i64), (42), (2345108766317314046_i64)),
+            hints = HashMap::from([
+                (0, vec![hint(ORACLE_HINT)]),
+                (2, vec![hint("memory[ap] = segments.add()")])
+            ]),
+            main = Some(0),
+        );
+        let mut hint_processor = OracleHintProcessor {
+            builtin_hint_processor: BuiltinHintProcessor::new_empty(),
+            fetched: 0,
+        };
+        let mut cairo_runner = cairo_runner!(program);
+        let mut vm = vm!();
+        let end = cairo_runner.initialize(&mut vm).unwrap();
+
+        assert_eq!(
+            block_on(cairo_runner.run_until_pc_async(end, &mut vm, &mut hint_processor)),
+            Ok(())
+        );
+        assert_eq!(hint_processor.fetched, 1);
+        assert_eq!(vm.run_context.pc, end);
+        check_memory![vm.memory, ((1, 2), 42), ((1, 3), (4, 0))];
+    }
+
+    #[test]
+    fn run_until_pc_async_hint_error() {
+        // [ap] = 42, ap++ with the oracle hint, over a cell that was already written
+        let program = program!(
+            data = vec_data!((5189976364521848832_i64), (42), (2345108766317314046_i64)),
+            hints = HashMap::from([(0, vec![hint(ORACLE_HINT)])]),
+            main = Some(0),
+        );
+        let mut hint_processor = OracleHintProcessor {
+            builtin_hint_processor: BuiltinHintProcessor::new_empty(),
+            fetched: 0,
+        };
+        let mut cairo_runner = cairo_runner!(program);
+        let mut vm = vm!();
+        let end = cairo_runner.initialize(&mut vm).unwrap();
+        vm.insert_value(&vm.get_ap(), Felt::new(7)).unwrap();
+
+        assert!(matches!(
+            block_on(cairo_runner.run_until_pc_async(end, &mut vm, &mut hint_processor)),
+            Err(VirtualMachineError::Hint(0, _))
+        ));
+        assert_eq!(hint_processor.fetched, 1);
+    }
+
+    #[test]
+    fn step_hint_range_async_skips_hints_out_of_range() {
+        let oracle_hint = || -> Box<dyn Any> {
+            Box::new(HintProcessorData::new_default(
+                ORACLE_HINT.to_string(),
+                HashMap::new(),
+            ))
+        };
+        let hint_data_dictionary = HashMap::from([(0, vec![oracle_hint(), oracle_hint()])]);
+        let mut hint_processor = OracleHintProcessor {
+            builtin_hint_processor: BuiltinHintProcessor::new_empty(),
+            fetched: 0,
+        };
+        let mut vm = vm!();
+        add_segments!(vm, 2);
+
+        assert_eq!(
+            block_on(vm.step_hint_range_async(
+                &mut hint_processor,
+                exec_scopes_ref!(),
+                &hint_data_dictionary,
+                &HashMap::new(),
+                1..usize::MAX,
+            )),
+            Ok(())
+        );
+        assert_eq!(hint_processor.fetched, 1);
+        check_memory![vm.memory, ((1, 0), 42)];
+    }
+}
@@ -0,0 +1,3 @@
+pub mod objects;
+pub mod simple_bootloader_hints;
+pub mod utils;
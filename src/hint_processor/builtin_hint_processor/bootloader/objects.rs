@@ -0,0 +1,154 @@
+use crate::{
+    serde::deserialize_program::deserialize_program, types::program::Program,
+    vm::errors::hint_errors::HintError,
+};
+use serde::Serialize;
+use std::path::PathBuf;
+
+/// A task to be run by the simple bootloader.
+/// Cairo PIE tasks aren't supported yet: running them needs a Cairo PIE representation, which
+/// the VM doesn't have, so they are left for a follow-up.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Task {
+    /// Runs the given program. Its hints can access `program_input`, if present.
+    RunProgram {
+        program: Program,
+        program_input: Option<serde_json::Value>,
+    },
+}
+
+impl Task {
+    pub fn get_program(&self) -> &Program {
+        match self {
+            Task::RunProgram { program, .. } => program,
+        }
+    }
+}
+
+/// The input of the simple bootloader, read by its hints as `simple_bootloader_input`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SimpleBootloaderInput {
+    pub tasks: Vec<Task>,
+    /// If set, the fact topologies are written to this file at the end of the run.
+    pub fact_topologies_path: Option<PathBuf>,
+    /// If true, the whole output is left in a single page, and no pages are added for each task.
+    pub single_page: bool,
+}
+
+impl SimpleBootloaderInput {
+    pub fn new(tasks: Vec<Task>) -> Self {
+        SimpleBootloaderInput {
+            tasks,
+            fact_topologies_path: None,
+            single_page: false,
+        }
+    }
+
+    /// Parses the input from the program input format used by cairo-lang:
+    /// `{"tasks": [{"type": "RunProgramTask", "program": {...}, "program_input": {...}}],
+    ///   "fact_topologies_path": null, "single_page": false}`
+    pub fn from_json(value: &serde_json::Value) -> Result<Self, HintError> {
+        let tasks = value
+            .get("tasks")
+            .and_then(|tasks| tasks.as_array())
+            .ok_or_else(|| invalid_input("missing tasks"))?
+            .iter()
+            .map(task_from_json)
+            .collect::<Result<Vec<Task>, HintError>>()?;
+        let fact_topologies_path = match value.get("fact_topologies_path") {
+            None | Some(serde_json::Value::Null) => None,
+            Some(path) => {
+                Some(PathBuf::from(path.as_str().ok_or_else(|| {
+                    invalid_input("fact_topologies_path should be a string")
+                })?))
+            }
+        };
+        let single_page = match value.get("single_page") {
+            None => false,
+            Some(single_page) => single_page
+                .as_bool()
+                .ok_or_else(|| invalid_input("single_page should be a boolean"))?,
+        };
+        Ok(SimpleBootloaderInput {
+            tasks,
+            fact_topologies_path,
+            single_page,
+        })
+    }
+}
+
+fn task_from_json(value: &serde_json::Value) -> Result<Task, HintError> {
+    match value.get("type").and_then(|task_type| task_type.as_str()) {
+        Some("RunProgramTask") => {
+            let program = value
+                .get("program")
+                .ok_or_else(|| invalid_input("missing task program"))?;
+            let program = deserialize_program(program.to_string().as_bytes(), Some("main"))
+                .map_err(|error| invalid_input(&error.to_string()))?;
+            Ok(Task::RunProgram {
+                program,
+                program_input: value.get("program_input").cloned(),
+            })
+        }
+        Some(task_type) => Err(HintError::UnsupportedTaskType(task_type.to_string())),
+        None => Err(invalid_input("missing task type")),
+    }
+}
+
+fn invalid_input(reason: &str) -> HintError {
+    HintError::InvalidSimpleBootloaderInput(reason.to_string())
+}
+
+/// Describes how the output of a task is split into pages (`page_sizes`), and how these pages
+/// are merged into the fact of the task (`tree_structure`).
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct FactTopology {
+    pub tree_structure: Vec<usize>,
+    pub page_sizes: Vec<usize>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_json_defaults() {
+        let input = SimpleBootloaderInput::from_json(&serde_json::json!({"tasks": []}));
+        assert_eq!(input, Ok(SimpleBootloaderInput::new(vec![])));
+    }
+
+    #[test]
+    fn from_json_options() {
+        let input = SimpleBootloaderInput::from_json(&serde_json::json!({
+            "tasks": [],
+            "fact_topologies_path": "fact_topologies.json",
+            "single_page": true
+        }))
+        .unwrap();
+        assert_eq!(
+            input.fact_topologies_path,
+            Some(PathBuf::from("fact_topologies.json"))
+        );
+        assert!(input.single_page);
+    }
+
+    #[test]
+    fn from_json_missing_tasks() {
+        assert_eq!(
+            SimpleBootloaderInput::from_json(&serde_json::json!({})),
+            Err(HintError::InvalidSimpleBootloaderInput(
+                "missing tasks".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn from_json_cairo_pie_task() {
+        assert_eq!(
+            SimpleBootloaderInput::from_json(&serde_json::json!({
+                "tasks": [{"type": "CairoPiePath", "path": "task.zip"}]
+            })),
+            Err(HintError::UnsupportedTaskType("CairoPiePath".to_string()))
+        );
+    }
+}
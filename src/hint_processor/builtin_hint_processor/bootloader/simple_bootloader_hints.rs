@@ -0,0 +1,805 @@
+use super::{
+    objects::{FactTopology, SimpleBootloaderInput, Task},
+    utils::{
+        compute_program_hash_chain, configure_fact_topologies, get_task_fact_topology,
+        load_program, write_return_builtins, ALL_BUILTINS,
+    },
+};
+use crate::{
+    any_box,
+    hint_processor::{
+        builtin_hint_processor::hint_utils::{
+            get_integer_from_var_name, get_ptr_from_var_name, get_relocatable_from_var_name,
+            insert_value_from_var_name,
+        },
+        hint_processor_definition::HintReference,
+    },
+    serde::deserialize_program::ApTracking,
+    types::{exec_scope::ExecutionScopes, relocatable::Relocatable},
+    vm::{
+        errors::{hint_errors::HintError, vm_errors::VirtualMachineError},
+        runners::builtin_runner::OutputBuiltinState,
+        vm_core::VirtualMachine,
+    },
+};
+use felt::{Felt, NewFelt};
+use num_traits::ToPrimitive;
+use std::{any::Any, collections::HashMap, fs::File, io::BufWriter, rc::Rc};
+
+/*
+Implements hint:
+%{
+    from starkware.cairo.bootloaders.simple_bootloader.objects import SimpleBootloaderInput
+    simple_bootloader_input = SimpleBootloaderInput.Schema().load(program_input)
+%}
+If `simple_bootloader_input` was already added to the main scope by the caller, it is used as is.
+*/
+pub fn load_simple_bootloader_input(exec_scopes: &mut ExecutionScopes) -> Result<(), HintError> {
+    if exec_scopes
+        .get_ref::<SimpleBootloaderInput>("simple_bootloader_input")
+        .is_ok()
+    {
+        return Ok(());
+    }
+    let simple_bootloader_input =
+        SimpleBootloaderInput::from_json(exec_scopes.get_program_input()?)?;
+    exec_scopes.insert_value("simple_bootloader_input", simple_bootloader_input);
+    Ok(())
+}
+
+/*
+Implements hint:
+%{
+    n_tasks = len(simple_bootloader_input.tasks)
+    memory[ids.output_ptr] = n_tasks
+
+    # Task range checks are located right after simple bootloader validation range checks, and
+    # this is validated later in this function.
+    ids.task_range_check_ptr = ids.range_check_ptr + ids.BuiltinData.SIZE * n_tasks
+
+    # A list of fact_toplogies that instruct how to generate the fact from the program output
+    # for each task.
+    fact_topologies = []
+%}
+*/
+pub fn prepare_task_range_checks(
+    vm: &mut VirtualMachine,
+    exec_scopes: &mut ExecutionScopes,
+    ids_data: &HashMap<String, HintReference>,
+    ap_tracking: &ApTracking,
+) -> Result<(), HintError> {
+    let n_tasks = exec_scopes
+        .get_ref::<SimpleBootloaderInput>("simple_bootloader_input")?
+        .tasks
+        .len();
+    let output_ptr = get_ptr_from_var_name("output_ptr", vm, ids_data, ap_tracking)?;
+    vm.insert_value(&output_ptr, Felt::new(n_tasks))?;
+
+    let range_check_ptr = get_ptr_from_var_name("range_check_ptr", vm, ids_data, ap_tracking)?;
+    insert_value_from_var_name(
+        "task_range_check_ptr",
+        range_check_ptr + ALL_BUILTINS.len() * n_tasks,
+        vm,
+        ids_data,
+        ap_tracking,
+    )?;
+
+    exec_scopes.insert_value("fact_topologies", Vec::<FactTopology>::new());
+    Ok(())
+}
+
+/*
+Implements hint:
+%{
+    from starkware.cairo.bootloaders.simple_bootloader.objects import Task
+
+    # Pass current task to execute_task.
+    task_id = len(simple_bootloader_input.tasks) - ids.n_tasks
+    task = simple_bootloader_input.tasks[task_id].load_task()
+%}
+*/
+pub fn set_current_task(
+    vm: &mut VirtualMachine,
+    exec_scopes: &mut ExecutionScopes,
+    ids_data: &HashMap<String, HintReference>,
+    ap_tracking: &ApTracking,
+) -> Result<(), HintError> {
+    let n_tasks = get_integer_from_var_name("n_tasks", vm, ids_data, ap_tracking)?
+        .to_usize()
+        .ok_or(HintError::BigintToUsizeFail)?;
+    let tasks = &exec_scopes
+        .get_ref::<SimpleBootloaderInput>("simple_bootloader_input")?
+        .tasks;
+    let task = tasks
+        .len()
+        .checked_sub(n_tasks)
+        .and_then(|task_id| tasks.get(task_id))
+        .cloned()
+        .ok_or_else(|| HintError::AssertionFailed(format!("Invalid n_tasks: {n_tasks}")))?;
+    exec_scopes.insert_value("task", task);
+    Ok(())
+}
+
+/*
+Implements hint:
+%{ ids.program_data_ptr = program_data_base = segments.add() %}
+*/
+pub fn allocate_program_data_segment(
+    vm: &mut VirtualMachine,
+    exec_scopes: &mut ExecutionScopes,
+    ids_data: &HashMap<String, HintReference>,
+    ap_tracking: &ApTracking,
+) -> Result<(), HintError> {
+    let program_data_base = vm.add_memory_segment();
+    insert_value_from_var_name(
+        "program_data_ptr",
+        program_data_base,
+        vm,
+        ids_data,
+        ap_tracking,
+    )?;
+    exec_scopes.insert_value("program_data_base", program_data_base);
+    Ok(())
+}
+
+/*
+Implements hint:
+%{
+    from starkware.cairo.bootloaders.simple_bootloader.utils import load_program
+
+    # Call load_program to load the program header and code to memory.
+    program_address, program_data_size = load_program(
+        task=task, memory=memory, program_header=ids.program_header,
+        builtins_offset=ids.ProgramHeader.builtin_list)
+    segments.finalize(program_data_base.segment_index, program_data_size)
+%}
+*/
+pub fn load_task_program(
+    vm: &mut VirtualMachine,
+    exec_scopes: &mut ExecutionScopes,
+    ids_data: &HashMap<String, HintReference>,
+    ap_tracking: &ApTracking,
+) -> Result<(), HintError> {
+    let program_header = get_ptr_from_var_name("program_header", vm, ids_data, ap_tracking)?;
+    let task = exec_scopes.get_ref::<Task>("task")?;
+    let (program_address, program_data_size) =
+        load_program(vm, task.get_program(), &program_header)?;
+
+    let program_data_base = exec_scopes.get::<Relocatable>("program_data_base")?;
+    vm.segments.finalize(
+        Some(program_data_size),
+        program_data_base.segment_index as usize,
+        None,
+    );
+    exec_scopes.insert_value("program_address", program_address);
+    Ok(())
+}
+
+/*
+Implements hint:
+%{
+    # Validate hash.
+    from starkware.cairo.bootloaders.hash_program import compute_program_hash_chain
+
+    assert memory[ids.output_ptr + 1] == compute_program_hash_chain(task.get_program()), \
+      'Computed hash does not match input.'
+%}
+*/
+pub fn validate_task_program_hash(
+    vm: &mut VirtualMachine,
+    exec_scopes: &mut ExecutionScopes,
+    ids_data: &HashMap<String, HintReference>,
+    ap_tracking: &ApTracking,
+) -> Result<(), HintError> {
+    let output_ptr = get_ptr_from_var_name("output_ptr", vm, ids_data, ap_tracking)?;
+    let program_hash = vm.get_integer(&(output_ptr + 1))?;
+    let task = exec_scopes.get_ref::<Task>("task")?;
+    if program_hash.as_ref() != &compute_program_hash_chain(task.get_program(), 0)? {
+        return Err(HintError::AssertionFailed(
+            "Computed hash does not match input.".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/*
+Implements hint:
+%{
+    # Sanity check.
+    assert ids.program_address == program_address
+%}
+*/
+pub fn assert_program_address(
+    vm: &mut VirtualMachine,
+    exec_scopes: &mut ExecutionScopes,
+    ids_data: &HashMap<String, HintReference>,
+    ap_tracking: &ApTracking,
+) -> Result<(), HintError> {
+    let program_address = get_ptr_from_var_name("program_address", vm, ids_data, ap_tracking)?;
+    let expected_program_address = exec_scopes.get::<Relocatable>("program_address")?;
+    if program_address != expected_program_address {
+        return Err(HintError::AssertionFailed(format!(
+            "Program address {program_address} doesn't match the loaded program address {expected_program_address}"
+        )));
+    }
+    Ok(())
+}
+
+/*
+Implements hint:
+%{
+    from starkware.cairo.bootloaders.simple_bootloader.objects import (
+        CairoPieTask,
+        RunProgramTask,
+        Task,
+    )
+    ...
+    assert isinstance(task, Task)
+    n_builtins = len(task.get_program().builtins)
+    new_task_locals = {}
+    if isinstance(task, RunProgramTask):
+        new_task_locals['program_input'] = task.program_input
+        new_task_locals['WITH_BOOTLOADER'] = True
+
+        vm_load_program(task.program, program_address)
+    ...
+    output_runner_data = prepare_output_runner(
+        task=task,
+        output_builtin=output_builtin,
+        output_ptr=ids.pre_execution_builtin_ptrs.output)
+    vm_enter_scope(new_task_locals)
+%}
+*/
+pub fn call_task(
+    vm: &mut VirtualMachine,
+    exec_scopes: &mut ExecutionScopes,
+    ids_data: &HashMap<String, HintReference>,
+    ap_tracking: &ApTracking,
+) -> Result<(), HintError> {
+    let task = exec_scopes.get::<Task>("task")?;
+    let program_address = exec_scopes.get::<Relocatable>("program_address")?;
+    exec_scopes.insert_value("n_builtins", task.get_program().builtins.len());
+
+    let mut new_task_locals = HashMap::<String, Box<dyn Any>>::new();
+    match task {
+        Task::RunProgram {
+            program,
+            program_input,
+        } => {
+            if let Some(program_input) = program_input {
+                new_task_locals.insert("program_input".to_string(), any_box!(program_input));
+            }
+            new_task_locals.insert("WITH_BOOTLOADER".to_string(), any_box!(true));
+            // The hints of the task access the members of its own structs
+            new_task_locals.insert(
+                "program_identifiers".to_string(),
+                any_box!(Rc::new(program.identifiers.clone())),
+            );
+            vm.load_program(program, program_address);
+        }
+    }
+
+    // Record the pages of the task separately from the bootloader ones
+    let pre_execution_builtin_ptrs =
+        get_relocatable_from_var_name("pre_execution_builtin_ptrs", vm, ids_data, ap_tracking)?;
+    let output_ptr = vm.get_relocatable(&pre_execution_builtin_ptrs)?;
+    let output_builtin = vm.get_output_builtin()?;
+    let output_runner_data = output_builtin.get_state();
+    output_builtin
+        .new_state(output_ptr)
+        .map_err(VirtualMachineError::RunnerError)?;
+    exec_scopes.insert_value("output_runner_data", output_runner_data);
+
+    exec_scopes.enter_scope(new_task_locals);
+    Ok(())
+}
+
+/*
+Implements hint:
+%{
+    from starkware.cairo.bootloaders.simple_bootloader.utils import write_return_builtins
+
+    # Fill the values of all builtin pointers after executing the task.
+    builtins = task.get_program().builtins
+    write_return_builtins(
+        memory=memory, return_builtins_addr=ids.return_builtin_ptrs.address_,
+        used_builtins=builtins, used_builtins_addr=ids.used_builtins_addr,
+        pre_execution_builtins_addr=ids.pre_execution_builtin_ptrs.address_, task=task)
+
+    vm_enter_scope({'n_selected_builtins': n_builtins})
+%}
+*/
+pub fn write_task_return_builtins(
+    vm: &mut VirtualMachine,
+    exec_scopes: &mut ExecutionScopes,
+    ids_data: &HashMap<String, HintReference>,
+    ap_tracking: &ApTracking,
+) -> Result<(), HintError> {
+    let return_builtins_addr =
+        get_relocatable_from_var_name("return_builtin_ptrs", vm, ids_data, ap_tracking)?;
+    let used_builtins_addr =
+        get_ptr_from_var_name("used_builtins_addr", vm, ids_data, ap_tracking)?;
+    let pre_execution_builtins_addr =
+        get_relocatable_from_var_name("pre_execution_builtin_ptrs", vm, ids_data, ap_tracking)?;
+    let task = exec_scopes.get_ref::<Task>("task")?;
+    write_return_builtins(
+        vm,
+        &return_builtins_addr,
+        &task.get_program().builtins,
+        &used_builtins_addr,
+        &pre_execution_builtins_addr,
+    )?;
+
+    let n_builtins = exec_scopes.get::<usize>("n_builtins")?;
+    exec_scopes.enter_scope(HashMap::from([(
+        "n_selected_builtins".to_string(),
+        any_box!(n_builtins),
+    )]));
+    Ok(())
+}
+
+/*
+Implements hint:
+%{
+    # A builtin should be selected iff its encoding appears in the selected encodings list
+    # and the list wasn't exhausted.
+    # Note that testing inclusion by a single comparison is possible since the lists are sorted.
+    ids.select_builtin = int(
+      n_selected_builtins > 0 and memory[ids.selected_encodings] == memory[ids.all_encodings])
+    if ids.select_builtin:
+      n_selected_builtins = n_selected_builtins - 1
+%}
+*/
+pub fn select_builtin(
+    vm: &mut VirtualMachine,
+    exec_scopes: &mut ExecutionScopes,
+    ids_data: &HashMap<String, HintReference>,
+    ap_tracking: &ApTracking,
+) -> Result<(), HintError> {
+    let n_selected_builtins = exec_scopes.get::<usize>("n_selected_builtins")?;
+    let select_builtin = n_selected_builtins > 0 && {
+        let selected_encodings =
+            get_ptr_from_var_name("selected_encodings", vm, ids_data, ap_tracking)?;
+        let all_encodings = get_ptr_from_var_name("all_encodings", vm, ids_data, ap_tracking)?;
+        vm.get_integer(&selected_encodings)? == vm.get_integer(&all_encodings)?
+    };
+    insert_value_from_var_name(
+        "select_builtin",
+        Felt::new(select_builtin as usize),
+        vm,
+        ids_data,
+        ap_tracking,
+    )?;
+    if select_builtin {
+        exec_scopes.insert_value("n_selected_builtins", n_selected_builtins - 1);
+    }
+    Ok(())
+}
+
+/*
+Implements hint:
+%{
+    from starkware.cairo.bootloaders.simple_bootloader.utils import get_task_fact_topology
+
+    # Add the fact topology of the current task to 'fact_topologies'.
+    output_start = ids.pre_execution_builtin_ptrs.output
+    output_end = ids.return_builtin_ptrs.output
+    fact_topologies.append(get_task_fact_topology(
+        output_size=output_end - output_start,
+        task=task,
+        output_builtin=output_builtin,
+        output_runner_data=output_runner_data,
+    ))
+%}
+*/
+pub fn append_fact_topology(
+    vm: &mut VirtualMachine,
+    exec_scopes: &mut ExecutionScopes,
+    ids_data: &HashMap<String, HintReference>,
+    ap_tracking: &ApTracking,
+) -> Result<(), HintError> {
+    let pre_execution_builtin_ptrs =
+        get_relocatable_from_var_name("pre_execution_builtin_ptrs", vm, ids_data, ap_tracking)?;
+    let return_builtin_ptrs =
+        get_relocatable_from_var_name("return_builtin_ptrs", vm, ids_data, ap_tracking)?;
+    let output_start = vm.get_relocatable(&pre_execution_builtin_ptrs)?;
+    let output_end = vm.get_relocatable(&return_builtin_ptrs)?;
+    let output_size = output_end.sub(&output_start)?;
+
+    let output_runner_data = exec_scopes.get::<OutputBuiltinState>("output_runner_data")?;
+    let fact_topology =
+        get_task_fact_topology(output_size, vm.get_output_builtin()?, output_runner_data)?;
+    exec_scopes
+        .get_mut_list_ref::<FactTopology>("fact_topologies")?
+        .push(fact_topology);
+    Ok(())
+}
+
+/*
+Implements hint:
+%{
+    # Dump fact topologies to a json file.
+    from starkware.cairo.bootloaders.simple_bootloader.utils import (
+        configure_fact_topologies,
+        write_to_fact_topologies_file,
+    )
+
+    # The task-related output is prefixed by a single word that contains the number of tasks.
+    tasks_output_start = output_builtin.base + 1
+
+    if not simple_bootloader_input.single_page:
+        # Configure the memory pages in the output builtin, based on fact_topologies.
+        configure_fact_topologies(
+            fact_topologies=fact_topologies, output_start=tasks_output_start,
+            output_builtin=output_builtin,
+        )
+
+    if simple_bootloader_input.fact_topologies_path is not None:
+        write_to_fact_topologies_file(
+            fact_topologies_path=simple_bootloader_input.fact_topologies_path,
+            fact_topologies=fact_topologies,
+        )
+%}
+*/
+pub fn configure_task_fact_topologies(
+    vm: &mut VirtualMachine,
+    exec_scopes: &mut ExecutionScopes,
+) -> Result<(), HintError> {
+    let simple_bootloader_input =
+        exec_scopes.get_ref::<SimpleBootloaderInput>("simple_bootloader_input")?;
+    let fact_topologies = exec_scopes.get_list_ref::<FactTopology>("fact_topologies")?;
+
+    let output_builtin = vm.get_output_builtin()?;
+    let tasks_output_start = Relocatable::from((output_builtin.base(), 1));
+    if !simple_bootloader_input.single_page {
+        configure_fact_topologies(fact_topologies, &tasks_output_start, output_builtin)?;
+    }
+
+    if let Some(path) = &simple_bootloader_input.fact_topologies_path {
+        let file = File::create(path)
+            .map_err(|error| HintError::FactTopologiesFileWrite(error.to_string()))?;
+        serde_json::to_writer_pretty(
+            BufWriter::new(file),
+            &serde_json::json!({ "fact_topologies": fact_topologies }),
+        )
+        .map_err(|error| HintError::FactTopologiesFileWrite(error.to_string()))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        hint_processor::{
+            builtin_hint_processor::{
+                builtin_hint_processor_definition::{BuiltinHintProcessor, HintProcessorData},
+                hint_code,
+            },
+            hint_processor_definition::HintProcessor,
+        },
+        types::{program::Program, relocatable::MaybeRelocatable},
+        utils::test_utils::*,
+        vm::{
+            errors::memory_errors::MemoryError, runners::builtin_runner::OutputBuiltinRunner,
+            vm_memory::memory::Memory,
+        },
+    };
+
+    fn run_program_task(main: usize) -> Task {
+        Task::RunProgram {
+            program: program!(main = Some(main),),
+            program_input: Some(serde_json::json!({ "main": main })),
+        }
+    }
+
+    #[test]
+    fn run_load_simple_bootloader_input_from_program_input() {
+        let mut vm = vm!();
+        let mut exec_scopes = ExecutionScopes::new();
+        exec_scopes.insert_value("program_input", serde_json::json!({"tasks": []}));
+
+        assert_eq!(
+            run_hint!(
+                vm,
+                HashMap::new(),
+                hint_code::SIMPLE_BOOTLOADER_LOAD_INPUT,
+                &mut exec_scopes
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            exec_scopes.get_ref::<SimpleBootloaderInput>("simple_bootloader_input"),
+            Ok(&SimpleBootloaderInput::new(vec![]))
+        );
+    }
+
+    #[test]
+    fn run_load_simple_bootloader_input_already_in_scope() {
+        let mut vm = vm!();
+        let input = SimpleBootloaderInput::new(vec![run_program_task(0)]);
+        let mut exec_scopes = ExecutionScopes::new();
+        exec_scopes.insert_value("simple_bootloader_input", input.clone());
+
+        assert_eq!(
+            run_hint!(
+                vm,
+                HashMap::new(),
+                hint_code::SIMPLE_BOOTLOADER_LOAD_INPUT,
+                &mut exec_scopes
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            exec_scopes.get_ref::<SimpleBootloaderInput>("simple_bootloader_input"),
+            Ok(&input)
+        );
+    }
+
+    #[test]
+    fn run_prepare_task_range_checks() {
+        let mut vm = vm!();
+        vm.run_context.fp = 3;
+        vm.memory = memory![((1, 0), (2, 0)), ((1, 1), (3, 4))];
+        add_segments!(vm, 2);
+        let ids_data = ids_data!["output_ptr", "range_check_ptr", "task_range_check_ptr"];
+        let mut exec_scopes = ExecutionScopes::new();
+        exec_scopes.insert_value(
+            "simple_bootloader_input",
+            SimpleBootloaderInput::new(vec![run_program_task(0), run_program_task(1)]),
+        );
+
+        assert_eq!(
+            run_hint!(
+                vm,
+                ids_data,
+                hint_code::SIMPLE_BOOTLOADER_PREPARE_TASK_RANGE_CHECKS,
+                &mut exec_scopes
+            ),
+            Ok(())
+        );
+        check_memory![vm.memory, ((2, 0), 2), ((1, 2), (3, 16))];
+        assert_eq!(
+            exec_scopes.get_list::<FactTopology>("fact_topologies"),
+            Ok(vec![])
+        );
+    }
+
+    #[test]
+    fn run_set_current_task() {
+        let mut vm = vm!();
+        vm.run_context.fp = 1;
+        vm.memory = memory![((1, 0), 1)];
+        let ids_data = ids_data!["n_tasks"];
+        let mut exec_scopes = ExecutionScopes::new();
+        exec_scopes.insert_value(
+            "simple_bootloader_input",
+            SimpleBootloaderInput::new(vec![run_program_task(0), run_program_task(1)]),
+        );
+
+        assert_eq!(
+            run_hint!(
+                vm,
+                ids_data,
+                hint_code::SIMPLE_BOOTLOADER_SET_CURRENT_TASK,
+                &mut exec_scopes
+            ),
+            Ok(())
+        );
+        assert_eq!(exec_scopes.get::<Task>("task"), Ok(run_program_task(1)));
+    }
+
+    #[test]
+    fn run_set_current_task_invalid_n_tasks() {
+        let mut vm = vm!();
+        vm.run_context.fp = 1;
+        vm.memory = memory![((1, 0), 3)];
+        let ids_data = ids_data!["n_tasks"];
+        let mut exec_scopes = ExecutionScopes::new();
+        exec_scopes.insert_value(
+            "simple_bootloader_input",
+            SimpleBootloaderInput::new(vec![run_program_task(0)]),
+        );
+
+        assert_eq!(
+            run_hint!(
+                vm,
+                ids_data,
+                hint_code::SIMPLE_BOOTLOADER_SET_CURRENT_TASK,
+                &mut exec_scopes
+            ),
+            Err(HintError::AssertionFailed("Invalid n_tasks: 3".to_string()))
+        );
+    }
+
+    #[test]
+    fn run_load_task_program() {
+        let mut vm = vm!();
+        vm.run_context.fp = 1;
+        vm.memory = memory![((1, 0), (2, 0))];
+        add_segments!(vm, 3);
+        let ids_data = ids_data!["program_header"];
+        let mut exec_scopes = ExecutionScopes::new();
+        exec_scopes.insert_value(
+            "task",
+            Task::RunProgram {
+                program: program!(data = vec![mayberelocatable!(9)], main = Some(0),),
+                program_input: None,
+            },
+        );
+        exec_scopes.insert_value("program_data_base", Relocatable::from((2, 0)));
+
+        assert_eq!(
+            run_hint!(
+                vm,
+                ids_data,
+                hint_code::EXECUTE_TASK_LOAD_PROGRAM,
+                &mut exec_scopes
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            exec_scopes.get::<Relocatable>("program_address"),
+            Ok((2, 4).into())
+        );
+        assert_eq!(vm.segments.segment_sizes.get(&2), Some(&5));
+        check_memory![vm.memory, ((2, 0), 4), ((2, 4), 9)];
+    }
+
+    #[test]
+    fn run_validate_task_program_hash_mismatch() {
+        let mut vm = vm!();
+        vm.run_context.fp = 1;
+        vm.memory = memory![((1, 0), (2, 0)), ((2, 1), 1)];
+        let ids_data = ids_data!["output_ptr"];
+        let mut exec_scopes = ExecutionScopes::new();
+        exec_scopes.insert_value("task", run_program_task(0));
+
+        assert_eq!(
+            run_hint!(
+                vm,
+                ids_data,
+                hint_code::EXECUTE_TASK_VALIDATE_HASH,
+                &mut exec_scopes
+            ),
+            Err(HintError::AssertionFailed(
+                "Computed hash does not match input.".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn run_call_task_and_append_fact_topology() {
+        let mut vm = vm!();
+        vm.builtin_runners = vec![("output".to_string(), OutputBuiltinRunner::new(true).into())];
+        vm.run_context.fp = 2;
+        // pre_execution_builtin_ptrs.output and return_builtin_ptrs.output
+        vm.memory = memory![((1, 0), (0, 3)), ((1, 1), (0, 5))];
+        add_segments!(vm, 3);
+        let ids_data = ids_data!["pre_execution_builtin_ptrs", "return_builtin_ptrs"];
+        let mut exec_scopes = ExecutionScopes::new();
+        exec_scopes.insert_value("task", run_program_task(0));
+        exec_scopes.insert_value("program_address", Relocatable::from((2, 4)));
+        exec_scopes.insert_value("fact_topologies", Vec::<FactTopology>::new());
+
+        assert_eq!(
+            run_hint!(
+                vm,
+                ids_data.clone(),
+                hint_code::EXECUTE_TASK_CALL_TASK,
+                &mut exec_scopes
+            ),
+            Ok(())
+        );
+        assert_eq!(vm.loaded_programs.len(), 1);
+        assert_eq!(exec_scopes.data.len(), 2);
+        assert_eq!(exec_scopes.get::<bool>("WITH_BOOTLOADER"), Ok(true));
+        assert_eq!(
+            exec_scopes.get_program_input(),
+            Ok(&serde_json::json!({"main": 0}))
+        );
+        assert_eq!(
+            exec_scopes.get_program_identifiers().unwrap().as_ref(),
+            &run_program_task(0).get_program().identifiers
+        );
+        assert_eq!(vm.get_output_builtin().unwrap().get_state().base_offset, 3);
+
+        exec_scopes.exit_scope().unwrap();
+        assert_eq!(
+            run_hint!(
+                vm,
+                ids_data,
+                hint_code::EXECUTE_TASK_APPEND_FACT_TOPOLOGIES,
+                &mut exec_scopes
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            exec_scopes.get_list::<FactTopology>("fact_topologies"),
+            Ok(vec![FactTopology {
+                tree_structure: vec![1, 0],
+                page_sizes: vec![2]
+            }])
+        );
+        assert_eq!(vm.get_output_builtin().unwrap().get_state().base_offset, 0);
+    }
+
+    #[test]
+    fn run_select_builtin() {
+        let mut vm = vm!();
+        vm.run_context.fp = 3;
+        vm.memory = memory![((1, 0), (2, 0)), ((1, 1), (2, 1)), ((2, 0), 7), ((2, 1), 7)];
+        add_segments!(vm, 1);
+        let ids_data = ids_data!["selected_encodings", "all_encodings", "select_builtin"];
+        let mut exec_scopes = ExecutionScopes::new();
+        exec_scopes.insert_value("n_selected_builtins", 1_usize);
+
+        assert_eq!(
+            run_hint!(
+                vm,
+                ids_data,
+                hint_code::SELECT_BUILTINS_SELECT_BUILTIN,
+                &mut exec_scopes
+            ),
+            Ok(())
+        );
+        check_memory![vm.memory, ((1, 2), 1)];
+        assert_eq!(exec_scopes.get::<usize>("n_selected_builtins"), Ok(0));
+    }
+
+    #[test]
+    fn run_select_builtin_list_exhausted() {
+        let mut vm = vm!();
+        vm.run_context.fp = 3;
+        vm.memory = memory![((1, 0), (2, 0)), ((1, 1), (2, 1)), ((2, 0), 7), ((2, 1), 7)];
+        let ids_data = ids_data!["selected_encodings", "all_encodings", "select_builtin"];
+        let mut exec_scopes = ExecutionScopes::new();
+        exec_scopes.insert_value("n_selected_builtins", 0_usize);
+
+        assert_eq!(
+            run_hint!(
+                vm,
+                ids_data,
+                hint_code::SELECT_BUILTINS_SELECT_BUILTIN,
+                &mut exec_scopes
+            ),
+            Ok(())
+        );
+        check_memory![vm.memory, ((1, 2), 0)];
+    }
+
+    #[test]
+    fn run_configure_fact_topologies() {
+        let mut vm = vm!();
+        vm.builtin_runners = vec![("output".to_string(), OutputBuiltinRunner::new(true).into())];
+        let mut exec_scopes = ExecutionScopes::new();
+        exec_scopes.insert_value(
+            "simple_bootloader_input",
+            SimpleBootloaderInput::new(vec![run_program_task(0)]),
+        );
+        exec_scopes.insert_value(
+            "fact_topologies",
+            vec![FactTopology {
+                tree_structure: vec![1, 0],
+                page_sizes: vec![2],
+            }],
+        );
+
+        assert_eq!(
+            run_hint!(
+                vm,
+                HashMap::new(),
+                hint_code::SIMPLE_BOOTLOADER_CONFIGURE_FACT_TOPOLOGIES,
+                &mut exec_scopes
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            vm.get_output_builtin().unwrap().get_pages(),
+            &HashMap::from([(
+                1,
+                crate::vm::runners::builtin_runner::PublicMemoryPage { start: 3, size: 2 }
+            )])
+        );
+    }
+}
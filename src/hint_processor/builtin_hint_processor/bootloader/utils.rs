@@ -0,0 +1,472 @@
+use super::objects::FactTopology;
+use crate::{
+    types::{
+        field_element::{FromFieldElement, ToFieldElement},
+        program::Program,
+        relocatable::{MaybeRelocatable, Relocatable},
+    },
+    vm::{
+        errors::{hint_errors::HintError, vm_errors::VirtualMachineError},
+        runners::builtin_runner::{OutputBuiltinRunner, OutputBuiltinState, PublicMemoryPage},
+        vm_core::VirtualMachine,
+    },
+};
+use felt::{Felt, FeltOps, NewFelt};
+use starknet_crypto::pedersen_hash;
+use std::collections::HashMap;
+
+/// The builtins supported by the simple bootloader, in the order of its `BuiltinData` struct.
+pub const ALL_BUILTINS: [&str; 6] = [
+    "output",
+    "pedersen",
+    "range_check",
+    "ecdsa",
+    "bitwise",
+    "ec_op",
+];
+
+/// The output builtin attribute that holds the fact topology tree structure of a task.
+pub const GPS_FACT_TOPOLOGY: &str = "gps_fact_topology";
+
+// Offsets of the fields of the ProgramHeader struct
+const PROGRAM_HEADER_DATA_LENGTH: usize = 0;
+const PROGRAM_HEADER_BOOTLOADER_VERSION: usize = 1;
+const PROGRAM_HEADER_PROGRAM_MAIN: usize = 2;
+const PROGRAM_HEADER_N_BUILTINS: usize = 3;
+const PROGRAM_HEADER_BUILTIN_LIST: usize = 4;
+
+fn encode_builtin(builtin: &str) -> Felt {
+    Felt::from_bytes_be(builtin.as_bytes())
+}
+
+/// Writes the program header and the program code into memory, starting at `program_header`.
+/// Returns the address of the program code and the size of the header and the code.
+pub fn load_program(
+    vm: &mut VirtualMachine,
+    program: &Program,
+    program_header: &Relocatable,
+) -> Result<(Relocatable, usize), HintError> {
+    let n_builtins = program.builtins.len();
+    // The program header ends with the list of builtins used by the program
+    let header_size = PROGRAM_HEADER_BUILTIN_LIST + n_builtins;
+    let program_main = program.main.ok_or(HintError::TaskProgramNoMain)?;
+
+    // data_length doesn't include the data_length field itself
+    vm.insert_value(
+        &(program_header + PROGRAM_HEADER_DATA_LENGTH),
+        Felt::new(header_size - 1 + program.data.len()),
+    )?;
+    vm.insert_value(
+        &(program_header + PROGRAM_HEADER_BOOTLOADER_VERSION),
+        Felt::new(0_usize),
+    )?;
+    vm.insert_value(
+        &(program_header + PROGRAM_HEADER_PROGRAM_MAIN),
+        Felt::new(program_main),
+    )?;
+    vm.insert_value(
+        &(program_header + PROGRAM_HEADER_N_BUILTINS),
+        Felt::new(n_builtins),
+    )?;
+    let builtin_list = program
+        .builtins
+        .iter()
+        .map(|builtin| MaybeRelocatable::from(encode_builtin(builtin)))
+        .collect();
+    vm.load_data(
+        &(program_header + PROGRAM_HEADER_BUILTIN_LIST).into(),
+        &builtin_list,
+    )
+    .map_err(VirtualMachineError::MemoryError)?;
+
+    let program_address = program_header + header_size;
+    vm.load_data(&program_address.into(), &program.data)
+        .map_err(VirtualMachineError::MemoryError)?;
+
+    Ok((program_address, header_size + program.data.len()))
+}
+
+/// Computes the hash of the program, as validated by the bootloader: the pedersen hash chain
+/// of the program header (without the data length) followed by the program code.
+pub fn compute_program_hash_chain(
+    program: &Program,
+    bootloader_version: usize,
+) -> Result<Felt, HintError> {
+    let program_main = program.main.ok_or(HintError::TaskProgramNoMain)?;
+    let mut data_chain = vec![
+        Felt::new(bootloader_version),
+        Felt::new(program_main),
+        Felt::new(program.builtins.len()),
+    ];
+    data_chain.extend(
+        program
+            .builtins
+            .iter()
+            .map(|builtin| encode_builtin(builtin)),
+    );
+    for value in program.data.iter() {
+        match value {
+            MaybeRelocatable::Int(value) => data_chain.push(value.clone()),
+            MaybeRelocatable::RelocatableValue(_) => return Err(HintError::ProgramHashChainFail),
+        }
+    }
+    data_chain.insert(0, Felt::new(data_chain.len()));
+    compute_hash_chain(&data_chain)
+}
+
+// Computes h(data[0], h(data[1], ... h(data[n-2], data[n-1])))
+fn compute_hash_chain(data: &[Felt]) -> Result<Felt, HintError> {
+    let to_field_element = |value: &Felt| {
+        value
+            .to_field_element()
+            .ok_or(HintError::ProgramHashChainFail)
+    };
+    let (last, rest) = data.split_last().ok_or(HintError::ProgramHashChainFail)?;
+    let mut hash = to_field_element(last)?;
+    for value in rest.iter().rev() {
+        hash = pedersen_hash(&to_field_element(value)?, &hash);
+    }
+    Ok(Felt::from_field_element(&hash))
+}
+
+/// Writes the builtin pointers after the execution of a task to `return_builtins_addr`.
+/// The builtins used by the task are read from `used_builtins_addr`, while the unused ones keep
+/// their value from before the execution.
+pub fn write_return_builtins(
+    vm: &mut VirtualMachine,
+    return_builtins_addr: &Relocatable,
+    used_builtins: &[String],
+    used_builtins_addr: &Relocatable,
+    pre_execution_builtins_addr: &Relocatable,
+) -> Result<(), HintError> {
+    let mut used_builtin_offset = 0;
+    for (index, builtin) in ALL_BUILTINS.iter().enumerate() {
+        let value_addr = if used_builtins.iter().any(|used| used == builtin) {
+            used_builtin_offset += 1;
+            used_builtins_addr + (used_builtin_offset - 1)
+        } else {
+            // The builtin is unused, hence its value is the same as before calling the program
+            pre_execution_builtins_addr + index
+        };
+        let value = vm
+            .get_maybe(&value_addr)
+            .map_err(VirtualMachineError::MemoryError)?
+            .ok_or(VirtualMachineError::MemoryGet(value_addr.into()))?;
+        vm.insert_value(&(return_builtins_addr + index), value)?;
+    }
+    Ok(())
+}
+
+/// Computes the fact topology of a task from the pages and attributes it added to the output
+/// builtin, and restores the output builtin to its state before the task.
+pub fn get_task_fact_topology(
+    output_size: usize,
+    output_builtin: &mut OutputBuiltinRunner,
+    output_runner_data: OutputBuiltinState,
+) -> Result<FactTopology, HintError> {
+    let tree_structure = match output_builtin.get_attributes().get(GPS_FACT_TOPOLOGY) {
+        Some(tree_structure) => tree_structure.clone(),
+        // Otherwise, the task is expected to use the entire output as a single page
+        None if output_builtin.get_pages().is_empty() => vec![1, 0],
+        None => {
+            return Err(HintError::AssertionFailed(format!(
+                "Tasks that add output pages must set the {GPS_FACT_TOPOLOGY} attribute"
+            )))
+        }
+    };
+    let fact_topology = FactTopology {
+        tree_structure,
+        page_sizes: get_page_sizes(output_size, output_builtin.get_pages())?,
+    };
+    output_builtin.set_state(output_runner_data);
+    Ok(fact_topology)
+}
+
+/// Returns the sizes of the output pages. Page 0 covers the output that comes before page 1.
+pub fn get_page_sizes(
+    output_size: usize,
+    pages: &HashMap<usize, PublicMemoryPage>,
+) -> Result<Vec<usize>, HintError> {
+    let mut sorted_pages: Vec<(&usize, &PublicMemoryPage)> = pages.iter().collect();
+    sorted_pages.sort_by_key(|(page_id, _)| **page_id);
+
+    // The pages must be adjacent to each other, and the first page id is expected to be 1
+    let mut page0_size = output_size;
+    let mut expected_page_start = None;
+    for (expected_page_id, (page_id, page)) in (1..).zip(sorted_pages.iter()) {
+        if **page_id != expected_page_id {
+            return Err(HintError::AssertionFailed(format!(
+                "Expected page id {expected_page_id}, found {page_id}."
+            )));
+        }
+        if expected_page_id == 1 {
+            if page.start > output_size {
+                return Err(HintError::AssertionFailed(format!(
+                    "Invalid page start {}.",
+                    page.start
+                )));
+            }
+            page0_size = page.start;
+        } else if Some(page.start) != expected_page_start {
+            return Err(HintError::AssertionFailed(format!(
+                "Expected page start {:?}, found {}.",
+                expected_page_start, page.start
+            )));
+        }
+        if page.size == 0 || page.size > output_size {
+            return Err(HintError::AssertionFailed(format!(
+                "Invalid page size {}.",
+                page.size
+            )));
+        }
+        expected_page_start = Some(page.start + page.size);
+    }
+    if expected_page_start.is_some() && expected_page_start != Some(output_size) {
+        return Err(HintError::AssertionFailed(
+            "Pages must cover the entire program output.".to_string(),
+        ));
+    }
+
+    let mut page_sizes = vec![page0_size];
+    page_sizes.extend(sorted_pages.iter().map(|(_, page)| page.size));
+    Ok(page_sizes)
+}
+
+/// Adds the pages of each task to the output builtin, following their fact topologies.
+/// The output of each task is preceded by the two words written by the bootloader.
+pub fn configure_fact_topologies(
+    fact_topologies: &[FactTopology],
+    output_start: &Relocatable,
+    output_builtin: &mut OutputBuiltinRunner,
+) -> Result<(), HintError> {
+    // Page 0 is reserved for the bootloader program and arguments
+    let mut page_id = 1;
+    let mut page_start = *output_start;
+    for fact_topology in fact_topologies {
+        page_start = page_start + 2;
+        for page_size in fact_topology.page_sizes.iter() {
+            output_builtin
+                .add_page(page_id, page_start, *page_size)
+                .map_err(VirtualMachineError::RunnerError)?;
+            page_id += 1;
+            page_start = page_start + *page_size;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        types::program::Program,
+        utils::test_utils::*,
+        vm::{
+            errors::memory_errors::MemoryError, vm_core::VirtualMachine, vm_memory::memory::Memory,
+        },
+    };
+    use starknet_crypto::FieldElement;
+
+    #[test]
+    fn load_program_writes_header_and_code() {
+        let mut vm = vm!();
+        vm.memory = Memory::new();
+        add_segments!(vm, 1);
+        let program = program!(
+            builtins = vec!["output".to_string()],
+            data = vec![mayberelocatable!(5), mayberelocatable!(6)],
+            main = Some(1),
+        );
+
+        assert_eq!(
+            load_program(&mut vm, &program, &(0, 0).into()),
+            Ok(((0, 5).into(), 7))
+        );
+        check_memory![
+            vm.memory,
+            ((0, 0), 6),
+            ((0, 1), 0),
+            ((0, 2), 1),
+            ((0, 3), 1),
+            ((0, 4), 0x6f7574707574),
+            ((0, 5), 5),
+            ((0, 6), 6)
+        ];
+    }
+
+    #[test]
+    fn load_program_no_main() {
+        let mut vm = vm!();
+        add_segments!(vm, 1);
+
+        assert_eq!(
+            load_program(&mut vm, &program!(), &(0, 0).into()),
+            Err(HintError::TaskProgramNoMain)
+        );
+    }
+
+    #[test]
+    fn compute_program_hash_chain_matches_pedersen_chain() {
+        let program = program!(data = vec![mayberelocatable!(7)], main = Some(0),);
+        // [len(data_chain), bootloader_version, main, n_builtins, *data]
+        let expected = pedersen_hash(
+            &FieldElement::from(4_u64),
+            &pedersen_hash(
+                &FieldElement::from(0_u64),
+                &pedersen_hash(
+                    &FieldElement::from(0_u64),
+                    &pedersen_hash(&FieldElement::from(0_u64), &FieldElement::from(7_u64)),
+                ),
+            ),
+        );
+
+        assert_eq!(
+            compute_program_hash_chain(&program, 0),
+            Ok(Felt::from_bytes_be(&expected.to_bytes_be()))
+        );
+    }
+
+    #[test]
+    fn write_return_builtins_used_and_unused() {
+        let mut vm = vm!();
+        vm.memory = memory![
+            ((1, 0), (2, 3)),
+            ((1, 1), (3, 0)),
+            ((1, 2), (4, 0)),
+            ((1, 3), (5, 0)),
+            ((1, 4), (6, 0)),
+            ((1, 5), (7, 0)),
+            ((1, 6), (2, 5)),
+            ((1, 7), (4, 4))
+        ];
+        add_segments!(vm, 8);
+
+        write_return_builtins(
+            &mut vm,
+            &(0, 0).into(),
+            &["output".to_string(), "range_check".to_string()],
+            &(1, 6).into(),
+            &(1, 0).into(),
+        )
+        .unwrap();
+        check_memory![
+            vm.memory,
+            ((0, 0), (2, 5)),
+            ((0, 1), (3, 0)),
+            ((0, 2), (4, 4)),
+            ((0, 3), (5, 0)),
+            ((0, 4), (6, 0)),
+            ((0, 5), (7, 0))
+        ];
+    }
+
+    #[test]
+    fn get_page_sizes_no_pages() {
+        assert_eq!(get_page_sizes(5, &HashMap::new()), Ok(vec![5]));
+    }
+
+    #[test]
+    fn get_page_sizes_with_pages() {
+        let pages = HashMap::from([
+            (1, PublicMemoryPage { start: 1, size: 2 }),
+            (2, PublicMemoryPage { start: 3, size: 2 }),
+        ]);
+        assert_eq!(get_page_sizes(5, &pages), Ok(vec![1, 2, 2]));
+    }
+
+    #[test]
+    fn get_page_sizes_not_covering_output() {
+        let pages = HashMap::from([(1, PublicMemoryPage { start: 1, size: 2 })]);
+        assert_eq!(
+            get_page_sizes(5, &pages),
+            Err(HintError::AssertionFailed(
+                "Pages must cover the entire program output.".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn get_page_sizes_wrong_page_id() {
+        let pages = HashMap::from([(2, PublicMemoryPage { start: 0, size: 5 })]);
+        assert_eq!(
+            get_page_sizes(5, &pages),
+            Err(HintError::AssertionFailed(
+                "Expected page id 1, found 2.".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn get_task_fact_topology_restores_state() {
+        let mut output_builtin = OutputBuiltinRunner::new(true);
+        output_builtin.add_page(1, (0, 0).into(), 2).unwrap();
+        let state = output_builtin.get_state();
+        output_builtin.new_state((0, 4).into()).unwrap();
+
+        assert_eq!(
+            get_task_fact_topology(3, &mut output_builtin, state.clone()),
+            Ok(FactTopology {
+                tree_structure: vec![1, 0],
+                page_sizes: vec![3]
+            })
+        );
+        assert_eq!(output_builtin.get_state(), state);
+    }
+
+    #[test]
+    fn get_task_fact_topology_from_attribute() {
+        let mut output_builtin = OutputBuiltinRunner::new(true);
+        let state = output_builtin.get_state();
+        output_builtin.new_state((0, 2).into()).unwrap();
+        output_builtin.add_page(1, (0, 3).into(), 2).unwrap();
+        output_builtin.add_attribute(GPS_FACT_TOPOLOGY.to_string(), vec![2, 1, 0, 2]);
+
+        assert_eq!(
+            get_task_fact_topology(3, &mut output_builtin, state),
+            Ok(FactTopology {
+                tree_structure: vec![2, 1, 0, 2],
+                page_sizes: vec![1, 2]
+            })
+        );
+        assert!(output_builtin.get_attributes().is_empty());
+    }
+
+    #[test]
+    fn get_task_fact_topology_pages_without_attribute() {
+        let mut output_builtin = OutputBuiltinRunner::new(true);
+        let state = output_builtin.get_state();
+        output_builtin.add_page(1, (0, 1).into(), 2).unwrap();
+
+        assert_eq!(
+            get_task_fact_topology(3, &mut output_builtin, state),
+            Err(HintError::AssertionFailed(
+                "Tasks that add output pages must set the gps_fact_topology attribute".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn configure_fact_topologies_adds_pages() {
+        let mut output_builtin = OutputBuiltinRunner::new(true);
+        let fact_topologies = vec![
+            FactTopology {
+                tree_structure: vec![1, 0],
+                page_sizes: vec![3],
+            },
+            FactTopology {
+                tree_structure: vec![2, 1, 0, 2],
+                page_sizes: vec![1, 2],
+            },
+        ];
+
+        configure_fact_topologies(&fact_topologies, &(0, 1).into(), &mut output_builtin).unwrap();
+        assert_eq!(
+            output_builtin.get_pages(),
+            &HashMap::from([
+                (1, PublicMemoryPage { start: 3, size: 3 }),
+                (2, PublicMemoryPage { start: 8, size: 1 }),
+                (3, PublicMemoryPage { start: 9, size: 2 }),
+            ])
+        );
+    }
+}
@@ -1,9 +1,16 @@
 use crate::{
+    any_box,
     hint_processor::{
         builtin_hint_processor::{
             blake2s_utils::{
                 blake2s_add_uint256, blake2s_add_uint256_bigend, compute_blake2s, finalize_blake2s,
             },
+            bootloader::simple_bootloader_hints::{
+                allocate_program_data_segment, append_fact_topology, assert_program_address,
+                call_task, configure_task_fact_topologies, load_simple_bootloader_input,
+                load_task_program, prepare_task_range_checks, select_builtin, set_current_task,
+                validate_task_program_hash, write_task_return_builtins,
+            },
             cairo_keccak::keccak_hints::{
                 block_permutation, cairo_keccak_finalize, compare_bytes_in_word_nondet,
                 compare_keccak_full_rate_in_bytes_nondet, keccak_write_args,
@@ -20,9 +27,11 @@ use crate::{
                 add_segment, enter_scope, exit_scope, memcpy_continue_copying, memcpy_enter_scope,
             },
             memset_utils::{memset_continue_loop, memset_enter_scope},
+            output_builtin_hints::set_tree_structure,
             pow_utils::pow,
             secp::{
                 bigint_utils::{bigint_to_uint256, nondet_bigint3},
+                curve::{get_secp_curve, import_secp_curve, SecpCurve},
                 ec_utils::{
                     compute_doubling_slope, compute_slope, ec_double_assign_new_x,
                     ec_double_assign_new_y, ec_mul_inner, ec_negate, fast_ec_add_assign_new_x,
@@ -52,11 +61,14 @@ use crate::{
                 verify_multiplicity_body, verify_usort,
             },
         },
-        hint_processor_definition::{HintProcessor, HintReference},
+        hint_processor_definition::{get_ids_data, HintProcessor, HintReference},
     },
     serde::deserialize_program::ApTracking,
     types::exec_scope::ExecutionScopes,
-    vm::{errors::hint_errors::HintError, vm_core::VirtualMachine},
+    vm::{
+        errors::{hint_errors::HintError, vm_errors::VirtualMachineError},
+        vm_core::VirtualMachine,
+    },
 };
 use felt::Felt;
 use std::{any::Any, collections::HashMap, rc::Rc};
@@ -65,16 +77,40 @@ pub struct HintProcessorData {
     pub code: String,
     pub ap_tracking: ApTracking,
     pub ids_data: HashMap<String, HintReference>,
+    // The function implementing the hint, if it was resolved by compile_hint
+    hint_impl: Option<HintImpl>,
 }
 
 impl HintProcessorData {
-    pub fn new_default(code: String, ids_data: HashMap<String, HintReference>) -> Self {
+    /// Builds hint data whose hint is resolved by its code each time it's executed by the
+    /// `BuiltinHintProcessor`, which fails with `HintError::UnknownHint` if the code isn't known.
+    /// Hint data built by `BuiltinHintProcessor::compile_hint` is resolved once instead.
+    pub fn new(
+        code: String,
+        ap_tracking: ApTracking,
+        ids_data: HashMap<String, HintReference>,
+    ) -> Self {
         HintProcessorData {
             code,
-            ap_tracking: ApTracking::default(),
+            ap_tracking,
             ids_data,
+            hint_impl: None,
         }
     }
+
+    pub fn new_default(code: String, ids_data: HashMap<String, HintReference>) -> Self {
+        Self::new(code, ApTracking::default(), ids_data)
+    }
+
+    pub(crate) fn with_hint_impl(mut self, hint_impl: HintImpl) -> Self {
+        self.hint_impl = Some(hint_impl);
+        self
+    }
+
+    /// Returns the function implementing the hint, if it was resolved by `compile_hint`.
+    pub fn hint_impl(&self) -> Option<&HintImpl> {
+        self.hint_impl.as_ref()
+    }
 }
 
 #[allow(clippy::type_complexity)]
@@ -90,6 +126,43 @@ pub struct HintFunc(
             + Sync,
     >,
 );
+
+/// A function implementing one of the hints of the `BuiltinHintProcessor`.
+pub type BuiltinHintFunc = fn(
+    &mut VirtualMachine,
+    &mut ExecutionScopes,
+    &HintProcessorData,
+    &HashMap<String, Felt>,
+) -> Result<(), HintError>;
+
+/// The implementation a hint code was resolved to.
+#[derive(Clone)]
+pub enum HintImpl {
+    Builtin(BuiltinHintFunc),
+    Extra(Rc<HintFunc>),
+}
+
+impl HintImpl {
+    fn execute(
+        &self,
+        vm: &mut VirtualMachine,
+        exec_scopes: &mut ExecutionScopes,
+        hint_data: &HintProcessorData,
+        constants: &HashMap<String, Felt>,
+    ) -> Result<(), HintError> {
+        match self {
+            HintImpl::Builtin(hint_func) => hint_func(vm, exec_scopes, hint_data, constants),
+            HintImpl::Extra(hint_func) => hint_func.0(
+                vm,
+                exec_scopes,
+                &hint_data.ids_data,
+                &hint_data.ap_tracking,
+                constants,
+            ),
+        }
+    }
+}
+
 pub struct BuiltinHintProcessor {
     pub extra_hints: HashMap<String, Rc<HintFunc>>,
 }
@@ -107,6 +180,14 @@ impl BuiltinHintProcessor {
     pub fn add_hint(&mut self, hint_code: String, hint_func: Rc<HintFunc>) {
         self.extra_hints.insert(hint_code, hint_func);
     }
+
+    // Extra hints take precedence over the builtin ones
+    fn get_hint_impl(&self, code: &str) -> Option<HintImpl> {
+        match self.extra_hints.get(code) {
+            Some(hint_func) => Some(HintImpl::Extra(Rc::clone(hint_func))),
+            None => get_builtin_hint_func(code).map(HintImpl::Builtin),
+        }
+    }
 }
 
 impl HintProcessor for BuiltinHintProcessor {
@@ -121,318 +202,540 @@ impl HintProcessor for BuiltinHintProcessor {
             .downcast_ref::<HintProcessorData>()
             .ok_or(HintError::WrongHintData)?;
 
-        if let Some(hint_func) = self.extra_hints.get(&hint_data.code) {
-            return hint_func.0(
+        match hint_data.hint_impl {
+            Some(ref hint_impl) => hint_impl.execute(vm, exec_scopes, hint_data, constants),
+            // Hint data that wasn't built by compile_hint is resolved on each execution
+            None => self
+                .get_hint_impl(&hint_data.code)
+                .ok_or_else(|| HintError::UnknownHint(hint_data.code.clone()))?
+                .execute(vm, exec_scopes, hint_data, constants),
+        }
+    }
+
+    // Resolves the hint code once, so that unknown hints are reported when the program is loaded
+    fn compile_hint(
+        &self,
+        hint_code: &str,
+        ap_tracking_data: &ApTracking,
+        reference_ids: &HashMap<String, usize>,
+        references: &HashMap<usize, HintReference>,
+    ) -> Result<Box<dyn Any>, VirtualMachineError> {
+        let hint_impl = self
+            .get_hint_impl(hint_code)
+            .ok_or_else(|| VirtualMachineError::UnknownHint(hint_code.to_string()))?;
+        Ok(any_box!(HintProcessorData::new(
+            hint_code.to_string(),
+            ap_tracking_data.clone(),
+            get_ids_data(reference_ids, references)?,
+        )
+        .with_hint_impl(hint_impl)))
+    }
+}
+
+/// Returns the function implementing the builtin hint whose code is `code`, or None if
+/// there is no such hint.
+pub fn get_builtin_hint_func(code: &str) -> Option<BuiltinHintFunc> {
+    let hint_func: BuiltinHintFunc = match code {
+        hint_code::ADD_SEGMENT => |vm, _, _, _| add_segment(vm),
+        hint_code::IS_NN => {
+            |vm, _, hint_data, _| is_nn(vm, &hint_data.ids_data, &hint_data.ap_tracking)
+        }
+        hint_code::IS_NN_OUT_OF_RANGE => |vm, _, hint_data, _| {
+            is_nn_out_of_range(vm, &hint_data.ids_data, &hint_data.ap_tracking)
+        },
+        hint_code::ASSERT_LE_FELT => |vm, exec_scopes, hint_data, constants| {
+            assert_le_felt(
                 vm,
                 exec_scopes,
                 &hint_data.ids_data,
                 &hint_data.ap_tracking,
                 constants,
-            );
+            )
+        },
+        hint_code::ASSERT_LE_FELT_EXCLUDED_2 => {
+            |_, exec_scopes, _, _| assert_le_felt_excluded_2(exec_scopes)
         }
-
-        match &*hint_data.code {
-            hint_code::ADD_SEGMENT => add_segment(vm),
-            hint_code::IS_NN => is_nn(vm, &hint_data.ids_data, &hint_data.ap_tracking),
-            hint_code::IS_NN_OUT_OF_RANGE => {
-                is_nn_out_of_range(vm, &hint_data.ids_data, &hint_data.ap_tracking)
-            }
-            hint_code::ASSERT_LE_FELT => assert_le_felt(
+        hint_code::ASSERT_LE_FELT_EXCLUDED_1 => {
+            |vm, exec_scopes, _, _| assert_le_felt_excluded_1(vm, exec_scopes)
+        }
+        hint_code::ASSERT_LE_FELT_EXCLUDED_0 => {
+            |vm, exec_scopes, _, _| assert_le_felt_excluded_0(vm, exec_scopes)
+        }
+        hint_code::IS_LE_FELT => {
+            |vm, _, hint_data, _| is_le_felt(vm, &hint_data.ids_data, &hint_data.ap_tracking)
+        }
+        hint_code::ASSERT_250_BITS => {
+            |vm, _, hint_data, _| assert_250_bit(vm, &hint_data.ids_data, &hint_data.ap_tracking)
+        }
+        hint_code::IS_POSITIVE => {
+            |vm, _, hint_data, _| is_positive(vm, &hint_data.ids_data, &hint_data.ap_tracking)
+        }
+        hint_code::SPLIT_INT_ASSERT_RANGE => |vm, _, hint_data, _| {
+            split_int_assert_range(vm, &hint_data.ids_data, &hint_data.ap_tracking)
+        },
+        hint_code::SPLIT_INT => {
+            |vm, _, hint_data, _| split_int(vm, &hint_data.ids_data, &hint_data.ap_tracking)
+        }
+        hint_code::ASSERT_NOT_EQUAL => {
+            |vm, _, hint_data, _| assert_not_equal(vm, &hint_data.ids_data, &hint_data.ap_tracking)
+        }
+        hint_code::ASSERT_NN => {
+            |vm, _, hint_data, _| assert_nn(vm, &hint_data.ids_data, &hint_data.ap_tracking)
+        }
+        hint_code::SQRT => {
+            |vm, _, hint_data, _| sqrt(vm, &hint_data.ids_data, &hint_data.ap_tracking)
+        }
+        hint_code::ASSERT_NOT_ZERO => {
+            |vm, _, hint_data, _| assert_not_zero(vm, &hint_data.ids_data, &hint_data.ap_tracking)
+        }
+        hint_code::VM_EXIT_SCOPE => |_, exec_scopes, _, _| exit_scope(exec_scopes),
+        hint_code::MEMCPY_ENTER_SCOPE => |vm, exec_scopes, hint_data, _| {
+            memcpy_enter_scope(vm, exec_scopes, &hint_data.ids_data, &hint_data.ap_tracking)
+        },
+        hint_code::MEMSET_ENTER_SCOPE => |vm, exec_scopes, hint_data, _| {
+            memset_enter_scope(vm, exec_scopes, &hint_data.ids_data, &hint_data.ap_tracking)
+        },
+        hint_code::MEMCPY_CONTINUE_COPYING => |vm, exec_scopes, hint_data, _| {
+            memcpy_continue_copying(vm, exec_scopes, &hint_data.ids_data, &hint_data.ap_tracking)
+        },
+        hint_code::MEMSET_CONTINUE_LOOP => |vm, exec_scopes, hint_data, _| {
+            memset_continue_loop(vm, exec_scopes, &hint_data.ids_data, &hint_data.ap_tracking)
+        },
+        hint_code::SPLIT_FELT => {
+            |vm, _, hint_data, _| split_felt(vm, &hint_data.ids_data, &hint_data.ap_tracking)
+        }
+        hint_code::UNSIGNED_DIV_REM => {
+            |vm, _, hint_data, _| unsigned_div_rem(vm, &hint_data.ids_data, &hint_data.ap_tracking)
+        }
+        hint_code::SIGNED_DIV_REM => {
+            |vm, _, hint_data, _| signed_div_rem(vm, &hint_data.ids_data, &hint_data.ap_tracking)
+        }
+        hint_code::ASSERT_LT_FELT => {
+            |vm, _, hint_data, _| assert_lt_felt(vm, &hint_data.ids_data, &hint_data.ap_tracking)
+        }
+        hint_code::FIND_ELEMENT => |vm, exec_scopes, hint_data, _| {
+            find_element(vm, exec_scopes, &hint_data.ids_data, &hint_data.ap_tracking)
+        },
+        hint_code::SEARCH_SORTED_LOWER => |vm, exec_scopes, hint_data, _| {
+            search_sorted_lower(vm, exec_scopes, &hint_data.ids_data, &hint_data.ap_tracking)
+        },
+        hint_code::POW => {
+            |vm, _, hint_data, _| pow(vm, &hint_data.ids_data, &hint_data.ap_tracking)
+        }
+        hint_code::SET_ADD => {
+            |vm, _, hint_data, _| set_add(vm, &hint_data.ids_data, &hint_data.ap_tracking)
+        }
+        hint_code::DICT_NEW => |vm, exec_scopes, _, _| dict_new(vm, exec_scopes),
+        hint_code::DICT_READ => |vm, exec_scopes, hint_data, _| {
+            dict_read(vm, exec_scopes, &hint_data.ids_data, &hint_data.ap_tracking)
+        },
+        hint_code::DICT_WRITE => |vm, exec_scopes, hint_data, _| {
+            dict_write(vm, exec_scopes, &hint_data.ids_data, &hint_data.ap_tracking)
+        },
+        hint_code::DEFAULT_DICT_NEW => |vm, exec_scopes, hint_data, _| {
+            default_dict_new(vm, exec_scopes, &hint_data.ids_data, &hint_data.ap_tracking)
+        },
+        hint_code::SQUASH_DICT_INNER_FIRST_ITERATION => |vm, exec_scopes, hint_data, _| {
+            squash_dict_inner_first_iteration(
+                vm,
+                exec_scopes,
+                &hint_data.ids_data,
+                &hint_data.ap_tracking,
+            )
+        },
+        hint_code::USORT_ENTER_SCOPE => |_, exec_scopes, _, _| usort_enter_scope(exec_scopes),
+        hint_code::USORT_BODY => |vm, exec_scopes, hint_data, _| {
+            usort_body(vm, exec_scopes, &hint_data.ids_data, &hint_data.ap_tracking)
+        },
+        hint_code::USORT_VERIFY => |vm, exec_scopes, hint_data, _| {
+            verify_usort(vm, exec_scopes, &hint_data.ids_data, &hint_data.ap_tracking)
+        },
+        hint_code::USORT_VERIFY_MULTIPLICITY_ASSERT => {
+            |_, exec_scopes, _, _| verify_multiplicity_assert(exec_scopes)
+        }
+        hint_code::USORT_VERIFY_MULTIPLICITY_BODY => |vm, exec_scopes, hint_data, _| {
+            verify_multiplicity_body(vm, exec_scopes, &hint_data.ids_data, &hint_data.ap_tracking)
+        },
+        hint_code::BLAKE2S_COMPUTE => {
+            |vm, _, hint_data, _| compute_blake2s(vm, &hint_data.ids_data, &hint_data.ap_tracking)
+        }
+        hint_code::VERIFY_ZERO => |vm, _, hint_data, _| {
+            verify_zero(
+                vm,
+                &hint_data.ids_data,
+                &hint_data.ap_tracking,
+                SecpCurve::secp256k1(),
+            )
+        },
+        hint_code::VERIFY_ZERO_V2 => |vm, exec_scopes, hint_data, _| {
+            verify_zero(
+                vm,
+                &hint_data.ids_data,
+                &hint_data.ap_tracking,
+                get_secp_curve(exec_scopes)?,
+            )
+        },
+        hint_code::IMPORT_SECP256R1_P
+        | hint_code::IMPORT_SECP256R1_N
+        | hint_code::IMPORT_SECP256R1_ALPHA => |_, exec_scopes, _, _| {
+            import_secp_curve(exec_scopes, SecpCurve::secp256r1());
+            Ok(())
+        },
+        hint_code::NONDET_BIGINT3 => |vm, exec_scopes, hint_data, constants| {
+            nondet_bigint3(
                 vm,
                 exec_scopes,
                 &hint_data.ids_data,
                 &hint_data.ap_tracking,
                 constants,
-            ),
-            hint_code::ASSERT_LE_FELT_EXCLUDED_2 => assert_le_felt_excluded_2(exec_scopes),
-            hint_code::ASSERT_LE_FELT_EXCLUDED_1 => assert_le_felt_excluded_1(vm, exec_scopes),
-            hint_code::ASSERT_LE_FELT_EXCLUDED_0 => assert_le_felt_excluded_0(vm, exec_scopes),
-            hint_code::IS_LE_FELT => is_le_felt(vm, &hint_data.ids_data, &hint_data.ap_tracking),
-            hint_code::ASSERT_250_BITS => {
-                assert_250_bit(vm, &hint_data.ids_data, &hint_data.ap_tracking)
-            }
-            hint_code::IS_POSITIVE => is_positive(vm, &hint_data.ids_data, &hint_data.ap_tracking),
-            hint_code::SPLIT_INT_ASSERT_RANGE => {
-                split_int_assert_range(vm, &hint_data.ids_data, &hint_data.ap_tracking)
-            }
-            hint_code::SPLIT_INT => split_int(vm, &hint_data.ids_data, &hint_data.ap_tracking),
-            hint_code::ASSERT_NOT_EQUAL => {
-                assert_not_equal(vm, &hint_data.ids_data, &hint_data.ap_tracking)
-            }
-            hint_code::ASSERT_NN => assert_nn(vm, &hint_data.ids_data, &hint_data.ap_tracking),
-            hint_code::SQRT => sqrt(vm, &hint_data.ids_data, &hint_data.ap_tracking),
-            hint_code::ASSERT_NOT_ZERO => {
-                assert_not_zero(vm, &hint_data.ids_data, &hint_data.ap_tracking)
-            }
-            hint_code::VM_EXIT_SCOPE => exit_scope(exec_scopes),
-            hint_code::MEMCPY_ENTER_SCOPE => {
-                memcpy_enter_scope(vm, exec_scopes, &hint_data.ids_data, &hint_data.ap_tracking)
-            }
-            hint_code::MEMSET_ENTER_SCOPE => {
-                memset_enter_scope(vm, exec_scopes, &hint_data.ids_data, &hint_data.ap_tracking)
-            }
-            hint_code::MEMCPY_CONTINUE_COPYING => memcpy_continue_copying(
+            )
+        },
+        hint_code::REDUCE => |vm, exec_scopes, hint_data, _| {
+            reduce(
                 vm,
                 exec_scopes,
                 &hint_data.ids_data,
                 &hint_data.ap_tracking,
-            ),
-            hint_code::MEMSET_CONTINUE_LOOP => {
-                memset_continue_loop(vm, exec_scopes, &hint_data.ids_data, &hint_data.ap_tracking)
-            }
-            hint_code::SPLIT_FELT => split_felt(vm, &hint_data.ids_data, &hint_data.ap_tracking),
-            hint_code::UNSIGNED_DIV_REM => {
-                unsigned_div_rem(vm, &hint_data.ids_data, &hint_data.ap_tracking)
-            }
-            hint_code::SIGNED_DIV_REM => {
-                signed_div_rem(vm, &hint_data.ids_data, &hint_data.ap_tracking)
-            }
-            hint_code::ASSERT_LT_FELT => {
-                assert_lt_felt(vm, &hint_data.ids_data, &hint_data.ap_tracking)
-            }
-            hint_code::FIND_ELEMENT => {
-                find_element(vm, exec_scopes, &hint_data.ids_data, &hint_data.ap_tracking)
-            }
-            hint_code::SEARCH_SORTED_LOWER => {
-                search_sorted_lower(vm, exec_scopes, &hint_data.ids_data, &hint_data.ap_tracking)
-            }
-            hint_code::POW => pow(vm, &hint_data.ids_data, &hint_data.ap_tracking),
-            hint_code::SET_ADD => set_add(vm, &hint_data.ids_data, &hint_data.ap_tracking),
-            hint_code::DICT_NEW => dict_new(vm, exec_scopes),
-            hint_code::DICT_READ => {
-                dict_read(vm, exec_scopes, &hint_data.ids_data, &hint_data.ap_tracking)
-            }
-            hint_code::DICT_WRITE => {
-                dict_write(vm, exec_scopes, &hint_data.ids_data, &hint_data.ap_tracking)
-            }
-            hint_code::DEFAULT_DICT_NEW => {
-                default_dict_new(vm, exec_scopes, &hint_data.ids_data, &hint_data.ap_tracking)
-            }
-            hint_code::SQUASH_DICT_INNER_FIRST_ITERATION => squash_dict_inner_first_iteration(
+                SecpCurve::secp256k1(),
+            )
+        },
+        hint_code::REDUCE_V2 => |vm, exec_scopes, hint_data, _| {
+            reduce(
                 vm,
                 exec_scopes,
                 &hint_data.ids_data,
                 &hint_data.ap_tracking,
-            ),
-            hint_code::USORT_ENTER_SCOPE => usort_enter_scope(exec_scopes),
-            hint_code::USORT_BODY => {
-                usort_body(vm, exec_scopes, &hint_data.ids_data, &hint_data.ap_tracking)
-            }
-            hint_code::USORT_VERIFY => {
-                verify_usort(vm, exec_scopes, &hint_data.ids_data, &hint_data.ap_tracking)
-            }
-            hint_code::USORT_VERIFY_MULTIPLICITY_ASSERT => verify_multiplicity_assert(exec_scopes),
-            hint_code::USORT_VERIFY_MULTIPLICITY_BODY => verify_multiplicity_body(
+                get_secp_curve(exec_scopes)?,
+            )
+        },
+        hint_code::BLAKE2S_FINALIZE => {
+            |vm, _, hint_data, _| finalize_blake2s(vm, &hint_data.ids_data, &hint_data.ap_tracking)
+        }
+        hint_code::BLAKE2S_ADD_UINT256 => |vm, _, hint_data, _| {
+            blake2s_add_uint256(vm, &hint_data.ids_data, &hint_data.ap_tracking)
+        },
+        hint_code::BLAKE2S_ADD_UINT256_BIGEND => |vm, _, hint_data, _| {
+            blake2s_add_uint256_bigend(vm, &hint_data.ids_data, &hint_data.ap_tracking)
+        },
+        hint_code::UNSAFE_KECCAK => |vm, exec_scopes, hint_data, _| {
+            unsafe_keccak(vm, exec_scopes, &hint_data.ids_data, &hint_data.ap_tracking)
+        },
+        hint_code::UNSAFE_KECCAK_FINALIZE => |vm, _, hint_data, _| {
+            unsafe_keccak_finalize(vm, &hint_data.ids_data, &hint_data.ap_tracking)
+        },
+        hint_code::SQUASH_DICT_INNER_SKIP_LOOP => |vm, exec_scopes, hint_data, _| {
+            squash_dict_inner_skip_loop(
                 vm,
                 exec_scopes,
                 &hint_data.ids_data,
                 &hint_data.ap_tracking,
-            ),
-            hint_code::BLAKE2S_COMPUTE => {
-                compute_blake2s(vm, &hint_data.ids_data, &hint_data.ap_tracking)
-            }
-            hint_code::VERIFY_ZERO => {
-                verify_zero(vm, &hint_data.ids_data, &hint_data.ap_tracking, constants)
-            }
-            hint_code::NONDET_BIGINT3 => nondet_bigint3(
+            )
+        },
+        hint_code::SQUASH_DICT_INNER_CHECK_ACCESS_INDEX => |vm, exec_scopes, hint_data, _| {
+            squash_dict_inner_check_access_index(
                 vm,
                 exec_scopes,
                 &hint_data.ids_data,
                 &hint_data.ap_tracking,
-                constants,
-            ),
-            hint_code::REDUCE => reduce(
+            )
+        },
+        hint_code::SQUASH_DICT_INNER_CONTINUE_LOOP => |vm, exec_scopes, hint_data, _| {
+            squash_dict_inner_continue_loop(
                 vm,
                 exec_scopes,
                 &hint_data.ids_data,
                 &hint_data.ap_tracking,
-                constants,
-            ),
-            hint_code::BLAKE2S_FINALIZE => {
-                finalize_blake2s(vm, &hint_data.ids_data, &hint_data.ap_tracking)
-            }
-            hint_code::BLAKE2S_ADD_UINT256 => {
-                blake2s_add_uint256(vm, &hint_data.ids_data, &hint_data.ap_tracking)
-            }
-            hint_code::BLAKE2S_ADD_UINT256_BIGEND => {
-                blake2s_add_uint256_bigend(vm, &hint_data.ids_data, &hint_data.ap_tracking)
-            }
-            hint_code::UNSAFE_KECCAK => {
-                unsafe_keccak(vm, exec_scopes, &hint_data.ids_data, &hint_data.ap_tracking)
-            }
-            hint_code::UNSAFE_KECCAK_FINALIZE => {
-                unsafe_keccak_finalize(vm, &hint_data.ids_data, &hint_data.ap_tracking)
-            }
-            hint_code::SQUASH_DICT_INNER_SKIP_LOOP => squash_dict_inner_skip_loop(
+            )
+        },
+        hint_code::SQUASH_DICT_INNER_ASSERT_LEN_KEYS => {
+            |_, exec_scopes, _, _| squash_dict_inner_assert_len_keys(exec_scopes)
+        }
+        hint_code::SQUASH_DICT_INNER_LEN_ASSERT => {
+            |_, exec_scopes, _, _| squash_dict_inner_len_assert(exec_scopes)
+        }
+        hint_code::SQUASH_DICT_INNER_USED_ACCESSES_ASSERT => |vm, exec_scopes, hint_data, _| {
+            squash_dict_inner_used_accesses_assert(
                 vm,
                 exec_scopes,
                 &hint_data.ids_data,
                 &hint_data.ap_tracking,
-            ),
-            hint_code::SQUASH_DICT_INNER_CHECK_ACCESS_INDEX => {
-                squash_dict_inner_check_access_index(
-                    vm,
-                    exec_scopes,
-                    &hint_data.ids_data,
-                    &hint_data.ap_tracking,
-                )
-            }
-            hint_code::SQUASH_DICT_INNER_CONTINUE_LOOP => squash_dict_inner_continue_loop(
+            )
+        },
+        hint_code::SQUASH_DICT_INNER_NEXT_KEY => |vm, exec_scopes, hint_data, _| {
+            squash_dict_inner_next_key(vm, exec_scopes, &hint_data.ids_data, &hint_data.ap_tracking)
+        },
+        hint_code::SQUASH_DICT => |vm, exec_scopes, hint_data, _| {
+            squash_dict(vm, exec_scopes, &hint_data.ids_data, &hint_data.ap_tracking)
+        },
+        hint_code::VM_ENTER_SCOPE => |_, exec_scopes, _, _| enter_scope(exec_scopes),
+        hint_code::DICT_UPDATE => |vm, exec_scopes, hint_data, _| {
+            dict_update(vm, exec_scopes, &hint_data.ids_data, &hint_data.ap_tracking)
+        },
+        hint_code::DICT_SQUASH_COPY_DICT => |vm, exec_scopes, hint_data, _| {
+            dict_squash_copy_dict(vm, exec_scopes, &hint_data.ids_data, &hint_data.ap_tracking)
+        },
+        hint_code::DICT_SQUASH_UPDATE_PTR => |vm, exec_scopes, hint_data, _| {
+            dict_squash_update_ptr(vm, exec_scopes, &hint_data.ids_data, &hint_data.ap_tracking)
+        },
+        hint_code::UINT256_ADD => {
+            |vm, _, hint_data, _| uint256_add(vm, &hint_data.ids_data, &hint_data.ap_tracking)
+        }
+        hint_code::SPLIT_64 => {
+            |vm, _, hint_data, _| split_64(vm, &hint_data.ids_data, &hint_data.ap_tracking)
+        }
+        hint_code::UINT256_SQRT => {
+            |vm, _, hint_data, _| uint256_sqrt(vm, &hint_data.ids_data, &hint_data.ap_tracking)
+        }
+        hint_code::UINT256_SIGNED_NN => {
+            |vm, _, hint_data, _| uint256_signed_nn(vm, &hint_data.ids_data, &hint_data.ap_tracking)
+        }
+        hint_code::UINT256_UNSIGNED_DIV_REM => |vm, _, hint_data, _| {
+            uint256_unsigned_div_rem(vm, &hint_data.ids_data, &hint_data.ap_tracking)
+        },
+        hint_code::BIGINT_TO_UINT256 => |vm, _, hint_data, constants| {
+            bigint_to_uint256(vm, &hint_data.ids_data, &hint_data.ap_tracking, constants)
+        },
+        hint_code::IS_ZERO_PACK => |vm, exec_scopes, hint_data, _| {
+            is_zero_pack(
                 vm,
                 exec_scopes,
                 &hint_data.ids_data,
                 &hint_data.ap_tracking,
-            ),
-            hint_code::SQUASH_DICT_INNER_ASSERT_LEN_KEYS => {
-                squash_dict_inner_assert_len_keys(exec_scopes)
-            }
-            hint_code::SQUASH_DICT_INNER_LEN_ASSERT => squash_dict_inner_len_assert(exec_scopes),
-            hint_code::SQUASH_DICT_INNER_USED_ACCESSES_ASSERT => {
-                squash_dict_inner_used_accesses_assert(
-                    vm,
-                    exec_scopes,
-                    &hint_data.ids_data,
-                    &hint_data.ap_tracking,
-                )
-            }
-            hint_code::SQUASH_DICT_INNER_NEXT_KEY => squash_dict_inner_next_key(
+                SecpCurve::secp256k1(),
+            )
+        },
+        hint_code::IS_ZERO_PACK_V2 => |vm, exec_scopes, hint_data, _| {
+            is_zero_pack(
                 vm,
                 exec_scopes,
                 &hint_data.ids_data,
                 &hint_data.ap_tracking,
-            ),
-            hint_code::SQUASH_DICT => {
-                squash_dict(vm, exec_scopes, &hint_data.ids_data, &hint_data.ap_tracking)
-            }
-            hint_code::VM_ENTER_SCOPE => enter_scope(exec_scopes),
-            hint_code::DICT_UPDATE => {
-                dict_update(vm, exec_scopes, &hint_data.ids_data, &hint_data.ap_tracking)
-            }
-            hint_code::DICT_SQUASH_COPY_DICT => {
-                dict_squash_copy_dict(vm, exec_scopes, &hint_data.ids_data, &hint_data.ap_tracking)
-            }
-            hint_code::DICT_SQUASH_UPDATE_PTR => {
-                dict_squash_update_ptr(vm, exec_scopes, &hint_data.ids_data, &hint_data.ap_tracking)
-            }
-            hint_code::UINT256_ADD => uint256_add(vm, &hint_data.ids_data, &hint_data.ap_tracking),
-            hint_code::SPLIT_64 => split_64(vm, &hint_data.ids_data, &hint_data.ap_tracking),
-            hint_code::UINT256_SQRT => {
-                uint256_sqrt(vm, &hint_data.ids_data, &hint_data.ap_tracking)
-            }
-            hint_code::UINT256_SIGNED_NN => {
-                uint256_signed_nn(vm, &hint_data.ids_data, &hint_data.ap_tracking)
-            }
-            hint_code::UINT256_UNSIGNED_DIV_REM => {
-                uint256_unsigned_div_rem(vm, &hint_data.ids_data, &hint_data.ap_tracking)
-            }
-            hint_code::BIGINT_TO_UINT256 => {
-                bigint_to_uint256(vm, &hint_data.ids_data, &hint_data.ap_tracking, constants)
-            }
-            hint_code::IS_ZERO_PACK => is_zero_pack(
+                get_secp_curve(exec_scopes)?,
+            )
+        },
+        hint_code::IS_ZERO_NONDET => |vm, exec_scopes, _, _| is_zero_nondet(vm, exec_scopes),
+        hint_code::IS_ZERO_ASSIGN_SCOPE_VARS => |_, exec_scopes, _, _| {
+            is_zero_assign_scope_variables(exec_scopes, SecpCurve::secp256k1())
+        },
+        hint_code::IS_ZERO_ASSIGN_SCOPE_VARS_V2 => |_, exec_scopes, _, _| {
+            is_zero_assign_scope_variables(exec_scopes, get_secp_curve(exec_scopes)?)
+        },
+        hint_code::DIV_MOD_N_PACKED_DIVMOD => |vm, exec_scopes, hint_data, _| {
+            div_mod_n_packed_divmod(
                 vm,
                 exec_scopes,
                 &hint_data.ids_data,
                 &hint_data.ap_tracking,
-                constants,
-            ),
-            hint_code::IS_ZERO_NONDET => is_zero_nondet(vm, exec_scopes),
-            hint_code::IS_ZERO_ASSIGN_SCOPE_VARS => {
-                is_zero_assign_scope_variables(exec_scopes, constants)
-            }
-            hint_code::DIV_MOD_N_PACKED_DIVMOD => div_mod_n_packed_divmod(
+                SecpCurve::secp256k1(),
+            )
+        },
+        hint_code::DIV_MOD_N_PACKED_DIVMOD_V2 => |vm, exec_scopes, hint_data, _| {
+            div_mod_n_packed_divmod(
                 vm,
                 exec_scopes,
                 &hint_data.ids_data,
                 &hint_data.ap_tracking,
-                constants,
-            ),
-            hint_code::DIV_MOD_N_SAFE_DIV => div_mod_n_safe_div(exec_scopes, constants),
-            hint_code::GET_POINT_FROM_X => get_point_from_x(
+                get_secp_curve(exec_scopes)?,
+            )
+        },
+        hint_code::DIV_MOD_N_SAFE_DIV => |_, exec_scopes, _, _| div_mod_n_safe_div(exec_scopes),
+        hint_code::GET_POINT_FROM_X => |vm, exec_scopes, hint_data, _| {
+            get_point_from_x(
                 vm,
                 exec_scopes,
                 &hint_data.ids_data,
                 &hint_data.ap_tracking,
-                constants,
-            ),
-            hint_code::EC_NEGATE => ec_negate(
+                SecpCurve::secp256k1(),
+            )
+        },
+        hint_code::EC_NEGATE => |vm, exec_scopes, hint_data, _| {
+            ec_negate(
                 vm,
                 exec_scopes,
                 &hint_data.ids_data,
                 &hint_data.ap_tracking,
-                constants,
-            ),
-            hint_code::EC_DOUBLE_SCOPE => compute_doubling_slope(
+                SecpCurve::secp256k1(),
+            )
+        },
+        hint_code::EC_NEGATE_V2 => |vm, exec_scopes, hint_data, _| {
+            ec_negate(
                 vm,
                 exec_scopes,
                 &hint_data.ids_data,
                 &hint_data.ap_tracking,
-                constants,
-            ),
-            hint_code::COMPUTE_SLOPE => compute_slope(
+                get_secp_curve(exec_scopes)?,
+            )
+        },
+        hint_code::EC_DOUBLE_SCOPE => |vm, exec_scopes, hint_data, _| {
+            compute_doubling_slope(
                 vm,
                 exec_scopes,
                 &hint_data.ids_data,
                 &hint_data.ap_tracking,
-                constants,
-            ),
-            hint_code::EC_DOUBLE_ASSIGN_NEW_X => ec_double_assign_new_x(
+                SecpCurve::secp256k1(),
+            )
+        },
+        hint_code::EC_DOUBLE_SCOPE_V2 => |vm, exec_scopes, hint_data, _| {
+            compute_doubling_slope(
                 vm,
                 exec_scopes,
                 &hint_data.ids_data,
                 &hint_data.ap_tracking,
-                constants,
-            ),
-            hint_code::EC_DOUBLE_ASSIGN_NEW_Y => ec_double_assign_new_y(exec_scopes, constants),
-            hint_code::KECCAK_WRITE_ARGS => {
-                keccak_write_args(vm, &hint_data.ids_data, &hint_data.ap_tracking)
-            }
-            hint_code::COMPARE_BYTES_IN_WORD_NONDET => compare_bytes_in_word_nondet(
+                get_secp_curve(exec_scopes)?,
+            )
+        },
+        hint_code::COMPUTE_SLOPE => |vm, exec_scopes, hint_data, _| {
+            compute_slope(
+                vm,
+                exec_scopes,
+                &hint_data.ids_data,
+                &hint_data.ap_tracking,
+                SecpCurve::secp256k1(),
+            )
+        },
+        hint_code::COMPUTE_SLOPE_V2 => |vm, exec_scopes, hint_data, _| {
+            compute_slope(
+                vm,
+                exec_scopes,
+                &hint_data.ids_data,
+                &hint_data.ap_tracking,
+                get_secp_curve(exec_scopes)?,
+            )
+        },
+        hint_code::EC_DOUBLE_ASSIGN_NEW_X => |vm, exec_scopes, hint_data, _| {
+            ec_double_assign_new_x(
+                vm,
+                exec_scopes,
+                &hint_data.ids_data,
+                &hint_data.ap_tracking,
+                SecpCurve::secp256k1(),
+            )
+        },
+        hint_code::EC_DOUBLE_ASSIGN_NEW_X_V2 => |vm, exec_scopes, hint_data, _| {
+            ec_double_assign_new_x(
+                vm,
+                exec_scopes,
+                &hint_data.ids_data,
+                &hint_data.ap_tracking,
+                get_secp_curve(exec_scopes)?,
+            )
+        },
+        hint_code::EC_DOUBLE_ASSIGN_NEW_Y => {
+            |_, exec_scopes, _, _| ec_double_assign_new_y(exec_scopes)
+        }
+        hint_code::KECCAK_WRITE_ARGS => {
+            |vm, _, hint_data, _| keccak_write_args(vm, &hint_data.ids_data, &hint_data.ap_tracking)
+        }
+        hint_code::COMPARE_BYTES_IN_WORD_NONDET => |vm, _, hint_data, constants| {
+            compare_bytes_in_word_nondet(vm, &hint_data.ids_data, &hint_data.ap_tracking, constants)
+        },
+        hint_code::SHA256_MAIN => {
+            |vm, _, hint_data, _| sha256_main(vm, &hint_data.ids_data, &hint_data.ap_tracking)
+        }
+        hint_code::SHA256_INPUT => {
+            |vm, _, hint_data, _| sha256_input(vm, &hint_data.ids_data, &hint_data.ap_tracking)
+        }
+        hint_code::SHA256_FINALIZE => {
+            |vm, _, hint_data, _| sha256_finalize(vm, &hint_data.ids_data, &hint_data.ap_tracking)
+        }
+        hint_code::COMPARE_KECCAK_FULL_RATE_IN_BYTES_NONDET => |vm, _, hint_data, constants| {
+            compare_keccak_full_rate_in_bytes_nondet(
                 vm,
                 &hint_data.ids_data,
                 &hint_data.ap_tracking,
                 constants,
-            ),
-            hint_code::SHA256_MAIN => sha256_main(vm, &hint_data.ids_data, &hint_data.ap_tracking),
-            hint_code::SHA256_INPUT => {
-                sha256_input(vm, &hint_data.ids_data, &hint_data.ap_tracking)
-            }
-            hint_code::SHA256_FINALIZE => {
-                sha256_finalize(vm, &hint_data.ids_data, &hint_data.ap_tracking)
-            }
-            hint_code::COMPARE_KECCAK_FULL_RATE_IN_BYTES_NONDET => {
-                compare_keccak_full_rate_in_bytes_nondet(
+            )
+        },
+        hint_code::BLOCK_PERMUTATION => |vm, _, hint_data, constants| {
+            block_permutation(vm, &hint_data.ids_data, &hint_data.ap_tracking, constants)
+        },
+        hint_code::CAIRO_KECCAK_FINALIZE => |vm, _, hint_data, constants| {
+            cairo_keccak_finalize(vm, &hint_data.ids_data, &hint_data.ap_tracking, constants)
+        },
+        hint_code::FAST_EC_ADD_ASSIGN_NEW_X => |vm, exec_scopes, hint_data, _| {
+            fast_ec_add_assign_new_x(
+                vm,
+                exec_scopes,
+                &hint_data.ids_data,
+                &hint_data.ap_tracking,
+                SecpCurve::secp256k1(),
+            )
+        },
+        hint_code::FAST_EC_ADD_ASSIGN_NEW_X_V2 => |vm, exec_scopes, hint_data, _| {
+            fast_ec_add_assign_new_x(
+                vm,
+                exec_scopes,
+                &hint_data.ids_data,
+                &hint_data.ap_tracking,
+                get_secp_curve(exec_scopes)?,
+            )
+        },
+        hint_code::FAST_EC_ADD_ASSIGN_NEW_Y => {
+            |_, exec_scopes, _, _| fast_ec_add_assign_new_y(exec_scopes)
+        }
+        hint_code::EC_MUL_INNER => {
+            |vm, _, hint_data, _| ec_mul_inner(vm, &hint_data.ids_data, &hint_data.ap_tracking)
+        }
+        hint_code::RELOCATE_SEGMENT => {
+            |vm, _, hint_data, _| relocate_segment(vm, &hint_data.ids_data, &hint_data.ap_tracking)
+        }
+        hint_code::TEMPORARY_ARRAY => {
+            |vm, _, hint_data, _| temporary_array(vm, &hint_data.ids_data, &hint_data.ap_tracking)
+        }
+        hint_code::SIMPLE_BOOTLOADER_LOAD_INPUT => {
+            |_, exec_scopes, _, _| load_simple_bootloader_input(exec_scopes)
+        }
+        hint_code::SIMPLE_BOOTLOADER_PREPARE_TASK_RANGE_CHECKS => {
+            |vm, exec_scopes, hint_data, _| {
+                prepare_task_range_checks(
                     vm,
+                    exec_scopes,
                     &hint_data.ids_data,
                     &hint_data.ap_tracking,
-                    constants,
                 )
             }
-            hint_code::BLOCK_PERMUTATION => {
-                block_permutation(vm, &hint_data.ids_data, &hint_data.ap_tracking, constants)
-            }
-            hint_code::CAIRO_KECCAK_FINALIZE => {
-                cairo_keccak_finalize(vm, &hint_data.ids_data, &hint_data.ap_tracking, constants)
-            }
-            hint_code::FAST_EC_ADD_ASSIGN_NEW_X => fast_ec_add_assign_new_x(
+        }
+        hint_code::SIMPLE_BOOTLOADER_SET_CURRENT_TASK => |vm, exec_scopes, hint_data, _| {
+            set_current_task(vm, exec_scopes, &hint_data.ids_data, &hint_data.ap_tracking)
+        },
+        hint_code::SIMPLE_BOOTLOADER_CONFIGURE_FACT_TOPOLOGIES => {
+            |vm, exec_scopes, _, _| configure_task_fact_topologies(vm, exec_scopes)
+        }
+        hint_code::EXECUTE_TASK_ALLOCATE_PROGRAM_DATA_SEGMENT => |vm, exec_scopes, hint_data, _| {
+            allocate_program_data_segment(
                 vm,
                 exec_scopes,
                 &hint_data.ids_data,
                 &hint_data.ap_tracking,
-                constants,
-            ),
-            hint_code::FAST_EC_ADD_ASSIGN_NEW_Y => fast_ec_add_assign_new_y(exec_scopes, constants),
-            hint_code::EC_MUL_INNER => {
-                ec_mul_inner(vm, &hint_data.ids_data, &hint_data.ap_tracking)
-            }
-            hint_code::RELOCATE_SEGMENT => {
-                relocate_segment(vm, &hint_data.ids_data, &hint_data.ap_tracking)
-            }
-            hint_code::TEMPORARY_ARRAY => {
-                temporary_array(vm, &hint_data.ids_data, &hint_data.ap_tracking)
-            }
-            code => Err(HintError::UnknownHint(code.to_string())),
-        }
-    }
+            )
+        },
+        hint_code::EXECUTE_TASK_LOAD_PROGRAM => |vm, exec_scopes, hint_data, _| {
+            load_task_program(vm, exec_scopes, &hint_data.ids_data, &hint_data.ap_tracking)
+        },
+        hint_code::EXECUTE_TASK_VALIDATE_HASH => |vm, exec_scopes, hint_data, _| {
+            validate_task_program_hash(vm, exec_scopes, &hint_data.ids_data, &hint_data.ap_tracking)
+        },
+        hint_code::EXECUTE_TASK_ASSERT_PROGRAM_ADDRESS => |vm, exec_scopes, hint_data, _| {
+            assert_program_address(vm, exec_scopes, &hint_data.ids_data, &hint_data.ap_tracking)
+        },
+        hint_code::EXECUTE_TASK_CALL_TASK => |vm, exec_scopes, hint_data, _| {
+            call_task(vm, exec_scopes, &hint_data.ids_data, &hint_data.ap_tracking)
+        },
+        hint_code::EXECUTE_TASK_WRITE_RETURN_BUILTINS => |vm, exec_scopes, hint_data, _| {
+            write_task_return_builtins(vm, exec_scopes, &hint_data.ids_data, &hint_data.ap_tracking)
+        },
+        hint_code::EXECUTE_TASK_APPEND_FACT_TOPOLOGIES => |vm, exec_scopes, hint_data, _| {
+            append_fact_topology(vm, exec_scopes, &hint_data.ids_data, &hint_data.ap_tracking)
+        },
+        hint_code::SELECT_BUILTINS_SELECT_BUILTIN => |vm, exec_scopes, hint_data, _| {
+            select_builtin(vm, exec_scopes, &hint_data.ids_data, &hint_data.ap_tracking)
+        },
+        hint_code::SET_TREE_STRUCTURE => |vm, _, hint_data, _| {
+            set_tree_structure(vm, &hint_data.ids_data, &hint_data.ap_tracking)
+        },
+        _ => return None,
+    };
+    Some(hint_func)
 }
 
 #[cfg(test)]
@@ -516,6 +819,58 @@ mod tests {
         );
     }
 
+    #[test]
+    fn compile_unknown_hint() {
+        let hint_processor = BuiltinHintProcessor::new_empty();
+        assert_eq!(
+            hint_processor
+                .compile_hint(
+                    "random_invalid_code",
+                    &ApTracking::default(),
+                    &HashMap::new(),
+                    &HashMap::new()
+                )
+                .err(),
+            Some(VirtualMachineError::UnknownHint(
+                "random_invalid_code".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn run_compiled_hint() {
+        let hint_code = "memory[ap] = segments.add()";
+        let mut vm = vm!();
+        add_segments!(vm, 3);
+        let mut hint_processor = BuiltinHintProcessor::new_empty();
+        let hint_data = hint_processor
+            .compile_hint(
+                hint_code,
+                &ApTracking::default(),
+                &HashMap::new(),
+                &HashMap::new(),
+            )
+            .unwrap();
+        assert!(matches!(
+            hint_data.downcast_ref::<HintProcessorData>(),
+            Some(HintProcessorData {
+                hint_impl: Some(HintImpl::Builtin(_)),
+                ..
+            })
+        ));
+        assert_eq!(
+            hint_processor.execute_hint(&mut vm, exec_scopes_ref!(), &hint_data, &HashMap::new()),
+            Ok(())
+        );
+        check_memory![vm.memory, ((1, 0), (3, 0))];
+    }
+
+    #[test]
+    fn get_builtin_hint_func_unknown_hint() {
+        assert!(get_builtin_hint_func("random_invalid_code").is_none());
+        assert!(get_builtin_hint_func(hint_code::ADD_SEGMENT).is_some());
+    }
+
     #[test]
     fn memcpy_enter_scope_valid() {
         let hint_code = "vm_enter_scope({'n': ids.len})";
@@ -823,6 +1178,31 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn compile_extra_hint_overriding_builtin_hint() {
+        let mut hint_processor = BuiltinHintProcessor::new_empty();
+        hint_processor.add_hint(
+            hint_code::ADD_SEGMENT.to_string(),
+            Rc::new(HintFunc(Box::new(enter_scope))),
+        );
+        let hint_data = hint_processor
+            .compile_hint(
+                hint_code::ADD_SEGMENT,
+                &ApTracking::default(),
+                &HashMap::new(),
+                &HashMap::new(),
+            )
+            .unwrap();
+        let mut vm = vm!();
+        let exec_scopes = exec_scopes_ref!();
+        assert_eq!(
+            hint_processor.execute_hint(&mut vm, exec_scopes, &hint_data, &HashMap::new()),
+            Ok(())
+        );
+        assert_eq!(exec_scopes.data.len(), 2);
+        assert_eq!(vm.segments.num_segments, 0);
+    }
+
     #[test]
     fn add_hint_add_same_hint_twice() {
         let mut hint_processor = BuiltinHintProcessor::new_empty();
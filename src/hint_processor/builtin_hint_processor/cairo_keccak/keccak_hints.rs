@@ -5,6 +5,7 @@ use crate::{
         },
         hint_processor_definition::HintReference,
     },
+    math_utils::keccak::{felt_to_lane, keccak_f1600},
     serde::deserialize_program::ApTracking,
     types::relocatable::MaybeRelocatable,
     vm::{
@@ -142,7 +143,9 @@ pub fn block_permutation(
 
     let keccak_ptr = get_ptr_from_var_name("keccak_ptr", vm, ids_data, ap_tracking)?;
 
-    let keccak_state_size_felts = keccak_state_size_felts.to_usize().unwrap();
+    let keccak_state_size_felts = keccak_state_size_felts
+        .to_usize()
+        .ok_or(HintError::BigintToUsizeFail)?;
     let values = vm
         .get_range(
             &MaybeRelocatable::RelocatableValue(keccak_ptr.sub_usize(keccak_state_size_felts)?),
@@ -154,9 +157,7 @@ pub fn block_permutation(
         .try_into()
         .map_err(|_| VirtualMachineError::SliceToArrayError)?;
 
-    // this function of the keccak crate is the one used instead of keccak_func from
-    // keccak_utils.py
-    keccak::f1600(&mut u64_values);
+    keccak_f1600(&mut u64_values);
 
     let bigint_values = u64_array_to_mayberelocatable_vec(&u64_values);
 
@@ -201,13 +202,15 @@ pub fn cairo_keccak_finalize(
         return Err(HintError::InvalidBlockSize(block_size.clone()));
     }
 
-    let keccak_state_size_felts = keccak_state_size_felts.to_usize().unwrap();
-    let block_size = block_size.to_usize().unwrap();
+    let keccak_state_size_felts = keccak_state_size_felts
+        .to_usize()
+        .ok_or(HintError::BigintToUsizeFail)?;
+    let block_size = block_size.to_usize().ok_or(HintError::BigintToUsizeFail)?;
 
     let mut inp = vec![0; keccak_state_size_felts]
         .try_into()
         .map_err(|_| VirtualMachineError::SliceToArrayError)?;
-    keccak::f1600(&mut inp);
+    keccak_f1600(&mut inp);
 
     let mut padding = vec![Felt::zero().into(); keccak_state_size_felts];
     padding.extend(u64_array_to_mayberelocatable_vec(&inp));
@@ -236,7 +239,7 @@ pub(crate) fn maybe_reloc_vec_to_u64_array(
         .map(|n| match n {
             Some(Cow::Owned(MaybeRelocatable::Int(ref num)))
             | Some(Cow::Borrowed(MaybeRelocatable::Int(ref num))) => {
-                num.to_u64().ok_or(VirtualMachineError::BigintToU64Fail)
+                felt_to_lane(num).ok_or(VirtualMachineError::BigintToU64Fail)
             }
             _ => Err(VirtualMachineError::ExpectedIntAtRange(
                 n.as_ref().map(|x| x.as_ref().to_owned()),
@@ -1,5 +1,5 @@
 use crate::{
-    types::{exec_scope::ExecutionScopes, relocatable::MaybeRelocatable},
+    types::{cairo_arg::CairoArg, exec_scope::ExecutionScopes, relocatable::MaybeRelocatable},
     vm::{errors::hint_errors::HintError, vm_core::VirtualMachine},
 };
 use std::{any::Any, cell::RefCell, collections::HashMap, rc::Rc};
@@ -32,6 +32,27 @@ fn copy_initial_dict(
     initial_dict
 }
 
+//Removes the initial_dict variable from scope. Its values can be given as MaybeRelocatable, or as
+//CairoArg to write lists to new segments, in which case the entries are ordered like a python dict.
+fn take_initial_dict(
+    exec_scopes: &mut ExecutionScopes,
+) -> Option<Vec<(MaybeRelocatable, CairoArg)>> {
+    let variable = exec_scopes
+        .get_local_variables_mut()
+        .ok()?
+        .remove("initial_dict")?;
+    if let Some(dict) = variable.downcast_ref::<HashMap<MaybeRelocatable, MaybeRelocatable>>() {
+        return Some(
+            dict.iter()
+                .map(|(key, value)| (key.clone(), CairoArg::Single(value.clone())))
+                .collect(),
+        );
+    }
+    variable
+        .downcast_ref::<Vec<(MaybeRelocatable, CairoArg)>>()
+        .cloned()
+}
+
 /*Implements hint:
    if '__dict_manager' not in globals():
            from starkware.cairo.common.dict import DictManager
@@ -40,24 +61,33 @@ fn copy_initial_dict(
        memory[ap] = __dict_manager.new_dict(segments, initial_dict)
        del initial_dict
 
-For now, the functionality to create a dictionary from a previously defined initial_dict (using a hint)
-is not available
+If there is no initial_dict in scope, the next initial dict supplied by the host to the dict manager
+is used (see DictManager::add_initial_dict)
 */
 pub fn dict_new(
     vm: &mut VirtualMachine,
     exec_scopes: &mut ExecutionScopes,
 ) -> Result<(), HintError> {
     //Get initial dictionary from scope (defined by an earlier hint)
-    let initial_dict = copy_initial_dict(exec_scopes).ok_or(HintError::NoInitialDict)?;
+    let initial_dict = take_initial_dict(exec_scopes);
     //Check if there is a dict manager in scope, create it if there isnt one
-    let base = if let Ok(dict_manager) = exec_scopes.get_dict_manager() {
-        dict_manager.borrow_mut().new_dict(vm, initial_dict)?
-    } else {
-        let mut dict_manager = DictManager::new();
-        let base = dict_manager.new_dict(vm, initial_dict)?;
-        exec_scopes.insert_value("dict_manager", Rc::new(RefCell::new(dict_manager)));
-        base
+    let dict_manager = match exec_scopes.get_dict_manager() {
+        Ok(dict_manager) => dict_manager,
+        Err(_) if initial_dict.is_none() => return Err(HintError::NoInitialDict),
+        Err(_) => {
+            let dict_manager = Rc::new(RefCell::new(DictManager::new()));
+            exec_scopes.insert_value("dict_manager", dict_manager.clone());
+            dict_manager
+        }
+    };
+    let mut dict_manager = dict_manager.borrow_mut();
+    let initial_dict = match initial_dict {
+        Some(initial_dict) => initial_dict,
+        None => dict_manager
+            .take_initial_dict()
+            .ok_or(HintError::NoInitialDict)?,
     };
+    let base = dict_manager.new_dict_with_args(vm, initial_dict)?;
     insert_value_into_ap(vm, base)
 }
 
@@ -300,6 +330,87 @@ mod tests {
         );
     }
 
+    #[test]
+    fn run_dict_new_with_nested_initial_dict() {
+        let mut vm = vm!();
+        add_segments!(vm, 2);
+        let mut exec_scopes = scope![(
+            "initial_dict",
+            vec![
+                (
+                    MaybeRelocatable::from(1),
+                    CairoArg::from(MaybeRelocatable::from(7))
+                ),
+                (
+                    MaybeRelocatable::from(2),
+                    CairoArg::Array(vec![
+                        MaybeRelocatable::from(8).into(),
+                        vec![MaybeRelocatable::from(9)].into(),
+                    ])
+                ),
+            ]
+        )];
+        run_hint!(vm, HashMap::new(), hint_code::DICT_NEW, &mut exec_scopes)
+            .expect("Error while executing hint");
+        //The dict segment is followed by the segments of the nested lists
+        check_memory![
+            vm.memory,
+            ((1, 0), (2, 0)),
+            ((3, 0), 8),
+            ((3, 1), (4, 0)),
+            ((4, 0), 9)
+        ];
+        assert_eq!(
+            exec_scopes
+                .get_dict_manager()
+                .unwrap()
+                .borrow()
+                .trackers
+                .get(&2)
+                .unwrap()
+                .get_dictionary_copy(),
+            HashMap::from([
+                (MaybeRelocatable::from(1), MaybeRelocatable::from(7)),
+                (MaybeRelocatable::from(2), MaybeRelocatable::from((3, 0))),
+            ])
+        );
+        //initial_dict is deleted
+        assert!(exec_scopes
+            .get_local_variables()
+            .unwrap()
+            .get("initial_dict")
+            .is_none());
+    }
+
+    #[test]
+    fn run_dict_new_with_host_initial_dict() {
+        let mut vm = vm!();
+        add_segments!(vm, 2);
+        let mut dict_manager = DictManager::new();
+        dict_manager.add_initial_dict(vec![(
+            MaybeRelocatable::from(3),
+            MaybeRelocatable::from(4).into(),
+        )]);
+        let mut exec_scopes = scope![("dict_manager", Rc::new(RefCell::new(dict_manager)))];
+        run_hint!(vm, HashMap::new(), hint_code::DICT_NEW, &mut exec_scopes)
+            .expect("Error while executing hint");
+        check_memory![vm.memory, ((1, 0), (2, 0))];
+        let dict_manager = exec_scopes.get_dict_manager().unwrap();
+        assert_eq!(
+            dict_manager.borrow().trackers.get(&2),
+            Some(&DictTracker::new_with_initial(
+                &relocatable!(2, 0),
+                HashMap::from([(MaybeRelocatable::from(3), MaybeRelocatable::from(4))])
+            ))
+        );
+        //The host supplied a single initial dict
+        vm.run_context.ap += 1;
+        assert_eq!(
+            run_hint!(vm, HashMap::new(), hint_code::DICT_NEW, &mut exec_scopes),
+            Err(HintError::NoInitialDict)
+        );
+    }
+
     #[test]
     fn run_dict_new_with_no_initial_dict() {
         let hint_code = "if '__dict_manager' not in globals():\n    from starkware.cairo.common.dict import DictManager\n    __dict_manager = DictManager()\n\nmemory[ap] = __dict_manager.new_dict(segments, initial_dict)\ndel initial_dict";
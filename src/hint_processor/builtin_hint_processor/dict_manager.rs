@@ -1,7 +1,14 @@
-use std::collections::HashMap;
+use std::{
+    cmp::Ordering,
+    collections::{HashMap, VecDeque},
+};
 
 use crate::{
-    types::relocatable::{MaybeRelocatable, Relocatable},
+    hint_processor::builtin_hint_processor::dict_hint_utils::DICT_ACCESS_SIZE,
+    types::{
+        cairo_arg::CairoArg,
+        relocatable::{MaybeRelocatable, Relocatable},
+    },
     vm::{
         errors::{
             hint_errors::HintError, memory_errors::MemoryError, vm_errors::VirtualMachineError,
@@ -15,6 +22,8 @@ use crate::{
 ///Uses the segment index to associate the corresponding python dict with the Cairo dict.
 pub struct DictManager {
     pub trackers: HashMap<isize, DictTracker>,
+    //Initial dicts supplied by the host, used by dict_new when no initial_dict is in scope.
+    initial_dicts: VecDeque<Vec<(MaybeRelocatable, CairoArg)>>,
 }
 
 #[derive(PartialEq, Eq, Debug, Clone)]
@@ -35,6 +44,19 @@ pub enum Dictionary {
     },
 }
 
+#[derive(PartialEq, Eq, Debug, Clone)]
+///Final state of a Cairo dict, as seen by its tracker.
+pub struct DictSnapshot {
+    //Index of the segment holding the dict accesses.
+    pub segment_index: isize,
+    //Key-value pairs of the dictionary, sorted by key.
+    pub data: Vec<(MaybeRelocatable, MaybeRelocatable)>,
+    //Value returned for missing keys, only present for dicts created with default_dict_new.
+    pub default_value: Option<MaybeRelocatable>,
+    //Number of DictAccess entries written to the dict segment.
+    pub n_accesses: usize,
+}
+
 impl Dictionary {
     fn get(&mut self, key: &MaybeRelocatable) -> Option<&MaybeRelocatable> {
         match self {
@@ -65,15 +87,31 @@ impl DictManager {
     pub fn new() -> Self {
         DictManager {
             trackers: HashMap::<isize, DictTracker>::new(),
+            initial_dicts: VecDeque::new(),
         }
     }
-    //Creates a new Cairo dictionary. The values of initial_dict can be integers, tuples or
-    //lists. See MemorySegments.gen_arg().
-    //For now, no initial dict will be processed (Assumes initial_dict = None)
+
+    //Creates a new Cairo dictionary, holding the values of initial_dict.
     pub fn new_dict(
         &mut self,
         vm: &mut VirtualMachine,
         initial_dict: HashMap<MaybeRelocatable, MaybeRelocatable>,
+    ) -> Result<MaybeRelocatable, HintError> {
+        self.new_dict_with_args(
+            vm,
+            initial_dict
+                .into_iter()
+                .map(|(key, value)| (key, CairoArg::Single(value))),
+        )
+    }
+
+    //Creates a new Cairo dictionary. The values of initial_dict can be integers, relocatables or
+    //(nested) lists, which are written to new segments in the order of initial_dict, after the
+    //segment of the dictionary. See MemorySegments.gen_arg().
+    pub fn new_dict_with_args(
+        &mut self,
+        vm: &mut VirtualMachine,
+        initial_dict: impl IntoIterator<Item = (MaybeRelocatable, CairoArg)>,
     ) -> Result<MaybeRelocatable, HintError> {
         let base = vm.add_memory_segment();
         if self.trackers.contains_key(&base.segment_index) {
@@ -88,6 +126,11 @@ impl DictManager {
             ))?;
         };
 
+        let initial_dict = initial_dict
+            .into_iter()
+            .map(|(key, value)| Ok((key, vm.segments.gen_cairo_arg(&value, &mut vm.memory)?)))
+            .collect::<Result<_, MemoryError>>()
+            .map_err(VirtualMachineError::MemoryError)?;
         self.trackers.insert(
             base.segment_index,
             DictTracker::new_with_initial(&base, initial_dict),
@@ -95,6 +138,17 @@ impl DictManager {
         Ok(MaybeRelocatable::RelocatableValue(base))
     }
 
+    //Queues an initial dict for the next dictionary created by dict_new without an initial_dict
+    //in scope, so that the host can supply the initial dicts of a run.
+    pub fn add_initial_dict(&mut self, initial_dict: Vec<(MaybeRelocatable, CairoArg)>) {
+        self.initial_dicts.push_back(initial_dict);
+    }
+
+    //Removes the next initial dict supplied by the host, if any.
+    pub fn take_initial_dict(&mut self) -> Option<Vec<(MaybeRelocatable, CairoArg)>> {
+        self.initial_dicts.pop_front()
+    }
+
     //Creates a new Cairo default dictionary
     pub fn new_default_dict(
         &mut self,
@@ -141,6 +195,14 @@ impl DictManager {
         }
         Ok(tracker)
     }
+
+    //Returns a snapshot of every tracked dictionary, sorted by segment index
+    pub fn get_snapshots(&self) -> Vec<DictSnapshot> {
+        let mut snapshots: Vec<DictSnapshot> =
+            self.trackers.values().map(DictTracker::snapshot).collect();
+        snapshots.sort_by_key(|snapshot| snapshot.segment_index);
+        snapshots
+    }
 }
 
 impl Default for DictManager {
@@ -205,6 +267,27 @@ impl DictTracker {
     pub fn insert_value(&mut self, key: &MaybeRelocatable, val: &MaybeRelocatable) {
         self.data.insert(key, val)
     }
+
+    //Returns the current contents of the dictionary along with the amount of accesses made to it.
+    //The dict segment always starts at offset 0, so the accesses can be counted from current_ptr.
+    pub fn snapshot(&self) -> DictSnapshot {
+        let mut data: Vec<(MaybeRelocatable, MaybeRelocatable)> =
+            self.get_dictionary_copy().into_iter().collect();
+        data.sort_by(|(key_a, _), (key_b, _)| key_a.partial_cmp(key_b).unwrap_or(Ordering::Equal));
+        let default_value = match &self.data {
+            Dictionary::SimpleDictionary(_) => None,
+            Dictionary::DefaultDictionary {
+                dict: _,
+                default_value,
+            } => Some(default_value.clone()),
+        };
+        DictSnapshot {
+            segment_index: self.current_ptr.segment_index,
+            data,
+            default_value,
+            n_accesses: self.current_ptr.offset / DICT_ACCESS_SIZE,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -293,6 +376,57 @@ mod tests {
         assert_eq!(vm.segments.num_segments, 1);
     }
 
+    #[test]
+    fn dict_manager_new_dict_with_args() {
+        let mut dict_manager = DictManager::new();
+        let mut vm = vm!();
+        let base = dict_manager.new_dict_with_args(
+            &mut vm,
+            vec![
+                (
+                    MaybeRelocatable::from(1),
+                    MaybeRelocatable::from((4, 2)).into(),
+                ),
+                (
+                    MaybeRelocatable::from(2),
+                    vec![MaybeRelocatable::from(5), MaybeRelocatable::from(6)].into(),
+                ),
+            ],
+        );
+        assert_eq!(base, Ok(MaybeRelocatable::from((0, 0))));
+        assert_eq!(
+            dict_manager.trackers.get(&0),
+            Some(&DictTracker::new_with_initial(
+                &relocatable!(0, 0),
+                HashMap::from([
+                    (MaybeRelocatable::from(1), MaybeRelocatable::from((4, 2))),
+                    (MaybeRelocatable::from(2), MaybeRelocatable::from((1, 0))),
+                ])
+            ))
+        );
+        assert_eq!(vm.segments.num_segments, 2);
+        check_memory![vm.memory, ((1, 0), 5), ((1, 1), 6)];
+    }
+
+    #[test]
+    fn dict_manager_take_initial_dicts_in_order() {
+        let mut dict_manager = DictManager::new();
+        dict_manager.add_initial_dict(vec![]);
+        dict_manager.add_initial_dict(vec![(
+            MaybeRelocatable::from(1),
+            MaybeRelocatable::from(2).into(),
+        )]);
+        assert_eq!(dict_manager.take_initial_dict(), Some(vec![]));
+        assert_eq!(
+            dict_manager.take_initial_dict(),
+            Some(vec![(
+                MaybeRelocatable::from(1),
+                MaybeRelocatable::from(2).into()
+            )])
+        );
+        assert_eq!(dict_manager.take_initial_dict(), None);
+    }
+
     #[test]
     fn dict_manager_new_default_dict_with_initial_dict() {
         let mut dict_manager = DictManager::new();
@@ -344,6 +478,54 @@ mod tests {
         );
     }
 
+    #[test]
+    fn dict_tracker_snapshot_simple() {
+        let mut initial_dict = HashMap::<MaybeRelocatable, MaybeRelocatable>::new();
+        initial_dict.insert(MaybeRelocatable::from(5), MaybeRelocatable::from(10));
+        initial_dict.insert(MaybeRelocatable::from(1), MaybeRelocatable::from((2, 3)));
+        let mut dict_tracker = DictTracker::new_with_initial(&relocatable!(2, 0), initial_dict);
+        dict_tracker.current_ptr = relocatable!(2, 6);
+        assert_eq!(
+            dict_tracker.snapshot(),
+            DictSnapshot {
+                segment_index: 2,
+                data: vec![
+                    (MaybeRelocatable::from(1), MaybeRelocatable::from((2, 3))),
+                    (MaybeRelocatable::from(5), MaybeRelocatable::from(10))
+                ],
+                default_value: None,
+                n_accesses: 2,
+            }
+        );
+    }
+
+    #[test]
+    fn dict_manager_get_snapshots() {
+        let mut dict_manager = DictManager::new();
+        let mut vm = vm!();
+        dict_manager
+            .new_default_dict(&mut vm, &MaybeRelocatable::from(7), None)
+            .unwrap();
+        dict_manager.new_dict(&mut vm, HashMap::new()).unwrap();
+        assert_eq!(
+            dict_manager.get_snapshots(),
+            vec![
+                DictSnapshot {
+                    segment_index: 0,
+                    data: Vec::new(),
+                    default_value: Some(MaybeRelocatable::from(7)),
+                    n_accesses: 0,
+                },
+                DictSnapshot {
+                    segment_index: 1,
+                    data: Vec::new(),
+                    default_value: None,
+                    n_accesses: 0,
+                }
+            ]
+        );
+    }
+
     #[test]
     fn dictionary_get_insert_simple() {
         let mut dictionary = Dictionary::SimpleDictionary(HashMap::new());
@@ -369,10 +369,20 @@ q, r = divmod(pack(ids.val, PRIME), SECP_P)
 assert r == 0, f"verify_zero: Invalid input {ids.val.d0, ids.val.d1, ids.val.d2}."
 ids.q = q % PRIME"#;
 
+pub(crate) const VERIFY_ZERO_V2: &str = r#"from starkware.cairo.common.cairo_secp.secp_utils import pack
+
+q, r = divmod(pack(ids.val, PRIME), SECP_P)
+assert r == 0, f"verify_zero: Invalid input {ids.val.d0, ids.val.d1, ids.val.d2}."
+ids.q = q % PRIME"#;
+
 pub(crate) const REDUCE: &str = r#"from starkware.cairo.common.cairo_secp.secp_utils import SECP_P, pack
 
 value = pack(ids.x, PRIME) % SECP_P"#;
 
+pub(crate) const REDUCE_V2: &str = r#"from starkware.cairo.common.cairo_secp.secp_utils import pack
+
+value = pack(ids.x, PRIME) % SECP_P"#;
+
 pub(crate) const UNSAFE_KECCAK: &str = r#"from eth_hash.auto import keccak
 
 data, length = ids.data, ids.length
@@ -405,12 +415,19 @@ ids.low = int.from_bytes(hashed[16:32], 'big')"#;
 pub(crate) const IS_ZERO_NONDET: &str = "memory[ap] = to_felt_or_relocatable(x == 0)";
 pub(crate) const IS_ZERO_PACK: &str = r#"from starkware.cairo.common.cairo_secp.secp_utils import SECP_P, pack
 
+x = pack(ids.x, PRIME) % SECP_P"#;
+pub(crate) const IS_ZERO_PACK_V2: &str = r#"from starkware.cairo.common.cairo_secp.secp_utils import pack
+
 x = pack(ids.x, PRIME) % SECP_P"#;
 pub(crate) const IS_ZERO_ASSIGN_SCOPE_VARS: &str = r#"from starkware.cairo.common.cairo_secp.secp_utils import SECP_P
 from starkware.python.math_utils import div_mod
 
 value = x_inv = div_mod(1, x, SECP_P)"#;
 
+pub(crate) const IS_ZERO_ASSIGN_SCOPE_VARS_V2: &str = r#"from starkware.python.math_utils import div_mod
+
+value = x_inv = div_mod(1, x, SECP_P)"#;
+
 pub(crate) const DIV_MOD_N_PACKED_DIVMOD: &str = r#"from starkware.cairo.common.cairo_secp.secp_utils import N, pack
 from starkware.python.math_utils import div_mod, safe_div
 
@@ -418,6 +435,13 @@ a = pack(ids.a, PRIME)
 b = pack(ids.b, PRIME)
 value = res = div_mod(a, b, N)"#;
 
+pub(crate) const DIV_MOD_N_PACKED_DIVMOD_V2: &str = r#"from starkware.cairo.common.cairo_secp.secp_utils import pack
+from starkware.python.math_utils import div_mod, safe_div
+
+a = pack(ids.a, PRIME)
+b = pack(ids.b, PRIME)
+value = res = div_mod(a, b, N)"#;
+
 pub(crate) const DIV_MOD_N_SAFE_DIV: &str = r#"value = k = safe_div(res * b - a, N)"#;
 
 pub(crate) const GET_POINT_FROM_X: &str = r#"from starkware.cairo.common.cairo_secp.secp_utils import SECP_P, pack
@@ -438,6 +462,12 @@ y = pack(ids.point.y, PRIME) % SECP_P
 # The modulo operation in python always returns a nonnegative number.
 value = (-y) % SECP_P"#;
 
+pub(crate) const EC_NEGATE_V2: &str = r#"from starkware.cairo.common.cairo_secp.secp_utils import pack
+
+y = pack(ids.point.y, PRIME) % SECP_P
+# The modulo operation in python always returns a nonnegative number.
+value = (-y) % SECP_P"#;
+
 pub(crate) const EC_DOUBLE_SCOPE: &str = r#"from starkware.cairo.common.cairo_secp.secp_utils import SECP_P, pack
 from starkware.python.math_utils import ec_double_slope
 
@@ -446,6 +476,14 @@ x = pack(ids.point.x, PRIME)
 y = pack(ids.point.y, PRIME)
 value = slope = ec_double_slope(point=(x, y), alpha=0, p=SECP_P)"#;
 
+pub(crate) const EC_DOUBLE_SCOPE_V2: &str = r#"from starkware.cairo.common.cairo_secp.secp_utils import pack
+from starkware.python.math_utils import ec_double_slope
+
+# Compute the slope.
+x = pack(ids.point.x, PRIME)
+y = pack(ids.point.y, PRIME)
+value = slope = ec_double_slope(point=(x, y), alpha=ALPHA, p=SECP_P)"#;
+
 pub(crate) const COMPUTE_SLOPE: &str = r#"from starkware.cairo.common.cairo_secp.secp_utils import SECP_P, pack
 from starkware.python.math_utils import line_slope
 
@@ -456,6 +494,16 @@ x1 = pack(ids.point1.x, PRIME)
 y1 = pack(ids.point1.y, PRIME)
 value = slope = line_slope(point1=(x0, y0), point2=(x1, y1), p=SECP_P)"#;
 
+pub(crate) const COMPUTE_SLOPE_V2: &str = r#"from starkware.cairo.common.cairo_secp.secp_utils import pack
+from starkware.python.math_utils import line_slope
+
+# Compute the slope.
+x0 = pack(ids.point0.x, PRIME)
+y0 = pack(ids.point0.y, PRIME)
+x1 = pack(ids.point1.x, PRIME)
+y1 = pack(ids.point1.y, PRIME)
+value = slope = line_slope(point1=(x0, y0), point2=(x1, y1), p=SECP_P)"#;
+
 pub(crate) const EC_DOUBLE_ASSIGN_NEW_X: &str = r#"from starkware.cairo.common.cairo_secp.secp_utils import SECP_P, pack
 
 slope = pack(ids.slope, PRIME)
@@ -464,9 +512,26 @@ y = pack(ids.point.y, PRIME)
 
 value = new_x = (pow(slope, 2, SECP_P) - 2 * x) % SECP_P"#;
 
+pub(crate) const EC_DOUBLE_ASSIGN_NEW_X_V2: &str = r#"from starkware.cairo.common.cairo_secp.secp_utils import pack
+
+slope = pack(ids.slope, PRIME)
+x = pack(ids.point.x, PRIME)
+y = pack(ids.point.y, PRIME)
+
+value = new_x = (pow(slope, 2, SECP_P) - 2 * x) % SECP_P"#;
+
 pub(crate) const EC_DOUBLE_ASSIGN_NEW_Y: &str =
     r#"value = new_y = (slope * (x - new_x) - y) % SECP_P"#;
 
+pub(crate) const IMPORT_SECP256R1_P: &str =
+    r#"from starkware.cairo.common.cairo_secp.secp256r1_utils import SECP256R1_P as SECP_P"#;
+
+pub(crate) const IMPORT_SECP256R1_N: &str =
+    r#"from starkware.cairo.common.cairo_secp.secp256r1_utils import SECP256R1_N as N"#;
+
+pub(crate) const IMPORT_SECP256R1_ALPHA: &str =
+    r#"from starkware.cairo.common.cairo_secp.secp256r1_utils import SECP256R1_ALPHA as ALPHA"#;
+
 pub(crate) const SHA256_INPUT: &str = r#"ids.full_word = int(ids.n_bytes >= 4)"#;
 
 pub(crate) const SHA256_MAIN: &str = r#"from starkware.cairo.common.cairo_sha256.sha256_utils import (
@@ -530,6 +595,15 @@ y0 = pack(ids.point0.y, PRIME)
 
 value = new_x = (pow(slope, 2, SECP_P) - x0 - x1) % SECP_P"#;
 
+pub(crate) const FAST_EC_ADD_ASSIGN_NEW_X_V2: &str = r#"from starkware.cairo.common.cairo_secp.secp_utils import pack
+
+slope = pack(ids.slope, PRIME)
+x0 = pack(ids.point0.x, PRIME)
+x1 = pack(ids.point1.x, PRIME)
+y0 = pack(ids.point0.y, PRIME)
+
+value = new_x = (pow(slope, 2, SECP_P) - x0 - x1) % SECP_P"#;
+
 pub(crate) const FAST_EC_ADD_ASSIGN_NEW_Y: &str =
     r#"value = new_y = (slope * (x0 - new_x) - y0) % SECP_P"#;
 
@@ -539,3 +613,159 @@ pub(crate) const RELOCATE_SEGMENT: &str =
     r#"memory.add_relocation_rule(src_ptr=ids.src_ptr, dest_ptr=ids.dest_ptr)"#;
 
 pub(crate) const TEMPORARY_ARRAY: &str = r#"ids.temporary_array = segments.add_temp_segment()"#;
+
+pub(crate) const SIMPLE_BOOTLOADER_LOAD_INPUT: &str = r#"from starkware.cairo.bootloaders.simple_bootloader.objects import SimpleBootloaderInput
+simple_bootloader_input = SimpleBootloaderInput.Schema().load(program_input)"#;
+
+pub(crate) const SIMPLE_BOOTLOADER_PREPARE_TASK_RANGE_CHECKS: &str = r#"n_tasks = len(simple_bootloader_input.tasks)
+memory[ids.output_ptr] = n_tasks
+
+# Task range checks are located right after simple bootloader validation range checks, and
+# this is validated later in this function.
+ids.task_range_check_ptr = ids.range_check_ptr + ids.BuiltinData.SIZE * n_tasks
+
+# A list of fact_toplogies that instruct how to generate the fact from the program output
+# for each task.
+fact_topologies = []"#;
+
+pub(crate) const SIMPLE_BOOTLOADER_SET_CURRENT_TASK: &str = r#"from starkware.cairo.bootloaders.simple_bootloader.objects import Task
+
+# Pass current task to execute_task.
+task_id = len(simple_bootloader_input.tasks) - ids.n_tasks
+task = simple_bootloader_input.tasks[task_id].load_task()"#;
+
+pub(crate) const SIMPLE_BOOTLOADER_CONFIGURE_FACT_TOPOLOGIES: &str = r#"# Dump fact topologies to a json file.
+from starkware.cairo.bootloaders.simple_bootloader.utils import (
+    configure_fact_topologies,
+    write_to_fact_topologies_file,
+)
+
+# The task-related output is prefixed by a single word that contains the number of tasks.
+tasks_output_start = output_builtin.base + 1
+
+if not simple_bootloader_input.single_page:
+    # Configure the memory pages in the output builtin, based on fact_topologies.
+    configure_fact_topologies(
+        fact_topologies=fact_topologies, output_start=tasks_output_start,
+        output_builtin=output_builtin,
+    )
+
+if simple_bootloader_input.fact_topologies_path is not None:
+    write_to_fact_topologies_file(
+        fact_topologies_path=simple_bootloader_input.fact_topologies_path,
+        fact_topologies=fact_topologies,
+    )"#;
+
+pub(crate) const EXECUTE_TASK_ALLOCATE_PROGRAM_DATA_SEGMENT: &str =
+    r#"ids.program_data_ptr = program_data_base = segments.add()"#;
+
+pub(crate) const EXECUTE_TASK_LOAD_PROGRAM: &str = r#"from starkware.cairo.bootloaders.simple_bootloader.utils import load_program
+
+# Call load_program to load the program header and code to memory.
+program_address, program_data_size = load_program(
+    task=task, memory=memory, program_header=ids.program_header,
+    builtins_offset=ids.ProgramHeader.builtin_list)
+segments.finalize(program_data_base.segment_index, program_data_size)"#;
+
+pub(crate) const EXECUTE_TASK_VALIDATE_HASH: &str = r#"# Validate hash.
+from starkware.cairo.bootloaders.hash_program import compute_program_hash_chain
+
+assert memory[ids.output_ptr + 1] == compute_program_hash_chain(task.get_program()), \
+  'Computed hash does not match input.'"#;
+
+pub(crate) const EXECUTE_TASK_ASSERT_PROGRAM_ADDRESS: &str = r#"# Sanity check.
+assert ids.program_address == program_address"#;
+
+pub(crate) const EXECUTE_TASK_CALL_TASK: &str = r#"from starkware.cairo.bootloaders.simple_bootloader.objects import (
+    CairoPieTask,
+    RunProgramTask,
+    Task,
+)
+from starkware.cairo.bootloaders.simple_bootloader.utils import (
+    load_cairo_pie,
+    prepare_output_runner,
+)
+
+assert isinstance(task, Task)
+n_builtins = len(task.get_program().builtins)
+new_task_locals = {}
+if isinstance(task, RunProgramTask):
+    new_task_locals['program_input'] = task.program_input
+    new_task_locals['WITH_BOOTLOADER'] = True
+
+    vm_load_program(task.program, program_address)
+elif isinstance(task, CairoPieTask):
+    ret_pc = ids.ret_pc_label.instruction_offset_ - ids.call_task.instruction_offset_ + pc
+    load_cairo_pie(
+        task=task.cairo_pie, memory=memory, segments=segments,
+        program_address=program_address, execution_segment_address= ap - n_builtins,
+        builtin_runners=builtin_runners, ret_fp=fp, ret_pc=ret_pc)
+else:
+    raise NotImplementedError(f'Unexpected task type: {type(task).__name__}.')
+
+output_runner_data = prepare_output_runner(
+    task=task,
+    output_builtin=output_builtin,
+    output_ptr=ids.pre_execution_builtin_ptrs.output)
+vm_enter_scope(new_task_locals)"#;
+
+pub(crate) const EXECUTE_TASK_WRITE_RETURN_BUILTINS: &str = r#"from starkware.cairo.bootloaders.simple_bootloader.utils import write_return_builtins
+
+# Fill the values of all builtin pointers after executing the task.
+builtins = task.get_program().builtins
+write_return_builtins(
+    memory=memory, return_builtins_addr=ids.return_builtin_ptrs.address_,
+    used_builtins=builtins, used_builtins_addr=ids.used_builtins_addr,
+    pre_execution_builtins_addr=ids.pre_execution_builtin_ptrs.address_, task=task)
+
+vm_enter_scope({'n_selected_builtins': n_builtins})"#;
+
+pub(crate) const EXECUTE_TASK_APPEND_FACT_TOPOLOGIES: &str = r#"from starkware.cairo.bootloaders.simple_bootloader.utils import get_task_fact_topology
+
+# Add the fact topology of the current task to 'fact_topologies'.
+output_start = ids.pre_execution_builtin_ptrs.output
+output_end = ids.return_builtin_ptrs.output
+fact_topologies.append(get_task_fact_topology(
+    output_size=output_end - output_start,
+    task=task,
+    output_builtin=output_builtin,
+    output_runner_data=output_runner_data,
+))"#;
+
+pub(crate) const SELECT_BUILTINS_SELECT_BUILTIN: &str = r#"# A builtin should be selected iff its encoding appears in the selected encodings list
+# and the list wasn't exhausted.
+# Note that testing inclusion by a single comparison is possible since the lists are sorted.
+ids.select_builtin = int(
+  n_selected_builtins > 0 and memory[ids.selected_encodings] == memory[ids.all_encodings])
+if ids.select_builtin:
+  n_selected_builtins = n_selected_builtins - 1"#;
+
+pub(crate) const SET_TREE_STRUCTURE: &str = r#"from starkware.python.math_utils import div_ceil
+onchain_data_start = ids.da_start
+onchain_data_size = ids.output_ptr - onchain_data_start
+
+max_page_size = 3800
+n_pages = div_ceil(onchain_data_size, max_page_size)
+for i in range(n_pages):
+    start_offset = i * max_page_size
+    output_builtin.add_page(
+        page_id=1 + i,
+        page_start=onchain_data_start + start_offset,
+        page_size=min(onchain_data_size - start_offset, max_page_size),
+    )
+# Set the tree structure to a root with two children:
+# * A leaf which represents the main part
+# * An inner node for the onchain data part (which contains n_pages children).
+#
+# This is encoded using the following sequence:
+output_builtin.add_attribute('gps_fact_topology', [
+    # Push 1 + n_pages pages (all of the pages).
+    1 + n_pages,
+    # Create a parent node for the last n_pages.
+    n_pages,
+    # Don't push additional pages.
+    0,
+    # Take the first page (the main part) and the node that was created (onchain data)
+    # and use them to construct the root of the fact tree.
+    2,
+])"#;
@@ -0,0 +1,280 @@
+use felt::Felt;
+
+use crate::hint_processor::builtin_hint_processor::hint_utils::{
+    get_reference_from_var_name, get_relocatable_from_var_name,
+};
+use crate::hint_processor::hint_processor_definition::HintReference;
+use crate::hint_processor::hint_processor_utils::{
+    compute_addr_from_reference, get_maybe_relocatable_from_reference,
+};
+use crate::serde::deserialize_program::{ApTracking, Identifier};
+use crate::types::exec_scope::ExecutionScopes;
+use crate::types::relocatable::{MaybeRelocatable, Relocatable};
+use crate::vm::errors::hint_errors::HintError;
+use crate::vm::errors::vm_errors::VirtualMachineError;
+use crate::vm::typed_value::{CairoType, ValueInspector};
+use crate::vm::vm_core::VirtualMachine;
+use std::{collections::HashMap, rc::Rc};
+
+/// A type the value of an ids variable can be read as.
+pub trait IdsValue: Sized {
+    /// Converts the value, or gives it back if it has another type.
+    fn try_from_value(value: MaybeRelocatable) -> Result<Self, MaybeRelocatable>;
+}
+
+impl IdsValue for Felt {
+    fn try_from_value(value: MaybeRelocatable) -> Result<Self, MaybeRelocatable> {
+        match value {
+            MaybeRelocatable::Int(num) => Ok(num),
+            value => Err(value),
+        }
+    }
+}
+
+impl IdsValue for Relocatable {
+    fn try_from_value(value: MaybeRelocatable) -> Result<Self, MaybeRelocatable> {
+        match value {
+            MaybeRelocatable::RelocatableValue(addr) => Ok(addr),
+            value => Err(value),
+        }
+    }
+}
+
+impl IdsValue for MaybeRelocatable {
+    fn try_from_value(value: MaybeRelocatable) -> Result<Self, MaybeRelocatable> {
+        Ok(value)
+    }
+}
+
+/// The ids variables of a hint, bound to the VM and the ap-tracking data of the hint, as an
+/// alternative to the `*_from_var_name` helpers of `hint_utils`.
+/// Struct members are accessed by name, with their offsets taken from the program identifiers
+/// that the runner stores in the execution scopes. Nested members are separated by dots (`value.low`), and
+/// pointers to structs are followed as in Cairo.
+pub struct Ids<'a> {
+    vm: &'a mut VirtualMachine,
+    ids_data: &'a HashMap<String, HintReference>,
+    ap_tracking: &'a ApTracking,
+    identifiers: Option<Rc<HashMap<String, Identifier>>>,
+}
+
+impl<'a> Ids<'a> {
+    pub fn new(
+        vm: &'a mut VirtualMachine,
+        exec_scopes: &ExecutionScopes,
+        ids_data: &'a HashMap<String, HintReference>,
+        ap_tracking: &'a ApTracking,
+    ) -> Ids<'a> {
+        Ids {
+            vm,
+            ids_data,
+            ap_tracking,
+            identifiers: exec_scopes.get_program_identifiers().ok(),
+        }
+    }
+
+    pub fn vm(&mut self) -> &mut VirtualMachine {
+        self.vm
+    }
+
+    /// Returns the address of the variable.
+    pub fn address(&self, name: &str) -> Result<Relocatable, HintError> {
+        get_relocatable_from_var_name(name, self.vm, self.ids_data, self.ap_tracking)
+    }
+
+    /// Returns the value of the variable. References that aren't dereferenced, such as
+    /// `cast(fp, felt*)`, evaluate to their address.
+    pub fn get<T: IdsValue>(&self, name: &str) -> Result<T, HintError> {
+        let reference = get_reference_from_var_name(name, self.ids_data)?;
+        let value = get_maybe_relocatable_from_reference(self.vm, reference, self.ap_tracking)?;
+        T::try_from_value(value).map_err(|value| HintError::WrongIdsValue(name.to_string(), value))
+    }
+
+    /// Writes the value of the variable.
+    pub fn set(&mut self, name: &str, value: impl Into<MaybeRelocatable>) -> Result<(), HintError> {
+        let addr = self.address(name)?;
+        self.vm
+            .insert_value(&addr, value)
+            .map_err(HintError::Internal)
+    }
+
+    /// Returns the address of a member of the variable.
+    pub fn member_address(&self, name: &str, member: &str) -> Result<Relocatable, HintError> {
+        let reference = get_reference_from_var_name(name, self.ids_data)?;
+        let unknown_type = || HintError::UnknownIdsType(name.to_string());
+        let cairo_type = reference.cairo_type.as_deref().ok_or_else(unknown_type)?;
+        let inspector = ValueInspector::new(self.identifiers.as_deref().ok_or_else(unknown_type)?);
+        // A reference that isn't dereferenced is a pointer to a value of its type, stored
+        // at the address it evaluates to
+        let mut cairo_type = inspector.parse_type(cairo_type)?;
+        let mut addr = compute_addr_from_reference(reference, self.vm, self.ap_tracking)?;
+        for member_name in member.split('.') {
+            if let CairoType::Pointer(pointee) = cairo_type {
+                addr = self.vm.get_relocatable(&addr)?;
+                cairo_type = *pointee;
+            }
+            let (offset, member_type) = inspector.get_member(&cairo_type, member_name)?;
+            addr = addr + offset;
+            cairo_type = member_type;
+        }
+        Ok(addr)
+    }
+
+    /// Returns the value of a member of the variable.
+    pub fn member<T: IdsValue>(&self, name: &str, member: &str) -> Result<T, HintError> {
+        let addr = self.member_address(name, member)?;
+        let value = self
+            .vm
+            .get_maybe(&addr)
+            .map_err(VirtualMachineError::MemoryError)?
+            .ok_or_else(|| VirtualMachineError::MemoryGet(addr.into()))?;
+        T::try_from_value(value)
+            .map_err(|value| HintError::WrongIdsValue(format!("{}.{}", name, member), value))
+    }
+
+    /// Writes the value of a member of the variable.
+    pub fn set_member(
+        &mut self,
+        name: &str,
+        member: &str,
+        value: impl Into<MaybeRelocatable>,
+    ) -> Result<(), HintError> {
+        let addr = self.member_address(name, member)?;
+        self.vm
+            .insert_value(&addr, value)
+            .map_err(HintError::Internal)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        types::program::Program,
+        utils::test_utils::*,
+        vm::{
+            errors::{memory_errors::MemoryError, typed_value_errors::TypedValueError},
+            runners::cairo_runner::CairoRunner,
+            vm_memory::memory::Memory,
+        },
+    };
+    use felt::NewFelt;
+
+    fn identifiers() -> HashMap<String, Identifier> {
+        HashMap::from([
+            (
+                String::from("starkware.cairo.common.uint256.Uint256"),
+                struct_identifier(
+                    "starkware.cairo.common.uint256.Uint256",
+                    &[("low", "felt", 0), ("high", "felt", 1)],
+                ),
+            ),
+            (
+                String::from("__main__.Wrapper"),
+                struct_identifier(
+                    "__main__.Wrapper",
+                    &[
+                        ("tag", "felt", 0),
+                        ("value", "starkware.cairo.common.uint256.Uint256", 1),
+                    ],
+                ),
+            ),
+        ])
+    }
+
+    fn reference(offset: i32, dereference: bool, cairo_type: &str) -> HintReference {
+        let mut reference = HintReference::new(offset, 0, false, dereference);
+        reference.cairo_type = Some(cairo_type.to_string());
+        reference
+    }
+
+    #[test]
+    fn get_and_set_values() {
+        let mut vm = vm!();
+        vm.run_context.fp = 2;
+        vm.memory = memory![((1, 0), 7), ((1, 1), (2, 0))];
+        let ids_data = HashMap::from([
+            (String::from("x"), reference(-2, true, "felt")),
+            (String::from("ptr"), reference(-1, true, "felt*")),
+            (String::from("addr"), reference(-2, false, "felt")),
+            (String::from("res"), reference(0, true, "felt")),
+        ]);
+        let ap_tracking = ApTracking::new();
+        let mut ids = Ids::new(&mut vm, &ExecutionScopes::new(), &ids_data, &ap_tracking);
+
+        assert_eq!(ids.get::<Felt>("x"), Ok(Felt::new(7)));
+        assert_eq!(ids.get::<Relocatable>("ptr"), Ok((2, 0).into()));
+        assert_eq!(ids.get::<Relocatable>("addr"), Ok((1, 0).into()));
+        assert_eq!(
+            ids.get::<Felt>("ptr"),
+            Err(HintError::WrongIdsValue(
+                String::from("ptr"),
+                MaybeRelocatable::from((2, 0))
+            ))
+        );
+        assert_eq!(ids.get::<Felt>("y"), Err(HintError::FailedToGetIds));
+
+        ids.set("res", Felt::new(9)).unwrap();
+        assert_eq!(
+            ids.get::<MaybeRelocatable>("res"),
+            Ok(MaybeRelocatable::from(9))
+        );
+    }
+
+    #[test]
+    fn get_and_set_members() {
+        let mut vm = vm!();
+        vm.run_context.fp = 3;
+        // A Wrapper at fp - 3, and a pointer to it at fp
+        vm.memory = memory![((1, 0), 1), ((1, 1), 10), ((1, 3), (1, 0))];
+        let ids_data = HashMap::from([
+            (String::from("a"), reference(-3, true, "Wrapper")),
+            (String::from("ptr"), reference(0, true, "Wrapper*")),
+            (String::from("b"), reference(-2, false, "Uint256")),
+        ]);
+        let ap_tracking = ApTracking::new();
+        let identifiers = identifiers();
+        let program = program!(identifiers = identifiers,);
+        let runner = cairo_runner!(program);
+        let mut ids = Ids::new(&mut vm, &runner.exec_scopes, &ids_data, &ap_tracking);
+
+        assert_eq!(ids.member::<Felt>("a", "tag"), Ok(Felt::new(1)));
+        assert_eq!(ids.member::<Felt>("a", "value.low"), Ok(Felt::new(10)));
+        assert_eq!(ids.member::<Felt>("ptr", "value.low"), Ok(Felt::new(10)));
+        assert_eq!(ids.member_address("b", "high"), Ok((1, 2).into()));
+
+        ids.set_member("ptr", "value.high", Felt::new(20)).unwrap();
+        assert_eq!(ids.member::<Felt>("a", "value.high"), Ok(Felt::new(20)));
+        assert_eq!(ids.member::<Felt>("b", "high"), Ok(Felt::new(20)));
+        assert_eq!(
+            ids.member::<Felt>("a", "size"),
+            Err(HintError::TypedValue(TypedValueError::UnknownMember(
+                String::from("__main__.Wrapper"),
+                String::from("size")
+            )))
+        );
+    }
+
+    #[test]
+    fn members_need_type_and_identifiers() {
+        let mut vm = vm!();
+        let ids_data = HashMap::from([
+            (String::from("a"), reference(0, true, "Uint256")),
+            (String::from("untyped"), HintReference::new_simple(0)),
+        ]);
+        let ap_tracking = ApTracking::new();
+        let ids = Ids::new(&mut vm, &ExecutionScopes::new(), &ids_data, &ap_tracking);
+        assert_eq!(
+            ids.member_address("a", "low"),
+            Err(HintError::UnknownIdsType(String::from("a")))
+        );
+
+        let mut exec_scopes = ExecutionScopes::new();
+        exec_scopes.insert_value("program_identifiers", Rc::new(identifiers()));
+        let ids = Ids::new(&mut vm, &exec_scopes, &ids_data, &ap_tracking);
+        assert_eq!(
+            ids.member_address("untyped", "low"),
+            Err(HintError::UnknownIdsType(String::from("untyped")))
+        );
+    }
+}
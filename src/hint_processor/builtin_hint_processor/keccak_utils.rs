@@ -5,6 +5,7 @@ use crate::{
         },
         hint_processor_definition::HintReference,
     },
+    math_utils::keccak::keccak256,
     serde::deserialize_program::ApTracking,
     types::{
         exec_scope::ExecutionScopes,
@@ -17,7 +18,6 @@ use crate::{
 };
 use felt::{Felt, FeltOps};
 use num_traits::{One, Signed, ToPrimitive};
-use sha3::{Digest, Keccak256};
 use std::{cmp, collections::HashMap, ops::Shl};
 
 /* Implements hint:
@@ -94,10 +94,7 @@ pub fn unsafe_keccak(
         keccak_input.append(&mut bytes);
     }
 
-    let mut hasher = Keccak256::new();
-    hasher.update(keccak_input);
-
-    let hashed = hasher.finalize();
+    let hashed = keccak256(&keccak_input);
 
     let high = Felt::from_bytes_be(&hashed[..16]);
     let low = Felt::from_bytes_be(&hashed[16..32]);
@@ -179,10 +176,7 @@ pub fn unsafe_keccak_finalize(
         keccak_input.append(&mut bytes);
     }
 
-    let mut hasher = Keccak256::new();
-    hasher.update(keccak_input);
-
-    let hashed = hasher.finalize();
+    let hashed = keccak256(&keccak_input);
 
     let high_addr = get_relocatable_from_var_name("high", vm, ids_data, ap_tracking)?;
     let low_addr = get_relocatable_from_var_name("low", vm, ids_data, ap_tracking)?;
@@ -202,13 +196,6 @@ fn left_pad(bytes_vector: &mut [u8], n_zeros: usize) -> Vec<u8> {
     res
 }
 
-pub(crate) fn left_pad_u64(bytes_vector: &mut [u64], n_zeros: usize) -> Vec<u64> {
-    let mut res: Vec<u64> = vec![0; n_zeros];
-    res.extend(bytes_vector.iter());
-
-    res
-}
-
 fn check_no_nones_in_range<T>(range: &Vec<Option<T>>) -> Result<(), VirtualMachineError> {
     for memory_cell in range {
         memory_cell
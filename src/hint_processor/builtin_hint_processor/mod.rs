@@ -1,5 +1,6 @@
 pub mod blake2s_hash;
 pub mod blake2s_utils;
+pub mod bootloader;
 pub mod builtin_hint_processor_definition;
 pub mod cairo_keccak;
 pub mod dict_hint_utils;
@@ -7,10 +8,12 @@ pub mod dict_manager;
 pub mod find_element_hint;
 pub mod hint_code;
 pub mod hint_utils;
+pub mod ids;
 pub mod keccak_utils;
 pub mod math_utils;
 pub mod memcpy_hint_utils;
 pub mod memset_utils;
+pub mod output_builtin_hints;
 pub mod pow_utils;
 pub mod secp;
 pub mod segments;
@@ -0,0 +1,200 @@
+use crate::{
+    hint_processor::{
+        builtin_hint_processor::{
+            bootloader::utils::GPS_FACT_TOPOLOGY, hint_utils::get_ptr_from_var_name,
+        },
+        hint_processor_definition::HintReference,
+    },
+    serde::deserialize_program::ApTracking,
+    vm::{
+        errors::{hint_errors::HintError, vm_errors::VirtualMachineError},
+        vm_core::VirtualMachine,
+    },
+};
+use num_integer::div_ceil;
+use std::collections::HashMap;
+
+// The maximum size of the pages added by the StarkNet OS for its onchain data
+const MAX_PAGE_SIZE: usize = 3800;
+
+/*
+Implements hint (from the StarkNet OS):
+%{
+    from starkware.python.math_utils import div_ceil
+    onchain_data_start = ids.da_start
+    onchain_data_size = ids.output_ptr - onchain_data_start
+
+    max_page_size = 3800
+    n_pages = div_ceil(onchain_data_size, max_page_size)
+    for i in range(n_pages):
+        start_offset = i * max_page_size
+        output_builtin.add_page(
+            page_id=1 + i,
+            page_start=onchain_data_start + start_offset,
+            page_size=min(onchain_data_size - start_offset, max_page_size),
+        )
+    # Set the tree structure to a root with two children:
+    # * A leaf which represents the main part
+    # * An inner node for the onchain data part (which contains n_pages children).
+    #
+    # This is encoded using the following sequence:
+    output_builtin.add_attribute('gps_fact_topology', [
+        # Push 1 + n_pages pages (all of the pages).
+        1 + n_pages,
+        # Create a parent node for the last n_pages.
+        n_pages,
+        # Don't push additional pages.
+        0,
+        # Take the first page (the main part) and the node that was created (onchain data)
+        # and use them to construct the root of the fact tree.
+        2,
+    ])
+%}
+*/
+pub fn set_tree_structure(
+    vm: &mut VirtualMachine,
+    ids_data: &HashMap<String, HintReference>,
+    ap_tracking: &ApTracking,
+) -> Result<(), HintError> {
+    let onchain_data_start = get_ptr_from_var_name("da_start", vm, ids_data, ap_tracking)?;
+    let output_ptr = get_ptr_from_var_name("output_ptr", vm, ids_data, ap_tracking)?;
+    let onchain_data_size = output_ptr.sub(&onchain_data_start)?;
+
+    let n_pages = div_ceil(onchain_data_size, MAX_PAGE_SIZE);
+    let output_builtin = vm.get_output_builtin()?;
+    for i in 0..n_pages {
+        let start_offset = i * MAX_PAGE_SIZE;
+        output_builtin
+            .add_page(
+                1 + i,
+                onchain_data_start + start_offset,
+                (onchain_data_size - start_offset).min(MAX_PAGE_SIZE),
+            )
+            .map_err(VirtualMachineError::RunnerError)?;
+    }
+    output_builtin.add_attribute(
+        GPS_FACT_TOPOLOGY.to_string(),
+        vec![1 + n_pages, n_pages, 0, 2],
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        any_box,
+        hint_processor::{
+            builtin_hint_processor::{
+                builtin_hint_processor_definition::{BuiltinHintProcessor, HintProcessorData},
+                hint_code,
+            },
+            hint_processor_definition::HintProcessor,
+        },
+        types::{exec_scope::ExecutionScopes, relocatable::MaybeRelocatable},
+        utils::test_utils::*,
+        vm::{
+            errors::{memory_errors::MemoryError, runner_errors::RunnerError},
+            runners::builtin_runner::{OutputBuiltinRunner, PublicMemoryPage},
+            vm_memory::memory::Memory,
+        },
+    };
+    use std::any::Any;
+
+    #[test]
+    fn run_set_tree_structure() {
+        let mut vm = vm!();
+        vm.builtin_runners = vec![("output".to_string(), OutputBuiltinRunner::new(true).into())];
+        vm.run_context.fp = 2;
+        vm.memory = memory![((1, 0), (0, 1)), ((1, 1), (0, 3))];
+        let ids_data = ids_data!["da_start", "output_ptr"];
+
+        assert_eq!(
+            run_hint!(vm, ids_data, hint_code::SET_TREE_STRUCTURE),
+            Ok(())
+        );
+        let output_builtin = vm.get_output_builtin().unwrap();
+        assert_eq!(
+            output_builtin.get_pages(),
+            &HashMap::from([(1, PublicMemoryPage { start: 1, size: 2 })])
+        );
+        assert_eq!(
+            output_builtin.get_attributes(),
+            &HashMap::from([(GPS_FACT_TOPOLOGY.to_string(), vec![2, 1, 0, 2])])
+        );
+    }
+
+    #[test]
+    fn run_set_tree_structure_several_pages() {
+        let mut vm = vm!();
+        vm.builtin_runners = vec![("output".to_string(), OutputBuiltinRunner::new(true).into())];
+        vm.run_context.fp = 2;
+        vm.memory = memory![((1, 0), (0, 0)), ((1, 1), (0, 7601))];
+        let ids_data = ids_data!["da_start", "output_ptr"];
+
+        assert_eq!(
+            run_hint!(vm, ids_data, hint_code::SET_TREE_STRUCTURE),
+            Ok(())
+        );
+        let output_builtin = vm.get_output_builtin().unwrap();
+        assert_eq!(
+            output_builtin.get_pages(),
+            &HashMap::from([
+                (
+                    1,
+                    PublicMemoryPage {
+                        start: 0,
+                        size: 3800
+                    }
+                ),
+                (
+                    2,
+                    PublicMemoryPage {
+                        start: 3800,
+                        size: 3800
+                    }
+                ),
+                (
+                    3,
+                    PublicMemoryPage {
+                        start: 7600,
+                        size: 1
+                    }
+                )
+            ])
+        );
+        assert_eq!(
+            output_builtin.get_attributes(),
+            &HashMap::from([(GPS_FACT_TOPOLOGY.to_string(), vec![4, 3, 0, 2])])
+        );
+    }
+
+    #[test]
+    fn run_set_tree_structure_outside_output_segment() {
+        let mut vm = vm!();
+        vm.builtin_runners = vec![("output".to_string(), OutputBuiltinRunner::new(true).into())];
+        vm.run_context.fp = 2;
+        vm.memory = memory![((1, 0), (2, 0)), ((1, 1), (2, 1))];
+        let ids_data = ids_data!["da_start", "output_ptr"];
+
+        assert_eq!(
+            run_hint!(vm, ids_data, hint_code::SET_TREE_STRUCTURE),
+            Err(HintError::Internal(VirtualMachineError::RunnerError(
+                RunnerError::PageNotOnOutputSegment((2, 0).into(), 0)
+            )))
+        );
+    }
+
+    #[test]
+    fn run_set_tree_structure_no_output_builtin() {
+        let mut vm = vm!();
+        vm.run_context.fp = 2;
+        vm.memory = memory![((1, 0), (0, 1)), ((1, 1), (0, 3))];
+        let ids_data = ids_data!["da_start", "output_ptr"];
+
+        assert_eq!(
+            run_hint!(vm, ids_data, hint_code::SET_TREE_STRUCTURE),
+            Err(HintError::Internal(VirtualMachineError::NoOutputBuiltin))
+        );
+    }
+}
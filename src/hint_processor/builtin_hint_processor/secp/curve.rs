@@ -0,0 +1,125 @@
+use crate::types::exec_scope::ExecutionScopes;
+use crate::vm::errors::hint_errors::HintError;
+use lazy_static::lazy_static;
+use num_bigint::BigInt;
+use num_traits::Num;
+
+// The scope variable holding the curve whose constants were last imported by a hint.
+pub(crate) const SECP_CURVE: &str = "secp_curve";
+
+/// The parameters of a curve y^2 = x^3 + alpha * x + beta over GF(p), with a group of order n,
+/// that the secp hints operate on.
+#[derive(Debug, PartialEq, Eq)]
+pub struct SecpCurve {
+    pub name: &'static str,
+    /// The field prime, SECP_P in the hints.
+    pub p: BigInt,
+    /// The order of the group, N in the hints.
+    pub n: BigInt,
+    pub alpha: BigInt,
+    pub beta: BigInt,
+}
+
+fn from_hex(value: &str) -> BigInt {
+    BigInt::from_str_radix(value, 16).expect("Invalid curve constant")
+}
+
+lazy_static! {
+    static ref SECP256K1: SecpCurve = SecpCurve {
+        name: "secp256k1",
+        p: from_hex("fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f"),
+        n: from_hex("fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141"),
+        alpha: BigInt::from(0),
+        beta: BigInt::from(7),
+    };
+    static ref SECP256R1: SecpCurve = SecpCurve {
+        name: "secp256r1",
+        p: from_hex("ffffffff00000001000000000000000000000000ffffffffffffffffffffffff"),
+        n: from_hex("ffffffff00000000ffffffffffffffffbce6faada7179e84f3b9cac2fc632551"),
+        alpha: from_hex("ffffffff00000001000000000000000000000000fffffffffffffffffffffffc"),
+        beta: from_hex("5ac635d8aa3a93e7b3ebbd55769886bc651d06b0cc53b0f63bce3c3e27d2604b"),
+    };
+}
+
+impl SecpCurve {
+    /// The curve of the cairo_secp library.
+    pub fn secp256k1() -> &'static SecpCurve {
+        &SECP256K1
+    }
+
+    /// The curve of the secp256r1 library, also known as P-256.
+    pub fn secp256r1() -> &'static SecpCurve {
+        &SECP256R1
+    }
+}
+
+/// Records the curve whose constants a hint imports (SECP_P, N, ALPHA), so that the hints that
+/// follow and refer to those constants operate on it.
+pub fn import_secp_curve(exec_scopes: &mut ExecutionScopes, curve: &'static SecpCurve) {
+    exec_scopes.insert_value(SECP_CURVE, curve);
+}
+
+/// Returns the curve whose constants were last imported into the scope.
+/// Fails if no hint imported a curve, as the constants the hint refers to aren't defined then.
+pub fn get_secp_curve(exec_scopes: &ExecutionScopes) -> Result<&'static SecpCurve, HintError> {
+    exec_scopes
+        .get::<&'static SecpCurve>(SECP_CURVE)
+        .map_err(|_| HintError::VariableNotInScopeError(String::from("SECP_P")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num_integer::Integer;
+
+    fn is_on_curve(curve: &SecpCurve, x: &BigInt, y: &BigInt) -> bool {
+        (y * y - x * x * x - &curve.alpha * x - &curve.beta)
+            .mod_floor(&curve.p)
+            .eq(&BigInt::from(0))
+    }
+
+    #[test]
+    fn generators_are_on_their_curves() {
+        assert!(is_on_curve(
+            SecpCurve::secp256k1(),
+            &from_hex("79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798"),
+            &from_hex("483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8"),
+        ));
+        assert!(is_on_curve(
+            SecpCurve::secp256r1(),
+            &from_hex("6b17d1f2e12c4247f8bce6e563a440f277037d812deb33a0f4a13945d898c296"),
+            &from_hex("4fe342e2fe1a7f9b8ee7eb4a7c0f9e162bce33576b315ececbb6406837bf51f5"),
+        ));
+    }
+
+    #[test]
+    fn secp256k1_matches_cairo_secp_constants() {
+        let curve = SecpCurve::secp256k1();
+        // SECP_P = 2**256 - SECP_REM
+        assert_eq!(
+            curve.p,
+            (BigInt::from(1) << 256_usize) - (BigInt::from(1) << 32_usize) - 977
+        );
+        // N = N0 + N1 * BASE + N2 * BASE**2
+        let base = BigInt::from(1) << 86_usize;
+        assert_eq!(
+            curve.n,
+            BigInt::from(10428087374290690730508609_u128)
+                + BigInt::from(77371252455330678278691517_u128) * &base
+                + BigInt::from(19342813113834066795298815_u128) * &base * &base
+        );
+    }
+
+    #[test]
+    fn get_imported_curve() {
+        let mut exec_scopes = ExecutionScopes::new();
+        assert_eq!(
+            get_secp_curve(&exec_scopes),
+            Err(HintError::VariableNotInScopeError(String::from("SECP_P")))
+        );
+        import_secp_curve(&mut exec_scopes, SecpCurve::secp256r1());
+        assert_eq!(get_secp_curve(&exec_scopes), Ok(SecpCurve::secp256r1()));
+        import_secp_curve(&mut exec_scopes, SecpCurve::secp256k1());
+        assert_eq!(get_secp_curve(&exec_scopes), Ok(SecpCurve::secp256k1()));
+    }
+}
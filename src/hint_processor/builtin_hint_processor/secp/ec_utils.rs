@@ -4,7 +4,10 @@ use crate::{
             hint_utils::{
                 get_integer_from_var_name, get_relocatable_from_var_name, insert_value_into_ap,
             },
-            secp::secp_utils::{pack, pack_from_relocatable, SECP_REM},
+            secp::{
+                curve::{get_secp_curve, import_secp_curve, SecpCurve},
+                secp_utils::{pack, pack_from_relocatable},
+            },
         },
         hint_processor_definition::HintReference,
     },
@@ -13,14 +16,11 @@ use crate::{
     types::exec_scope::ExecutionScopes,
     vm::{errors::hint_errors::HintError, vm_core::VirtualMachine},
 };
-use felt::{Felt, FeltOps};
+use felt::Felt;
 use num_bigint::BigInt;
 use num_integer::Integer;
-use num_traits::{One, Zero};
-use std::{
-    collections::HashMap,
-    ops::{BitAnd, Shl},
-};
+use num_traits::One;
+use std::{collections::HashMap, ops::BitAnd};
 
 /*
 Implements hint:
@@ -37,19 +37,12 @@ pub fn ec_negate(
     exec_scopes: &mut ExecutionScopes,
     ids_data: &HashMap<String, HintReference>,
     ap_tracking: &ApTracking,
-    constants: &HashMap<String, Felt>,
+    curve: &SecpCurve,
 ) -> Result<(), HintError> {
-    let secp_p = num_bigint::BigInt::one().shl(256u32)
-        - constants
-            .get(SECP_REM)
-            .ok_or(HintError::MissingConstant(SECP_REM))?
-            .clone()
-            .to_bigint();
-
     //ids.point
     let point_y = get_relocatable_from_var_name("point", vm, ids_data, ap_tracking)? + 3i32;
     let y = pack_from_relocatable(point_y, vm)?;
-    let value = (-y).mod_floor(&secp_p);
+    let value = (-y).mod_floor(&curve.p);
     exec_scopes.insert_value("value", value);
     Ok(())
 }
@@ -71,14 +64,8 @@ pub fn compute_doubling_slope(
     exec_scopes: &mut ExecutionScopes,
     ids_data: &HashMap<String, HintReference>,
     ap_tracking: &ApTracking,
-    constants: &HashMap<String, Felt>,
+    curve: &SecpCurve,
 ) -> Result<(), HintError> {
-    let secp_p = num_bigint::BigInt::one().shl(256usize)
-        - constants
-            .get(SECP_REM)
-            .ok_or(HintError::MissingConstant(SECP_REM))?
-            .to_bigint();
-
     //ids.point
     let point_reloc = get_relocatable_from_var_name("point", vm, ids_data, ap_tracking)?;
 
@@ -96,8 +83,8 @@ pub fn compute_doubling_slope(
             pack(x_d0.as_ref(), x_d1.as_ref(), x_d2.as_ref()),
             pack(y_d0.as_ref(), y_d1.as_ref(), y_d2.as_ref()),
         ),
-        &BigInt::zero(),
-        &secp_p,
+        &curve.alpha,
+        &curve.p,
     );
     exec_scopes.insert_value("value", value.clone());
     exec_scopes.insert_value("slope", value);
@@ -123,14 +110,8 @@ pub fn compute_slope(
     exec_scopes: &mut ExecutionScopes,
     ids_data: &HashMap<String, HintReference>,
     ap_tracking: &ApTracking,
-    constants: &HashMap<String, Felt>,
+    curve: &SecpCurve,
 ) -> Result<(), HintError> {
-    let secp_p = BigInt::one().shl(256usize)
-        - constants
-            .get(SECP_REM)
-            .ok_or(HintError::MissingConstant(SECP_REM))?
-            .to_bigint();
-
     //ids.point0
     let point0_reloc = get_relocatable_from_var_name("point0", vm, ids_data, ap_tracking)?;
 
@@ -180,7 +161,7 @@ pub fn compute_slope(
                 point1_y_d2.as_ref(),
             ),
         ),
-        &secp_p,
+        &curve.p,
     );
     exec_scopes.insert_value("value", value.clone());
     exec_scopes.insert_value("slope", value);
@@ -204,14 +185,8 @@ pub fn ec_double_assign_new_x(
     exec_scopes: &mut ExecutionScopes,
     ids_data: &HashMap<String, HintReference>,
     ap_tracking: &ApTracking,
-    constants: &HashMap<String, Felt>,
+    curve: &'static SecpCurve,
 ) -> Result<(), HintError> {
-    let secp_p = BigInt::one().shl(256usize)
-        - constants
-            .get(SECP_REM)
-            .ok_or(HintError::MissingConstant(SECP_REM))?
-            .to_bigint();
-
     //ids.slope
     let slope_reloc = get_relocatable_from_var_name("slope", vm, ids_data, ap_tracking)?;
 
@@ -237,9 +212,10 @@ pub fn ec_double_assign_new_x(
     let x = pack(x_d0.as_ref(), x_d1.as_ref(), x_d2.as_ref());
     let y = pack(y_d0.as_ref(), y_d1.as_ref(), y_d2.as_ref());
 
-    let value = (slope.pow(2) - (&x << 1u32)).mod_floor(&secp_p);
+    let value = (slope.pow(2) - (&x << 1u32)).mod_floor(&curve.p);
 
     //Assign variables to vm scope
+    import_secp_curve(exec_scopes, curve);
     exec_scopes.insert_value("slope", slope);
     exec_scopes.insert_value("x", x);
     exec_scopes.insert_value("y", y);
@@ -252,15 +228,8 @@ pub fn ec_double_assign_new_x(
 Implements hint:
 %{ value = new_y = (slope * (x - new_x) - y) % SECP_P %}
 */
-pub fn ec_double_assign_new_y(
-    exec_scopes: &mut ExecutionScopes,
-    constants: &HashMap<String, Felt>,
-) -> Result<(), HintError> {
-    let secp_p = BigInt::one().shl(256usize)
-        - constants
-            .get(SECP_REM)
-            .ok_or(HintError::MissingConstant(SECP_REM))?
-            .to_bigint();
+pub fn ec_double_assign_new_y(exec_scopes: &mut ExecutionScopes) -> Result<(), HintError> {
+    let curve = get_secp_curve(exec_scopes)?;
 
     //Get variables from vm scope
     let (slope, x, new_x, y) = (
@@ -270,7 +239,7 @@ pub fn ec_double_assign_new_y(
         exec_scopes.get::<BigInt>("y")?,
     );
 
-    let value = (slope * (x - new_x) - y).mod_floor(&secp_p);
+    let value = (slope * (x - new_x) - y).mod_floor(&curve.p);
     exec_scopes.insert_value("value", value.clone());
     exec_scopes.insert_value("new_y", value);
     Ok(())
@@ -294,14 +263,8 @@ pub fn fast_ec_add_assign_new_x(
     exec_scopes: &mut ExecutionScopes,
     ids_data: &HashMap<String, HintReference>,
     ap_tracking: &ApTracking,
-    constants: &HashMap<String, Felt>,
+    curve: &'static SecpCurve,
 ) -> Result<(), HintError> {
-    let secp_p = BigInt::one().shl(256usize)
-        - constants
-            .get(SECP_REM)
-            .ok_or(HintError::MissingConstant(SECP_REM))?
-            .to_bigint();
-
     //ids.slope
     let slope_reloc = get_relocatable_from_var_name("slope", vm, ids_data, ap_tracking)?;
 
@@ -349,8 +312,9 @@ pub fn fast_ec_add_assign_new_x(
         point0_y_d2.as_ref(),
     );
 
-    let value = (&slope * &slope - &x0 - &x1).mod_floor(&secp_p);
+    let value = (&slope * &slope - &x0 - &x1).mod_floor(&curve.p);
     //Assign variables to vm scope
+    import_secp_curve(exec_scopes, curve);
     exec_scopes.insert_value("slope", slope);
     exec_scopes.insert_value("x0", x0);
     exec_scopes.insert_value("y0", y0);
@@ -364,15 +328,8 @@ pub fn fast_ec_add_assign_new_x(
 Implements hint:
 %{ value = new_y = (slope * (x0 - new_x) - y0) % SECP_P %}
 */
-pub fn fast_ec_add_assign_new_y(
-    exec_scopes: &mut ExecutionScopes,
-    constants: &HashMap<String, Felt>,
-) -> Result<(), HintError> {
-    let secp_p = BigInt::one().shl(256usize)
-        - constants
-            .get(SECP_REM)
-            .ok_or(HintError::MissingConstant(SECP_REM))?
-            .to_bigint();
+pub fn fast_ec_add_assign_new_y(exec_scopes: &mut ExecutionScopes) -> Result<(), HintError> {
+    let curve = get_secp_curve(exec_scopes)?;
 
     //Get variables from vm scope
     let (slope, x0, new_x, y0) = (
@@ -381,7 +338,7 @@ pub fn fast_ec_add_assign_new_y(
         exec_scopes.get::<BigInt>("new_x")?,
         exec_scopes.get::<BigInt>("y0")?,
     );
-    let value = (slope * (x0 - new_x) - y0).mod_floor(&secp_p);
+    let value = (slope * (x0 - new_x) - y0).mod_floor(&curve.p);
     exec_scopes.insert_value("value", value.clone());
     exec_scopes.insert_value("new_y", value);
 
@@ -410,8 +367,9 @@ mod tests {
     use crate::{
         any_box,
         hint_processor::{
-            builtin_hint_processor::builtin_hint_processor_definition::{
-                BuiltinHintProcessor, HintProcessorData,
+            builtin_hint_processor::{
+                builtin_hint_processor_definition::{BuiltinHintProcessor, HintProcessorData},
+                hint_code,
             },
             hint_processor_definition::HintProcessor,
         },
@@ -439,28 +397,7 @@ mod tests {
         let ids_data = ids_data!["point"];
         let mut exec_scopes = ExecutionScopes::new();
         //Execute the hint
-        assert_eq!(
-            run_hint!(
-                vm,
-                ids_data,
-                hint_code,
-                &mut exec_scopes,
-                &[(
-                    SECP_REM,
-                    Felt::one().shl(32_u32)
-                        + Felt::one().shl(9_u32)
-                        + Felt::one().shl(8_u32)
-                        + Felt::one().shl(7_u32)
-                        + Felt::one().shl(6_u32)
-                        + Felt::one().shl(4_u32)
-                        + Felt::one()
-                )]
-                .into_iter()
-                .map(|(k, v)| (k.to_string(), v))
-                .collect()
-            ),
-            Ok(())
-        );
+        assert_eq!(run_hint!(vm, ids_data, hint_code, &mut exec_scopes), Ok(()));
         //Check 'value' is defined in the vm scope
         assert_eq!(
             exec_scopes.get::<BigInt>("value"),
@@ -490,28 +427,7 @@ mod tests {
         let mut exec_scopes = ExecutionScopes::new();
 
         //Execute the hint
-        assert_eq!(
-            run_hint!(
-                vm,
-                ids_data,
-                hint_code,
-                &mut exec_scopes,
-                &[(
-                    SECP_REM,
-                    Felt::one().shl(32_u32)
-                        + Felt::one().shl(9_u32)
-                        + Felt::one().shl(8_u32)
-                        + Felt::one().shl(7_u32)
-                        + Felt::one().shl(6_u32)
-                        + Felt::one().shl(4_u32)
-                        + Felt::one()
-                )]
-                .into_iter()
-                .map(|(k, v)| (k.to_string(), v))
-                .collect()
-            ),
-            Ok(())
-        );
+        assert_eq!(run_hint!(vm, ids_data, hint_code, &mut exec_scopes), Ok(()));
         check_scope!(
             &exec_scopes,
             [
@@ -561,28 +477,7 @@ mod tests {
         let mut exec_scopes = ExecutionScopes::new();
 
         //Execute the hint
-        assert_eq!(
-            run_hint!(
-                vm,
-                ids_data,
-                hint_code,
-                &mut exec_scopes,
-                &[(
-                    SECP_REM,
-                    Felt::one().shl(32_u32)
-                        + Felt::one().shl(9_u32)
-                        + Felt::one().shl(8_u32)
-                        + Felt::one().shl(7_u32)
-                        + Felt::one().shl(6_u32)
-                        + Felt::one().shl(4_u32)
-                        + Felt::one()
-                )]
-                .into_iter()
-                .map(|(k, v)| (k.to_string(), v))
-                .collect()
-            ),
-            Ok(())
-        );
+        assert_eq!(run_hint!(vm, ids_data, hint_code, &mut exec_scopes), Ok(()));
         check_scope!(
             &exec_scopes,
             [
@@ -629,28 +524,7 @@ mod tests {
         let mut exec_scopes = ExecutionScopes::new();
 
         //Execute the hint
-        assert_eq!(
-            run_hint!(
-                vm,
-                ids_data,
-                hint_code,
-                &mut exec_scopes,
-                &[(
-                    SECP_REM,
-                    Felt::one().shl(32_u32)
-                        + Felt::one().shl(9_u32)
-                        + Felt::one().shl(8_u32)
-                        + Felt::one().shl(7_u32)
-                        + Felt::one().shl(6_u32)
-                        + Felt::one().shl(4_u32)
-                        + Felt::one()
-                )]
-                .into_iter()
-                .map(|(k, v)| (k.to_string(), v))
-                .collect()
-            ),
-            Ok(())
-        );
+        assert_eq!(run_hint!(vm, ids_data, hint_code, &mut exec_scopes), Ok(()));
 
         check_scope!(
             &exec_scopes,
@@ -711,27 +585,11 @@ mod tests {
                 bigint_str!("4310143708685312414132851373791311001152018708061750480")
             )
         ];
+        // Imported by the hint computing new_x
+        import_secp_curve(&mut exec_scopes, SecpCurve::secp256k1());
         //Execute the hint
         assert_eq!(
-            run_hint!(
-                vm,
-                HashMap::new(),
-                hint_code,
-                &mut exec_scopes,
-                &[(
-                    SECP_REM,
-                    Felt::one().shl(32_u32)
-                        + Felt::one().shl(9_u32)
-                        + Felt::one().shl(8_u32)
-                        + Felt::one().shl(7_u32)
-                        + Felt::one().shl(6_u32)
-                        + Felt::one().shl(4_u32)
-                        + Felt::one()
-                )]
-                .into_iter()
-                .map(|(k, v)| (k.to_string(), v))
-                .collect()
-            ),
+            run_hint!(vm, HashMap::new(), hint_code, &mut exec_scopes),
             Ok(())
         );
 
@@ -789,28 +647,7 @@ mod tests {
         let mut exec_scopes = ExecutionScopes::new();
 
         //Execute the hint
-        assert_eq!(
-            run_hint!(
-                vm,
-                ids_data,
-                hint_code,
-                &mut exec_scopes,
-                &[(
-                    SECP_REM,
-                    Felt::one().shl(32_u32)
-                        + Felt::one().shl(9_u32)
-                        + Felt::one().shl(8_u32)
-                        + Felt::one().shl(7_u32)
-                        + Felt::one().shl(6_u32)
-                        + Felt::one().shl(4_u32)
-                        + Felt::one()
-                )]
-                .into_iter()
-                .map(|(k, v)| (k.to_string(), v))
-                .collect()
-            ),
-            Ok(())
-        );
+        assert_eq!(run_hint!(vm, ids_data, hint_code, &mut exec_scopes), Ok(()));
 
         check_scope!(
             &exec_scopes,
@@ -858,28 +695,12 @@ mod tests {
                 bigint_str!("4310143708685312414132851373791311001152018708061750480")
             )
         ];
+        // Imported by the hint computing new_x
+        import_secp_curve(&mut exec_scopes, SecpCurve::secp256k1());
 
         //Execute the hint
         assert_eq!(
-            run_hint!(
-                vm,
-                HashMap::new(),
-                hint_code,
-                &mut exec_scopes,
-                &[(
-                    SECP_REM,
-                    Felt::one().shl(32_u32)
-                        + Felt::one().shl(9_u32)
-                        + Felt::one().shl(8_u32)
-                        + Felt::one().shl(7_u32)
-                        + Felt::one().shl(6_u32)
-                        + Felt::one().shl(4_u32)
-                        + Felt::one()
-                )]
-                .into_iter()
-                .map(|(k, v)| (k.to_string(), v))
-                .collect()
-            ),
+            run_hint!(vm, HashMap::new(), hint_code, &mut exec_scopes),
             Ok(())
         );
 
@@ -902,6 +723,81 @@ mod tests {
         );
     }
 
+    #[test]
+    fn run_ec_double_secp256r1_ok() {
+        let mut vm = vm_with_range_check!();
+
+        //Insert ids.point (the secp256r1 generator) and ids.slope into memory
+        vm.memory = memory![
+            ((1, 0), 52227620040540588600771222_i128),
+            ((1, 1), 33347259622618539004134583_i128),
+            ((1, 2), 8091721874918813684698062_i128),
+            ((1, 3), 59685082318776612195095029_i128),
+            ((1, 4), 54599710628478995760242092_i128),
+            ((1, 5), 6036146923926000695307902_i128),
+            ((1, 6), 18381657395733761514123165_i128),
+            ((1, 7), 60250936249481871670557728_i128),
+            ((1, 8), 12262137276504867214243183_i128)
+        ];
+
+        //Initialize fp
+        vm.run_context.fp = 10;
+        let ids_data = HashMap::from([
+            ("point".to_string(), HintReference::new_simple(-10)),
+            ("slope".to_string(), HintReference::new_simple(-4)),
+        ]);
+        let mut exec_scopes = ExecutionScopes::new();
+
+        //Execute the hints
+        for hint_code in [
+            hint_code::IMPORT_SECP256R1_P,
+            hint_code::IMPORT_SECP256R1_ALPHA,
+            hint_code::EC_DOUBLE_SCOPE_V2,
+        ] {
+            assert_eq!(
+                run_hint!(vm, ids_data.clone(), hint_code, &mut exec_scopes),
+                Ok(())
+            );
+        }
+        check_scope!(
+            &exec_scopes,
+            [(
+                "slope",
+                bigint_str!(
+                    "73404963663004311880882944372748989162084677934852963787452504780932599885725"
+                )
+            )]
+        );
+
+        assert_eq!(
+            run_hint!(
+                vm,
+                ids_data,
+                hint_code::EC_DOUBLE_ASSIGN_NEW_X_V2,
+                &mut exec_scopes
+            ),
+            Ok(())
+        );
+        assert_eq!(ec_double_assign_new_y(&mut exec_scopes), Ok(()));
+        check_scope!(
+            &exec_scopes,
+            [
+                (
+                    "new_x",
+                    bigint_str!(
+                        "56515219790691171413109057904011688695424810155802929973526481321309856242040"
+                    )
+                ),
+                (
+                    "new_y",
+                    bigint_str!(
+                        "3377031843712258259223711451491452598088675519751548567112458094635497583569"
+                    )
+                )
+            ]
+        );
+    }
+
     #[test]
     fn run_ec_mul_inner_ok() {
         let hint_code = "memory[ap] = (ids.scalar % PRIME) % 2";
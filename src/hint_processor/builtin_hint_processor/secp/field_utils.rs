@@ -1,10 +1,7 @@
-use super::secp_utils::pack_from_var_name;
+use super::{curve::SecpCurve, secp_utils::pack_from_var_name};
 use crate::{
     hint_processor::{
-        builtin_hint_processor::{
-            hint_utils::{insert_value_from_var_name, insert_value_into_ap},
-            secp::secp_utils::SECP_REM,
-        },
+        builtin_hint_processor::hint_utils::{insert_value_from_var_name, insert_value_into_ap},
         hint_processor_definition::HintReference,
     },
     math_utils::div_mod,
@@ -12,11 +9,11 @@ use crate::{
     types::exec_scope::ExecutionScopes,
     vm::{errors::hint_errors::HintError, vm_core::VirtualMachine},
 };
-use felt::{Felt, FeltOps, NewFelt};
+use felt::{Felt, NewFelt};
 use num_bigint::BigInt;
 use num_integer::Integer;
 use num_traits::{One, Zero};
-use std::{collections::HashMap, ops::Shl};
+use std::collections::HashMap;
 
 /*
 Implements hint:
@@ -32,16 +29,10 @@ pub fn verify_zero(
     vm: &mut VirtualMachine,
     ids_data: &HashMap<String, HintReference>,
     ap_tracking: &ApTracking,
-    constants: &HashMap<String, Felt>,
+    curve: &SecpCurve,
 ) -> Result<(), HintError> {
-    let secp_p = BigInt::one().shl(256_u32)
-        - constants
-            .get(SECP_REM)
-            .ok_or(HintError::MissingConstant(SECP_REM))?
-            .to_bigint();
-
     let val = pack_from_var_name("val", vm, ids_data, ap_tracking)?;
-    let (q, r) = val.div_rem(&secp_p);
+    let (q, r) = val.div_rem(&curve.p);
     if !r.is_zero() {
         return Err(HintError::SecpVerifyZero(val));
     }
@@ -62,16 +53,10 @@ pub fn reduce(
     exec_scopes: &mut ExecutionScopes,
     ids_data: &HashMap<String, HintReference>,
     ap_tracking: &ApTracking,
-    constants: &HashMap<String, Felt>,
+    curve: &SecpCurve,
 ) -> Result<(), HintError> {
-    let secp_p = num_bigint::BigInt::one().shl(256_u32)
-        - constants
-            .get(SECP_REM)
-            .ok_or(HintError::MissingConstant(SECP_REM))?
-            .to_bigint();
-
     let value = pack_from_var_name("x", vm, ids_data, ap_tracking)?;
-    exec_scopes.insert_value("value", value.mod_floor(&secp_p));
+    exec_scopes.insert_value("value", value.mod_floor(&curve.p));
     Ok(())
 }
 
@@ -88,16 +73,10 @@ pub fn is_zero_pack(
     exec_scopes: &mut ExecutionScopes,
     ids_data: &HashMap<String, HintReference>,
     ap_tracking: &ApTracking,
-    constants: &HashMap<String, Felt>,
+    curve: &SecpCurve,
 ) -> Result<(), HintError> {
-    let secp_p = BigInt::one().shl(256_u32)
-        - constants
-            .get(SECP_REM)
-            .ok_or(HintError::MissingConstant(SECP_REM))?
-            .to_bigint();
-
     let x_packed = pack_from_var_name("x", vm, ids_data, ap_tracking)?;
-    let x = x_packed.mod_floor(&secp_p);
+    let x = x_packed.mod_floor(&curve.p);
     exec_scopes.insert_value("x", x);
     Ok(())
 }
@@ -136,18 +115,12 @@ Implements hint:
 */
 pub fn is_zero_assign_scope_variables(
     exec_scopes: &mut ExecutionScopes,
-    constants: &HashMap<String, Felt>,
+    curve: &SecpCurve,
 ) -> Result<(), HintError> {
-    let secp_p = BigInt::one().shl(256_u32)
-        - constants
-            .get(SECP_REM)
-            .ok_or(HintError::MissingConstant(SECP_REM))?
-            .to_bigint();
-
     //Get `x` variable from vm scope
     let x = exec_scopes.get::<BigInt>("x")?;
 
-    let value = div_mod(&BigInt::one(), &x, &secp_p);
+    let value = div_mod(&BigInt::one(), &x, &curve.p);
     exec_scopes.insert_value("value", value.clone());
     exec_scopes.insert_value("x_inv", value);
     Ok(())
@@ -159,8 +132,9 @@ mod tests {
     use crate::{
         any_box,
         hint_processor::{
-            builtin_hint_processor::builtin_hint_processor_definition::{
-                BuiltinHintProcessor, HintProcessorData,
+            builtin_hint_processor::{
+                builtin_hint_processor_definition::{BuiltinHintProcessor, HintProcessorData},
+                hint_code,
             },
             hint_processor_definition::HintProcessor,
         },
@@ -189,25 +163,7 @@ mod tests {
         vm.memory = memory![((1, 4), 0), ((1, 5), 0), ((1, 6), 0)];
         //Execute the hint
         assert_eq!(
-            run_hint!(
-                vm,
-                ids_data,
-                hint_code,
-                exec_scopes_ref!(),
-                &[(
-                    SECP_REM,
-                    Felt::one().shl(32_u32)
-                        + Felt::one().shl(9_u32)
-                        + Felt::one().shl(8_u32)
-                        + Felt::one().shl(7_u32)
-                        + Felt::one().shl(6_u32)
-                        + Felt::one().shl(4_u32)
-                        + Felt::one()
-                )]
-                .into_iter()
-                .map(|(k, v)| (k.to_string(), v))
-                .collect()
-            ),
+            run_hint!(vm, ids_data, hint_code, exec_scopes_ref!()),
             Ok(())
         );
         //Check hint memory inserts
@@ -227,25 +183,7 @@ mod tests {
         vm.memory = memory![((1, 4), 0), ((1, 5), 0), ((1, 6), 150)];
         //Execute the hint
         assert_eq!(
-            run_hint!(
-                vm,
-                ids_data,
-                hint_code,
-                exec_scopes_ref!(),
-                &[(
-                    SECP_REM,
-                    Felt::one().shl(32_u32)
-                        + Felt::one().shl(9_u32)
-                        + Felt::one().shl(8_u32)
-                        + Felt::one().shl(7_u32)
-                        + Felt::one().shl(6_u32)
-                        + Felt::one().shl(4_u32)
-                        + Felt::one()
-                )]
-                .into_iter()
-                .map(|(k, v)| (k.to_string(), v))
-                .collect()
-            ),
+            run_hint!(vm, ids_data, hint_code, exec_scopes_ref!()),
             Err(HintError::SecpVerifyZero(bigint_str!(
                 "897946605976106752944343961220884287276604954404454400"
             )))
@@ -266,25 +204,7 @@ mod tests {
         vm.memory = memory![((1, 4), 0), ((1, 5), 0), ((1, 6), 0), ((1, 9), 55)];
         //Execute the hint
         assert_eq!(
-            run_hint!(
-                vm,
-                ids_data,
-                hint_code,
-                exec_scopes_ref!(),
-                &[(
-                    SECP_REM,
-                    Felt::one().shl(32_u32)
-                        + Felt::one().shl(9_u32)
-                        + Felt::one().shl(8_u32)
-                        + Felt::one().shl(7_u32)
-                        + Felt::one().shl(6_u32)
-                        + Felt::one().shl(4_u32)
-                        + Felt::one()
-                )]
-                .into_iter()
-                .map(|(k, v)| (k.to_string(), v))
-                .collect()
-            ),
+            run_hint!(vm, ids_data, hint_code, exec_scopes_ref!()),
             Err(HintError::Internal(VirtualMachineError::MemoryError(
                 MemoryError::InconsistentMemory(
                     MaybeRelocatable::from((1, 9)),
@@ -315,34 +235,92 @@ mod tests {
 
         let mut exec_scopes = ExecutionScopes::new();
         //Execute the hint
+        assert_eq!(run_hint!(vm, ids_data, hint_code, &mut exec_scopes), Ok(()));
+
+        //Check 'value' is defined in the vm scope
+        assert_eq!(
+            exec_scopes.get::<BigInt>("value"),
+            Ok(bigint_str!(
+                "59863107065205964761754162760883789350782881856141750"
+            ))
+        );
+    }
+
+    #[test]
+    fn run_verify_zero_secp256r1_ok() {
+        let mut vm = vm_with_range_check!();
+        //Initialize run_context
+        run_context!(vm, 0, 9, 9);
+        //Create hint data
+        let ids_data = non_continuous_ids_data![("val", -5), ("q", 0)];
+        //ids.val = SECP256R1_P
+        vm.memory = memory![
+            ((1, 4), 77371252455336267181195263_i128),
+            ((1, 5), 1023),
+            ((1, 6), 19342813109330467168976896_i128)
+        ];
+        let mut exec_scopes = ExecutionScopes::new();
+        //Execute the hints
         assert_eq!(
             run_hint!(
                 vm,
-                ids_data,
-                hint_code,
-                &mut exec_scopes,
-                &[(
-                    SECP_REM,
-                    Felt::one().shl(32_u32)
-                        + Felt::one().shl(9_u32)
-                        + Felt::one().shl(8_u32)
-                        + Felt::one().shl(7_u32)
-                        + Felt::one().shl(6_u32)
-                        + Felt::one().shl(4_u32)
-                        + Felt::one()
-                )]
-                .into_iter()
-                .map(|(k, v)| (k.to_string(), v))
-                .collect()
+                HashMap::new(),
+                hint_code::IMPORT_SECP256R1_P,
+                &mut exec_scopes
             ),
             Ok(())
         );
+        assert_eq!(
+            run_hint!(vm, ids_data, hint_code::VERIFY_ZERO_V2, &mut exec_scopes),
+            Ok(())
+        );
+        //Check hint memory inserts
+        //ids.q
+        check_memory![&vm.memory, ((1, 9), 1)];
+    }
 
-        //Check 'value' is defined in the vm scope
+    #[test]
+    fn run_reduce_secp256r1_ok() {
+        let mut vm = vm_with_range_check!();
+
+        //Initialize fp
+        vm.run_context.fp = 25;
+
+        //Create hint data
+        let ids_data = non_continuous_ids_data![("x", -5)];
+
+        //ids.x = SECP256R1_P + 5
+        vm.memory = memory![
+            ((1, 20), 4),
+            ((1, 21), 1024),
+            ((1, 22), 19342813109330467168976896_i128)
+        ];
+
+        let mut exec_scopes = ExecutionScopes::new();
+        assert_eq!(
+            run_hint!(
+                vm,
+                HashMap::new(),
+                hint_code::IMPORT_SECP256R1_P,
+                &mut exec_scopes
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            run_hint!(vm, ids_data.clone(), hint_code::REDUCE_V2, &mut exec_scopes),
+            Ok(())
+        );
+        assert_eq!(exec_scopes.get::<BigInt>("value"), Ok(BigInt::from(5)));
+
+        //The cairo_secp hint imports the secp256k1 prime itself
+        assert_eq!(
+            run_hint!(vm, ids_data, hint_code::REDUCE, &mut exec_scopes),
+            Ok(())
+        );
         assert_eq!(
             exec_scopes.get::<BigInt>("value"),
             Ok(bigint_str!(
-                "59863107065205964761754162760883789350782881856141750"
+                "115792089210356248762697446949407573530086143415290314195533631308867097853956"
             ))
         );
     }
@@ -361,25 +339,7 @@ mod tests {
         //Skip ids.x values insert so the hint fails.
         //Execute the hint
         assert_eq!(
-            run_hint!(
-                vm,
-                ids_data,
-                hint_code,
-                exec_scopes_ref!(),
-                &[(
-                    SECP_REM,
-                    Felt::one().shl(32_u32)
-                        + Felt::one().shl(9_u32)
-                        + Felt::one().shl(8_u32)
-                        + Felt::one().shl(7_u32)
-                        + Felt::one().shl(6_u32)
-                        + Felt::one().shl(4_u32)
-                        + Felt::one()
-                )]
-                .into_iter()
-                .map(|(k, v)| (k.to_string(), v))
-                .collect()
-            ),
+            run_hint!(vm, ids_data, hint_code, exec_scopes_ref!()),
             Err(HintError::Internal(VirtualMachineError::ExpectedInteger(
                 MaybeRelocatable::from((1, 20))
             )))
@@ -406,28 +366,7 @@ mod tests {
         let mut exec_scopes = ExecutionScopes::new();
 
         //Execute the hint
-        assert_eq!(
-            run_hint!(
-                vm,
-                ids_data,
-                hint_code,
-                &mut exec_scopes,
-                &[(
-                    SECP_REM,
-                    Felt::one().shl(32_u32)
-                        + Felt::one().shl(9_u32)
-                        + Felt::one().shl(8_u32)
-                        + Felt::one().shl(7_u32)
-                        + Felt::one().shl(6_u32)
-                        + Felt::one().shl(4_u32)
-                        + Felt::one()
-                )]
-                .into_iter()
-                .map(|(k, v)| (k.to_string(), v))
-                .collect()
-            ),
-            Ok(())
-        );
+        assert_eq!(run_hint!(vm, ids_data, hint_code, &mut exec_scopes), Ok(()));
 
         //Check 'x' is defined in the vm scope
         check_scope!(
@@ -456,25 +395,7 @@ mod tests {
 
         //Execute the hint
         assert_eq!(
-            run_hint!(
-                vm,
-                ids_data,
-                hint_code,
-                exec_scopes_ref!(),
-                &[(
-                    SECP_REM,
-                    Felt::one().shl(32_u32)
-                        + Felt::one().shl(9_u32)
-                        + Felt::one().shl(8_u32)
-                        + Felt::one().shl(7_u32)
-                        + Felt::one().shl(6_u32)
-                        + Felt::one().shl(4_u32)
-                        + Felt::one()
-                )]
-                .into_iter()
-                .map(|(k, v)| (k.to_string(), v))
-                .collect()
-            ),
+            run_hint!(vm, ids_data, hint_code, exec_scopes_ref!()),
             Err(HintError::Internal(VirtualMachineError::ExpectedInteger(
                 MaybeRelocatable::from((1, 10))
             )))
@@ -595,25 +516,7 @@ mod tests {
         );
         //Execute the hint
         assert_eq!(
-            run_hint!(
-                vm,
-                HashMap::new(),
-                hint_code,
-                &mut exec_scopes,
-                &[(
-                    SECP_REM,
-                    Felt::one().shl(32_u32)
-                        + Felt::one().shl(9_u32)
-                        + Felt::one().shl(8_u32)
-                        + Felt::one().shl(7_u32)
-                        + Felt::one().shl(6_u32)
-                        + Felt::one().shl(4_u32)
-                        + Felt::one()
-                )]
-                .into_iter()
-                .map(|(k, v)| (k.to_string(), v))
-                .collect()
-            ),
+            run_hint!(vm, HashMap::new(), hint_code, &mut exec_scopes),
             Ok(())
         );
 
@@ -641,25 +544,7 @@ mod tests {
         //Skip `x` assignment
         //Execute the hint
         assert_eq!(
-            run_hint!(
-                vm,
-                HashMap::new(),
-                hint_code,
-                exec_scopes_ref!(),
-                &[(
-                    SECP_REM,
-                    Felt::one().shl(32_u32)
-                        + Felt::one().shl(9_u32)
-                        + Felt::one().shl(8_u32)
-                        + Felt::one().shl(7_u32)
-                        + Felt::one().shl(6_u32)
-                        + Felt::one().shl(4_u32)
-                        + Felt::one()
-                )]
-                .into_iter()
-                .map(|(k, v)| (k.to_string(), v))
-                .collect()
-            ),
+            run_hint!(vm, HashMap::new(), hint_code, exec_scopes_ref!()),
             Err(HintError::VariableNotInScopeError("x".to_string()))
         );
     }
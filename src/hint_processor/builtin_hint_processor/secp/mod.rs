@@ -1,4 +1,5 @@
 pub mod bigint_utils;
+pub mod curve;
 pub mod ec_utils;
 pub mod field_utils;
 pub mod secp_utils;
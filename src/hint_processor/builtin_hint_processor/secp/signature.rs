@@ -2,7 +2,10 @@ use crate::{
     hint_processor::{
         builtin_hint_processor::{
             hint_utils::get_integer_from_var_name,
-            secp::secp_utils::{pack_from_var_name, BASE_86, BETA, N0, N1, N2, SECP_REM},
+            secp::{
+                curve::{get_secp_curve, import_secp_curve, SecpCurve},
+                secp_utils::pack_from_var_name,
+            },
         },
         hint_processor_definition::HintReference,
     },
@@ -12,14 +15,10 @@ use crate::{
     vm::errors::hint_errors::HintError,
     vm::vm_core::VirtualMachine,
 };
-use felt::{Felt, FeltOps};
+use felt::FeltOps;
 use num_bigint::BigInt;
 use num_integer::Integer;
-use num_traits::One;
-use std::{
-    collections::HashMap,
-    ops::{Shl, Shr},
-};
+use std::{collections::HashMap, ops::Shr};
 
 /* Implements hint:
 from starkware.cairo.common.cairo_secp.secp_utils import N, pack
@@ -34,33 +33,13 @@ pub fn div_mod_n_packed_divmod(
     exec_scopes: &mut ExecutionScopes,
     ids_data: &HashMap<String, HintReference>,
     ap_tracking: &ApTracking,
-    constants: &HashMap<String, Felt>,
+    curve: &'static SecpCurve,
 ) -> Result<(), HintError> {
     let a = pack_from_var_name("a", vm, ids_data, ap_tracking)?;
     let b = pack_from_var_name("b", vm, ids_data, ap_tracking)?;
 
-    let n = {
-        let base = constants
-            .get(BASE_86)
-            .ok_or(HintError::MissingConstant(BASE_86))?
-            .to_bigint();
-        let n0 = constants
-            .get(N0)
-            .ok_or(HintError::MissingConstant(N0))?
-            .to_bigint();
-        let n1 = constants
-            .get(N1)
-            .ok_or(HintError::MissingConstant(N1))?
-            .to_bigint();
-        let n2 = constants
-            .get(N2)
-            .ok_or(HintError::MissingConstant(N2))?
-            .to_bigint();
-
-        (n2 * &base * &base) | (n1 * base) | n0
-    };
-
-    let value = div_mod(&a, &b, &n);
+    let value = div_mod(&a, &b, &curve.n);
+    import_secp_curve(exec_scopes, curve);
     exec_scopes.insert_value("a", a);
     exec_scopes.insert_value("b", b);
     exec_scopes.insert_value("value", value.clone());
@@ -70,36 +49,13 @@ pub fn div_mod_n_packed_divmod(
 
 // Implements hint:
 // value = k = safe_div(res * b - a, N)
-pub fn div_mod_n_safe_div(
-    exec_scopes: &mut ExecutionScopes,
-    constants: &HashMap<String, Felt>,
-) -> Result<(), HintError> {
+pub fn div_mod_n_safe_div(exec_scopes: &mut ExecutionScopes) -> Result<(), HintError> {
+    let curve = get_secp_curve(exec_scopes)?;
     let a = exec_scopes.get_ref::<BigInt>("a")?;
     let b = exec_scopes.get_ref::<BigInt>("b")?;
     let res = exec_scopes.get_ref::<BigInt>("res")?;
 
-    let n = {
-        let base = constants
-            .get(BASE_86)
-            .ok_or(HintError::MissingConstant(BASE_86))?
-            .to_bigint();
-        let n0 = constants
-            .get(N0)
-            .ok_or(HintError::MissingConstant(N0))?
-            .to_bigint();
-        let n1 = constants
-            .get(N1)
-            .ok_or(HintError::MissingConstant(N1))?
-            .to_bigint();
-        let n2 = constants
-            .get(N2)
-            .ok_or(HintError::MissingConstant(N2))?
-            .to_bigint();
-
-        n2 * &base * &base + n1 * base + n0
-    };
-
-    let value = safe_div_bigint(&(res * b - a), &n)?;
+    let value = safe_div_bigint(&(res * b - a), &curve.n)?;
 
     exec_scopes.insert_value("value", value);
     Ok(())
@@ -110,28 +66,18 @@ pub fn get_point_from_x(
     exec_scopes: &mut ExecutionScopes,
     ids_data: &HashMap<String, HintReference>,
     ap_tracking: &ApTracking,
-    constants: &HashMap<String, Felt>,
+    curve: &SecpCurve,
 ) -> Result<(), HintError> {
-    let beta = constants
-        .get(BETA)
-        .ok_or(HintError::MissingConstant(BETA))?
-        .to_bigint();
-    let secp_p = BigInt::one().shl(256_u32)
-        - constants
-            .get(SECP_REM)
-            .ok_or(HintError::MissingConstant(SECP_REM))?
-            .to_bigint();
-
-    let x_cube_int = pack_from_var_name("x_cube", vm, ids_data, ap_tracking)?.mod_floor(&secp_p);
+    let x_cube_int = pack_from_var_name("x_cube", vm, ids_data, ap_tracking)?.mod_floor(&curve.p);
     //.mod_floor(&BigInt::from_biguint(num_bigint::Sign::Plus, secp_p.clone()))
     //.to_biguint().ok_or(VirtualMachineError::BigIntToBigUintFail)?;
-    let y_cube_int = (x_cube_int + beta).mod_floor(&secp_p);
+    let y_cube_int = (x_cube_int + &curve.beta).mod_floor(&curve.p);
     // Divide by 4
-    let mut y = y_cube_int.modpow(&(&secp_p + 1_u32).shr(2_u32), &secp_p);
+    let mut y = y_cube_int.modpow(&(&curve.p + 1_u32).shr(2_u32), &curve.p);
 
     let v = get_integer_from_var_name("v", vm, ids_data, ap_tracking)?.to_biguint();
     if v.is_even() != y.is_even() {
-        y = &secp_p - y;
+        y = &curve.p - y;
     }
     exec_scopes.insert_value("value", y);
     Ok(())
@@ -156,9 +102,8 @@ mod tests {
             vm_memory::memory::Memory,
         },
     };
-    use felt::NewFelt;
-    use num_traits::Zero;
-    use std::{any::Any, ops::Shl};
+    use num_traits::{One, Zero};
+    use std::any::Any;
 
     #[test]
     fn safe_div_ok() {
@@ -176,20 +121,54 @@ mod tests {
         vm.run_context.fp = 3;
         let ids_data = non_continuous_ids_data![("a", -3), ("b", 0)];
         let mut exec_scopes = ExecutionScopes::new();
-        let constants = [
-            (BASE_86, Felt::one().shl(86_u32)),
-            (N0, Felt::new(10428087374290690730508609u128)),
-            (N1, Felt::new(77371252455330678278691517u128)),
-            (N2, Felt::new(19342813113834066795298815u128)),
-        ]
-        .into_iter()
-        .map(|(k, v)| (k.to_string(), v))
-        .collect();
+        assert_eq!(run_hint!(vm, ids_data, hint_code, &mut exec_scopes), Ok(()));
+        assert_eq!(div_mod_n_safe_div(&mut exec_scopes), Ok(()));
+    }
+
+    #[test]
+    fn safe_div_secp256r1_ok() {
+        let mut vm = vm!();
+
+        vm.memory = memory![
+            ((1, 0), 12345678901234567890_u64),
+            ((1, 1), 0),
+            ((1, 2), 0),
+            ((1, 3), 98765432109876543210_u128),
+            ((1, 4), 0),
+            ((1, 5), 0)
+        ];
+        vm.run_context.fp = 3;
+        let ids_data = non_continuous_ids_data![("a", -3), ("b", 0)];
+        let mut exec_scopes = ExecutionScopes::new();
         assert_eq!(
-            run_hint!(vm, ids_data, hint_code, &mut exec_scopes, &constants),
+            run_hint!(
+                vm,
+                HashMap::new(),
+                hint_code::IMPORT_SECP256R1_N,
+                &mut exec_scopes
+            ),
             Ok(())
         );
-        assert_eq!(div_mod_n_safe_div(&mut exec_scopes, &constants), Ok(()));
+        assert_eq!(
+            run_hint!(
+                vm,
+                ids_data,
+                hint_code::DIV_MOD_N_PACKED_DIVMOD_V2,
+                &mut exec_scopes
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            exec_scopes.get::<BigInt>("res"),
+            Ok(bigint_str!(
+                "90552006745726814100246673365327028294418496441910897305192760385177837998553"
+            ))
+        );
+        assert_eq!(div_mod_n_safe_div(&mut exec_scopes), Ok(()));
+        assert_eq!(
+            exec_scopes.get::<BigInt>("value"),
+            Ok(bigint_str!("77236779607723677960"))
+        );
     }
 
     #[test]
@@ -199,6 +178,7 @@ mod tests {
             ("b", BigInt::one()),
             ("res", BigInt::one())
         ];
+        import_secp_curve(&mut exec_scopes, SecpCurve::secp256k1());
         assert_eq!(
             Err(
                 HintError::Internal(VirtualMachineError::SafeDivFailBigInt(
@@ -206,18 +186,21 @@ mod tests {
                     bigint_str!("115792089237316195423570985008687907852837564279074904382605163141518161494337"),
                 )
             )),
-            div_mod_n_safe_div(
-                &mut exec_scopes,
-                &[
-                    (BASE_86, Felt::one().shl(86_u32)),
-                    (N0, Felt::new(10428087374290690730508609u128)),
-                    (N1, Felt::new(77371252455330678278691517u128)),
-                    (N2, Felt::new(19342813113834066795298815u128)),
-                ]
-                .into_iter()
-                .map(|(k, v)| (k.to_string(), v))
-                .collect()
-            ),
+            div_mod_n_safe_div(&mut exec_scopes),
+        );
+    }
+
+    #[test]
+    fn safe_div_without_curve() {
+        // N is imported by the hint computing res
+        let mut exec_scopes = scope![
+            ("a", BigInt::zero()),
+            ("b", BigInt::one()),
+            ("res", BigInt::one())
+        ];
+        assert_eq!(
+            div_mod_n_safe_div(&mut exec_scopes),
+            Err(HintError::VariableNotInScopeError(String::from("SECP_P")))
         );
     }
 
@@ -234,28 +217,7 @@ mod tests {
         vm.run_context.fp = 1;
         let ids_data = non_continuous_ids_data![("v", -1), ("x_cube", 0)];
         assert_eq!(
-            run_hint!(
-                vm,
-                ids_data,
-                hint_code,
-                exec_scopes_ref!(),
-                &[
-                    (BETA, Felt::new(7)),
-                    (
-                        SECP_REM,
-                        Felt::one().shl(32_u32)
-                            + Felt::one().shl(9_u32)
-                            + Felt::one().shl(8_u32)
-                            + Felt::one().shl(7_u32)
-                            + Felt::one().shl(6_u32)
-                            + Felt::one().shl(4_u32)
-                            + Felt::one()
-                    ),
-                ]
-                .into_iter()
-                .map(|(k, v)| (k.to_string(), v))
-                .collect()
-            ),
+            run_hint!(vm, ids_data, hint_code, exec_scopes_ref!()),
             Ok(())
         )
     }
@@ -274,31 +236,7 @@ mod tests {
         vm.run_context.fp = 2;
 
         let ids_data = ids_data!["v", "x_cube"];
-        assert_eq!(
-            run_hint!(
-                vm,
-                ids_data,
-                hint_code,
-                &mut exec_scopes,
-                &[
-                    (BETA, Felt::new(7)),
-                    (
-                        SECP_REM,
-                        Felt::one().shl(32_u32)
-                            + Felt::one().shl(9_u32)
-                            + Felt::one().shl(8_u32)
-                            + Felt::one().shl(7_u32)
-                            + Felt::one().shl(6_u32)
-                            + Felt::one().shl(4_u32)
-                            + Felt::one()
-                    ),
-                ]
-                .into_iter()
-                .map(|(k, v)| (k.to_string(), v))
-                .collect()
-            ),
-            Ok(())
-        );
+        assert_eq!(run_hint!(vm, ids_data, hint_code, &mut exec_scopes), Ok(()));
 
         check_scope!(
             &exec_scopes,
@@ -279,10 +279,10 @@ pub fn squash_dict(
     keys.sort();
     keys.reverse();
     //Are the keys used bigger than the range_check bound.
-    let big_keys = if keys[0] >= range_check_bound.unwrap() {
-        Felt::one()
-    } else {
-        Felt::zero()
+    let big_keys = match (keys.first(), &range_check_bound) {
+        (Some(max_key), Some(bound)) if max_key >= bound => Felt::one(),
+        (Some(_), _) => Felt::zero(),
+        (None, _) => return Err(HintError::EmptyKeys),
     };
     insert_value_from_var_name("big_keys", big_keys, vm, ids_data, ap_tracking)?;
     let key = keys.pop().ok_or(HintError::EmptyKeys)?;
@@ -1001,6 +1001,31 @@ mod tests {
         );
     }
 
+    #[test]
+    fn squash_dict_invalid_no_accesses() {
+        //Dict = {}
+        let hint_code = SQUASH_DICT;
+        //Create vm
+        let mut vm = vm_with_range_check!();
+        //Initialize fp
+        vm.run_context.fp = 5;
+        //Insert ids into memory
+        vm.memory = memory![((1, 0), (2, 0)), ((1, 3), 0), ((1, 4), 0)];
+        //Create hint_data
+        let ids_data = ids_data![
+            "dict_accesses",
+            "big_keys",
+            "first_key",
+            "ptr_diff",
+            "n_accesses"
+        ];
+        //Execute the hint
+        assert_eq!(
+            run_hint!(vm, ids_data, hint_code),
+            Err(HintError::EmptyKeys)
+        );
+    }
+
     #[test]
     fn squash_dict_valid_one_key_dict_no_max_size_big_keys() {
         //Dict = {(prime - 1): (1,1), (prime - 1): (1,2)}
@@ -42,15 +42,15 @@ pub trait HintProcessor {
         //List of all references (key corresponds to element of the previous dictionary)
         references: &HashMap<usize, HintReference>,
     ) -> Result<Box<dyn Any>, VirtualMachineError> {
-        Ok(any_box!(HintProcessorData {
-            code: hint_code.to_string(),
-            ap_tracking: ap_tracking_data.clone(),
-            ids_data: get_ids_data(reference_ids, references)?,
-        }))
+        Ok(any_box!(HintProcessorData::new(
+            hint_code.to_string(),
+            ap_tracking_data.clone(),
+            get_ids_data(reference_ids, references)?,
+        )))
     }
 }
 
-fn get_ids_data(
+pub(crate) fn get_ids_data(
     reference_ids: &HashMap<String, usize>,
     references: &HashMap<usize, HintReference>,
 ) -> Result<HashMap<String, HintReference>, VirtualMachineError> {
@@ -87,11 +87,30 @@ pub fn compute_addr_from_reference(
     vm: &VirtualMachine,
     //ApTracking of the Hint itself
     hint_ap_tracking: &ApTracking,
+) -> Result<Relocatable, HintError> {
+    compute_addr_from_reference_at(
+        hint_reference,
+        vm,
+        vm.get_fp(),
+        vm.get_ap(),
+        hint_ap_tracking,
+    )
+}
+
+/// Same as `compute_addr_from_reference`, with the given values of fp and ap instead of the
+/// current ones (ie: the registers of a frame of the traceback).
+pub(crate) fn compute_addr_from_reference_at(
+    hint_reference: &HintReference,
+    vm: &VirtualMachine,
+    fp: Relocatable,
+    ap: Relocatable,
+    hint_ap_tracking: &ApTracking,
 ) -> Result<Relocatable, HintError> {
     let offset1 =
         if let OffsetValue::Reference(_register, _offset, _deref) = &hint_reference.offset1 {
             get_offset_value_reference(
                 vm,
+                (fp, ap),
                 hint_reference,
                 hint_ap_tracking,
                 &hint_reference.offset1,
@@ -107,6 +126,7 @@ pub fn compute_addr_from_reference(
             // So OffSet2 must be Bigint
             let value = get_offset_value_reference(
                 vm,
+                (fp, ap),
                 hint_reference,
                 hint_ap_tracking,
                 &hint_reference.offset2,
@@ -152,6 +172,7 @@ pub fn felt_to_u32(felt: &Felt) -> Result<u32, VirtualMachineError> {
 
 fn get_offset_value_reference(
     vm: &VirtualMachine,
+    (fp, ap): (Relocatable, Relocatable),
     hint_reference: &HintReference,
     hint_ap_tracking: &ApTracking,
     offset_value: &OffsetValue,
@@ -167,14 +188,14 @@ fn get_offset_value_reference(
     };
 
     let base_addr = if register == &Register::FP {
-        vm.get_fp()
+        fp
     } else {
         let var_ap_trackig = hint_reference
             .ap_tracking_data
             .as_ref()
             .ok_or(HintError::NoneApTrackingData)?;
 
-        apply_ap_tracking_correction(&vm.get_ap(), var_ap_trackig, hint_ap_tracking)?
+        apply_ap_tracking_correction(&ap, var_ap_trackig, hint_ap_tracking)?
     };
 
     if offset.is_negative() && base_addr.offset < offset.unsigned_abs() as usize {
@@ -1,3 +1,5 @@
+#[cfg(feature = "async_hints")]
+pub mod async_hint_processor;
 pub mod builtin_hint_processor;
 pub mod hint_processor_definition;
 pub mod hint_processor_utils;
@@ -1,11 +1,16 @@
 #![deny(warnings)]
 use cairo_vm::cairo_run;
 use cairo_vm::hint_processor::builtin_hint_processor::builtin_hint_processor_definition::BuiltinHintProcessor;
+use cairo_vm::types::program::Program;
 use cairo_vm::vm::errors::cairo_run_errors::CairoRunError;
+use cairo_vm::vm::errors::diagnostics::{Diagnostic, DiagnosticRenderer, SourceMap};
 use cairo_vm::vm::errors::runner_errors::RunnerError;
 use cairo_vm::vm::errors::trace_errors::TraceError;
+use cairo_vm::vm::runners::cairo_runner::CairoRunner;
 use clap::{Parser, ValueHint};
-use std::path::PathBuf;
+use std::any::Any;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 
 #[cfg(feature = "with_mimalloc")]
 use mimalloc::MiMalloc;
@@ -32,6 +37,20 @@ struct Args {
     layout: String,
     #[structopt(long = "--proof_mode")]
     proof_mode: bool,
+    #[structopt(long = "--dump_dicts")]
+    dump_dicts: bool,
+    #[structopt(long = "--program_input")]
+    program_input: Option<PathBuf>,
+    #[structopt(long = "--relocation_table")]
+    relocation_table: Option<PathBuf>,
+    #[structopt(long = "--streaming")]
+    streaming: bool,
+    #[structopt(long = "--trace_chunk_size", default_value_t = cairo_run::DEFAULT_TRACE_CHUNK_SIZE)]
+    trace_chunk_size: usize,
+    #[structopt(long = "--hint_stats")]
+    hint_stats: bool,
+    #[clap(long = "--error_format", default_value = "text", validator=validate_error_format)]
+    error_format: String,
 }
 
 fn validate_layout(value: &str) -> Result<(), String> {
@@ -43,26 +62,122 @@ fn validate_layout(value: &str) -> Result<(), String> {
     }
 }
 
+fn validate_error_format(value: &str) -> Result<(), String> {
+    match value {
+        "text" | "pretty" | "json" => Ok(()),
+        _ => Err(format!("{} is not a valid error format", value)),
+    }
+}
+
+// Colors are only used when printing to a terminal, unless disabled by NO_COLOR
+// (https://no-color.org).
+fn use_color() -> bool {
+    atty::is(atty::Stream::Stdout) && std::env::var_os("NO_COLOR").is_none()
+}
+
+// Prints the error in the given format. VM exceptions are rendered as diagnostics in the
+// "pretty" and "json" formats, with the sources included in the program as a fallback.
+fn print_error(error: &CairoRunError, args: &Args, program: &Program) {
+    let exception = match error {
+        CairoRunError::VmException(exception) if args.error_format != "text" => exception,
+        _ => {
+            println!("{}", error);
+            return;
+        }
+    };
+    let diagnostic = Diagnostic::from_vm_exception(exception);
+    if args.error_format == "json" {
+        match diagnostic.to_json() {
+            Ok(json) => println!("{}", json),
+            Err(_) => println!("{}", error),
+        }
+        return;
+    }
+    let source_map = SourceMap::from_program(program);
+    print!(
+        "{}",
+        DiagnosticRenderer::new(&source_map)
+            .with_color(use_color())
+            .render(&diagnostic)
+    );
+}
+
 fn main() -> Result<(), CairoRunError> {
     let args = Args::parse();
     let trace_enabled = args.trace_file.is_some();
     let mut hint_executor = BuiltinHintProcessor::new_empty();
-    let cairo_runner = match cairo_run::cairo_run(
-        &args.filename,
-        &args.entrypoint,
+    let mut main_scope_variables = HashMap::<String, Box<dyn Any>>::new();
+    if let Some(program_input_path) = &args.program_input {
+        let program_input = cairo_run::read_program_input(program_input_path)?;
+        main_scope_variables.insert(String::from("program_input"), Box::new(program_input));
+    }
+    let program = match Program::from_file(&args.filename, Some(&args.entrypoint)) {
+        Ok(program) => program,
+        Err(error) => {
+            let error = CairoRunError::Program(error);
+            println!("{}", error);
+            return Err(error);
+        }
+    };
+    let config = cairo_run::CairoRunConfig {
         trace_enabled,
-        args.print_output,
-        &args.layout,
-        args.proof_mode,
+        print_output: args.print_output,
+        layout: &args.layout,
+        proof_mode: args.proof_mode,
+        hint_stats: args.hint_stats,
+    };
+    if args.streaming {
+        let output = cairo_run::StreamingOutput {
+            trace_file: args.trace_file.clone(),
+            memory_file: args.memory_file.clone(),
+            trace_chunk_size: args.trace_chunk_size,
+        };
+        let cairo_runner = match cairo_run::cairo_run_streaming(
+            &program,
+            &config,
+            &mut hint_executor,
+            main_scope_variables,
+            &output,
+        ) {
+            Ok(runner) => runner,
+            Err(error) => {
+                print_error(&error, &args, &program);
+                return Err(error);
+            }
+        };
+        if args.dump_dicts {
+            cairo_run::write_dicts(&cairo_runner)?;
+        }
+        if let Some(hint_stats) = &cairo_runner.hint_stats {
+            println!("{}", hint_stats);
+        }
+        if let Some(relocation_table_path) = args.relocation_table {
+            write_relocation_table(&cairo_runner, &relocation_table_path)?;
+        }
+        return Ok(());
+    }
+
+    let cairo_runner = match cairo_run::cairo_run_with_initial_scope(
+        &program,
+        &config,
         &mut hint_executor,
+        main_scope_variables,
     ) {
         Ok(runner) => runner,
         Err(error) => {
-            println!("{}", error);
+            print_error(&error, &args, &program);
             return Err(error);
         }
     };
 
+    if args.dump_dicts {
+        cairo_run::write_dicts(&cairo_runner)?;
+    }
+
+    if let Some(hint_stats) = &cairo_runner.hint_stats {
+        println!("{}", hint_stats);
+    }
+
     if let Some(trace_path) = args.trace_file {
         let relocated_trace = cairo_runner
             .relocated_trace
@@ -81,9 +196,25 @@ fn main() -> Result<(), CairoRunError> {
         }
     }
 
+    if let Some(relocation_table_path) = args.relocation_table {
+        write_relocation_table(&cairo_runner, &relocation_table_path)?;
+    }
+
     Ok(())
 }
 
+fn write_relocation_table(
+    cairo_runner: &CairoRunner,
+    relocation_table_path: &Path,
+) -> Result<(), CairoRunError> {
+    let relocation_table = cairo_runner
+        .relocation_table
+        .as_ref()
+        .ok_or(CairoRunError::Trace(TraceError::NoRelocationFound))?;
+    cairo_run::write_relocation_table(relocation_table, relocation_table_path)
+        .map_err(|_| CairoRunError::Runner(RunnerError::WriteFail))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -110,4 +241,12 @@ mod tests {
         let invalid_layout = "invalid layout name";
         assert!(validate_layout(invalid_layout).is_err());
     }
+
+    #[test]
+    fn test_error_formats() {
+        for error_format in ["text", "pretty", "json"] {
+            assert_eq!(validate_error_format(error_format), Ok(()));
+        }
+        assert!(validate_error_format("xml").is_err());
+    }
 }
@@ -0,0 +1,238 @@
+use felt::{Felt, FeltOps};
+
+// The Keccak-f[1600] permutation, shared by the keccak builtin and the keccak hints, together with
+// the conversions between its state and the felts they work with.
+
+/// Number of 64-bit lanes in the Keccak-f[1600] state.
+pub const STATE_LANES: usize = 25;
+/// Number of bits of the state held by each input (and output) felt of the keccak builtin.
+pub const BUILTIN_FELT_BITS: usize = 200;
+/// Number of input (and output) felts of the keccak builtin.
+pub const BUILTIN_STATE_FELTS: usize = 8;
+// Number of bytes absorbed by each permutation when computing keccak256.
+const KECCAK256_RATE: usize = 136;
+
+pub type KeccakState = [u64; STATE_LANES];
+
+/// Applies the Keccak-f[1600] permutation to the state.
+pub fn keccak_f1600(state: &mut KeccakState) {
+    keccak::f1600(state)
+}
+
+/// Returns the felt as a lane, or None if it doesn't fit in 64 bits.
+pub fn felt_to_lane(value: &Felt) -> Option<u64> {
+    // Reads the digits directly, so that no intermediate big integer is built
+    let mut digits = value.iter_u64_digits();
+    match (digits.next(), digits.next()) {
+        (None, _) => Some(0),
+        (Some(digit), None) => Some(digit),
+        _ => None,
+    }
+}
+
+/// Builds the state from the input felts of the keccak builtin: the state bits, in little endian
+/// order, are split in BUILTIN_STATE_FELTS felts of BUILTIN_FELT_BITS bits each.
+/// Missing felts are taken to be zero. Returns None if there are too many felts or one of them
+/// doesn't fit in BUILTIN_FELT_BITS bits.
+pub fn state_from_builtin_felts(values: &[Felt]) -> Option<KeccakState> {
+    if values.len() > BUILTIN_STATE_FELTS {
+        return None;
+    }
+    let mut state = [0_u64; STATE_LANES];
+    for (i, value) in values.iter().enumerate() {
+        if value.bits() > BUILTIN_FELT_BITS as u64 {
+            return None;
+        }
+        // BUILTIN_FELT_BITS is a multiple of 8, so each digit lands on at most two lanes
+        for (j, digit) in value.iter_u64_digits().enumerate() {
+            let bit = i * BUILTIN_FELT_BITS + j * 64;
+            let (lane, shift) = (bit / 64, bit % 64);
+            state[lane] |= digit << shift;
+            if shift != 0 && lane + 1 < STATE_LANES {
+                state[lane + 1] |= digit >> (64 - shift);
+            }
+        }
+    }
+    Some(state)
+}
+
+/// Splits the state into the BUILTIN_STATE_FELTS output felts of the keccak builtin.
+pub fn state_to_builtin_felts(state: &KeccakState) -> Vec<Felt> {
+    let mut bytes = [0_u8; STATE_LANES * 8];
+    for (chunk, lane) in bytes.chunks_exact_mut(8).zip(state.iter()) {
+        chunk.copy_from_slice(&lane.to_le_bytes());
+    }
+    bytes
+        .chunks_exact(BUILTIN_FELT_BITS / 8)
+        .map(|chunk| {
+            let mut chunk = chunk.to_vec();
+            chunk.reverse();
+            Felt::from_bytes_be(&chunk)
+        })
+        .collect()
+}
+
+fn absorb(state: &mut KeccakState, block: &[u8]) {
+    for (lane, chunk) in state.iter_mut().zip(block.chunks_exact(8)) {
+        let mut word = [0_u8; 8];
+        word.copy_from_slice(chunk);
+        *lane ^= u64::from_le_bytes(word);
+    }
+}
+
+/// Returns the keccak256 hash of the data, as used by Ethereum (and not the padding of SHA3-256).
+pub fn keccak256(data: &[u8]) -> [u8; 32] {
+    let mut state = [0_u64; STATE_LANES];
+    let mut blocks = data.chunks_exact(KECCAK256_RATE);
+    for block in &mut blocks {
+        absorb(&mut state, block);
+        keccak_f1600(&mut state);
+    }
+
+    let remainder = blocks.remainder();
+    let mut last_block = [0_u8; KECCAK256_RATE];
+    last_block[..remainder.len()].copy_from_slice(remainder);
+    last_block[remainder.len()] ^= 0x01;
+    last_block[KECCAK256_RATE - 1] ^= 0x80;
+    absorb(&mut state, &last_block);
+    keccak_f1600(&mut state);
+
+    let mut hash = [0_u8; 32];
+    for (chunk, lane) in hash.chunks_exact_mut(8).zip(state.iter()) {
+        chunk.copy_from_slice(&lane.to_le_bytes());
+    }
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use felt::{felt_str, NewFelt};
+    use num_traits::{One, Zero};
+    use sha3::{Digest, Keccak256};
+
+    fn sha3_keccak256(data: &[u8]) -> [u8; 32] {
+        let mut hasher = Keccak256::new();
+        hasher.update(data);
+        hasher.finalize().into()
+    }
+
+    #[test]
+    fn keccak_f1600_zero_state() {
+        let mut state = [0_u64; STATE_LANES];
+        keccak_f1600(&mut state);
+        assert_eq!(state[0], 0xf1258f7940e1dde7);
+        assert_eq!(state[24], 0xeaf1ff7b5ceca249);
+    }
+
+    #[test]
+    fn keccak256_matches_sha3() {
+        // Covers the empty input, inputs around the rate, and several blocks
+        let data: Vec<u8> = (0..600_u32).map(|i| (i * 31 + 7) as u8).collect();
+        for len in (0..=300).chain([407, 408, 409, 600]) {
+            assert_eq!(
+                keccak256(&data[..len]),
+                sha3_keccak256(&data[..len]),
+                "length {len}"
+            );
+        }
+    }
+
+    #[test]
+    fn keccak256_known_value() {
+        assert_eq!(
+            hex::encode(keccak256(b"")),
+            "c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470"
+        );
+    }
+
+    #[test]
+    fn felt_to_lane_bounds() {
+        assert_eq!(felt_to_lane(&Felt::zero()), Some(0));
+        assert_eq!(felt_to_lane(&Felt::new(u64::MAX)), Some(u64::MAX));
+        assert_eq!(felt_to_lane(&(Felt::one() << 64_u32)), None);
+        assert_eq!(felt_to_lane(&(Felt::zero() - Felt::one())), None);
+    }
+
+    #[test]
+    fn builtin_felts_pack_little_endian_bits() {
+        // The second felt starts at bit 200, that is, at bit 8 of the fourth lane
+        let state = state_from_builtin_felts(&[Felt::new(u64::MAX), Felt::new(0x1234)]).unwrap();
+        let mut expected = [0_u64; STATE_LANES];
+        expected[0] = u64::MAX;
+        expected[3] = 0x123400;
+        assert_eq!(state, expected);
+
+        // A full felt spans four lanes, and the last felt ends with the state
+        let full = (Felt::one() << 200_u32) - Felt::one();
+        let values: Vec<Felt> = (0..BUILTIN_STATE_FELTS).map(|_| full.clone()).collect();
+        let state = state_from_builtin_felts(&values).unwrap();
+        assert_eq!(state, [u64::MAX; STATE_LANES]);
+    }
+
+    #[test]
+    fn builtin_felts_out_of_range() {
+        assert_eq!(state_from_builtin_felts(&[Felt::one() << 200_u32]), None);
+        assert_eq!(
+            state_from_builtin_felts(&vec![Felt::one(); BUILTIN_STATE_FELTS + 1]),
+            None
+        );
+    }
+
+    #[test]
+    fn builtin_felts_round_trip() {
+        let values: Vec<Felt> = (0..BUILTIN_STATE_FELTS)
+            .map(|i| (Felt::one() << 200_u32) - Felt::new(i * 0x1000_0001 + 1))
+            .collect();
+        let state = state_from_builtin_felts(&values).unwrap();
+        assert_eq!(state_to_builtin_felts(&state), values);
+    }
+
+    #[test]
+    fn builtin_permutation_matches_sha3() {
+        // Absorbing a single block and permuting is what keccak256 does for a short input, so
+        // the builtin state format can be checked against sha3 through it
+        let data = b"keccak builtin";
+        let mut block = [0_u8; KECCAK256_RATE];
+        block[..data.len()].copy_from_slice(data);
+        block[data.len()] ^= 0x01;
+        block[KECCAK256_RATE - 1] ^= 0x80;
+
+        let mut bytes = [0_u8; STATE_LANES * 8];
+        bytes[..KECCAK256_RATE].copy_from_slice(&block);
+        let values: Vec<Felt> = bytes
+            .chunks_exact(BUILTIN_FELT_BITS / 8)
+            .map(|chunk| {
+                let mut chunk = chunk.to_vec();
+                chunk.reverse();
+                Felt::from_bytes_be(&chunk)
+            })
+            .collect();
+
+        let mut state = state_from_builtin_felts(&values).unwrap();
+        keccak_f1600(&mut state);
+        let output = state_to_builtin_felts(&state);
+
+        let mut bytes = Vec::new();
+        for value in output {
+            let mut value_bytes = value.to_bytes_be();
+            value_bytes.reverse();
+            value_bytes.resize(BUILTIN_FELT_BITS / 8, 0);
+            bytes.extend(value_bytes);
+        }
+        assert_eq!(bytes[..32], sha3_keccak256(data));
+    }
+
+    #[test]
+    fn builtin_permutation_known_value() {
+        let values: Vec<Felt> = (0..BUILTIN_STATE_FELTS)
+            .map(|i| (Felt::one() << 200_u32) - Felt::new(i + 1))
+            .collect();
+        let mut state = state_from_builtin_felts(&values).unwrap();
+        keccak_f1600(&mut state);
+        assert_eq!(
+            state_to_builtin_felts(&state)[0],
+            felt_str!("101472217641568559453688497960663139512078558539060505971943")
+        );
+    }
+}
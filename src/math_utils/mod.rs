@@ -1,3 +1,7 @@
+pub mod keccak;
+pub mod stark_curve;
+
+use self::stark_curve::{AffinePoint, StarkFieldElement};
 use crate::vm::errors::vm_errors::VirtualMachineError;
 use felt::Felt;
 use num_bigint::{BigInt, BigUint};
@@ -134,6 +138,11 @@ pub fn ec_add(
     point_b: (BigInt, BigInt),
     prime: &BigInt,
 ) -> (BigInt, BigInt) {
+    if stark_curve::is_stark_prime(prime) {
+        return AffinePoint::from_bigints(&point_a)
+            .add(&AffinePoint::from_bigints(&point_b))
+            .to_bigints();
+    }
     let m = line_slope(&point_a, &point_b, prime);
     let x = (m.clone() * m.clone() - point_a.0.clone() - point_b.0).mod_floor(prime);
     let y = (m * (point_a.0 - x.clone()) - point_a.1).mod_floor(prime);
@@ -158,6 +167,11 @@ pub fn line_slope(
 ///  Doubles a point on an elliptic curve with the equation y^2 = x^3 + alpha*x + beta mod p.
 /// Assumes the point is given in affine form (x, y) and has y != 0.
 pub fn ec_double(point: (BigInt, BigInt), alpha: &BigInt, prime: &BigInt) -> (BigInt, BigInt) {
+    if stark_curve::is_stark_prime(prime) {
+        return AffinePoint::from_bigints(&point)
+            .double(&StarkFieldElement::from_bigint(alpha))
+            .to_bigints();
+    }
     let m = ec_double_slope(&point, alpha, prime);
     let x = ((&m * &m) - (2_i32 * &point.0)).mod_floor(prime);
     let y = (m * (point.0 - &x) - point.1).mod_floor(prime);
@@ -0,0 +1,512 @@
+//! Fixed-width arithmetic over the STARK curve y^2 = x^3 + alpha * x + beta, defined over the
+//! field of the Cairo prime P = 2^251 + 17 * 2^192 + 1.
+//!
+//! Field elements are kept in Montgomery form over four 64-bit limbs, and points can be kept in
+//! Jacobian coordinates (X, Y, Z), which represent the affine point (X / Z^2, Y / Z^3), so that
+//! long chains of additions and doublings only need a single inversion at the end.
+//!
+//! The formulas never use beta, so they behave like the affine formulas in `math_utils` for any
+//! pair of coordinates, even if they don't belong to the curve.
+
+use felt::{Felt, FeltOps};
+use num_bigint::{BigInt, BigUint, Sign};
+use num_integer::Integer;
+use std::ops::{Add, Mul, Neg, Sub};
+
+/// The Cairo prime, as little endian limbs.
+const MODULUS: [u64; 4] = [1, 0, 0, 0x0800_0000_0000_0011];
+/// The exponent used to invert through Fermat's little theorem: P - 2, as little endian limbs.
+const MODULUS_MINUS_TWO: [u64; 4] = [u64::MAX, u64::MAX, u64::MAX, 0x0800_0000_0000_0010];
+/// -P^-1 mod 2^64
+const INV: u64 = u64::MAX;
+/// 2^256 mod P, the Montgomery form of 1.
+const R: [u64; 4] = [
+    0xffff_ffff_ffff_ffe1,
+    0xffff_ffff_ffff_ffff,
+    0xffff_ffff_ffff_ffff,
+    0x07ff_ffff_ffff_fdf0,
+];
+/// 2^512 mod P, used to move values into Montgomery form.
+const R2: [u64; 4] = [
+    0xffff_fd73_7e00_0401,
+    0x0000_0001_330f_ffff,
+    0xffff_ffff_ff6f_8000,
+    0x07ff_d4ab_5e00_8810,
+];
+
+/// Returns a + b * c + carry, as (low, high) words.
+#[inline(always)]
+fn mac(a: u64, b: u64, c: u64, carry: u64) -> (u64, u64) {
+    let t = a as u128 + (b as u128) * (c as u128) + carry as u128;
+    (t as u64, (t >> 64) as u64)
+}
+
+/// Returns a + b + carry, as (sum, carry).
+#[inline(always)]
+fn adc(a: u64, b: u64, carry: u64) -> (u64, u64) {
+    let t = a as u128 + b as u128 + carry as u128;
+    (t as u64, (t >> 64) as u64)
+}
+
+/// Returns a - b - borrow, as (difference, borrow), with borrow being either 0 or 1.
+#[inline(always)]
+fn sbb(a: u64, b: u64, borrow: u64) -> (u64, u64) {
+    let t = (a as u128).wrapping_sub(b as u128 + borrow as u128);
+    (t as u64, (t >> 127) as u64)
+}
+
+fn add_limbs(a: &[u64; 4], b: &[u64; 4]) -> ([u64; 4], u64) {
+    let mut result = [0; 4];
+    let mut carry = 0;
+    for i in 0..4 {
+        (result[i], carry) = adc(a[i], b[i], carry);
+    }
+    (result, carry)
+}
+
+fn sub_limbs(a: &[u64; 4], b: &[u64; 4]) -> ([u64; 4], u64) {
+    let mut result = [0; 4];
+    let mut borrow = 0;
+    for i in 0..4 {
+        (result[i], borrow) = sbb(a[i], b[i], borrow);
+    }
+    (result, borrow)
+}
+
+/// Reduces a value lower than 2 * P into the [0, P) range.
+#[inline(always)]
+fn subtract_modulus_if_needed(limbs: [u64; 4]) -> [u64; 4] {
+    match sub_limbs(&limbs, &MODULUS) {
+        (reduced, 0) => reduced,
+        _ => limbs,
+    }
+}
+
+/// Montgomery multiplication (CIOS): returns a * b / 2^256 mod P.
+fn montgomery_mul(a: &[u64; 4], b: &[u64; 4]) -> [u64; 4] {
+    let mut t = [0_u64; 6];
+    for b_i in b {
+        let mut carry = 0;
+        for j in 0..4 {
+            (t[j], carry) = mac(t[j], a[j], *b_i, carry);
+        }
+        (t[4], t[5]) = adc(t[4], carry, 0);
+
+        let m = t[0].wrapping_mul(INV);
+        let (_, mut carry) = mac(t[0], m, MODULUS[0], 0);
+        for j in 1..4 {
+            (t[j - 1], carry) = mac(t[j], m, MODULUS[j], carry);
+        }
+        (t[3], carry) = adc(t[4], carry, 0);
+        t[4] = t[5] + carry;
+    }
+    // Both inputs are lower than P < 2^252, so the result is lower than 2 * P and t[4] is zero
+    subtract_modulus_if_needed([t[0], t[1], t[2], t[3]])
+}
+
+fn limbs_to_bytes_be(limbs: &[u64; 4]) -> [u8; 32] {
+    let mut bytes = [0_u8; 32];
+    for (chunk, limb) in bytes.rchunks_exact_mut(8).zip(limbs) {
+        chunk.copy_from_slice(&limb.to_be_bytes());
+    }
+    bytes
+}
+
+/// An element of the Cairo prime field, stored in Montgomery form.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StarkFieldElement([u64; 4]);
+
+impl StarkFieldElement {
+    pub const ZERO: StarkFieldElement = StarkFieldElement([0; 4]);
+    pub const ONE: StarkFieldElement = StarkFieldElement(R);
+
+    /// Builds an element from canonical little endian limbs, which must be lower than P.
+    fn from_canonical_limbs(limbs: [u64; 4]) -> Self {
+        StarkFieldElement(montgomery_mul(&limbs, &R2))
+    }
+
+    /// Returns the canonical little endian limbs of the element.
+    fn to_canonical_limbs(self) -> [u64; 4] {
+        montgomery_mul(&self.0, &[1, 0, 0, 0])
+    }
+
+    pub fn from_felt(value: &Felt) -> Self {
+        let mut limbs = [0; 4];
+        // A Felt is always reduced modulo the Cairo prime, so it has at most four digits
+        for (limb, digit) in limbs.iter_mut().zip(value.iter_u64_digits()) {
+            *limb = digit;
+        }
+        Self::from_canonical_limbs(limbs)
+    }
+
+    pub fn to_felt(self) -> Felt {
+        Felt::from_bytes_be(&limbs_to_bytes_be(&self.to_canonical_limbs()))
+    }
+
+    /// Builds an element from any integer, reducing it modulo the Cairo prime.
+    pub fn from_bigint(value: &BigInt) -> Self {
+        let modulus = BigInt::from(BigUint::from_bytes_be(&limbs_to_bytes_be(&MODULUS)));
+        let mut limbs = [0; 4];
+        for (limb, digit) in limbs
+            .iter_mut()
+            .zip(value.mod_floor(&modulus).iter_u64_digits())
+        {
+            *limb = digit;
+        }
+        Self::from_canonical_limbs(limbs)
+    }
+
+    pub fn to_bigint(self) -> BigInt {
+        BigInt::from(BigUint::from_bytes_be(&limbs_to_bytes_be(
+            &self.to_canonical_limbs(),
+        )))
+    }
+
+    pub fn is_zero(&self) -> bool {
+        *self == Self::ZERO
+    }
+
+    pub fn square(self) -> Self {
+        self * self
+    }
+
+    pub fn double(self) -> Self {
+        self + self
+    }
+
+    /// Returns the multiplicative inverse of the element, or zero for zero, which matches the
+    /// result `math_utils::div_mod` yields when dividing by zero.
+    pub fn inverse(self) -> Self {
+        let mut result = Self::ONE;
+        for limb in MODULUS_MINUS_TWO.iter().rev() {
+            for bit in (0..64).rev() {
+                result = result.square();
+                if (limb >> bit) & 1 == 1 {
+                    result = result * self;
+                }
+            }
+        }
+        result
+    }
+}
+
+impl Add for StarkFieldElement {
+    type Output = Self;
+    fn add(self, other: Self) -> Self {
+        // Both operands are lower than P < 2^252, so the sum can't overflow
+        let (sum, _) = add_limbs(&self.0, &other.0);
+        StarkFieldElement(subtract_modulus_if_needed(sum))
+    }
+}
+
+impl Sub for StarkFieldElement {
+    type Output = Self;
+    fn sub(self, other: Self) -> Self {
+        match sub_limbs(&self.0, &other.0) {
+            (difference, 0) => StarkFieldElement(difference),
+            (difference, _) => StarkFieldElement(add_limbs(&difference, &MODULUS).0),
+        }
+    }
+}
+
+impl Neg for StarkFieldElement {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Self::ZERO - self
+    }
+}
+
+impl Mul for StarkFieldElement {
+    type Output = Self;
+    fn mul(self, other: Self) -> Self {
+        StarkFieldElement(montgomery_mul(&self.0, &other.0))
+    }
+}
+
+/// Returns true if the given prime is the Cairo prime, the field of the STARK curve.
+pub fn is_stark_prime(prime: &BigInt) -> bool {
+    prime.sign() == Sign::Plus && prime.iter_u64_digits().eq(MODULUS.iter().copied())
+}
+
+/// A point in affine form (x, y).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AffinePoint {
+    pub x: StarkFieldElement,
+    pub y: StarkFieldElement,
+}
+
+impl AffinePoint {
+    pub fn from_felts(x: &Felt, y: &Felt) -> Self {
+        AffinePoint {
+            x: StarkFieldElement::from_felt(x),
+            y: StarkFieldElement::from_felt(y),
+        }
+    }
+
+    pub fn from_bigints(point: &(BigInt, BigInt)) -> Self {
+        AffinePoint {
+            x: StarkFieldElement::from_bigint(&point.0),
+            y: StarkFieldElement::from_bigint(&point.1),
+        }
+    }
+
+    pub fn to_bigints(self) -> (BigInt, BigInt) {
+        (self.x.to_bigint(), self.y.to_bigint())
+    }
+
+    /// Adds two points. Assumes they have different x coordinates.
+    pub fn add(&self, other: &AffinePoint) -> AffinePoint {
+        debug_assert!(self.x != other.x);
+        let slope = (self.y - other.y) * (self.x - other.x).inverse();
+        let x = slope.square() - self.x - other.x;
+        let y = slope * (self.x - x) - self.y;
+        AffinePoint { x, y }
+    }
+
+    /// Doubles a point on a curve with the given alpha. Assumes the point has y != 0.
+    pub fn double(&self, alpha: &StarkFieldElement) -> AffinePoint {
+        debug_assert!(!self.y.is_zero());
+        let x_squared = self.x.square();
+        let slope = (x_squared.double() + x_squared + *alpha) * self.y.double().inverse();
+        let x = slope.square() - self.x.double();
+        let y = slope * (self.x - x) - self.y;
+        AffinePoint { x, y }
+    }
+}
+
+/// A point in Jacobian coordinates (X, Y, Z), representing the affine point (X / Z^2, Y / Z^3).
+/// Z is never zero, as the point at infinity can't be reached by the operations below.
+#[derive(Debug, Clone, Copy)]
+pub struct ProjectivePoint {
+    x: StarkFieldElement,
+    y: StarkFieldElement,
+    z: StarkFieldElement,
+}
+
+impl From<AffinePoint> for ProjectivePoint {
+    fn from(point: AffinePoint) -> Self {
+        ProjectivePoint {
+            x: point.x,
+            y: point.y,
+            z: StarkFieldElement::ONE,
+        }
+    }
+}
+
+impl ProjectivePoint {
+    pub fn to_affine(&self) -> AffinePoint {
+        let z_inv = self.z.inverse();
+        let z_inv_squared = z_inv.square();
+        AffinePoint {
+            x: self.x * z_inv_squared,
+            y: self.y * z_inv_squared * z_inv,
+        }
+    }
+
+    /// Returns true if both points have the same affine x coordinate.
+    pub fn has_same_x(&self, other: &ProjectivePoint) -> bool {
+        self.x * other.z.square() == other.x * self.z.square()
+    }
+
+    /// Adds two points (add-2007-bl). Assumes they have different affine x coordinates,
+    /// otherwise the result is the one of the affine formulas.
+    pub fn add(&self, other: &ProjectivePoint) -> ProjectivePoint {
+        let z1z1 = self.z.square();
+        let z2z2 = other.z.square();
+        let u1 = self.x * z2z2;
+        let u2 = other.x * z1z1;
+        let h = u2 - u1;
+        if h.is_zero() {
+            return ProjectivePoint::from(self.to_affine().add(&other.to_affine()));
+        }
+        let s1 = self.y * other.z * z2z2;
+        let s2 = other.y * self.z * z1z1;
+        let i = h.double().square();
+        let j = h * i;
+        let r = (s2 - s1).double();
+        let v = u1 * i;
+        let x = r.square() - j - v.double();
+        let y = r * (v - x) - (s1 * j).double();
+        let z = ((self.z + other.z).square() - z1z1 - z2z2) * h;
+        ProjectivePoint { x, y, z }
+    }
+
+    /// Doubles a point on a curve with the given alpha (dbl-2007-bl).
+    /// A point with y = 0 is handled like the affine formulas do, with a zero slope, which
+    /// maps (x, 0) into (-2x, 0).
+    pub fn double(&self, alpha: &StarkFieldElement) -> ProjectivePoint {
+        if self.y.is_zero() {
+            return ProjectivePoint {
+                x: -self.x.double(),
+                y: self.y,
+                z: self.z,
+            };
+        }
+        let xx = self.x.square();
+        let yy = self.y.square();
+        let yyyy = yy.square();
+        let zz = self.z.square();
+        let s = ((self.x + yy).square() - xx - yyyy).double();
+        let m = xx.double() + xx + *alpha * zz.square();
+        let x = m.square() - s.double();
+        let y = m * (s - x) - yyyy.double().double().double();
+        let z = (self.y + self.z).square() - yy - zz;
+        ProjectivePoint { x, y, z }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math_utils::div_mod;
+    use crate::utils::test_utils::*;
+    use felt::{felt_str, NewFelt};
+    use num_traits::{Num, One, Zero};
+
+    fn prime() -> BigInt {
+        BigInt::from_str_radix(&felt::PRIME_STR[2..], 16).expect("Couldn't parse prime")
+    }
+
+    fn sample_values() -> Vec<BigInt> {
+        vec![
+            BigInt::zero(),
+            BigInt::one(),
+            bigint!(34),
+            prime() - 1_i32,
+            bigint_str!(
+                "874739451078007766457464989774322083649278607533249481151382481072868806602"
+            ),
+            bigint_str!(
+                "3141592653589793238462643383279502884197169399375105820974944592307816406665"
+            ),
+            bigint_str!("18446744073709551616"),
+        ]
+    }
+
+    #[test]
+    fn montgomery_constants() {
+        let prime = prime();
+        assert!(is_stark_prime(&prime));
+        assert!(!is_stark_prime(&(prime.clone() + 2_i32)));
+        assert!(!is_stark_prime(&-prime.clone()));
+        let r = BigInt::one() << 256_usize;
+        assert_eq!(
+            StarkFieldElement(R).to_canonical_limbs(),
+            StarkFieldElement::ONE.to_canonical_limbs()
+        );
+        assert_eq!(
+            BigInt::from(BigUint::from_bytes_be(&limbs_to_bytes_be(&R))),
+            r.mod_floor(&prime)
+        );
+        assert_eq!(
+            BigInt::from(BigUint::from_bytes_be(&limbs_to_bytes_be(&R2))),
+            (&r * &r).mod_floor(&prime)
+        );
+    }
+
+    #[test]
+    fn conversions_round_trip() {
+        for value in sample_values() {
+            let element = StarkFieldElement::from_bigint(&value);
+            assert_eq!(element.to_bigint(), value);
+            assert_eq!(element.to_felt(), Felt::new(value.clone()));
+            assert_eq!(StarkFieldElement::from_felt(&Felt::new(value)), element);
+        }
+        assert_eq!(
+            StarkFieldElement::from_bigint(&bigint!(-1)).to_bigint(),
+            prime() - 1_i32
+        );
+        assert_eq!(
+            StarkFieldElement::from_bigint(&(prime() + 5_i32)),
+            StarkFieldElement::from_felt(&Felt::new(5))
+        );
+    }
+
+    #[test]
+    fn field_operations_match_bigint() {
+        let prime = prime();
+        for a in sample_values() {
+            for b in sample_values() {
+                let (fa, fb) = (
+                    StarkFieldElement::from_bigint(&a),
+                    StarkFieldElement::from_bigint(&b),
+                );
+                assert_eq!((fa + fb).to_bigint(), (&a + &b).mod_floor(&prime));
+                assert_eq!((fa - fb).to_bigint(), (&a - &b).mod_floor(&prime));
+                assert_eq!((fa * fb).to_bigint(), (&a * &b).mod_floor(&prime));
+                if !b.is_zero() {
+                    assert_eq!((fa * fb.inverse()).to_bigint(), div_mod(&a, &b, &prime));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn inverse_of_zero_is_zero() {
+        assert_eq!(StarkFieldElement::ZERO.inverse(), StarkFieldElement::ZERO);
+        assert_eq!(StarkFieldElement::ONE.inverse(), StarkFieldElement::ONE);
+    }
+
+    #[test]
+    fn projective_operations_match_affine() {
+        let alpha = StarkFieldElement::ONE;
+        let point = AffinePoint::from_felts(
+            &felt_str!(
+                "874739451078007766457464989774322083649278607533249481151382481072868806602"
+            ),
+            &felt_str!(
+                "152666792071518830868575557812948353041420400780739481342941381225525861407"
+            ),
+        );
+        let other = AffinePoint::from_felts(
+            &felt_str!(
+                "3139037544796708144595053687182055617920475701120786241351436619796497072089"
+            ),
+            &felt_str!(
+                "2119589567875935397690285099786081818522144748339117565577200220779667999801"
+            ),
+        );
+        let mut affine = (point, other);
+        let mut projective = (ProjectivePoint::from(point), ProjectivePoint::from(other));
+        for _ in 0..16 {
+            affine = (affine.0.double(&alpha), affine.1.add(&affine.0));
+            projective = (projective.0.double(&alpha), projective.1.add(&projective.0));
+            assert_eq!(projective.0.to_affine(), affine.0);
+            assert_eq!(projective.1.to_affine(), affine.1);
+            assert!(!projective.0.has_same_x(&projective.1));
+        }
+    }
+
+    #[test]
+    fn has_same_x_in_different_representations() {
+        let alpha = StarkFieldElement::ONE;
+        let point = AffinePoint::from_felts(&Felt::new(1), &Felt::new(9));
+        let doubled = ProjectivePoint::from(point).double(&alpha);
+        let doubled_affine = ProjectivePoint::from(doubled.to_affine());
+        assert!(doubled.has_same_x(&doubled_affine));
+        assert!(!doubled.has_same_x(&ProjectivePoint::from(point)));
+    }
+
+    #[test]
+    fn double_point_with_zero_y() {
+        let alpha = StarkFieldElement::ONE;
+        let point = AffinePoint::from_felts(&Felt::new(3), &Felt::new(0));
+        let expected_x = StarkFieldElement::from_bigint(&bigint!(-6));
+        assert_eq!(
+            ProjectivePoint::from(point).double(&alpha).to_affine(),
+            AffinePoint {
+                x: expected_x,
+                y: StarkFieldElement::ZERO
+            }
+        );
+        // Doubling again keeps mapping x into -2x
+        assert_eq!(
+            ProjectivePoint::from(point)
+                .double(&alpha)
+                .double(&alpha)
+                .to_affine()
+                .x,
+            StarkFieldElement::from_bigint(&bigint!(12))
+        );
+    }
+}
@@ -99,12 +99,19 @@ pub struct Location {
 #[derive(Deserialize, Debug, PartialEq, Eq)]
 pub struct DebugInfo {
     instruction_locations: HashMap<usize, InstructionLocation>,
+    // The contents of the files that aren't on disk, ie: the code generated by the compiler
+    #[serde(default)]
+    file_contents: HashMap<String, String>,
 }
 
 #[derive(Deserialize, Debug, PartialEq, Eq, Clone)]
 pub struct InstructionLocation {
     pub inst: Location,
     pub hints: Vec<HintLocation>,
+    // The ap tracking before the instruction, used to evaluate the ap-based references of
+    // error messages
+    #[serde(default)]
+    pub flow_tracking_data: Option<FlowTrackingData>,
 }
 
 #[derive(Deserialize, Clone, Debug, PartialEq, Eq)]
@@ -370,6 +377,11 @@ pub fn deserialize_program(
             .into_iter()
             .filter(|attr| attr.name == "error_message")
             .collect(),
+        file_contents: program_json
+            .debug_info
+            .as_ref()
+            .map(|debug_info| debug_info.file_contents.clone())
+            .unwrap_or_default(),
         instruction_locations: program_json
             .debug_info
             .map(|debug_info| debug_info.instruction_locations),
@@ -1156,6 +1168,13 @@ mod tests {
                             start_col: 5,
                         },
                         hints: vec![],
+                        flow_tracking_data: Some(FlowTrackingData {
+                            ap_tracking: ApTracking {
+                                group: 0,
+                                offset: 0,
+                            },
+                            reference_ids: HashMap::new(),
+                        }),
                     },
                 ),
                 (
@@ -1170,9 +1189,17 @@ mod tests {
                             start_col: 5,
                         },
                         hints: vec![],
+                        flow_tracking_data: Some(FlowTrackingData {
+                            ap_tracking: ApTracking {
+                                group: 1,
+                                offset: 1,
+                            },
+                            reference_ids: HashMap::new(),
+                        }),
                     },
                 ),
             ]),
+            file_contents: HashMap::new(),
         };
 
         assert_eq!(program_json.debug_info, Some(debug_info));
@@ -1271,10 +1298,46 @@ mod tests {
                         }), String::from( "While expanding the reference 'syscall_ptr' in:"))
                     ), start_line: 9, start_col: 18 },
                     hints: vec![],
+                    flow_tracking_data: None,
                 }),
             ]
-        ) };
+        ), file_contents: HashMap::new() };
 
         assert_eq!(program_json.debug_info, Some(debug_info));
     }
+
+    #[test]
+    fn deserialize_program_with_file_contents() {
+        let valid_json = r#"
+            {
+                "prime": "0x800000000000011000000000000000000000000000000000000000000000001",
+                "attributes": [],
+                "debug_info": {
+                    "file_contents": {
+                        "<start>": "__start__:\nap += main.Args.SIZE + main.ImplicitArgs.SIZE;\n"
+                    },
+                    "instruction_locations": {}
+                },
+                "builtins": [],
+                "data": [
+                ],
+                "identifiers": {
+                },
+                "hints": {
+                },
+                "reference_manager": {
+                    "references": [
+                    ]
+                }
+            }"#;
+
+        let program = deserialize_program(valid_json.as_bytes(), None).unwrap();
+        assert_eq!(
+            program.file_contents,
+            HashMap::from([(
+                String::from("<start>"),
+                String::from("__start__:\nap += main.Args.SIZE + main.ImplicitArgs.SIZE;\n")
+            )])
+        );
+    }
 }
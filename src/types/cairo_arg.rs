@@ -0,0 +1,35 @@
+use crate::types::relocatable::{MaybeRelocatable, Relocatable};
+
+/// A value written to the memory by the host, like the arguments accepted by the Python VM's
+/// `segments.gen_arg`: either a single value, or a list (or tuple) of values, which is written
+/// to a new segment and replaced by a pointer to it. Lists can be nested, each one getting its
+/// own segment.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CairoArg {
+    Single(MaybeRelocatable),
+    Array(Vec<CairoArg>),
+}
+
+impl From<MaybeRelocatable> for CairoArg {
+    fn from(value: MaybeRelocatable) -> Self {
+        CairoArg::Single(value)
+    }
+}
+
+impl From<Relocatable> for CairoArg {
+    fn from(value: Relocatable) -> Self {
+        CairoArg::Single(value.into())
+    }
+}
+
+impl From<Vec<MaybeRelocatable>> for CairoArg {
+    fn from(values: Vec<MaybeRelocatable>) -> Self {
+        CairoArg::Array(values.into_iter().map(CairoArg::Single).collect())
+    }
+}
+
+impl From<Vec<CairoArg>> for CairoArg {
+    fn from(values: Vec<CairoArg>) -> Self {
+        CairoArg::Array(values)
+    }
+}
@@ -1,6 +1,7 @@
 use crate::{
     any_box,
     hint_processor::builtin_hint_processor::dict_manager::DictManager,
+    serde::deserialize_program::Identifier,
     vm::errors::{exec_scope_errors::ExecScopeError, hint_errors::HintError},
 };
 use std::{any::Any, cell::RefCell, collections::HashMap, rc::Rc};
@@ -175,6 +176,36 @@ impl ExecutionScopes {
     pub fn insert_value<T: 'static>(&mut self, name: &str, value: T) {
         self.assign_or_update_variable(name, any_box!(value));
     }
+
+    ///Inserts the boxed values into the main scope, overwriting any variable with the same name
+    pub fn insert_main_scope_values(&mut self, values: HashMap<String, Box<dyn Any>>) {
+        if let Some(main_scope) = self.data.first_mut() {
+            main_scope.extend(values);
+        }
+    }
+
+    ///Returns the program input. Unlike other variables, it is read from the innermost scope that defines it, so hints can access it from any scope,
+    ///while the tasks run by the bootloader get their own input
+    pub fn get_program_input(&self) -> Result<&serde_json::Value, HintError> {
+        self.data
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get("program_input"))
+            .and_then(|variable| variable.downcast_ref::<serde_json::Value>())
+            .ok_or_else(|| HintError::VariableNotInScopeError("program_input".to_string()))
+    }
+
+    ///Returns the identifiers of the program being run, which the runner inserts into the main scope.
+    ///Like the program input, they are read from the innermost scope that defines them
+    pub fn get_program_identifiers(&self) -> Result<Rc<HashMap<String, Identifier>>, HintError> {
+        self.data
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get("program_identifiers"))
+            .and_then(|variable| variable.downcast_ref::<Rc<HashMap<String, Identifier>>>())
+            .cloned()
+            .ok_or_else(|| HintError::VariableNotInScopeError("program_identifiers".to_string()))
+    }
 }
 
 impl Default for ExecutionScopes {
@@ -186,6 +217,7 @@ impl Default for ExecutionScopes {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::utils::test_utils::struct_identifier;
     use felt::{Felt, NewFelt};
     use num_traits::One;
 
@@ -447,4 +479,64 @@ mod tests {
         assert!(scopes.get_any_boxed_mut("no_variable").is_err());
         assert!(scopes.get_any_boxed_ref("no_variable").is_err());
     }
+
+    #[test]
+    fn insert_main_scope_values_from_inner_scope() {
+        let mut scopes = ExecutionScopes::new();
+        scopes.enter_scope(HashMap::new());
+
+        scopes
+            .insert_main_scope_values(HashMap::from([(String::from("a"), any_box!(Felt::new(2)))]));
+
+        assert!(scopes.get::<Felt>("a").is_err());
+        assert!(scopes.exit_scope().is_ok());
+        assert_eq!(scopes.get::<Felt>("a"), Ok(Felt::new(2)));
+    }
+
+    #[test]
+    fn get_program_input_from_inner_scope() {
+        let program_input = serde_json::json!({"a": [1, 2]});
+        let mut scopes = ExecutionScopes::new();
+        scopes.insert_value("program_input", program_input.clone());
+        scopes.enter_scope(HashMap::new());
+
+        assert_eq!(scopes.get_program_input(), Ok(&program_input));
+    }
+
+    #[test]
+    fn get_program_input_missing() {
+        let scopes = ExecutionScopes::new();
+
+        assert_eq!(
+            scopes.get_program_input(),
+            Err(HintError::VariableNotInScopeError(
+                "program_input".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn get_program_identifiers_from_inner_scope() {
+        let identifiers = Rc::new(HashMap::from([(
+            String::from("__main__.Point"),
+            struct_identifier("__main__.Point", &[("x", "felt", 0)]),
+        )]));
+        let mut scopes = ExecutionScopes::new();
+        scopes.insert_value("program_identifiers", identifiers.clone());
+        scopes.enter_scope(HashMap::new());
+
+        assert_eq!(scopes.get_program_identifiers(), Ok(identifiers));
+    }
+
+    #[test]
+    fn get_program_identifiers_missing() {
+        let scopes = ExecutionScopes::new();
+
+        assert_eq!(
+            scopes.get_program_identifiers(),
+            Err(HintError::VariableNotInScopeError(
+                "program_identifiers".to_string()
+            ))
+        );
+    }
 }
@@ -0,0 +1,86 @@
+use felt::{Felt, FeltOps};
+use starknet_crypto::FieldElement;
+
+// Conversions between Felt and the FieldElement used by starknet-crypto (pedersen, ecdsa),
+// through their 32-byte big endian representation.
+
+pub trait ToFieldElement {
+    /// Returns None if the value doesn't fit in the field of starknet-crypto, which can't happen
+    /// for a Felt of the Cairo prime.
+    fn to_field_element(&self) -> Option<FieldElement>;
+}
+
+pub trait FromFieldElement {
+    fn from_field_element(value: &FieldElement) -> Self;
+}
+
+impl ToFieldElement for Felt {
+    fn to_field_element(&self) -> Option<FieldElement> {
+        let mut bytes = [0_u8; 32];
+        // The digits are little endian, and there are at most four of them
+        for (chunk, digit) in bytes.rchunks_exact_mut(8).zip(self.iter_u64_digits()) {
+            chunk.copy_from_slice(&digit.to_be_bytes());
+        }
+        FieldElement::from_bytes_be(&bytes).ok()
+    }
+}
+
+impl FromFieldElement for Felt {
+    fn from_field_element(value: &FieldElement) -> Self {
+        Felt::from_bytes_be(&value.to_bytes_be())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use felt::{felt_str, NewFelt};
+    use num_traits::{One, Zero};
+
+    #[test]
+    fn to_field_element() {
+        assert_eq!(
+            Felt::zero().to_field_element(),
+            Some(FieldElement::from(0_u64))
+        );
+        assert_eq!(
+            Felt::one().to_field_element(),
+            Some(FieldElement::from(1_u64))
+        );
+        assert_eq!(
+            Felt::new(u64::MAX).to_field_element(),
+            Some(FieldElement::from(u64::MAX))
+        );
+        let value = felt_str!(
+            "3618502788666131213697322783095070105623107215331596699973092056135872020480"
+        );
+        assert_eq!(
+            value.to_field_element(),
+            FieldElement::from_dec_str(&value.to_str_radix(10)).ok()
+        );
+        let value = felt_str!("340282366920938463463374607431768211457");
+        assert_eq!(
+            value.to_field_element(),
+            FieldElement::from_dec_str(&value.to_str_radix(10)).ok()
+        );
+    }
+
+    #[test]
+    fn from_field_element() {
+        assert_eq!(
+            Felt::from_field_element(&FieldElement::from(0_u64)),
+            Felt::zero()
+        );
+        let value = felt_str!(
+            "2089986280348253421170679821480865132823066470938446095505822317253594081284"
+        );
+        assert_eq!(
+            Felt::from_field_element(&value.to_field_element().unwrap()),
+            value
+        );
+        assert_eq!(
+            Felt::from_field_element(&FieldElement::from(7_u64)),
+            Felt::new(7)
+        );
+    }
+}
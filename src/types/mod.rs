@@ -1,5 +1,7 @@
+pub mod cairo_arg;
 pub mod errors;
 pub mod exec_scope;
+pub mod field_element;
 pub mod instance_definitions;
 pub mod instruction;
 pub mod layout;
@@ -27,6 +27,8 @@ pub struct Program {
     pub identifiers: HashMap<String, Identifier>,
     pub error_message_attributes: Vec<Attribute>,
     pub instruction_locations: Option<HashMap<usize, InstructionLocation>>,
+    /// The contents of the source files included in the debug info, keyed by filename.
+    pub file_contents: HashMap<String, String>,
 }
 
 impl Program {
@@ -68,6 +70,7 @@ impl Program {
             identifiers,
             error_message_attributes,
             instruction_locations,
+            file_contents: HashMap::new(),
         })
     }
 
@@ -103,6 +106,7 @@ impl Default for Program {
             identifiers: HashMap::new(),
             error_message_attributes: Vec::new(),
             instruction_locations: None,
+            file_contents: HashMap::new(),
         }
     }
 }
@@ -501,6 +505,7 @@ mod tests {
             identifiers: HashMap::new(),
             error_message_attributes: Vec::new(),
             instruction_locations: None,
+            file_contents: HashMap::new(),
         };
 
         assert_eq!(program, Program::default())
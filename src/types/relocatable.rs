@@ -270,9 +270,9 @@ impl MaybeRelocatable {
                 MaybeRelocatable::RelocatableValue(rel_b),
             ) => {
                 if rel_a.segment_index == rel_b.segment_index {
-                    return Ok(MaybeRelocatable::from(Felt::new(
-                        rel_a.offset - rel_b.offset,
-                    )));
+                    return Ok(MaybeRelocatable::from(
+                        Felt::new(rel_a.offset) - Felt::new(rel_b.offset),
+                    ));
                 }
                 Err(VirtualMachineError::DiffIndexSub)
             }
@@ -540,6 +540,17 @@ mod tests {
         assert_eq!(Ok(MaybeRelocatable::from(Felt::new(10))), sub_addr);
     }
 
+    #[test]
+    fn sub_relocatable_from_relocatable_bigger_offset() {
+        let addr_a = &MaybeRelocatable::from((7, 7));
+        let addr_b = &MaybeRelocatable::from((7, 17));
+        let sub_addr = addr_a.sub(addr_b);
+        assert_eq!(
+            Ok(MaybeRelocatable::from(Felt::zero() - Felt::new(10))),
+            sub_addr
+        );
+    }
+
     #[test]
     fn sub_relocatable_from_relocatable_diff_offset() {
         let addr_a = &MaybeRelocatable::from((7, 17));
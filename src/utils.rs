@@ -55,8 +55,11 @@ pub fn to_field_element(num: Felt, prime: Felt) -> Felt {
 #[cfg(test)]
 #[macro_use]
 pub mod test_utils {
+    use crate::serde::deserialize_program::{HintParams, Identifier, Member};
     use crate::types::exec_scope::ExecutionScopes;
+    use crate::types::program::Program;
     use crate::types::relocatable::MaybeRelocatable;
+    use std::collections::HashMap;
 
     #[macro_export]
     macro_rules! bigint {
@@ -132,7 +135,8 @@ pub mod test_utils {
             );
             let mut res = $mem.insert(k, v);
             while matches!(res, Err(MemoryError::UnallocatedSegment(_, _))) {
-                $mem.data.push(Vec::new());
+                $mem.data
+                    .push($crate::vm::vm_memory::segment_storage::MemorySegment::new());
                 res = $mem.insert(k, v);
             }
         };
@@ -140,7 +144,8 @@ pub mod test_utils {
             let (k, v) = (&mayberelocatable!($si, $off), &mayberelocatable!($val));
             let mut res = $mem.insert(k, v);
             while matches!(res, Err(MemoryError::UnallocatedSegment(_, _))) {
-                $mem.data.push(Vec::new());
+                $mem.data
+                    .push($crate::vm::vm_memory::segment_storage::MemorySegment::new());
                 res = $mem.insert(k, v);
             }
         };
@@ -249,17 +254,21 @@ pub mod test_utils {
                 identifiers: HashMap::new(),
                 error_message_attributes: Vec::new(),
                 instruction_locations: None,
+                file_contents: HashMap::new(),
             }
         };
         // Custom program definition
-        ($($field:ident = $value:expr),* $(,)?) => {
-            Program {
+        ($($field:ident = $value:expr),* $(,)?) => {{
+            // Allows writing program!(hints = hints)
+            #[allow(clippy::redundant_field_names)]
+            let program = Program {
                 $(
                     $field: $value,
                 )*
                 ..Default::default()
-            }
-        }
+            };
+            program
+        }}
     }
     pub(crate) use program;
 
@@ -508,6 +517,65 @@ pub mod test_utils {
         let scope_value = scopes.get_any_boxed_ref(name).unwrap();
         assert_eq!(scope_value.downcast_ref::<T>(), Some(&value));
     }
+
+    /// Builds the identifier of a struct, from its members as (name, type, offset).
+    pub(crate) fn struct_identifier(
+        full_name: &str,
+        members: &[(&str, &str, usize)],
+    ) -> Identifier {
+        Identifier {
+            pc: None,
+            type_: Some(String::from("struct")),
+            value: None,
+            full_name: Some(full_name.to_string()),
+            members: Some(
+                members
+                    .iter()
+                    .map(|(name, cairo_type, offset)| {
+                        (
+                            name.to_string(),
+                            Member {
+                                cairo_type: cairo_type.to_string(),
+                                offset: *offset,
+                            },
+                        )
+                    })
+                    .collect(),
+            ),
+        }
+    }
+
+    /*Program used, which runs the pcs 3, 5, 0, 2 and 7:
+    func myfunc(a: felt) -> (r: felt):
+        let b = a * 2
+        return(b)
+    end
+
+    func main():
+        let a = 1
+        let b = myfunc(a)
+        return()
+    end
+    */
+    pub(crate) fn function_call_program(hints: HashMap<usize, Vec<HintParams>>) -> Program {
+        program!(
+            data = vec_data!(
+                (5207990763031199744_i64),
+                (2),
+                (2345108766317314046_i64),
+                (5189976364521848832_i64),
+                (1),
+                (1226245742482522112_i64),
+                ((
+                    "3618502788666131213697322783095070105623107215331596699973092056135872020476",
+                    10
+                )),
+                (2345108766317314046_i64)
+            ),
+            main = Some(3),
+            hints = hints,
+        )
+    }
 }
 
 #[cfg(test)]
@@ -524,8 +592,10 @@ mod test {
         types::{exec_scope::ExecutionScopes, program::Program, relocatable::MaybeRelocatable},
         utils::test_utils::*,
         vm::{
-            errors::memory_errors::MemoryError, trace::trace_entry::TraceEntry,
-            vm_core::VirtualMachine, vm_memory::memory::Memory,
+            errors::memory_errors::MemoryError,
+            trace::trace_entry::TraceEntry,
+            vm_core::VirtualMachine,
+            vm_memory::{memory::Memory, segment_storage::MemorySegment},
         },
     };
     use felt::{Felt, NewFelt};
@@ -538,7 +608,7 @@ mod test {
     fn memory_macro_test() {
         let mut memory = Memory::new();
         for _ in 0..2 {
-            memory.data.push(Vec::new());
+            memory.data.push(MemorySegment::new());
         }
         memory
             .insert(
@@ -560,7 +630,7 @@ mod test {
     fn check_memory_macro_test() {
         let mut memory = Memory::new();
         for _ in 0..2 {
-            memory.data.push(Vec::new());
+            memory.data.push(MemorySegment::new());
         }
         memory
             .insert(
@@ -583,7 +653,7 @@ mod test {
     fn check_memory_address_macro_test() {
         let mut memory = Memory::new();
         for _ in 0..2 {
-            memory.data.push(Vec::new());
+            memory.data.push(MemorySegment::new());
         }
         memory
             .insert(
@@ -874,6 +944,7 @@ mod test {
             identifiers: HashMap::new(),
             error_message_attributes: Vec::new(),
             instruction_locations: None,
+            file_contents: HashMap::new(),
         };
 
         assert_eq!(program, program!())
@@ -896,6 +967,7 @@ mod test {
             identifiers: HashMap::new(),
             error_message_attributes: Vec::new(),
             instruction_locations: None,
+            file_contents: HashMap::new(),
         };
 
         assert_eq!(program, program!["range_check"])
@@ -918,6 +990,7 @@ mod test {
             identifiers: HashMap::new(),
             error_message_attributes: Vec::new(),
             instruction_locations: None,
+            file_contents: HashMap::new(),
         };
 
         assert_eq!(
@@ -0,0 +1,147 @@
+use crate::{
+    types::{instruction::Instruction, relocatable::Relocatable},
+    vm::vm_memory::{memory::Memory, segment_storage::MemoryCell},
+};
+use std::rc::Rc;
+
+/// Decoded instructions, indexed by the segment and offset of their pc.
+/// Memory cells can't be overwritten, but an entry is only returned if the instruction word and
+/// immediate it was decoded from are still the ones in memory, so writing to the program
+/// segment by other means (ie: replacing the memory) invalidates it.
+/// Instructions in temporary segments aren't cached.
+#[derive(Debug, Default)]
+pub(crate) struct InstructionCache {
+    segments: Vec<Vec<Option<CachedInstruction>>>,
+}
+
+#[derive(Debug)]
+struct CachedInstruction {
+    encoding: u64,
+    // The cell holding the immediate, if the instruction has one
+    imm: Option<MemoryCell>,
+    instruction: Rc<Instruction>,
+}
+
+impl InstructionCache {
+    /// Returns the instruction decoded at `pc`, if it is still valid.
+    pub(crate) fn get(&self, pc: &Relocatable, memory: &Memory) -> Option<Rc<Instruction>> {
+        let segment_index = usize::try_from(pc.segment_index).ok()?;
+        let cached = self.segments.get(segment_index)?.get(pc.offset)?.as_ref()?;
+        if memory.get_u64(pc) != Some(cached.encoding) {
+            return None;
+        }
+        if let Some(ref imm) = cached.imm {
+            if memory.get_cell(&(*pc + 1_usize)) != Some(imm) {
+                return None;
+            }
+        }
+        Some(Rc::clone(&cached.instruction))
+    }
+
+    /// Caches `instruction`, which was decoded from the memory at `pc`.
+    pub(crate) fn insert(
+        &mut self,
+        pc: &Relocatable,
+        memory: &Memory,
+        instruction: Rc<Instruction>,
+    ) {
+        let (segment_index, encoding) =
+            match (usize::try_from(pc.segment_index), memory.get_u64(pc)) {
+                (Ok(segment_index), Some(encoding)) => (segment_index, encoding),
+                _ => return,
+            };
+        let imm = match instruction.imm {
+            Some(_) => match memory.get_cell(&(*pc + 1_usize)) {
+                Some(imm) => Some(imm.clone()),
+                None => return,
+            },
+            None => None,
+        };
+        if self.segments.len() <= segment_index {
+            self.segments.resize_with(segment_index + 1, Vec::new);
+        }
+        let segment = &mut self.segments[segment_index];
+        if segment.len() <= pc.offset {
+            segment.resize_with(pc.offset + 1, || None);
+        }
+        segment[pc.offset] = Some(CachedInstruction {
+            encoding,
+            imm,
+            instruction,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        types::relocatable::MaybeRelocatable,
+        utils::test_utils::*,
+        vm::{decoding::decoder::decode_instruction, errors::memory_errors::MemoryError},
+    };
+    use felt::{Felt, NewFelt};
+
+    // [ap] = 5, ap++
+    const ENCODING_WITH_IMM: i64 = 0x480680017fff8000;
+    // ret
+    const ENCODING_WITHOUT_IMM: i64 = 0x208b7fff7fff7ffe;
+
+    fn decode(encoding: i64, imm: Option<i64>) -> Rc<Instruction> {
+        Rc::new(decode_instruction(encoding, imm.map(Felt::new).as_ref()).unwrap())
+    }
+
+    #[test]
+    fn get_cached_instruction() {
+        let memory = memory![((0, 0), ENCODING_WITH_IMM), ((0, 1), 5)];
+        let mut cache = InstructionCache::default();
+        assert_eq!(cache.get(&(0, 0).into(), &memory), None);
+
+        cache.insert(&(0, 0).into(), &memory, decode(ENCODING_WITH_IMM, Some(5)));
+        assert_eq!(
+            cache.get(&(0, 0).into(), &memory),
+            Some(decode(ENCODING_WITH_IMM, Some(5)))
+        );
+        assert_eq!(cache.get(&(0, 1).into(), &memory), None);
+        assert_eq!(cache.get(&(1, 0).into(), &memory), None);
+    }
+
+    #[test]
+    fn get_stale_instruction() {
+        let memory = memory![((0, 0), ENCODING_WITH_IMM), ((0, 1), 5)];
+        let mut cache = InstructionCache::default();
+        cache.insert(&(0, 0).into(), &memory, decode(ENCODING_WITH_IMM, Some(5)));
+
+        let memory = memory![((0, 0), ENCODING_WITH_IMM), ((0, 1), 6)];
+        assert_eq!(cache.get(&(0, 0).into(), &memory), None);
+        let memory = memory![((0, 0), ENCODING_WITHOUT_IMM), ((0, 1), 5)];
+        assert_eq!(cache.get(&(0, 0).into(), &memory), None);
+        assert_eq!(cache.get(&(0, 0).into(), &Memory::new()), None);
+    }
+
+    #[test]
+    fn get_instruction_without_imm_ignores_next_cell() {
+        let memory = memory![((0, 0), ENCODING_WITHOUT_IMM)];
+        let mut cache = InstructionCache::default();
+        cache.insert(&(0, 0).into(), &memory, decode(ENCODING_WITHOUT_IMM, None));
+
+        let memory = memory![((0, 0), ENCODING_WITHOUT_IMM), ((0, 1), 7)];
+        assert_eq!(
+            cache.get(&(0, 0).into(), &memory),
+            Some(decode(ENCODING_WITHOUT_IMM, None))
+        );
+    }
+
+    #[test]
+    fn insert_in_temporary_segment() {
+        let mut memory = Memory::new();
+        memory.temp_data = vec![vec![Some(MaybeRelocatable::from(Felt::new(
+            ENCODING_WITHOUT_IMM,
+        )))]
+        .into()];
+        let mut cache = InstructionCache::default();
+        cache.insert(&(-1, 0).into(), &memory, decode(ENCODING_WITHOUT_IMM, None));
+        assert_eq!(cache.get(&(-1, 0).into(), &memory), None);
+        assert!(cache.segments.is_empty());
+    }
+}
@@ -1 +1,2 @@
 pub mod decoder;
+pub(crate) mod instruction_cache;
@@ -2,7 +2,8 @@ use super::memory_errors::MemoryError;
 use super::vm_exception::VmException;
 use crate::types::errors::program_errors::ProgramError;
 use crate::vm::errors::{
-    runner_errors::RunnerError, trace_errors::TraceError, vm_errors::VirtualMachineError,
+    hint_errors::HintError, runner_errors::RunnerError, trace_errors::TraceError,
+    vm_errors::VirtualMachineError,
 };
 use thiserror::Error;
 
@@ -20,4 +21,6 @@ pub enum CairoRunError {
     MemoryError(#[from] MemoryError),
     #[error(transparent)]
     VmException(#[from] VmException),
+    #[error(transparent)]
+    Hint(#[from] HintError),
 }
@@ -0,0 +1,586 @@
+use crate::{
+    serde::deserialize_program::Location,
+    types::program::Program,
+    vm::errors::vm_exception::{TracebackFrame, VmException},
+};
+use serde::Serialize;
+use std::{borrow::Cow, collections::HashMap, fs};
+
+const RED: &str = "\x1b[1;31m";
+const BLUE: &str = "\x1b[1;34m";
+const BOLD: &str = "\x1b[1m";
+const RESET: &str = "\x1b[0m";
+
+/// The contents of the source files of a program, used to print snippets of the files that
+/// aren't on disk.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SourceMap {
+    files: HashMap<String, String>,
+}
+
+impl SourceMap {
+    pub fn new() -> SourceMap {
+        SourceMap::default()
+    }
+
+    /// Returns a source map with the file contents included in the debug info of the program.
+    pub fn from_program(program: &Program) -> SourceMap {
+        SourceMap {
+            files: program.file_contents.clone(),
+        }
+    }
+
+    pub fn insert(&mut self, filename: &str, contents: &str) {
+        self.files
+            .insert(filename.to_string(), contents.to_string());
+    }
+
+    /// Returns the contents of the file, read from disk if possible, or from the source map
+    /// otherwise.
+    pub fn get_source(&self, filename: &str) -> Option<Cow<str>> {
+        match fs::read_to_string(filename) {
+            Ok(contents) => Some(Cow::Owned(contents)),
+            Err(_) => self
+                .files
+                .get(filename)
+                .map(|contents| Cow::Borrowed(contents.as_str())),
+        }
+    }
+}
+
+/// The role of a span in a diagnostic.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SpanKind {
+    /// The code that failed.
+    Primary,
+    /// A location the primary span was expanded from (ie: the use of a reference).
+    Parent,
+    /// A call of the traceback.
+    Traceback,
+}
+
+/// A range of a source file, with 1-based lines and columns. `end_col` is exclusive.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct DiagnosticSpan {
+    pub kind: SpanKind,
+    pub filename: String,
+    pub start_line: u32,
+    pub start_col: u32,
+    pub end_line: u32,
+    pub end_col: u32,
+    pub label: String,
+}
+
+impl DiagnosticSpan {
+    fn new(location: &Location, kind: SpanKind, label: String) -> DiagnosticSpan {
+        DiagnosticSpan {
+            kind,
+            filename: location.input_file.filename.clone(),
+            start_line: location.start_line,
+            start_col: location.start_col,
+            end_line: location.end_line,
+            end_col: location.end_col,
+            label,
+        }
+    }
+}
+
+/// A call of the Cairo traceback.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct DiagnosticFrame {
+    pub pc: usize,
+    pub error_messages: Vec<String>,
+    pub span: Option<DiagnosticSpan>,
+}
+
+/// The information of a `VmException`, in a form that can be rendered with source snippets
+/// by a `DiagnosticRenderer`, or serialized as JSON for IDEs and CI annotations.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Diagnostic {
+    pub message: String,
+    pub pc: usize,
+    /// The values of the error_message attributes around the pc.
+    pub error_messages: Vec<String>,
+    /// The primary span, followed by the locations it was expanded from.
+    pub spans: Vec<DiagnosticSpan>,
+    /// The calls that led to the error, from the outermost one.
+    pub traceback: Vec<DiagnosticFrame>,
+}
+
+impl Diagnostic {
+    pub fn from_vm_exception(exception: &VmException) -> Diagnostic {
+        let mut spans = Vec::new();
+        let mut location = exception.inst_location.as_ref().map(|location| {
+            (
+                location,
+                SpanKind::Primary,
+                format!("Error at pc=0:{}", exception.pc),
+            )
+        });
+        while let Some((current, kind, label)) = location {
+            spans.push(DiagnosticSpan::new(current, kind, label));
+            location = current
+                .parent_location
+                .as_ref()
+                .map(|(parent, message)| (parent.as_ref(), SpanKind::Parent, message.clone()));
+        }
+
+        Diagnostic {
+            message: exception.inner_exc.to_string(),
+            pc: exception.pc,
+            error_messages: exception.error_messages.clone(),
+            spans,
+            traceback: exception
+                .traceback_frames
+                .iter()
+                .map(DiagnosticFrame::from)
+                .collect(),
+        }
+    }
+
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(self)
+    }
+}
+
+impl From<&TracebackFrame> for DiagnosticFrame {
+    fn from(frame: &TracebackFrame) -> Self {
+        DiagnosticFrame {
+            pc: frame.pc,
+            error_messages: frame.error_messages.clone(),
+            span: frame.location.as_ref().map(|location| {
+                DiagnosticSpan::new(
+                    location,
+                    SpanKind::Traceback,
+                    format!("(pc=0:{})", frame.pc),
+                )
+            }),
+        }
+    }
+}
+
+/// Renders diagnostics as text, with a snippet of the source code of each span.
+/// Spans whose source can't be found are printed as `filename:line:col: label`, as in the
+/// `Display` of `VmException`.
+pub struct DiagnosticRenderer<'a> {
+    source_map: &'a SourceMap,
+    color: bool,
+}
+
+impl<'a> DiagnosticRenderer<'a> {
+    pub fn new(source_map: &'a SourceMap) -> DiagnosticRenderer<'a> {
+        DiagnosticRenderer {
+            source_map,
+            color: false,
+        }
+    }
+
+    /// Sets whether the output is colored with ANSI escape codes.
+    pub fn with_color(mut self, color: bool) -> DiagnosticRenderer<'a> {
+        self.color = color;
+        self
+    }
+
+    pub fn render(&self, diagnostic: &Diagnostic) -> String {
+        let mut output = format!(
+            "{}{}\n",
+            self.paint(RED, "error"),
+            self.paint(BOLD, &format!(": {}", diagnostic.message))
+        );
+        self.render_error_messages(&diagnostic.error_messages, &mut output);
+        // The outermost location is printed first, as in the Display of VmException
+        for span in diagnostic.spans.iter().rev() {
+            self.render_span(span, &mut output);
+        }
+        if diagnostic.spans.is_empty() {
+            output.push_str(&format!(
+                "{} at pc=0:{}\n",
+                self.paint(BLUE, " -->"),
+                diagnostic.pc
+            ));
+        }
+
+        if !diagnostic.traceback.is_empty() {
+            output.push_str(&format!(
+                "{}\n",
+                self.paint(BOLD, "Cairo traceback (most recent call last):")
+            ));
+        }
+        for frame in &diagnostic.traceback {
+            self.render_error_messages(&frame.error_messages, &mut output);
+            match &frame.span {
+                Some(span) => self.render_span(span, &mut output),
+                None => output.push_str(&format!("Unknown location (pc=0:{})\n", frame.pc)),
+            }
+        }
+        output
+    }
+
+    fn render_error_messages(&self, error_messages: &[String], output: &mut String) {
+        for error_message in error_messages {
+            output.push_str(&format!(
+                "{} {}\n",
+                self.paint(BLUE, "  = Error message:"),
+                error_message
+            ));
+        }
+    }
+
+    fn render_span(&self, span: &DiagnosticSpan, output: &mut String) {
+        let source = self.source_map.get_source(&span.filename);
+        let lines: Vec<&str> = source
+            .as_deref()
+            .map(|source| source.split('\n').collect())
+            .unwrap_or_default();
+        let (start_line, end_line) = (span.start_line as usize, span.end_line as usize);
+        if start_line == 0 || start_line > lines.len() {
+            let msg_prefix = if span.label.is_empty() { "" } else { ": " };
+            output.push_str(&format!(
+                "{}:{}:{}{}{}\n",
+                span.filename, span.start_line, span.start_col, msg_prefix, span.label
+            ));
+            return;
+        }
+        let end_line = end_line.clamp(start_line, lines.len());
+
+        let gutter_width = end_line.to_string().len();
+        let style = match span.kind {
+            SpanKind::Primary => RED,
+            SpanKind::Parent | SpanKind::Traceback => BLUE,
+        };
+        let empty_gutter = self.paint(BLUE, &format!("{} |", " ".repeat(gutter_width)));
+        output.push_str(&format!(
+            "{}{} {}:{}:{}\n",
+            " ".repeat(gutter_width),
+            self.paint(BLUE, "-->"),
+            span.filename,
+            span.start_line,
+            span.start_col
+        ));
+        output.push_str(&format!("{}\n", empty_gutter));
+        for line_number in start_line..=end_line {
+            let line = lines[line_number - 1];
+            output.push_str(&format!(
+                "{} {}\n",
+                self.paint(
+                    BLUE,
+                    &format!("{:>width$} |", line_number, width = gutter_width)
+                ),
+                line
+            ));
+
+            // Columns are 1-based, and the end column is exclusive
+            let first_col = if line_number == start_line {
+                span.start_col.max(1) as usize
+            } else {
+                1
+            };
+            let last_col = if line_number == span.end_line as usize {
+                (span.end_col as usize).max(first_col + 1)
+            } else {
+                line.chars().count() + 1
+            };
+            let mut marks = self.paint(style, &"^".repeat(last_col.saturating_sub(first_col)));
+            if line_number == end_line && !span.label.is_empty() {
+                marks.push(' ');
+                marks.push_str(&self.paint(style, &span.label));
+            }
+            output.push_str(&format!(
+                "{} {}{}\n",
+                empty_gutter,
+                " ".repeat(first_col - 1),
+                marks
+            ));
+        }
+    }
+
+    fn paint(&self, style: &str, text: &str) -> String {
+        if self.color {
+            format!("{}{}{}", style, text, RESET)
+        } else {
+            text.to_string()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        hint_processor::builtin_hint_processor::builtin_hint_processor_definition::BuiltinHintProcessor,
+        serde::deserialize_program::InputFile,
+        utils::test_utils::*,
+        vm::{
+            errors::vm_errors::VirtualMachineError, runners::cairo_runner::CairoRunner,
+            vm_core::VirtualMachine,
+        },
+    };
+    use std::path::Path;
+
+    fn location(filename: &str, (start_line, start_col): (u32, u32), end: (u32, u32)) -> Location {
+        Location {
+            end_line: end.0,
+            end_col: end.1,
+            input_file: InputFile {
+                filename: filename.to_string(),
+            },
+            parent_location: None,
+            start_line,
+            start_col,
+        }
+    }
+
+    fn vm_exception(inst_location: Option<Location>) -> VmException {
+        VmException {
+            pc: 2,
+            inst_location,
+            inner_exc: VirtualMachineError::NoImm,
+            error_attr_value: None,
+            traceback: None,
+            error_messages: Vec::new(),
+            traceback_frames: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn source_map_reads_disk_first() {
+        let mut source_map = SourceMap::new();
+        source_map.insert("<autogen>", "let x = 1;");
+        source_map.insert("Cargo.toml", "not the file on disk");
+        assert_eq!(
+            source_map.get_source("<autogen>").as_deref(),
+            Some("let x = 1;")
+        );
+        assert!(source_map
+            .get_source("Cargo.toml")
+            .unwrap()
+            .contains("[package]"));
+        assert_eq!(source_map.get_source("Folder/missing.cairo"), None);
+    }
+
+    #[test]
+    fn diagnostic_from_vm_exception_with_parent() {
+        let mut inst_location = location("a.cairo", (1, 5), (1, 9));
+        inst_location.parent_location = Some((
+            Box::new(location("b.cairo", (3, 1), (3, 4))),
+            String::from("While expanding the reference 'x' in:"),
+        ));
+        let mut exception = vm_exception(Some(inst_location));
+        exception.error_messages = vec![String::from("x must be positive")];
+        exception.traceback_frames = vec![
+            TracebackFrame {
+                pc: 10,
+                location: Some(location("a.cairo", (7, 5), (7, 10))),
+                error_messages: Vec::new(),
+            },
+            TracebackFrame {
+                pc: 12,
+                location: None,
+                error_messages: Vec::new(),
+            },
+        ];
+
+        let diagnostic = Diagnostic::from_vm_exception(&exception);
+        assert_eq!(diagnostic.message, VirtualMachineError::NoImm.to_string());
+        assert_eq!(diagnostic.pc, 2);
+        assert_eq!(diagnostic.error_messages, vec!["x must be positive"]);
+        assert_eq!(
+            diagnostic.spans,
+            vec![
+                DiagnosticSpan {
+                    kind: SpanKind::Primary,
+                    filename: String::from("a.cairo"),
+                    start_line: 1,
+                    start_col: 5,
+                    end_line: 1,
+                    end_col: 9,
+                    label: String::from("Error at pc=0:2"),
+                },
+                DiagnosticSpan {
+                    kind: SpanKind::Parent,
+                    filename: String::from("b.cairo"),
+                    start_line: 3,
+                    start_col: 1,
+                    end_line: 3,
+                    end_col: 4,
+                    label: String::from("While expanding the reference 'x' in:"),
+                },
+            ]
+        );
+        assert_eq!(
+            diagnostic.traceback[0].span.as_ref().map(|span| span.kind),
+            Some(SpanKind::Traceback)
+        );
+        assert_eq!(diagnostic.traceback[1].span, None);
+    }
+
+    #[test]
+    fn render_snippets_from_source_map() {
+        let mut source_map = SourceMap::new();
+        source_map.insert(
+            "main.cairo",
+            "func main() {\n    assert x = 1;\n    ret;\n}",
+        );
+        source_map.insert("lib.cairo", "let x = [fp];");
+        let mut inst_location = location("main.cairo", (2, 5), (2, 18));
+        inst_location.parent_location = Some((
+            Box::new(location("lib.cairo", (1, 9), (1, 13))),
+            String::from("While expanding the reference 'x' in:"),
+        ));
+        let mut exception = vm_exception(Some(inst_location));
+        exception.traceback_frames = vec![TracebackFrame {
+            pc: 8,
+            location: Some(location("main.cairo", (3, 5), (3, 9))),
+            error_messages: vec![String::from("Call failed")],
+        }];
+
+        let rendered =
+            DiagnosticRenderer::new(&source_map).render(&Diagnostic::from_vm_exception(&exception));
+        let expected = format!(
+            "error: {}
+ --> lib.cairo:1:9
+  |
+1 | let x = [fp];
+  |         ^^^^ While expanding the reference 'x' in:
+ --> main.cairo:2:5
+  |
+2 |     assert x = 1;
+  |     ^^^^^^^^^^^^^ Error at pc=0:2
+Cairo traceback (most recent call last):
+  = Error message: Call failed
+ --> main.cairo:3:5
+  |
+3 |     ret;
+  |     ^^^^ (pc=0:8)
+",
+            VirtualMachineError::NoImm
+        );
+        assert_eq!(rendered, expected);
+    }
+
+    #[test]
+    fn render_multiline_span() {
+        let mut source_map = SourceMap::new();
+        source_map.insert("main.cairo", "foo(\n    a=1,\n);");
+        let exception = vm_exception(Some(location("main.cairo", (1, 1), (3, 2))));
+        let rendered =
+            DiagnosticRenderer::new(&source_map).render(&Diagnostic::from_vm_exception(&exception));
+        assert!(rendered.ends_with(
+            " --> main.cairo:1:1
+  |
+1 | foo(
+  | ^^^^
+2 |     a=1,
+  | ^^^^^^^^
+3 | );
+  | ^ Error at pc=0:2
+"
+        ));
+    }
+
+    #[test]
+    fn render_without_source_falls_back_to_location_text() {
+        let exception = vm_exception(Some(location("Folder/file.cairo", (1, 1), (2, 2))));
+        let rendered = DiagnosticRenderer::new(&SourceMap::new())
+            .render(&Diagnostic::from_vm_exception(&exception));
+        assert_eq!(
+            rendered,
+            format!(
+                "error: {}\nFolder/file.cairo:1:1: Error at pc=0:2\n",
+                VirtualMachineError::NoImm
+            )
+        );
+    }
+
+    #[test]
+    fn render_without_location() {
+        let rendered = DiagnosticRenderer::new(&SourceMap::new())
+            .render(&Diagnostic::from_vm_exception(&vm_exception(None)));
+        assert_eq!(
+            rendered,
+            format!("error: {}\n --> at pc=0:2\n", VirtualMachineError::NoImm)
+        );
+    }
+
+    #[test]
+    fn render_with_color() {
+        let rendered = DiagnosticRenderer::new(&SourceMap::new())
+            .with_color(true)
+            .render(&Diagnostic::from_vm_exception(&vm_exception(None)));
+        assert!(rendered.starts_with("\x1b[1;31merror\x1b[0m"));
+    }
+
+    #[test]
+    fn diagnostic_to_json() {
+        let exception = vm_exception(Some(location("Folder/file.cairo", (1, 1), (2, 2))));
+        let json: serde_json::Value =
+            serde_json::from_str(&Diagnostic::from_vm_exception(&exception).to_json().unwrap())
+                .unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "message": VirtualMachineError::NoImm.to_string(),
+                "pc": 2,
+                "error_messages": [],
+                "spans": [{
+                    "kind": "primary",
+                    "filename": "Folder/file.cairo",
+                    "start_line": 1,
+                    "start_col": 1,
+                    "end_line": 2,
+                    "end_col": 2,
+                    "label": "Error at pc=0:2"
+                }],
+                "traceback": []
+            })
+        );
+    }
+
+    #[test]
+    fn render_bad_range_check() {
+        let program = Program::from_file(
+            Path::new("cairo_programs/bad_programs/bad_range_check.json"),
+            Some("main"),
+        )
+        .expect("Call to `Program::from_file()` failed.");
+        let source_map = SourceMap::from_program(&program);
+
+        let mut hint_processor = BuiltinHintProcessor::new_empty();
+        let mut cairo_runner = cairo_runner!(program, "all", false);
+        let mut vm = vm!();
+
+        let end = cairo_runner.initialize(&mut vm).unwrap();
+        let error = cairo_runner
+            .run_until_pc(end, &mut vm, &mut hint_processor)
+            .unwrap_err();
+        let exception = VmException::from_vm_error(&cairo_runner, &vm, error);
+        let rendered =
+            DiagnosticRenderer::new(&source_map).render(&Diagnostic::from_vm_exception(&exception));
+        let expected = r#"error: An ASSERT_EQ instruction failed: 4 != 5.
+  = Error message: Failed range-check
+ --> cairo_programs/bad_programs/bad_range_check.cairo:5:9
+  |
+5 |         [range_check_ptr] = num;
+  |         ^^^^^^^^^^^^^^^^^^^^^^^ Error at pc=0:0
+Cairo traceback (most recent call last):
+  --> cairo_programs/bad_programs/bad_range_check.cairo:23:5
+   |
+23 |     sub_by_1_check_range(6, 7);
+   |     ^^^^^^^^^^^^^^^^^^^^^^^^^^ (pc=0:29)
+  --> cairo_programs/bad_programs/bad_range_check.cairo:19:12
+   |
+19 |     return sub_by_1_check_range(sub_1_check_range(num), sub_amount -1);
+   |            ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^ (pc=0:21)
+  --> cairo_programs/bad_programs/bad_range_check.cairo:19:33
+   |
+19 |     return sub_by_1_check_range(sub_1_check_range(num), sub_amount -1);
+   |                                 ^^^^^^^^^^^^^^^^^^^^^^ (pc=0:17)
+  --> cairo_programs/bad_programs/bad_range_check.cairo:11:5
+   |
+11 |     check_range(num - 1);
+   |     ^^^^^^^^^^^^^^^^^^^^ (pc=0:6)
+"#;
+        assert_eq!(rendered, expected);
+    }
+}
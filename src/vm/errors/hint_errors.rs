@@ -4,7 +4,10 @@ use thiserror::Error;
 
 use crate::types::relocatable::{MaybeRelocatable, Relocatable};
 
-use super::{exec_scope_errors::ExecScopeError, vm_errors::VirtualMachineError};
+use super::{
+    exec_scope_errors::ExecScopeError, typed_value_errors::TypedValueError,
+    vm_errors::VirtualMachineError,
+};
 
 #[derive(Debug, PartialEq, Error)]
 pub enum HintError {
@@ -12,6 +15,10 @@ pub enum HintError {
     WrongHintData,
     #[error("Failed to get ids for hint execution")]
     FailedToGetIds,
+    #[error("The type of ids.{0} is unknown")]
+    UnknownIdsType(String),
+    #[error("Unexpected value for ids.{0}: {1}")]
+    WrongIdsValue(String, MaybeRelocatable),
     #[error("Tried to compute an address but there was no register in the reference.")]
     NoRegisterInReference,
     #[error("Custom Hint Error: {0}")]
@@ -116,6 +123,8 @@ pub enum HintError {
     InvalidKeccakInputLength(Felt),
     #[error(transparent)]
     FromScopeError(#[from] ExecScopeError),
+    #[error(transparent)]
+    TypedValue(#[from] TypedValueError),
     #[error("assert_not_equal failed: {0} =  {1}")]
     AssertNotEqualFail(MaybeRelocatable, MaybeRelocatable),
     #[error("split_int(): value is out of range")]
@@ -142,6 +151,18 @@ pub enum HintError {
     ValueOutsideValidRange(Felt),
     #[error("Assertion failed, {0}, is not less or equal to {1}")]
     NonLeFelt(Felt, Felt),
+    // Hints compiled by the BuiltinHintProcessor are resolved by compile_hint, which fails with
+    // VirtualMachineError::UnknownHint instead
     #[error("Unknown Hint: {0}")]
     UnknownHint(String),
+    #[error("Invalid simple bootloader input: {0}")]
+    InvalidSimpleBootloaderInput(String),
+    #[error("Unsupported task type: {0}")]
+    UnsupportedTaskType(String),
+    #[error("Task program has no main entrypoint")]
+    TaskProgramNoMain,
+    #[error("Failed to compute the program hash chain")]
+    ProgramHashChainFail,
+    #[error("Failed to write the fact topologies file: {0}")]
+    FactTopologiesFileWrite(String),
 }
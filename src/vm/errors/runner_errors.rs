@@ -1,7 +1,8 @@
 use std::collections::HashSet;
 
 use super::memory_errors::MemoryError;
-use crate::types::relocatable::MaybeRelocatable;
+use crate::types::relocatable::{MaybeRelocatable, Relocatable};
+use crate::vm::runners::proof_mode::ComponentUsage;
 use felt::Felt;
 use thiserror::Error;
 
@@ -77,6 +78,13 @@ pub enum RunnerError {
     NoProgramStart,
     #[error("Running in proof-mode but no __end__ label found, try compiling with proof-mode")]
     NoProgramEnd,
+    #[error("Running in proof-mode but the instruction at the __end__ label (pc {0}) isn't `jmp rel 0`, try compiling with proof-mode")]
+    NoProgramEndLoop(usize),
+    #[error(
+        "Not enough allocated cells for a run of {0} steps, overflowed components: {}",
+        .1.iter().map(ToString::to_string).collect::<Vec<_>>().join(", ")
+    )]
+    InsufficientAllocatedCells(usize, Vec<ComponentUsage>),
     #[error("Could not convert slice to array")]
     SliceToArrayError,
     #[error("Missing builtin: {0}")]
@@ -95,4 +103,10 @@ pub enum RunnerError {
     SafeDivFailUsize(usize, usize),
     #[error(transparent)]
     MemoryError(#[from] MemoryError),
+    #[error("Page start {0} is not on the output segment {1}")]
+    PageNotOnOutputSegment(Relocatable, isize),
+    #[error("Page {0} was already used")]
+    PageAlreadyUsed(usize),
+    #[error("Page {0} is out of the bounds of the output segment, of size {1}")]
+    PageOutOfBounds(usize, usize),
 }
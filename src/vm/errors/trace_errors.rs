@@ -11,6 +11,14 @@ pub enum TraceError {
     RegNotRelocatable,
     #[error("No relocation found for this segment")]
     NoRelocationFound,
+    #[error("Trace chunk size must be greater than zero")]
+    InvalidChunkSize,
+    #[error("The trace was streamed to disk, and can only be relocated while being written")]
+    StreamedTrace,
+    #[error("Failed to write the trace: {0}")]
+    WriteFail(String),
+    #[error("Failed to read the streamed trace: {0}")]
+    ReadFail(String),
     #[error(transparent)]
     MemoryError(#[from] MemoryError),
 }
@@ -0,0 +1,16 @@
+use crate::vm::errors::memory_errors::MemoryError;
+use thiserror::Error;
+
+#[derive(Debug, PartialEq, Eq, Error)]
+pub enum TypedValueError {
+    #[error("Invalid Cairo type: {0}")]
+    InvalidType(String),
+    #[error("Unknown Cairo type: {0}")]
+    UnknownType(String),
+    #[error("Cairo type {0} matches several structs")]
+    AmbiguousType(String),
+    #[error("Cairo type {0} has no member {1}")]
+    UnknownMember(String, String),
+    #[error(transparent)]
+    MemoryError(#[from] MemoryError),
+}
@@ -67,6 +67,8 @@ pub enum VirtualMachineError {
     NoRangeCheckBuiltin,
     #[error("Expected ecdsa builtin to be present")]
     NoSignatureBuiltin,
+    #[error("Expected output builtin to be present")]
+    NoOutputBuiltin,
     #[error("Failed to retrieve value from address {0}")]
     MemoryGet(MaybeRelocatable),
     #[error("Expected integer at address {0}")]
@@ -113,6 +115,8 @@ pub enum VirtualMachineError {
     SliceToArrayError,
     #[error("Failed to compile hint: {0}")]
     CompileHintFail(String),
+    #[error("Unknown hint: {0}")]
+    UnknownHint(String),
     #[error("op1_addr is Op1Addr.IMM, but no immediate was given")]
     NoImm,
     #[error("Cant substract {0} from offset {1}, offsets cant be negative")]
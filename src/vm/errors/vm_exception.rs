@@ -2,6 +2,7 @@ use std::{
     fmt::{self, Display},
     fs::File,
     io::{BufReader, Read},
+    iter,
     path::Path,
 };
 
@@ -10,21 +11,37 @@ use thiserror::Error;
 use crate::{
     hint_processor::{
         hint_processor_definition::HintReference,
-        hint_processor_utils::get_maybe_relocatable_from_reference,
+        hint_processor_utils::compute_addr_from_reference_at,
     },
     serde::deserialize_program::{ApTracking, Attribute, Location, OffsetValue},
-    types::{instruction::Register, relocatable::MaybeRelocatable},
-    vm::{runners::cairo_runner::CairoRunner, vm_core::VirtualMachine},
+    types::relocatable::Relocatable,
+    vm::{
+        runners::cairo_runner::CairoRunner,
+        typed_value::{CairoValue, ValueInspector},
+        vm_core::VirtualMachine,
+    },
 };
 
 use super::vm_errors::VirtualMachineError;
 #[derive(Debug, PartialEq, Error)]
 pub struct VmException {
-    pc: usize,
-    inst_location: Option<Location>,
-    inner_exc: VirtualMachineError,
-    error_attr_value: Option<String>,
-    traceback: Option<String>,
+    pub(crate) pc: usize,
+    pub(crate) inst_location: Option<Location>,
+    pub(crate) inner_exc: VirtualMachineError,
+    pub(crate) error_attr_value: Option<String>,
+    pub(crate) traceback: Option<String>,
+    // The structured versions of error_attr_value and traceback, used by the diagnostics
+    pub(crate) error_messages: Vec<String>,
+    pub(crate) traceback_frames: Vec<TracebackFrame>,
+}
+
+/// A call of the Cairo traceback: the pc of the call instruction, its location, and the
+/// error messages of the error_message attributes around it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TracebackFrame {
+    pub pc: usize,
+    pub location: Option<Location>,
+    pub error_messages: Vec<String>,
 }
 
 impl VmException {
@@ -34,20 +51,65 @@ impl VmException {
         error: VirtualMachineError,
     ) -> Self {
         let pc = vm.run_context.pc.offset;
-        let error_attr_value = get_error_attr_value(pc, runner, vm);
+        let error_messages = get_error_messages(pc, runner, vm);
         let hint_index = if let VirtualMachineError::Hint(hint_index, _) = error {
             Some(hint_index)
         } else {
             None
         };
+        let traceback_frames = get_traceback_frames(vm, runner);
         VmException {
             pc,
             inst_location: get_location(pc, runner, hint_index),
             inner_exc: error,
-            error_attr_value,
-            traceback: get_traceback(vm, runner),
+            error_attr_value: format_error_messages(&error_messages),
+            traceback: format_traceback(&traceback_frames),
+            error_messages,
+            traceback_frames,
         }
     }
+
+    pub fn pc(&self) -> usize {
+        self.pc
+    }
+
+    pub fn inner_error(&self) -> &VirtualMachineError {
+        &self.inner_exc
+    }
+}
+
+// The registers of the frame in which the references of an error message are evaluated.
+// `ap` is the value of ap before running the instruction at `pc`.
+#[derive(Clone, Copy, Debug)]
+struct FrameRegisters {
+    pc: usize,
+    fp: Relocatable,
+    ap: Option<Relocatable>,
+}
+
+// Returns the values of the error_message attributes around the pc, with their references
+// substituted.
+pub fn get_error_messages(pc: usize, runner: &CairoRunner, vm: &VirtualMachine) -> Vec<String> {
+    let frame = FrameRegisters {
+        pc,
+        fp: vm.get_fp(),
+        ap: Some(vm.get_ap()),
+    };
+    get_frame_error_messages(&frame, runner, vm)
+}
+
+fn get_frame_error_messages(
+    frame: &FrameRegisters,
+    runner: &CairoRunner,
+    vm: &VirtualMachine,
+) -> Vec<String> {
+    runner
+        .program
+        .error_message_attributes
+        .iter()
+        .filter(|attribute| attribute.start_pc <= frame.pc && attribute.end_pc > frame.pc)
+        .map(|attribute| substitute_error_message_references(attribute, frame, runner, vm))
+        .collect()
 }
 
 pub fn get_error_attr_value(
@@ -55,14 +117,13 @@ pub fn get_error_attr_value(
     runner: &CairoRunner,
     vm: &VirtualMachine,
 ) -> Option<String> {
+    format_error_messages(&get_error_messages(pc, runner, vm))
+}
+
+fn format_error_messages(error_messages: &[String]) -> Option<String> {
     let mut errors = String::new();
-    for attribute in &runner.program.error_message_attributes {
-        if attribute.start_pc <= pc && attribute.end_pc > pc {
-            errors.push_str(&format!(
-                "Error message: {}\n",
-                substitute_error_message_references(attribute, runner, vm)
-            ));
-        }
+    for error_message in error_messages {
+        errors.push_str(&format!("Error message: {}\n", error_message));
     }
     (!errors.is_empty()).then_some(errors)
 }
@@ -83,19 +144,51 @@ pub fn get_location(
     }
 }
 
+// Returns the calls of the traceback at the current pc, from the outermost one.
+// The error messages around each call are evaluated in the frame that made it.
+pub fn get_traceback_frames(vm: &VirtualMachine, runner: &CairoRunner) -> Vec<TracebackFrame> {
+    let entries = vm.get_traceback_entries();
+    // The frame of the function called by each entry starts right after the ap of the call
+    let callee_fps = entries
+        .iter()
+        .skip(1)
+        .map(|(fp, _)| *fp)
+        .chain(iter::once(vm.get_fp()));
+    entries
+        .iter()
+        .zip(callee_fps)
+        .map(|((fp, traceback_pc), callee_fp)| {
+            let frame = FrameRegisters {
+                pc: traceback_pc.offset,
+                fp: *fp,
+                ap: callee_fp.sub_usize(2).ok(),
+            };
+            TracebackFrame {
+                pc: traceback_pc.offset,
+                location: get_location(traceback_pc.offset, runner, None),
+                error_messages: get_frame_error_messages(&frame, runner, vm),
+            }
+        })
+        .collect()
+}
+
 // Returns the traceback at the current pc.
 pub fn get_traceback(vm: &VirtualMachine, runner: &CairoRunner) -> Option<String> {
+    format_traceback(&get_traceback_frames(vm, runner))
+}
+
+fn format_traceback(traceback_frames: &[TracebackFrame]) -> Option<String> {
     let mut traceback = String::new();
-    for (_fp, traceback_pc) in vm.get_traceback_entries() {
-        if let Some(ref attr) = get_error_attr_value(traceback_pc.offset, runner, vm) {
+    for frame in traceback_frames {
+        if let Some(ref attr) = format_error_messages(&frame.error_messages) {
             traceback.push_str(attr)
         }
-        match get_location(traceback_pc.offset, runner, None) {
+        match &frame.location {
             Some(location) => traceback.push_str(&format!(
                 "{}\n",
-                location.to_string_with_content(&format!("(pc=0:{})", traceback_pc.offset))
+                location.to_string_with_content(&format!("(pc=0:{})", frame.pc))
             )),
-            None => traceback.push_str(&format!("Unknown location (pc=0:{})", traceback_pc.offset)),
+            None => traceback.push_str(&format!("Unknown location (pc=0:{})", frame.pc)),
         }
     }
     (!traceback.is_empty())
@@ -106,6 +199,7 @@ pub fn get_traceback(vm: &VirtualMachine, runner: &CairoRunner) -> Option<String
 // References are defined with '{}'. E.g., 'x must be positive. Got: {x}'.
 fn substitute_error_message_references(
     error_message_attr: &Attribute,
+    frame: &FrameRegisters,
     runner: &CairoRunner,
     vm: &VirtualMachine,
 ) -> String {
@@ -125,20 +219,14 @@ fn substitute_error_message_references(
             // Look for the formated name inside the error message
             if error_msg.contains(&formated_variable_name) {
                 // Get the value of the cairo variable from its reference id
-                match get_value_from_simple_reference(
-                    *ref_id,
-                    &tracking_data.ap_tracking,
-                    runner,
-                    vm,
-                ) {
+                match get_reference_value(*ref_id, frame, runner, vm) {
                     Some(cairo_variable) => {
                         // Replace the value in the error message
-                        error_msg = error_msg
-                            .replace(&formated_variable_name, &format!("{}", cairo_variable))
+                        error_msg = error_msg.replace(&formated_variable_name, &cairo_variable)
                     }
                     None => {
-                        // If the reference is too complex or ap-based it might lead to a wrong value
-                        // So we append the variable's name to the list of invalid reference
+                        // The reference can't be evaluated, ie: it is ap-based and the ap
+                        // tracking of the pc is unknown, or its type has no members
                         invalid_references.push(cairo_variable_name.to_string());
                     }
                 }
@@ -157,12 +245,22 @@ fn substitute_error_message_references(
     error_msg
 }
 
-fn get_value_from_simple_reference(
+// Returns the ap tracking before running the instruction at pc, if the debug info records it.
+fn get_ap_tracking(pc: usize, runner: &CairoRunner) -> Option<&ApTracking> {
+    let instruction_location = runner.program.instruction_locations.as_ref()?.get(&pc)?;
+    instruction_location
+        .flow_tracking_data
+        .as_ref()
+        .map(|flow_tracking_data| &flow_tracking_data.ap_tracking)
+}
+
+// Returns the value of the reference in the frame, formatting structs member by member.
+fn get_reference_value(
     ref_id: usize,
-    ap_tracking: &ApTracking,
+    frame: &FrameRegisters,
     runner: &CairoRunner,
     vm: &VirtualMachine,
-) -> Option<MaybeRelocatable> {
+) -> Option<String> {
     let reference: HintReference = runner
         .program
         .reference_manager
@@ -170,18 +268,46 @@ fn get_value_from_simple_reference(
         .get(ref_id)?
         .clone()
         .into();
-    // Filter ap-based references
-    match reference.offset1 {
-        OffsetValue::Reference(Register::AP, _, _) => None,
-        _ => {
-            // Filer complex types (only felt/felt pointers)
-            match reference.cairo_type {
-                Some(ref cairo_type) if cairo_type.contains("felt") => {
-                    Some(get_maybe_relocatable_from_reference(vm, &reference, ap_tracking).ok()?)
-                }
-                _ => None,
-            }
-        }
+    if let OffsetValue::Immediate(value) = &reference.offset1 {
+        return Some(value.to_string());
+    }
+
+    // Ap-based references are relative to the ap of the frame, which is corrected with the
+    // ap tracking of the pc. fp-based references don't use the ap tracking.
+    let (ap, ap_tracking) = match (&reference.ap_tracking_data, frame.ap) {
+        (None, _) => (frame.fp, ApTracking::default()),
+        (Some(_), Some(ap)) => (ap, get_ap_tracking(frame.pc, runner)?.clone()),
+        (Some(_), None) => return None,
+    };
+    let addr = compute_addr_from_reference_at(&reference, vm, frame.fp, ap, &ap_tracking).ok()?;
+    // The value of references that aren't dereferenced is the address itself
+    if !reference.dereference {
+        return Some(addr.to_string());
+    }
+    format_value_at(&addr, reference.cairo_type.as_deref()?, runner, vm)
+}
+
+// Formats the value of type `cairo_type` stored at `addr`. Pointers aren't followed, and values
+// with unwritten cells can't be evaluated.
+fn format_value_at(
+    addr: &Relocatable,
+    cairo_type: &str,
+    runner: &CairoRunner,
+    vm: &VirtualMachine,
+) -> Option<String> {
+    let value = ValueInspector::new(&runner.program.identifiers)
+        .with_max_depth(0)
+        .inspect(vm, addr, cairo_type)
+        .ok()?;
+    is_fully_written(&value).then(|| value.to_string())
+}
+
+fn is_fully_written(value: &CairoValue) -> bool {
+    match value {
+        CairoValue::Value(_) | CairoValue::Pointer { .. } => true,
+        CairoValue::Struct { members, .. } => members.iter().all(|(_, v)| is_fully_written(v)),
+        CairoValue::Tuple(elements) => elements.iter().all(|(_, v)| is_fully_written(v)),
+        CairoValue::Unknown => false,
     }
 }
 
@@ -259,7 +385,7 @@ impl Location {
         } else {
             start_line.len() + 1
         };
-        let left_margin: String = vec![' '; start_col - 1].into_iter().collect();
+        let left_margin: String = vec![' '; start_col.saturating_sub(1)].into_iter().collect();
         if end_col > start_col + 1 {
             let highlight: String = vec!['*'; end_col - start_col - 2].into_iter().collect();
             result.push_str(&format!("{}^{}^", left_margin, highlight));
@@ -276,11 +402,14 @@ mod test {
 
     use crate::hint_processor::builtin_hint_processor::builtin_hint_processor_definition::BuiltinHintProcessor;
     use crate::serde::deserialize_program::{
-        Attribute, HintLocation, InputFile, InstructionLocation,
+        Attribute, FlowTrackingData, HintLocation, Identifier, InputFile, InstructionLocation,
+        Reference, ReferenceManager,
     };
+    use crate::serde::deserialize_utils::parse_value;
     use crate::types::program::Program;
-    use crate::types::relocatable::Relocatable;
+    use crate::types::relocatable::{MaybeRelocatable, Relocatable};
     use crate::utils::test_utils::*;
+    use crate::vm::{errors::memory_errors::MemoryError, vm_memory::memory::Memory};
 
     use super::*;
     #[test]
@@ -299,6 +428,7 @@ mod test {
         let instruction_location = InstructionLocation {
             inst: location.clone(),
             hints: vec![],
+            flow_tracking_data: None,
         };
         let program =
             program!(instruction_locations = Some(HashMap::from([(pc, instruction_location)])),);
@@ -309,6 +439,8 @@ mod test {
             inner_exc: VirtualMachineError::NoImm,
             error_attr_value: None,
             traceback: None,
+            error_messages: Vec::new(),
+            traceback_frames: Vec::new(),
         };
         assert_eq!(
             VmException::from_vm_error(&runner, &vm!(), VirtualMachineError::NoImm,),
@@ -365,6 +497,8 @@ mod test {
             ),
             error_attr_value: None,
             traceback: None,
+            error_messages: Vec::new(),
+            traceback_frames: Vec::new(),
         };
         assert_eq!(
             vm_excep.to_string(),
@@ -389,6 +523,8 @@ mod test {
             ),
             error_attr_value: Some(String::from("Error message: Block may fail\n")),
             traceback: None,
+            error_messages: vec![String::from("Block may fail")],
+            traceback_frames: Vec::new(),
         };
         assert_eq!(
             vm_excep.to_string(),
@@ -423,6 +559,8 @@ mod test {
             ),
             error_attr_value: None,
             traceback: None,
+            error_messages: Vec::new(),
+            traceback_frames: Vec::new(),
         };
         assert_eq!(
             vm_excep.to_string(),
@@ -469,6 +607,8 @@ mod test {
             ),
             error_attr_value: None,
             traceback: None,
+            error_messages: Vec::new(),
+            traceback_frames: Vec::new(),
         };
         assert_eq!(
             vm_excep.to_string(),
@@ -527,6 +667,7 @@ mod test {
         let instruction_location = InstructionLocation {
             inst: location.clone(),
             hints: vec![],
+            flow_tracking_data: None,
         };
         let program =
             program!(instruction_locations = Some(HashMap::from([(2, instruction_location)])),);
@@ -549,6 +690,7 @@ mod test {
         let instruction_location = InstructionLocation {
             inst: location,
             hints: vec![],
+            flow_tracking_data: None,
         };
         let program =
             program!(instruction_locations = Some(HashMap::from([(2, instruction_location)])),);
@@ -585,6 +727,7 @@ mod test {
         let instruction_location = InstructionLocation {
             inst: location_a,
             hints: vec![hint_location],
+            flow_tracking_data: None,
         };
         let program =
             program!(instruction_locations = Some(HashMap::from([(2, instruction_location)])),);
@@ -712,6 +855,25 @@ mod test {
         )
     }
 
+    #[test]
+    fn location_get_location_marks_zero_start_col() {
+        let location = Location {
+            end_line: 1,
+            end_col: 0,
+            input_file: InputFile {
+                filename: String::from("file.cairo"),
+            },
+            parent_location: None,
+            start_line: 1,
+            start_col: 0,
+        };
+        let mut reader = "func main():".as_bytes();
+        assert_eq!(
+            location.get_location_marks(&mut reader),
+            String::from("func main():\n^")
+        )
+    }
+
     #[test]
     fn location_get_location_marks_empty_file() {
         let location = Location {
@@ -802,81 +964,217 @@ cairo_programs/bad_programs/bad_usort.cairo:64:5: (pc=0:60)
         assert_eq!(vm_excepction.to_string(), expected_error_string);
     }
 
-    #[test]
-    fn get_value_from_simple_reference_ap_based() {
-        let program = Program::from_file(
-            Path::new("cairo_programs/bad_programs/error_msg_attr_tempvar.json"),
-            Some("main"),
+    // A program whose error_message attribute covers the pcs 0 to 4, and uses the reference 0
+    // as {x}. The pc 2 is recorded with the ap tracking (0, 3).
+    fn error_msg_attr_program(
+        reference: &str,
+        ap_tracking_data: ApTracking,
+        identifiers: HashMap<String, Identifier>,
+    ) -> Program {
+        let reference = Reference {
+            ap_tracking_data,
+            pc: None,
+            value_address: parse_value(reference).unwrap().1,
+        };
+        let attribute = Attribute {
+            name: String::from("error_message"),
+            start_pc: 0,
+            end_pc: 4,
+            value: String::from("Got {x}"),
+            flow_tracking_data: Some(FlowTrackingData {
+                ap_tracking: ApTracking::new(),
+                reference_ids: HashMap::from([(String::from("__main__.main.x"), 0)]),
+            }),
+        };
+        let location = Location {
+            end_line: 2,
+            end_col: 2,
+            input_file: InputFile {
+                filename: String::from("Folder/file.cairo"),
+            },
+            parent_location: None,
+            start_line: 1,
+            start_col: 1,
+        };
+        let instruction_location = InstructionLocation {
+            inst: location,
+            hints: vec![],
+            flow_tracking_data: Some(FlowTrackingData {
+                ap_tracking: ApTracking {
+                    group: 0,
+                    offset: 3,
+                },
+                reference_ids: HashMap::new(),
+            }),
+        };
+        program!(
+            reference_manager = ReferenceManager {
+                references: vec![reference]
+            },
+            identifiers = identifiers,
+            error_message_attributes = vec![attribute],
+            instruction_locations = Some(HashMap::from([(2, instruction_location)])),
         )
-        .expect("Call to `Program::from_file()` failed.");
-        // This program uses a tempvar inside an error attribute
-        // This reference should be rejected when substituting the error attribute references
+    }
+
+    #[test]
+    fn error_message_ap_based_reference() {
+        // x was pushed at the ap tracking (0, 1), ap advanced by 2 since then
+        let program = error_msg_attr_program(
+            "[cast(ap + (-1), felt*)]",
+            ApTracking {
+                group: 0,
+                offset: 1,
+            },
+            HashMap::new(),
+        );
         let runner = cairo_runner!(program);
-        let vm = vm!();
-        // Ref id 0 corresponds to __main__.main.x, our tempvar
+        let mut vm = vm!();
+        run_context!(vm, 2, 5, 0);
+        vm.memory = memory![((1, 2), 7), ((1, 4), 8)];
         assert_eq!(
-            get_value_from_simple_reference(0, &ApTracking::default(), &runner, &vm),
-            None
-        )
+            get_error_messages(2, &runner, &vm),
+            vec![String::from("Got 7")]
+        );
     }
 
     #[test]
-    fn substitute_error_message_references_ap_based() {
-        let program = Program::from_file(
-            Path::new("cairo_programs/bad_programs/error_msg_attr_tempvar.json"),
-            Some("main"),
-        )
-        .expect("Call to `Program::from_file()` failed.");
-        // This program uses a tempvar inside an error attribute
-        // This reference should be rejected when substituting the error attribute references
+    fn error_message_ap_based_reference_other_group() {
+        let program = error_msg_attr_program(
+            "[cast(ap + (-1), felt*)]",
+            ApTracking {
+                group: 1,
+                offset: 1,
+            },
+            HashMap::new(),
+        );
         let runner = cairo_runner!(program);
-        let vm = vm!();
-        let attribute = &program.error_message_attributes[0];
+        let mut vm = vm!();
+        run_context!(vm, 2, 5, 0);
+        vm.memory = memory![((1, 2), 7), ((1, 4), 8)];
         assert_eq!(
-            substitute_error_message_references(attribute, &runner, &vm),
-            format!(
-                "{} (Cannot evaluate ap-based or complex references: ['x'])",
-                attribute.value
-            )
+            get_error_messages(2, &runner, &vm),
+            vec![String::from(
+                "Got {x} (Cannot evaluate ap-based or complex references: ['x'])"
+            )]
         );
     }
 
     #[test]
-    fn get_value_from_simple_reference_complex() {
-        let program = Program::from_file(
-            Path::new("cairo_programs/bad_programs/error_msg_attr_struct.json"),
-            Some("main"),
-        )
-        .expect("Call to `Program::from_file()` failed.");
-        // This program uses a struct inside an error attribute
-        // This reference should be rejected when substituting the error attribute references
+    fn error_message_ap_based_reference_unknown_ap_tracking() {
+        // The pc 1 isn't recorded in the debug info
+        let program = error_msg_attr_program(
+            "[cast(ap + (-1), felt*)]",
+            ApTracking {
+                group: 0,
+                offset: 1,
+            },
+            HashMap::new(),
+        );
         let runner = cairo_runner!(program);
-        let vm = vm!();
-        // Ref id 0 corresponds to __main__.main.cat, our struct
+        let mut vm = vm!();
+        run_context!(vm, 1, 5, 0);
+        vm.memory = memory![((1, 4), 8)];
         assert_eq!(
-            get_value_from_simple_reference(0, &ApTracking::default(), &runner, &vm),
-            None
-        )
+            get_error_messages(1, &runner, &vm),
+            vec![String::from(
+                "Got {x} (Cannot evaluate ap-based or complex references: ['x'])"
+            )]
+        );
     }
 
     #[test]
-    fn substitute_error_message_references_complex() {
-        let program = Program::from_file(
-            Path::new("cairo_programs/bad_programs/error_msg_attr_struct.json"),
-            Some("main"),
-        )
-        .expect("Call to `Program::from_file()` failed.");
-        // This program uses a struct inside an error attribute
-        // This reference should be rejected when substituting the error attribute references
+    fn error_message_struct_reference() {
+        let identifiers = HashMap::from([
+            (
+                String::from("__main__.Cat"),
+                struct_identifier("__main__.Cat", &[("lives", "felt", 1), ("paws", "felt", 0)]),
+            ),
+            (
+                String::from("__main__.Owner"),
+                struct_identifier(
+                    "__main__.Owner",
+                    &[("cat", "__main__.Cat", 0), ("home", "felt*", 2)],
+                ),
+            ),
+        ]);
+        let program = error_msg_attr_program(
+            "[cast(fp + 1, __main__.Owner*)]",
+            ApTracking::new(),
+            identifiers,
+        );
+        let runner = cairo_runner!(program);
+        let mut vm = vm!();
+        run_context!(vm, 2, 6, 2);
+        vm.memory = memory![((1, 3), 4), ((1, 4), 10), ((1, 5), (2, 0))];
+        assert_eq!(
+            get_error_messages(2, &runner, &vm),
+            vec![String::from(
+                "Got Owner(cat=Cat(paws=4, lives=10), home=2:0)"
+            )]
+        );
+    }
+
+    #[test]
+    fn error_message_struct_reference_unknown_member_type() {
+        let identifiers = HashMap::from([(
+            String::from("__main__.Pair"),
+            struct_identifier("__main__.Pair", &[("values", "__main__.Unknown", 0)]),
+        )]);
+        let program =
+            error_msg_attr_program("[cast(fp, __main__.Pair*)]", ApTracking::new(), identifiers);
+        let runner = cairo_runner!(program);
+        let mut vm = vm!();
+        run_context!(vm, 2, 6, 2);
+        vm.memory = memory![((1, 2), 4), ((1, 3), 10)];
+        assert_eq!(
+            get_error_messages(2, &runner, &vm),
+            vec![String::from(
+                "Got {x} (Cannot evaluate ap-based or complex references: ['x'])"
+            )]
+        );
+    }
+
+    #[test]
+    fn error_message_struct_reference_tuple_member() {
+        let identifiers = HashMap::from([(
+            String::from("__main__.Pair"),
+            struct_identifier("__main__.Pair", &[("values", "(felt, felt)", 0)]),
+        )]);
+        let program =
+            error_msg_attr_program("[cast(fp, __main__.Pair*)]", ApTracking::new(), identifiers);
+        let runner = cairo_runner!(program);
+        let mut vm = vm!();
+        run_context!(vm, 2, 6, 2);
+        vm.memory = memory![((1, 2), 4), ((1, 3), 10)];
+        assert_eq!(
+            get_error_messages(2, &runner, &vm),
+            vec![String::from("Got Pair(values=(4, 10))")]
+        );
+    }
+
+    #[test]
+    fn error_message_pointer_reference() {
+        // References that aren't dereferenced evaluate to their address
+        let program =
+            error_msg_attr_program("cast(fp + (-3), felt*)", ApTracking::new(), HashMap::new());
+        let runner = cairo_runner!(program);
+        let mut vm = vm!();
+        run_context!(vm, 2, 6, 5);
+        assert_eq!(
+            get_error_messages(2, &runner, &vm),
+            vec![String::from("Got 1:2")]
+        );
+    }
+
+    #[test]
+    fn error_message_immediate_reference() {
+        let program = error_msg_attr_program("cast(42, felt)", ApTracking::new(), HashMap::new());
         let runner = cairo_runner!(program);
         let vm = vm!();
-        let attribute = &program.error_message_attributes[0];
         assert_eq!(
-            substitute_error_message_references(attribute, &runner, &vm),
-            format!(
-                "{} (Cannot evaluate ap-based or complex references: ['cat'])",
-                attribute.value
-            )
+            get_error_messages(2, &runner, &vm),
+            vec![String::from("Got 42")]
         );
     }
 }
@@ -0,0 +1,316 @@
+use crate::{
+    hint_processor::builtin_hint_processor::builtin_hint_processor_definition::{
+        HintImpl, HintProcessorData,
+    },
+    types::relocatable::Relocatable,
+};
+use std::{any::Any, collections::HashMap, fmt, time::Duration};
+
+/// The number of executions of a hint, and the time they took.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct HintTiming {
+    pub calls: usize,
+    pub total_time: Duration,
+}
+
+impl HintTiming {
+    fn record(&mut self, elapsed: Duration) {
+        self.calls += 1;
+        self.total_time += elapsed;
+    }
+
+    fn add(&mut self, other: &HintTiming) {
+        self.calls += other.calls;
+        self.total_time += other.total_time;
+    }
+
+    pub fn average_time(&self) -> Duration {
+        match u32::try_from(self.calls) {
+            Ok(0) => Duration::ZERO,
+            Ok(calls) => self.total_time / calls,
+            Err(_) => Duration::from_secs_f64(self.total_time.as_secs_f64() / self.calls as f64),
+        }
+    }
+}
+
+/// The executions of the hint at a given location.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HintLocationStats {
+    /// The code of the hint, known if its data was built by the `BuiltinHintProcessor`.
+    pub code: Option<String>,
+    /// Whether the hint is one of the extra hints added to the `BuiltinHintProcessor`.
+    pub extra: bool,
+    pub timing: HintTiming,
+}
+
+/// The executions of all the hints with the same code.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HintCodeStats {
+    pub code: String,
+    pub extra: bool,
+    pub timing: HintTiming,
+}
+
+/// Counts the executions of each hint, and measures the time they took.
+/// Hints are recorded by location (the pc of the instruction and the index of the hint), and
+/// can be aggregated by code when their data was built by the `BuiltinHintProcessor`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct HintStats {
+    locations: HashMap<(Relocatable, usize), HintLocationStats>,
+}
+
+impl HintStats {
+    pub fn new() -> HintStats {
+        HintStats::default()
+    }
+
+    pub(crate) fn record(
+        &mut self,
+        pc: Relocatable,
+        hint_index: usize,
+        hint_data: &dyn Any,
+        elapsed: Duration,
+    ) {
+        self.locations
+            .entry((pc, hint_index))
+            .or_insert_with(|| {
+                // The hint data is only inspected the first time the location is reached
+                let hint_data = hint_data.downcast_ref::<HintProcessorData>();
+                HintLocationStats {
+                    code: hint_data.map(|hint_data| hint_data.code.clone()),
+                    extra: matches!(
+                        hint_data.and_then(HintProcessorData::hint_impl),
+                        Some(HintImpl::Extra(_))
+                    ),
+                    timing: HintTiming::default(),
+                }
+            })
+            .timing
+            .record(elapsed);
+    }
+
+    /// Returns the stats of each hint location, keyed by pc and hint index.
+    pub fn by_location(&self) -> &HashMap<(Relocatable, usize), HintLocationStats> {
+        &self.locations
+    }
+
+    /// Returns the stats of each hint code, from the slowest to the fastest in total.
+    /// Hints whose code is unknown are left out.
+    pub fn by_code(&self) -> Vec<HintCodeStats> {
+        let mut codes = HashMap::<&str, HintCodeStats>::new();
+        for location in self.locations.values() {
+            let code = match &location.code {
+                Some(code) => code,
+                None => continue,
+            };
+            codes
+                .entry(code)
+                .or_insert_with(|| HintCodeStats {
+                    code: code.clone(),
+                    extra: location.extra,
+                    timing: HintTiming::default(),
+                })
+                .timing
+                .add(&location.timing);
+        }
+        let mut codes: Vec<HintCodeStats> = codes.into_values().collect();
+        codes.sort_by(|a, b| {
+            b.timing
+                .total_time
+                .cmp(&a.timing.total_time)
+                .then_with(|| a.code.cmp(&b.code))
+        });
+        codes
+    }
+
+    /// Same as `by_code`, keeping only the extra hints.
+    pub fn extra_hints(&self) -> Vec<HintCodeStats> {
+        self.by_code()
+            .into_iter()
+            .filter(|hint| hint.extra)
+            .collect()
+    }
+
+    /// Returns the executions of all the hints.
+    pub fn total(&self) -> HintTiming {
+        let mut total = HintTiming::default();
+        for location in self.locations.values() {
+            total.add(&location.timing);
+        }
+        total
+    }
+}
+
+// Prints a table with the stats of each hint code, and the total
+impl fmt::Display for HintStats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "{:>10} {:>12} {:>12}  hint",
+            "calls", "total (ms)", "avg (us)"
+        )?;
+        for hint in self.by_code() {
+            // Only the first line of the code is printed, to keep the table readable
+            let mut lines = hint.code.lines();
+            let mut code = lines.next().unwrap_or_default().to_string();
+            if lines.next().is_some() {
+                code.push_str(" ...");
+            }
+            if hint.extra {
+                code.push_str(" (extra)");
+            }
+            writeln!(
+                f,
+                "{:>10} {:>12.3} {:>12.3}  {}",
+                hint.timing.calls,
+                hint.timing.total_time.as_secs_f64() * 1e3,
+                hint.timing.average_time().as_secs_f64() * 1e6,
+                code
+            )?;
+        }
+        let total = self.total();
+        write!(
+            f,
+            "{:>10} {:>12.3} {:>12.3}  total",
+            total.calls,
+            total.total_time.as_secs_f64() * 1e3,
+            total.average_time().as_secs_f64() * 1e6,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hint_processor::builtin_hint_processor::builtin_hint_processor_definition::HintFunc;
+    use std::rc::Rc;
+
+    fn hint_data(code: &str, hint_impl: Option<HintImpl>) -> HintProcessorData {
+        let hint_data = HintProcessorData::new_default(code.to_string(), HashMap::new());
+        match hint_impl {
+            Some(hint_impl) => hint_data.with_hint_impl(hint_impl),
+            None => hint_data,
+        }
+    }
+
+    #[test]
+    fn record_by_location_and_code() {
+        let mut hint_stats = HintStats::new();
+        let alloc = hint_data("memory[ap] = segments.add()", None);
+        hint_stats.record((0, 2).into(), 0, &alloc, Duration::from_millis(3));
+        hint_stats.record((0, 2).into(), 0, &alloc, Duration::from_millis(1));
+        hint_stats.record((0, 8).into(), 1, &alloc, Duration::from_millis(2));
+        hint_stats.record((0, 9).into(), 0, &5, Duration::from_millis(10));
+
+        assert_eq!(
+            hint_stats.by_location().get(&((0, 2).into(), 0)),
+            Some(&HintLocationStats {
+                code: Some("memory[ap] = segments.add()".to_string()),
+                extra: false,
+                timing: HintTiming {
+                    calls: 2,
+                    total_time: Duration::from_millis(4)
+                }
+            })
+        );
+        assert_eq!(hint_stats.by_location()[&((0, 9).into(), 0)].code, None);
+        // The hint with unknown code is only left out of the stats by code
+        assert_eq!(
+            hint_stats.by_code(),
+            vec![HintCodeStats {
+                code: "memory[ap] = segments.add()".to_string(),
+                extra: false,
+                timing: HintTiming {
+                    calls: 3,
+                    total_time: Duration::from_millis(6)
+                }
+            }]
+        );
+        assert_eq!(
+            hint_stats.total(),
+            HintTiming {
+                calls: 4,
+                total_time: Duration::from_millis(16)
+            }
+        );
+    }
+
+    #[test]
+    fn by_code_sorted_by_total_time() {
+        let mut hint_stats = HintStats::new();
+        hint_stats.record(
+            (0, 0).into(),
+            0,
+            &hint_data("fast", None),
+            Duration::from_millis(1),
+        );
+        hint_stats.record(
+            (0, 1).into(),
+            0,
+            &hint_data("slow", None),
+            Duration::from_millis(5),
+        );
+        let codes: Vec<String> = hint_stats
+            .by_code()
+            .into_iter()
+            .map(|hint| hint.code)
+            .collect();
+        assert_eq!(codes, vec!["slow".to_string(), "fast".to_string()]);
+    }
+
+    #[test]
+    fn extra_hints_only() {
+        let extra = HintImpl::Extra(Rc::new(HintFunc(Box::new(|_, _, _, _, _| Ok(())))));
+        let mut hint_stats = HintStats::new();
+        hint_stats.record(
+            (0, 0).into(),
+            0,
+            &hint_data("extra", Some(extra)),
+            Duration::from_millis(1),
+        );
+        hint_stats.record(
+            (0, 1).into(),
+            0,
+            &hint_data("builtin", None),
+            Duration::from_millis(5),
+        );
+        assert_eq!(
+            hint_stats.extra_hints(),
+            vec![HintCodeStats {
+                code: "extra".to_string(),
+                extra: true,
+                timing: HintTiming {
+                    calls: 1,
+                    total_time: Duration::from_millis(1)
+                }
+            }]
+        );
+        assert!(hint_stats.to_string().contains("extra (extra)"));
+    }
+
+    #[test]
+    fn average_time() {
+        assert_eq!(HintTiming::default().average_time(), Duration::ZERO);
+        let timing = HintTiming {
+            calls: 4,
+            total_time: Duration::from_millis(10),
+        };
+        assert_eq!(timing.average_time(), Duration::from_micros(2500));
+    }
+
+    #[test]
+    fn display_multiline_code() {
+        let mut hint_stats = HintStats::new();
+        hint_stats.record(
+            (0, 0).into(),
+            0,
+            &hint_data("a = 1\nb = 2", None),
+            Duration::from_millis(2),
+        );
+        let table = hint_stats.to_string();
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[1].ends_with("  a = 1 ..."));
+        assert!(lines[2].ends_with("  total"));
+    }
+}
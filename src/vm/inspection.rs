@@ -0,0 +1,175 @@
+use crate::types::relocatable::Relocatable;
+
+// The views returned by the read-only accessors of `VirtualMachine` and `CairoRunner`, for hosts
+// embedding the VM. They are copies of the state, so that they don't depend on how the VM
+// stores it.
+
+/// The registers of the VM.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Registers {
+    pub pc: Relocatable,
+    pub ap: Relocatable,
+    pub fp: Relocatable,
+}
+
+/// A memory segment of the VM.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SegmentInfo {
+    /// Negative for temporary segments.
+    pub index: isize,
+    /// One past the highest offset written so far.
+    pub len: usize,
+    /// The size of the segment, once it was finalized or its used size was computed (see
+    /// `VirtualMachine::compute_effective_sizes`). Always None for temporary segments.
+    pub size: Option<usize>,
+}
+
+/// A builtin included in the run.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BuiltinInfo {
+    pub name: &'static str,
+    pub base: Relocatable,
+    /// Set once the run ended and the builtin's final stack was read.
+    pub stop_ptr: Option<Relocatable>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        hint_processor::builtin_hint_processor::builtin_hint_processor_definition::BuiltinHintProcessor,
+        serde::deserialize_program::ReferenceManager,
+        types::{program::Program, relocatable::MaybeRelocatable},
+        utils::test_utils::*,
+        vm::{errors::memory_errors::MemoryError, vm_memory::memory::Memory},
+        vm::{
+            errors::typed_value_errors::TypedValueError, runners::cairo_runner::CairoRunner,
+            vm_core::VirtualMachine,
+        },
+    };
+    use std::collections::HashMap;
+
+    #[test]
+    fn inspect_finished_run() {
+        let program = function_call_program(HashMap::new());
+        let mut hint_processor = BuiltinHintProcessor::new_empty();
+        let mut cairo_runner = cairo_runner!(program, "plain");
+        let mut vm = vm!(true);
+        let end = cairo_runner.initialize(&mut vm).unwrap();
+        cairo_runner
+            .run_until_pc(end, &mut vm, &mut hint_processor)
+            .unwrap();
+        vm.add_temporary_segment();
+
+        assert_eq!(cairo_runner.get_program_base(), Some((0, 0).into()));
+        assert_eq!(cairo_runner.get_execution_base(), Some((1, 0).into()));
+        assert_eq!(cairo_runner.get_initial_pc(), Some((0, 3).into()));
+        assert_eq!(cairo_runner.get_initial_ap(), Some((1, 2).into()));
+        assert_eq!(cairo_runner.get_final_pc(), Some(end));
+        assert_eq!(cairo_runner.get_layout_name(), "plain");
+        assert!(!cairo_runner.is_proof_mode());
+
+        assert_eq!(
+            vm.get_registers(),
+            Registers {
+                pc: (3, 0).into(),
+                ap: (1, 6).into(),
+                fp: (1, 0).into(),
+            }
+        );
+        assert_eq!(vm.get_current_step(), 5);
+        let trace = vm.get_trace().unwrap();
+        assert_eq!(trace.len(), 5);
+        assert_eq!(trace[1].pc, (0, 5).into());
+        assert!(vm.get_builtins().is_empty());
+
+        assert_eq!(vm.get_segments()[1].size, None);
+        vm.compute_effective_sizes();
+        assert_eq!(
+            vm.get_segments(),
+            vec![
+                SegmentInfo {
+                    index: 0,
+                    len: 8,
+                    size: Some(8),
+                },
+                SegmentInfo {
+                    index: 1,
+                    len: 6,
+                    size: Some(6),
+                },
+                SegmentInfo {
+                    index: 2,
+                    len: 0,
+                    size: Some(0),
+                },
+                SegmentInfo {
+                    index: 3,
+                    len: 0,
+                    size: Some(0),
+                },
+                SegmentInfo {
+                    index: -1,
+                    len: 0,
+                    size: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn inspect_builtins() {
+        let program = program!["output", "range_check"];
+        let mut cairo_runner = cairo_runner!(program);
+        let mut vm = vm!();
+        cairo_runner.initialize_builtins(&mut vm).unwrap();
+        cairo_runner.initialize_segments(&mut vm, None);
+        vm.get_output_builtin().unwrap().stop_ptr = Some(1);
+
+        assert_eq!(
+            vm.get_builtins(),
+            vec![
+                BuiltinInfo {
+                    name: "output",
+                    base: (2, 0).into(),
+                    stop_ptr: Some((2, 1).into()),
+                },
+                BuiltinInfo {
+                    name: "range_check",
+                    base: (3, 0).into(),
+                    stop_ptr: None,
+                },
+            ]
+        );
+        assert_eq!(vm.get_registers().pc, (0, 0).into());
+        assert_eq!(vm.get_trace(), None);
+    }
+
+    #[test]
+    fn inspect_value_with_program_identifiers() {
+        let program = program!(
+            identifiers = HashMap::from([(
+                String::from("starkware.cairo.common.uint256.Uint256"),
+                struct_identifier(
+                    "starkware.cairo.common.uint256.Uint256",
+                    &[("low", "felt", 0), ("high", "felt", 1)],
+                ),
+            )]),
+        );
+        let cairo_runner = cairo_runner!(program);
+        let mut vm = vm!();
+        vm.memory = memory![((1, 0), (1, 1)), ((1, 1), 3), ((1, 2), 4)];
+
+        assert_eq!(
+            cairo_runner
+                .inspect_value(&vm, &(1, 0).into(), "Uint256*")
+                .unwrap()
+                .to_string(),
+            "1:1 -> Uint256(low=3, high=4)"
+        );
+        assert_eq!(
+            cairo_runner.inspect_value(&vm, &(1, 0).into(), "Point"),
+            Err(TypedValueError::UnknownType(String::from("Point")))
+        );
+    }
+}
@@ -1,8 +1,11 @@
 pub mod context;
 pub mod decoding;
 pub mod errors;
+pub mod hint_stats;
+pub mod inspection;
 pub mod runners;
 pub mod security;
 pub mod trace;
+pub mod typed_value;
 pub mod vm_core;
 pub mod vm_memory;
@@ -1,4 +1,5 @@
-use crate::math_utils::{ec_add, ec_double, safe_div_usize};
+use crate::math_utils::safe_div_usize;
+use crate::math_utils::stark_curve::{AffinePoint, ProjectivePoint, StarkFieldElement};
 use crate::types::instance_definitions::ec_op_instance_def::{
     EcOpInstanceDef, CELLS_PER_EC_OP, INPUT_CELLS_PER_EC_OP,
 };
@@ -9,9 +10,8 @@ use crate::vm::vm_core::VirtualMachine;
 use crate::vm::vm_memory::memory::Memory;
 use crate::vm::vm_memory::memory_segments::MemorySegmentManager;
 use felt::{Felt, FeltOps, NewFelt};
-use num_bigint::BigInt;
 use num_integer::{div_ceil, Integer};
-use num_traits::{Num, One, Pow, Zero};
+use num_traits::{One, Pow, Signed};
 use std::borrow::Cow;
 
 #[derive(Debug, Clone)]
@@ -23,7 +23,7 @@ pub struct EcOpBuiltinRunner {
     ec_op_builtin: EcOpInstanceDef,
     pub(crate) stop_ptr: Option<usize>,
     _included: bool,
-    instances_per_component: u32,
+    pub(crate) instances_per_component: u32,
 }
 
 impl EcOpBuiltinRunner {
@@ -52,32 +52,70 @@ impl EcOpBuiltinRunner {
     /// Mimics the operation of the AIR, so that this function fails whenever the builtin AIR
     /// would not yield a correct result, i.e. when any part of the computation attempts to add
     /// two points with the same x coordinate.
+    /// The points are kept in Jacobian coordinates over the Cairo prime field, so only the
+    /// final result (or the points reported in an error) need an inversion.
+    /// Until a point is first updated, it is compared and reported through the signed
+    /// representation of its Felt coordinates, and the bits of m are taken from its signed
+    /// representation as well.
     fn ec_op_impl(
         partial_sum: (Felt, Felt),
         doubled_point: (Felt, Felt),
         m: &Felt,
-        alpha: &BigInt,
-        prime: &BigInt,
+        alpha: &Felt,
         height: u32,
-    ) -> Result<(BigInt, BigInt), RunnerError> {
-        let mut slope = m.clone().to_bigint();
-        let mut partial_sum_b = (partial_sum.0.to_bigint(), partial_sum.1.to_bigint());
-        let mut doubled_point_b = (doubled_point.0.to_bigint(), doubled_point.1.to_bigint());
-        for _ in 0..height {
-            if (doubled_point_b.0.clone() - partial_sum_b.0.clone()).is_zero() {
+    ) -> Result<(Felt, Felt), RunnerError> {
+        let alpha = StarkFieldElement::from_felt(alpha);
+        let scalar = m.to_bigint();
+        let mut partial_sum_p =
+            ProjectivePoint::from(AffinePoint::from_felts(&partial_sum.0, &partial_sum.1));
+        let mut doubled_point_p =
+            ProjectivePoint::from(AffinePoint::from_felts(&doubled_point.0, &doubled_point.1));
+        let mut partial_sum_input = Some(partial_sum);
+        let mut doubled_point_input = Some(doubled_point);
+        for i in 0..height as u64 {
+            if Self::same_x_coordinate(
+                (&partial_sum_p, partial_sum_input.as_ref()),
+                (&doubled_point_p, doubled_point_input.as_ref()),
+            ) {
                 return Err(RunnerError::EcOpSameXCoordinate(Self::format_ec_op_error(
-                    partial_sum_b,
-                    m.clone().to_bigint(),
-                    doubled_point_b,
+                    Self::point_to_bigints(&partial_sum_p, partial_sum_input),
+                    scalar,
+                    Self::point_to_bigints(&doubled_point_p, doubled_point_input),
                 )));
             };
-            if !(slope.clone() & &BigInt::one()).is_zero() {
-                partial_sum_b = ec_add(partial_sum_b, doubled_point_b.clone(), prime);
+            if scalar.bit(i) {
+                partial_sum_p = partial_sum_p.add(&doubled_point_p);
+                partial_sum_input = None;
             }
-            doubled_point_b = ec_double(doubled_point_b, alpha, prime);
-            slope = slope.clone() >> 1_u32;
+            doubled_point_p = doubled_point_p.double(&alpha);
+            doubled_point_input = None;
+        }
+        let result = partial_sum_p.to_affine();
+        Ok((result.x.to_felt(), result.y.to_felt()))
+    }
+
+    /// Compares the x coordinates of two points, which match only if both are still the input
+    /// points or both have been updated, or if the x coordinate of the input point has the same
+    /// signed and unsigned representation.
+    fn same_x_coordinate(
+        a: (&ProjectivePoint, Option<&(Felt, Felt)>),
+        b: (&ProjectivePoint, Option<&(Felt, Felt)>),
+    ) -> bool {
+        a.0.has_same_x(b.0)
+            && match (a.1, b.1) {
+                (Some((x, _)), None) | (None, Some((x, _))) => !x.is_negative(),
+                _ => true,
+            }
+    }
+
+    fn point_to_bigints(
+        point: &ProjectivePoint,
+        input: Option<(Felt, Felt)>,
+    ) -> (num_bigint::BigInt, num_bigint::BigInt) {
+        match input {
+            Some((x, y)) => (x.to_bigint(), y.to_bigint()),
+            None => point.to_affine().to_bigints(),
         }
-        Ok(partial_sum_b)
     }
 
     pub fn initialize_segments(
@@ -165,8 +203,6 @@ impl EcOpBuiltinRunner {
                 return Err(RunnerError::PointNotOnCurve(*pair));
             };
         }
-        let prime = BigInt::from_str_radix(&felt::PRIME_STR[2..], 16)
-            .map_err(|_| RunnerError::CouldntParsePrime)?;
         let result = EcOpBuiltinRunner::ec_op_impl(
             (
                 input_cells[0].as_ref().to_owned(),
@@ -177,13 +213,12 @@ impl EcOpBuiltinRunner {
                 input_cells[3].as_ref().to_owned(),
             ),
             input_cells[4].as_ref(),
-            &alpha.to_bigint(),
-            &prime,
+            &alpha,
             self.ec_op_builtin.scalar_height,
         )?;
         match index - self.n_input_cells as usize {
-            0 => Ok(Some(MaybeRelocatable::Int(Felt::new(result.0)))),
-            _ => Ok(Some(MaybeRelocatable::Int(Felt::new(result.1)))),
+            0 => Ok(Some(MaybeRelocatable::Int(result.0))),
+            _ => Ok(Some(MaybeRelocatable::Int(result.1))),
             //Default case corresponds to 1, as there are no other possible cases
         }
     }
@@ -285,6 +320,7 @@ impl EcOpBuiltinRunner {
 mod tests {
     use super::*;
     use crate::hint_processor::builtin_hint_processor::builtin_hint_processor_definition::BuiltinHintProcessor;
+    use crate::math_utils::{ec_double_slope, line_slope};
     use crate::types::program::Program;
     use crate::utils::test_utils::*;
     use crate::vm::runners::cairo_runner::CairoRunner;
@@ -294,8 +330,48 @@ mod tests {
         vm_core::VirtualMachine,
     };
     use felt::felt_str;
+    use num_bigint::BigInt;
+    use num_traits::{Num, Zero};
     use EcOpBuiltinRunner;
 
+    /// The affine BigInt implementation of the EC operation, used to check that the Jacobian one
+    /// yields the same results and errors.
+    fn ec_op_impl_reference(
+        partial_sum: (Felt, Felt),
+        doubled_point: (Felt, Felt),
+        m: &Felt,
+        height: u32,
+    ) -> Result<(Felt, Felt), RunnerError> {
+        let prime = BigInt::from_str_radix(&felt::PRIME_STR[2..], 16).unwrap();
+        let alpha = BigInt::one();
+        let mut partial_sum = (partial_sum.0.to_bigint(), partial_sum.1.to_bigint());
+        let mut doubled_point = (doubled_point.0.to_bigint(), doubled_point.1.to_bigint());
+        let mut scalar = m.to_bigint();
+        for _ in 0..height {
+            if doubled_point.0 == partial_sum.0 {
+                return Err(RunnerError::EcOpSameXCoordinate(
+                    EcOpBuiltinRunner::format_ec_op_error(
+                        partial_sum,
+                        m.to_bigint(),
+                        doubled_point,
+                    ),
+                ));
+            }
+            if scalar.is_odd() {
+                let slope = line_slope(&partial_sum, &doubled_point, &prime);
+                let x = (&slope * &slope - &partial_sum.0 - &doubled_point.0).mod_floor(&prime);
+                let y = (slope * (&partial_sum.0 - &x) - &partial_sum.1).mod_floor(&prime);
+                partial_sum = (x, y);
+            }
+            let slope = ec_double_slope(&doubled_point, &alpha, &prime);
+            let x = (&slope * &slope - 2_i32 * &doubled_point.0).mod_floor(&prime);
+            let y = (slope * (&doubled_point.0 - &x) - &doubled_point.1).mod_floor(&prime);
+            doubled_point = (x, y);
+            scalar >>= 1_u32;
+        }
+        Ok((Felt::new(partial_sum.0), Felt::new(partial_sum.1)))
+    }
+
     #[test]
     fn get_used_instances() {
         let builtin = EcOpBuiltinRunner::new(&EcOpInstanceDef::new(10), true);
@@ -572,20 +648,16 @@ mod tests {
             ),
         );
         let m = Felt::new(34);
-        let alpha = bigint!(1);
+        let alpha = Felt::one();
         let height = 256;
-        let prime = bigint_str!(
-            "3618502788666131213697322783095070105623107215331596699973092056135872020481"
-        );
-        let result =
-            EcOpBuiltinRunner::ec_op_impl(partial_sum, doubled_point, &m, &alpha, &prime, height);
+        let result = EcOpBuiltinRunner::ec_op_impl(partial_sum, doubled_point, &m, &alpha, height);
         assert_eq!(
             result,
             Ok((
-                bigint_str!(
+                felt_str!(
                     "1977874238339000383330315148209250828062304908491266318460063803060754089297"
                 ),
-                bigint_str!(
+                felt_str!(
                     "2969386888251099938335087541720168257053975603483053253007176033556822156706"
                 )
             ))
@@ -611,20 +683,16 @@ mod tests {
             ),
         );
         let m = Felt::new(34);
-        let alpha = bigint!(1);
+        let alpha = Felt::one();
         let height = 256;
-        let prime = bigint_str!(
-            "3618502788666131213697322783095070105623107215331596699973092056135872020481"
-        );
-        let result =
-            EcOpBuiltinRunner::ec_op_impl(partial_sum, doubled_point, &m, &alpha, &prime, height);
+        let result = EcOpBuiltinRunner::ec_op_impl(partial_sum, doubled_point, &m, &alpha, height);
         assert_eq!(
             result,
             Ok((
-                bigint_str!(
+                felt_str!(
                     "2778063437308421278851140253538604815869848682781135193774472480292420096757"
                 ),
-                bigint_str!(
+                felt_str!(
                     "3598390311618116577316045819420613574162151407434885460365915347732568210029"
                 )
             ))
@@ -636,17 +704,13 @@ mod tests {
         let partial_sum = (Felt::one(), Felt::new(9));
         let doubled_point = (Felt::one(), Felt::new(12));
         let m = Felt::new(34);
-        let alpha = bigint!(1);
+        let alpha = Felt::one();
         let height = 256;
-        let prime = bigint_str!(
-            "3618502788666131213697322783095070105623107215331596699973092056135872020481"
-        );
         let result = EcOpBuiltinRunner::ec_op_impl(
             partial_sum.clone(),
             doubled_point.clone(),
             &m,
             &alpha,
-            &prime,
             height,
         );
         assert_eq!(
@@ -661,6 +725,87 @@ mod tests {
         );
     }
 
+    #[test]
+    fn compute_ec_op_impl_matches_bigint_reference() {
+        let partial_sum = (
+            felt_str!(
+                "3139037544796708144595053687182055617920475701120786241351436619796497072089"
+            ),
+            felt_str!(
+                "2119589567875935397690285099786081818522144748339117565577200220779667999801"
+            ),
+        );
+        let doubled_point = (
+            felt_str!(
+                "874739451078007766457464989774322083649278607533249481151382481072868806602"
+            ),
+            felt_str!(
+                "152666792071518830868575557812948353041420400780739481342941381225525861407"
+            ),
+        );
+        let scalars = [
+            Felt::zero(),
+            Felt::one(),
+            Felt::new(34),
+            felt_str!(
+                "3618502788666131213697322783095070105623107215331596699973092056135872020480"
+            ),
+            felt_str!(
+                "1809251394333065553493296640760748560207343510400633813116524750123642650623"
+            ),
+        ];
+        for m in scalars {
+            for height in [0, 1, 64, 252, 256] {
+                let result = EcOpBuiltinRunner::ec_op_impl(
+                    partial_sum.clone(),
+                    doubled_point.clone(),
+                    &m,
+                    &Felt::one(),
+                    height,
+                );
+                assert_eq!(
+                    result,
+                    ec_op_impl_reference(partial_sum.clone(), doubled_point.clone(), &m, height)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn compute_ec_op_invalid_same_x_coordinate_after_doubling() {
+        let doubled_point = (
+            felt_str!(
+                "874739451078007766457464989774322083649278607533249481151382481072868806602"
+            ),
+            felt_str!(
+                "152666792071518830868575557812948353041420400780739481342941381225525861407"
+            ),
+        );
+        // P = -3 * Q, so that after one step P + Q = -2 * Q and the doubled point is 2 * Q
+        let prime = BigInt::from_str_radix(&felt::PRIME_STR[2..], 16).unwrap();
+        let q = (doubled_point.0.to_bigint(), doubled_point.1.to_bigint());
+        let slope = ec_double_slope(&q, &BigInt::one(), &prime);
+        let x = (&slope * &slope - 2_i32 * &q.0).mod_floor(&prime);
+        let double_q = (x.clone(), (slope * (&q.0 - &x) - &q.1).mod_floor(&prime));
+        let slope = line_slope(&double_q, &q, &prime);
+        let x = (&slope * &slope - &double_q.0 - &q.0).mod_floor(&prime);
+        let y = (slope * (&double_q.0 - &x) - &double_q.1).mod_floor(&prime);
+        let partial_sum = (Felt::new(x), -Felt::new(y));
+        let m = Felt::one();
+        let result = EcOpBuiltinRunner::ec_op_impl(
+            partial_sum.clone(),
+            doubled_point.clone(),
+            &m,
+            &Felt::one(),
+            256,
+        );
+        assert!(matches!(result, Err(RunnerError::EcOpSameXCoordinate(_))));
+        assert_eq!(
+            result,
+            ec_op_impl_reference(partial_sum, doubled_point, &m, 256)
+        );
+    }
+
     #[test]
     /* Data taken from this program execution:
        %builtins output ec_op
@@ -1,6 +1,7 @@
 use std::cell::RefCell;
 
 use crate::math_utils::safe_div_usize;
+use crate::types::field_element::{FromFieldElement, ToFieldElement};
 use crate::types::instance_definitions::pedersen_instance_def::{
     CELLS_PER_HASH, INPUT_CELLS_PER_HASH,
 };
@@ -10,9 +11,9 @@ use crate::vm::errors::runner_errors::RunnerError;
 use crate::vm::vm_core::VirtualMachine;
 use crate::vm::vm_memory::memory::Memory;
 use crate::vm::vm_memory::memory_segments::MemorySegmentManager;
-use felt::{Felt, FeltOps};
+use felt::Felt;
 use num_integer::{div_ceil, Integer};
-use starknet_crypto::{pedersen_hash, FieldElement};
+use starknet_crypto::pedersen_hash;
 
 #[derive(Debug, Clone)]
 pub struct HashBuiltinRunner {
@@ -22,7 +23,7 @@ pub struct HashBuiltinRunner {
     pub(crate) n_input_cells: u32,
     pub(crate) stop_ptr: Option<usize>,
     pub(crate) _included: bool,
-    instances_per_component: u32,
+    pub(crate) instances_per_component: u32,
     // This act as a cache to optimize calls to deduce_memory_cell
     // Therefore need interior mutability
     pub(self) verified_addresses: RefCell<Vec<Relocatable>>,
@@ -99,20 +100,16 @@ impl HashBuiltinRunner {
             self.verified_addresses.borrow_mut().push(*address);
 
             //Convert MaybeRelocatable to FieldElement
-            let a_string = num_a.to_str_radix(10);
-            let b_string = num_b.to_str_radix(10);
-            let (y, x) = match (
-                FieldElement::from_dec_str(&a_string),
-                FieldElement::from_dec_str(&b_string),
-            ) {
-                (Ok(field_element_a), Ok(field_element_b)) => (field_element_a, field_element_b),
+            let (y, x) = match (num_a.to_field_element(), num_b.to_field_element()) {
+                (Some(field_element_a), Some(field_element_b)) => {
+                    (field_element_a, field_element_b)
+                }
                 _ => return Err(RunnerError::FailedStringConversion),
             };
             //Compute pedersen Hash
             let fe_result = pedersen_hash(&x, &y);
             //Convert result from FieldElement to MaybeRelocatable
-            let r_byte_slice = fe_result.to_bytes_be();
-            let result = Felt::from_bytes_be(&r_byte_slice);
+            let result = Felt::from_field_element(&fe_result);
             return Ok(Some(MaybeRelocatable::from(result)));
         }
         Ok(None)
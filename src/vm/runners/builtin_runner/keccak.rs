@@ -1,7 +1,4 @@
-use crate::hint_processor::builtin_hint_processor::cairo_keccak::keccak_hints::{
-    maybe_reloc_vec_to_u64_array, u64_array_to_mayberelocatable_vec,
-};
-use crate::hint_processor::builtin_hint_processor::keccak_utils::left_pad_u64;
+use crate::math_utils::keccak::{keccak_f1600, state_from_builtin_felts, state_to_builtin_felts};
 use crate::math_utils::safe_div_usize;
 use crate::types::instance_definitions::keccak_instance_def::KeccakInstanceDef;
 use crate::types::relocatable::{MaybeRelocatable, Relocatable};
@@ -14,8 +11,6 @@ use felt::Felt;
 use num_integer::div_ceil;
 use num_traits::One;
 
-const KECCAK_ARRAY_LEN: usize = 25;
-
 #[derive(Debug, Clone)]
 pub struct KeccakBuiltinRunner {
     ratio: u32,
@@ -26,7 +21,7 @@ pub struct KeccakBuiltinRunner {
     pub(crate) stop_ptr: Option<usize>,
     _included: bool,
     state_rep: Vec<u32>,
-    instances_per_component: u32,
+    pub(crate) instances_per_component: u32,
 }
 
 impl KeccakBuiltinRunner {
@@ -98,50 +93,38 @@ impl KeccakBuiltinRunner {
             };
         }
 
-        if let Some((i, bits)) = self.state_rep.iter().enumerate().next() {
-            let value1 = memory
+        let mut input_felts = Vec::with_capacity(self.n_input_cells as usize);
+        for (i, bits) in self.state_rep.iter().enumerate() {
+            let value = memory
                 .get(&(first_input_addr + i))
                 .map_err(RunnerError::FailedMemoryGet)?
                 .ok_or(RunnerError::NonRelocatableAddress)?;
 
-            let val = match value1.as_ref() {
+            let val = match value.as_ref() {
                 MaybeRelocatable::Int(val) => val,
                 _ => return Err(RunnerError::FoundNonInt),
             };
 
             if val >= &(Felt::one() << *bits) {
                 return Err(RunnerError::IntegerBiggerThanPowerOfTwo(
-                    value1.clone().into_owned(),
+                    value.clone().into_owned(),
                     *bits,
                     val.clone(),
                 ));
             }
 
-            let mut input_felts = vec![];
-
-            for i in 0..self.n_input_cells {
-                let value2 = memory
-                    .get(&(first_input_addr + i as usize))
-                    .map_err(RunnerError::FailedMemoryGet)?;
-
-                input_felts.push(value2)
-            }
-
-            let mut input_felts_u64 = maybe_reloc_vec_to_u64_array(&input_felts)
-                .map_err(|_| RunnerError::MaybeRelocVecToU64ArrayError)?;
-
-            let len = input_felts_u64.len();
-            let mut input_felts_u64 = left_pad_u64(&mut input_felts_u64, KECCAK_ARRAY_LEN - len)
-                .try_into()
-                .map_err(|_| RunnerError::SliceToArrayError)?;
-
-            keccak::f1600(&mut input_felts_u64);
+            input_felts.push(val.clone());
+        }
 
-            let bigint_values = u64_array_to_mayberelocatable_vec(&input_felts_u64);
+        let mut state =
+            state_from_builtin_felts(&input_felts).ok_or(RunnerError::SliceToArrayError)?;
+        keccak_f1600(&mut state);
+        let output_felts = state_to_builtin_felts(&state);
 
-            return Ok(Some(bigint_values[address.offset - 1].clone()));
-        }
-        Ok(None)
+        Ok(output_felts
+            .get(index - self.n_input_cells as usize)
+            .cloned()
+            .map(MaybeRelocatable::from))
     }
 
     pub fn get_allocated_memory_units(&self, vm: &VirtualMachine) -> Result<usize, MemoryError> {
@@ -265,7 +248,7 @@ mod tests {
         runners::builtin_runner::BuiltinRunner,
         vm_core::VirtualMachine,
     };
-    use felt::NewFelt;
+    use felt::felt_str;
     use std::path::Path;
 
     #[test]
@@ -572,8 +555,142 @@ mod tests {
         let result = builtin.deduce_memory_cell(&Relocatable::from((0, 25)), &memory);
         assert_eq!(
             result,
-            Ok(Some(MaybeRelocatable::from(Felt::new(
-                3086936446498698982_u64
+            Ok(Some(MaybeRelocatable::from(felt_str!(
+                "1006979841721999878391288827876533441431370448293338267890891"
+            ))))
+        );
+    }
+
+    #[test]
+    fn deduce_memory_cell_packed_200_bit_inputs() {
+        // Each input holds 200 bits of the state: 2**200 - 1, 2**200 - 2, ..., 2**200 - 8
+        let memory = memory![
+            (
+                (0, 0),
+                (
+                    "1606938044258990275541962092341162602522202993782792835301375",
+                    10
+                )
+            ),
+            (
+                (0, 1),
+                (
+                    "1606938044258990275541962092341162602522202993782792835301374",
+                    10
+                )
+            ),
+            (
+                (0, 2),
+                (
+                    "1606938044258990275541962092341162602522202993782792835301373",
+                    10
+                )
+            ),
+            (
+                (0, 3),
+                (
+                    "1606938044258990275541962092341162602522202993782792835301372",
+                    10
+                )
+            ),
+            (
+                (0, 4),
+                (
+                    "1606938044258990275541962092341162602522202993782792835301371",
+                    10
+                )
+            ),
+            (
+                (0, 5),
+                (
+                    "1606938044258990275541962092341162602522202993782792835301370",
+                    10
+                )
+            ),
+            (
+                (0, 6),
+                (
+                    "1606938044258990275541962092341162602522202993782792835301369",
+                    10
+                )
+            ),
+            (
+                (0, 7),
+                (
+                    "1606938044258990275541962092341162602522202993782792835301368",
+                    10
+                )
+            )
+        ];
+        let builtin = KeccakBuiltinRunner::new(&KeccakInstanceDef::default(), true);
+
+        let result = builtin.deduce_memory_cell(&Relocatable::from((0, 8)), &memory);
+        assert_eq!(
+            result,
+            Ok(Some(MaybeRelocatable::from(felt_str!(
+                "101472217641568559453688497960663139512078558539060505971943"
+            ))))
+        );
+        let result = builtin.deduce_memory_cell(&Relocatable::from((0, 15)), &memory);
+        assert_eq!(
+            result,
+            Ok(Some(MaybeRelocatable::from(felt_str!(
+                "1193197883321241341096645172282366499134306865586867467236844"
+            ))))
+        );
+    }
+
+    #[test]
+    fn deduce_memory_cell_input_bigger_than_200_bits() {
+        let memory = memory![
+            ((0, 0), 43),
+            ((0, 1), 199),
+            ((0, 2), 0),
+            ((0, 3), 0),
+            ((0, 4), 0),
+            ((0, 5), 0),
+            ((0, 6), 0),
+            (
+                (0, 7),
+                (
+                    "1606938044258990275541962092341162602522202993782792835301376",
+                    10
+                )
+            )
+        ];
+        let builtin = KeccakBuiltinRunner::new(&KeccakInstanceDef::default(), true);
+        let too_big = Felt::one() << 200_u32;
+
+        let result = builtin.deduce_memory_cell(&Relocatable::from((0, 8)), &memory);
+        assert_eq!(
+            result,
+            Err(RunnerError::IntegerBiggerThanPowerOfTwo(
+                MaybeRelocatable::from(too_big.clone()),
+                200,
+                too_big
+            ))
+        );
+    }
+
+    #[test]
+    fn deduce_memory_cell_first_output() {
+        let memory = memory![
+            ((0, 32), 43),
+            ((0, 33), 199),
+            ((0, 34), 0),
+            ((0, 35), 0),
+            ((0, 36), 0),
+            ((0, 37), 0),
+            ((0, 38), 0),
+            ((0, 39), 1)
+        ];
+        let builtin = KeccakBuiltinRunner::new(&KeccakInstanceDef::default(), true);
+
+        let result = builtin.deduce_memory_cell(&Relocatable::from((0, 40)), &memory);
+        assert_eq!(
+            result,
+            Ok(Some(MaybeRelocatable::from(felt_str!(
+                "564514457304291355949254928395241013971879337011439882107889"
             ))))
         );
     }
@@ -19,7 +19,9 @@ pub use bitwise::BitwiseBuiltinRunner;
 pub use ec_op::EcOpBuiltinRunner;
 pub use hash::HashBuiltinRunner;
 use num_integer::div_floor;
-pub use output::OutputBuiltinRunner;
+pub use output::{
+    OutputBuiltinAdditionalData, OutputBuiltinRunner, OutputBuiltinState, PublicMemoryPage,
+};
 pub use range_check::RangeCheckBuiltinRunner;
 pub use signature::SignatureBuiltinRunner;
 
@@ -139,6 +141,49 @@ impl BuiltinRunner {
         }
     }
 
+    /// Returns the ratio, cells per instance and instances per component of the builtin, or None
+    /// if it isn't allocated cells for each step (ie: the output builtin).
+    pub(crate) fn get_allocation_params(&self) -> Option<(usize, usize, usize)> {
+        let (ratio, cells_per_instance, instances_per_component) = match self {
+            BuiltinRunner::Bitwise(bitwise) => (
+                bitwise.ratio(),
+                bitwise.cells_per_instance,
+                bitwise.instances_per_component,
+            ),
+            BuiltinRunner::EcOp(ec) => (
+                ec.ratio(),
+                ec.cells_per_instance,
+                ec.instances_per_component,
+            ),
+            BuiltinRunner::Hash(hash) => (
+                hash.ratio(),
+                hash.cells_per_instance,
+                hash.instances_per_component,
+            ),
+            BuiltinRunner::Output(_) => return None,
+            BuiltinRunner::RangeCheck(range_check) => (
+                range_check.ratio(),
+                range_check.cells_per_instance,
+                range_check.instances_per_component,
+            ),
+            BuiltinRunner::Keccak(keccak) => (
+                keccak.ratio(),
+                keccak.cells_per_instance,
+                keccak.instances_per_component,
+            ),
+            BuiltinRunner::Signature(signature) => (
+                signature.ratio(),
+                signature.cells_per_instance,
+                signature.instances_per_component,
+            ),
+        };
+        Some((
+            ratio as usize,
+            cells_per_instance as usize,
+            instances_per_component as usize,
+        ))
+    }
+
     pub fn add_validation_rule(&self, memory: &mut Memory) -> Result<(), RunnerError> {
         match *self {
             BuiltinRunner::Bitwise(ref bitwise) => bitwise.add_validation_rule(memory),
@@ -281,10 +326,9 @@ impl BuiltinRunner {
                     .map_err(|_| MemoryError::AddressInTemporarySegment(base))?,
             )
             .ok_or(MemoryError::NumOutOfBounds)?
-            .iter()
-            .enumerate()
+            .iter_written()
             .filter_map(|(offset, value)| match value {
-                Some(MaybeRelocatable::RelocatableValue(_)) => Some(offset),
+                MaybeRelocatable::RelocatableValue(_) => Some(offset),
                 _ => None,
             })
             .collect::<Vec<_>>();
@@ -438,7 +482,7 @@ mod tests {
             bitwise_instance_def::BitwiseInstanceDef, ec_op_instance_def::EcOpInstanceDef,
         },
         utils::test_utils::*,
-        vm::vm_core::VirtualMachine,
+        vm::{vm_core::VirtualMachine, vm_memory::segment_storage::MemorySegment},
     };
 
     #[test]
@@ -918,7 +962,7 @@ mod tests {
         ));
         let mut vm = vm!();
 
-        vm.memory.data = vec![vec![]];
+        vm.memory.data = vec![MemorySegment::new()];
 
         assert_eq!(builtin.run_security_checks(&mut vm), Ok(()));
     }
@@ -938,7 +982,8 @@ mod tests {
             mayberelocatable!(0, 3).into(),
             mayberelocatable!(0, 4).into(),
             mayberelocatable!(0, 5).into(),
-        ]];
+        ]
+        .into()];
 
         assert_eq!(
             builtin.run_security_checks(&mut vm),
@@ -964,7 +1009,8 @@ mod tests {
             mayberelocatable!(0, 3).into(),
             mayberelocatable!(0, 4).into(),
             mayberelocatable!(0, 5).into(),
-        ]];
+        ]
+        .into()];
 
         assert_eq!(
             builtin.run_security_checks(&mut vm),
@@ -984,7 +1030,8 @@ mod tests {
             mayberelocatable!(0, 3).into(),
             mayberelocatable!(0, 4).into(),
             mayberelocatable!(0, 5).into(),
-        ]];
+        ]
+        .into()];
 
         assert_eq!(
             builtin.run_security_checks(&mut vm),
@@ -1010,7 +1057,8 @@ mod tests {
             mayberelocatable!(0, 3).into(),
             mayberelocatable!(0, 4).into(),
             mayberelocatable!(0, 5).into(),
-        ]];
+        ]
+        .into()];
 
         assert_eq!(
             builtin.run_security_checks(&mut vm),
@@ -1031,7 +1079,8 @@ mod tests {
             mayberelocatable!(0, 3).into(),
             mayberelocatable!(0, 4).into(),
             mayberelocatable!(0, 5).into(),
-        ]];
+        ]
+        .into()];
 
         assert_eq!(
             builtin.run_security_checks(&mut vm),
@@ -1057,7 +1106,8 @@ mod tests {
             mayberelocatable!(0, 3).into(),
             mayberelocatable!(0, 4).into(),
             mayberelocatable!(0, 5).into(),
-        ]];
+        ]
+        .into()];
 
         assert_eq!(
             builtin.run_security_checks(&mut vm),
@@ -1082,7 +1132,8 @@ mod tests {
             mayberelocatable!(0, 3).into(),
             mayberelocatable!(0, 4).into(),
             mayberelocatable!(0, 5).into(),
-        ]];
+        ]
+        .into()];
 
         assert_eq!(builtin.run_security_checks(&mut vm), Ok(()));
     }
@@ -1102,7 +1153,8 @@ mod tests {
             mayberelocatable!(0, 5).into(),
             mayberelocatable!(0, 6).into(),
             mayberelocatable!(0, 7).into(),
-        ]];
+        ]
+        .into()];
 
         assert_eq!(
             builtin.run_security_checks(&mut vm),
@@ -1132,7 +1184,8 @@ mod tests {
             mayberelocatable!(0, 8).into(),
             mayberelocatable!(0, 9).into(),
             mayberelocatable!(0, 10).into(),
-        ]];
+        ]
+        .into()];
 
         assert_eq!(
             builtin.run_security_checks(&mut vm),
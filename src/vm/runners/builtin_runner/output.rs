@@ -4,10 +4,50 @@ use crate::vm::errors::runner_errors::RunnerError;
 use crate::vm::vm_core::VirtualMachine;
 use crate::vm::vm_memory::memory::Memory;
 use crate::vm::vm_memory::memory_segments::MemorySegmentManager;
+use serde::{ser::SerializeTuple, Serialize, Serializer};
+use std::collections::{BTreeMap, HashMap};
+
+/// A page of the public memory, as used by the fact registration (see `OutputBuiltinRunner::add_page`).
+/// `start` is an offset relative to the base of the output builtin.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PublicMemoryPage {
+    pub start: usize,
+    pub size: usize,
+}
+
+// Serialized as [start, size], as in the additional data of a Cairo PIE
+impl Serialize for PublicMemoryPage {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut page = serializer.serialize_tuple(2)?;
+        page.serialize_element(&self.start)?;
+        page.serialize_element(&self.size)?;
+        page.end()
+    }
+}
+
+/// The state that the bootloader saves before running a task, and restores after it,
+/// so that the pages and attributes added by the task are recorded separately.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutputBuiltinState {
+    pub base_offset: usize,
+    pub pages: HashMap<usize, PublicMemoryPage>,
+    pub attributes: HashMap<String, Vec<usize>>,
+}
+
+/// The pages and attributes of the output builtin, in the format of the additional data of a
+/// Cairo PIE. These are needed to compute the fact of the program.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct OutputBuiltinAdditionalData {
+    pub pages: BTreeMap<usize, PublicMemoryPage>,
+    pub attributes: BTreeMap<String, Vec<usize>>,
+}
 
 #[derive(Debug, Clone)]
 pub struct OutputBuiltinRunner {
     base: isize,
+    base_offset: usize,
+    pages: HashMap<usize, PublicMemoryPage>,
+    attributes: HashMap<String, Vec<usize>>,
     pub(crate) stop_ptr: Option<usize>,
     pub(crate) _included: bool,
 }
@@ -16,6 +56,9 @@ impl OutputBuiltinRunner {
     pub fn new(included: bool) -> OutputBuiltinRunner {
         OutputBuiltinRunner {
             base: 0,
+            base_offset: 0,
+            pages: HashMap::new(),
+            attributes: HashMap::new(),
             stop_ptr: None,
             _included: included,
         }
@@ -115,6 +158,94 @@ impl OutputBuiltinRunner {
             Ok((pointer, stop_ptr))
         }
     }
+
+    /// Adds a public memory page starting at `page_start`, which must lie on the output segment.
+    pub fn add_page(
+        &mut self,
+        page_id: usize,
+        page_start: Relocatable,
+        page_size: usize,
+    ) -> Result<(), RunnerError> {
+        if page_start.segment_index != self.base {
+            return Err(RunnerError::PageNotOnOutputSegment(page_start, self.base));
+        }
+        let start = page_start
+            .offset
+            .checked_sub(self.base_offset)
+            .ok_or(RunnerError::PageNotOnOutputSegment(page_start, self.base))?;
+        if self.pages.contains_key(&page_id) {
+            return Err(RunnerError::PageAlreadyUsed(page_id));
+        }
+        self.pages.insert(
+            page_id,
+            PublicMemoryPage {
+                start,
+                size: page_size,
+            },
+        );
+        Ok(())
+    }
+
+    pub fn get_pages(&self) -> &HashMap<usize, PublicMemoryPage> {
+        &self.pages
+    }
+
+    /// Adds an attribute to the output builtin, such as the `gps_fact_topology` used to compute
+    /// the fact of a task run by the bootloader.
+    pub fn add_attribute(&mut self, name: String, value: Vec<usize>) {
+        self.attributes.insert(name, value);
+    }
+
+    pub fn get_attributes(&self) -> &HashMap<String, Vec<usize>> {
+        &self.attributes
+    }
+
+    pub fn get_additional_data(&self) -> OutputBuiltinAdditionalData {
+        OutputBuiltinAdditionalData {
+            pages: self.pages.clone().into_iter().collect(),
+            attributes: self.attributes.clone().into_iter().collect(),
+        }
+    }
+
+    pub fn get_state(&self) -> OutputBuiltinState {
+        OutputBuiltinState {
+            base_offset: self.base_offset,
+            pages: self.pages.clone(),
+            attributes: self.attributes.clone(),
+        }
+    }
+
+    /// Starts recording pages and attributes from scratch, relative to `base`.
+    pub fn new_state(&mut self, base: Relocatable) -> Result<(), RunnerError> {
+        if base.segment_index != self.base {
+            return Err(RunnerError::PageNotOnOutputSegment(base, self.base));
+        }
+        self.base_offset = base.offset;
+        self.pages = HashMap::new();
+        self.attributes = HashMap::new();
+        Ok(())
+    }
+
+    pub fn set_state(&mut self, state: OutputBuiltinState) {
+        self.base_offset = state.base_offset;
+        self.pages = state.pages;
+        self.attributes = state.attributes;
+    }
+
+    /// Returns the public memory of the output segment, as (offset, page_id) pairs.
+    /// Cells that don't belong to any page are assigned to page 0.
+    pub fn get_public_memory(&self, size: usize) -> Result<Vec<(usize, usize)>, RunnerError> {
+        let mut public_memory: Vec<(usize, usize)> = (0..size).map(|i| (i, 0)).collect();
+        for (page_id, page) in self.pages.iter() {
+            for index in page.start..page.start + page.size {
+                let cell = public_memory
+                    .get_mut(self.base_offset + index)
+                    .ok_or(RunnerError::PageOutOfBounds(*page_id, size))?;
+                cell.1 = *page_id;
+            }
+        }
+        Ok(public_memory)
+    }
 }
 
 impl Default for OutputBuiltinRunner {
@@ -363,4 +494,88 @@ mod tests {
         vm.segments.segment_used_sizes = Some(vec![4]);
         assert_eq!(builtin.get_used_cells(&vm), Ok(4));
     }
+
+    #[test]
+    fn add_page_and_get_public_memory() {
+        let mut builtin = OutputBuiltinRunner::new(true);
+        builtin.add_page(1, (0, 1).into(), 2).unwrap();
+        builtin.add_page(2, (0, 3).into(), 1).unwrap();
+
+        assert_eq!(
+            builtin.get_pages().get(&1),
+            Some(&PublicMemoryPage { start: 1, size: 2 })
+        );
+        assert_eq!(
+            builtin.get_public_memory(5),
+            Ok(vec![(0, 0), (1, 1), (2, 1), (3, 2), (4, 0)])
+        );
+    }
+
+    #[test]
+    fn add_page_errors() {
+        let mut builtin = OutputBuiltinRunner::new(true);
+        assert_eq!(
+            builtin.add_page(1, (1, 0).into(), 2),
+            Err(RunnerError::PageNotOnOutputSegment((1, 0).into(), 0))
+        );
+        builtin.add_page(1, (0, 0).into(), 2).unwrap();
+        assert_eq!(
+            builtin.add_page(1, (0, 2).into(), 2),
+            Err(RunnerError::PageAlreadyUsed(1))
+        );
+        assert_eq!(
+            builtin.get_public_memory(1),
+            Err(RunnerError::PageOutOfBounds(1, 1))
+        );
+    }
+
+    #[test]
+    fn new_state_records_pages_relative_to_base() {
+        let mut builtin = OutputBuiltinRunner::new(true);
+        builtin.add_page(1, (0, 0).into(), 2).unwrap();
+        let state = builtin.get_state();
+
+        builtin.new_state((0, 4).into()).unwrap();
+        assert!(builtin.get_pages().is_empty());
+        builtin.add_page(1, (0, 5).into(), 3).unwrap();
+        assert_eq!(
+            builtin.get_pages().get(&1),
+            Some(&PublicMemoryPage { start: 1, size: 3 })
+        );
+
+        builtin.set_state(state.clone());
+        assert_eq!(builtin.get_state(), state);
+    }
+
+    #[test]
+    fn new_state_resets_attributes() {
+        let mut builtin = OutputBuiltinRunner::new(true);
+        builtin.add_attribute("gps_fact_topology".to_string(), vec![1, 0]);
+        let state = builtin.get_state();
+
+        builtin.new_state((0, 2).into()).unwrap();
+        assert!(builtin.get_attributes().is_empty());
+
+        builtin.set_state(state);
+        assert_eq!(
+            builtin.get_attributes().get("gps_fact_topology"),
+            Some(&vec![1, 0])
+        );
+    }
+
+    #[test]
+    fn get_additional_data_serialization() {
+        let mut builtin = OutputBuiltinRunner::new(true);
+        builtin.add_page(1, (0, 1).into(), 2).unwrap();
+        builtin.add_page(2, (0, 3).into(), 4).unwrap();
+        builtin.add_attribute("gps_fact_topology".to_string(), vec![2, 1, 0, 2]);
+
+        assert_eq!(
+            serde_json::to_value(builtin.get_additional_data()).unwrap(),
+            serde_json::json!({
+                "pages": {"1": [1, 2], "2": [3, 4]},
+                "attributes": {"gps_fact_topology": [2, 1, 0, 2]}
+            })
+        );
+    }
 }
@@ -31,8 +31,8 @@ pub struct RangeCheckBuiltinRunner {
     inner_rc_bound: usize,
     pub _bound: Option<Felt>,
     pub(crate) _included: bool,
-    n_parts: u32,
-    instances_per_component: u32,
+    pub(crate) n_parts: u32,
+    pub(crate) instances_per_component: u32,
 }
 
 impl RangeCheckBuiltinRunner {
@@ -166,7 +166,7 @@ impl RangeCheckBuiltinRunner {
         let mut rc_bounds: Option<(usize, usize)> = None;
         let range_check_segment = memory.data.get(self.base as usize)?;
         let inner_rc_bound = Felt::new(self.inner_rc_bound);
-        for value in range_check_segment {
+        for value in range_check_segment.iter() {
             //Split val into n_parts parts.
             for _ in 0..self.n_parts {
                 let part_val = value
@@ -1,6 +1,7 @@
 use crate::{
     math_utils::safe_div_usize,
     types::{
+        field_element::ToFieldElement,
         instance_definitions::ecdsa_instance_def::EcdsaInstanceDef,
         relocatable::{MaybeRelocatable, Relocatable},
     },
@@ -16,7 +17,7 @@ use crate::{
 use felt::{Felt, FeltOps};
 use num_integer::{div_ceil, Integer};
 use num_traits::ToPrimitive;
-use starknet_crypto::{verify, FieldElement, Signature};
+use starknet_crypto::{verify, Signature};
 use std::{any::Any, cell::RefCell, collections::HashMap, rc::Rc};
 
 #[derive(Debug, Clone)]
@@ -28,7 +29,7 @@ pub struct SignatureBuiltinRunner {
     pub(crate) n_input_cells: u32,
     _total_n_bits: u32,
     pub(crate) stop_ptr: Option<usize>,
-    instances_per_component: u32,
+    pub(crate) instances_per_component: u32,
     signatures: Rc<RefCell<HashMap<Relocatable, Signature>>>,
 }
 
@@ -52,13 +53,11 @@ impl SignatureBuiltinRunner {
         relocatable: Relocatable,
         (r, s): &(Felt, Felt),
     ) -> Result<(), MemoryError> {
-        let r_string = r.to_str_radix(10);
-        let s_string = s.to_str_radix(10);
         let (r_felt, s_felt) = (
-            FieldElement::from_dec_str(&r_string)
-                .map_err(|_| MemoryError::AddressNotRelocatable)?,
-            FieldElement::from_dec_str(&s_string)
-                .map_err(|_| MemoryError::AddressNotRelocatable)?,
+            r.to_field_element()
+                .ok_or(MemoryError::AddressNotRelocatable)?,
+            s.to_field_element()
+                .ok_or(MemoryError::AddressNotRelocatable)?,
         );
 
         let signature = Signature {
@@ -144,11 +143,13 @@ impl SignatureBuiltinRunner {
                 let signature = signatures_map
                     .get(&pubkey_addr)
                     .ok_or(MemoryError::SignatureNotFound)?;
-                let public_key = FieldElement::from_dec_str(&pub_key.to_str_radix(10))
-                    .map_err(|_| MemoryError::ErrorParsingPubKey(pub_key.to_str_radix(10)))?;
+                let public_key = pub_key
+                    .to_field_element()
+                    .ok_or_else(|| MemoryError::ErrorParsingPubKey(pub_key.to_str_radix(10)))?;
                 let (r, s) = (signature.r, signature.s);
-                let message = FieldElement::from_dec_str(&msg.to_str_radix(10))
-                    .map_err(|_| MemoryError::ErrorRetrievingMessage(msg.to_str_radix(10)))?;
+                let message = msg
+                    .to_field_element()
+                    .ok_or_else(|| MemoryError::ErrorRetrievingMessage(msg.to_str_radix(10)))?;
                 let was_verified = verify(&public_key, &message, &r, &s)
                     .map_err(|_| MemoryError::ErrorVerifyingSignature)?;
                 if was_verified {
@@ -1,8 +1,13 @@
 use crate::{
-    hint_processor::hint_processor_definition::{HintProcessor, HintReference},
+    any_box,
+    hint_processor::{
+        builtin_hint_processor::dict_manager::{DictManager, DictSnapshot},
+        hint_processor_definition::{HintProcessor, HintReference},
+    },
     math_utils::safe_div_usize,
     serde::deserialize_program::OffsetValue,
     types::{
+        cairo_arg::CairoArg,
         errors::program_errors::ProgramError,
         exec_scope::ExecutionScopes,
         instance_definitions::{
@@ -18,15 +23,18 @@ use crate::{
     vm::{
         errors::{
             memory_errors::MemoryError, runner_errors::RunnerError, trace_errors::TraceError,
-            vm_errors::VirtualMachineError,
+            typed_value_errors::TypedValueError, vm_errors::VirtualMachineError,
         },
+        hint_stats::HintStats,
         security::verify_secure_runner,
         trace::get_perm_range_check_limits,
+        typed_value::{CairoValue, ValueInspector},
         vm_memory::{memory::RelocateValue, memory_segments::gen_typed_args},
         {
             runners::builtin_runner::{
                 BitwiseBuiltinRunner, BuiltinRunner, EcOpBuiltinRunner, HashBuiltinRunner,
-                OutputBuiltinRunner, RangeCheckBuiltinRunner, SignatureBuiltinRunner,
+                OutputBuiltinAdditionalData, OutputBuiltinRunner, RangeCheckBuiltinRunner,
+                SignatureBuiltinRunner,
             },
             trace::trace_entry::{relocate_trace_register, RelocatedTraceEntry},
             vm_core::VirtualMachine,
@@ -36,31 +44,77 @@ use crate::{
 use felt::{Felt, FeltOps};
 use num_integer::div_rem;
 use num_traits::Zero;
+use serde::{Serialize, Serializer};
 use std::{
     any::Any,
+    cell::RefCell,
     collections::{HashMap, HashSet},
     io,
+    rc::Rc,
 };
 
 use super::builtin_runner::KeccakBuiltinRunner;
 
+#[cfg(feature = "async_hints")]
+use crate::hint_processor::async_hint_processor::AsyncHintProcessor;
+
 pub struct CairoRunner {
     pub(crate) program: Program,
-    layout: CairoLayout,
-    final_pc: Option<Relocatable>,
+    pub(crate) layout: CairoLayout,
+    pub(crate) final_pc: Option<Relocatable>,
     pub(crate) program_base: Option<Relocatable>,
-    execution_base: Option<Relocatable>,
-    initial_ap: Option<Relocatable>,
+    pub(crate) execution_base: Option<Relocatable>,
+    pub(crate) initial_ap: Option<Relocatable>,
     initial_fp: Option<Relocatable>,
-    initial_pc: Option<Relocatable>,
+    pub(crate) initial_pc: Option<Relocatable>,
     run_ended: bool,
     segments_finalized: bool,
     execution_public_memory: Option<Vec<usize>>,
-    proof_mode: bool,
+    pub(crate) proof_mode: bool,
     pub original_steps: Option<usize>,
     pub relocated_memory: Vec<Option<Felt>>,
     pub relocated_trace: Option<Vec<RelocatedTraceEntry>>,
+    /// Set by `relocate`, see `get_relocation_table`.
+    pub relocation_table: Option<RelocationTable>,
+    /// Set by `end_run` if the hint stats were enabled with `VirtualMachine::enable_hint_stats`.
+    pub hint_stats: Option<HintStats>,
+    /// The pages and attributes of the output builtin, set by `end_run` if the program uses it.
+    pub output_builtin_additional_data: Option<OutputBuiltinAdditionalData>,
     pub exec_scopes: ExecutionScopes,
+    breakpoints: HashSet<Relocatable>,
+    host_hints: HashSet<String>,
+    // Where the last call to run_until paused, so that the next one resumes from there
+    paused_at: Option<(Relocatable, PausePoint)>,
+    // The hints compiled by the first call to run_until, reused when resuming the run
+    run_until_hint_data: Option<RunHintData>,
+}
+
+/// The state in which `CairoRunner::run_until` left the run.
+/// Unless it is `Finished`, the run can be resumed by calling `run_until` again.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RunStatus {
+    /// The pc reached the end of the program.
+    Finished,
+    /// The run was paused after a step.
+    Paused(PauseReason),
+    /// The pc reached a breakpoint, whose hints and instruction weren't executed yet.
+    BreakpointHit(Relocatable),
+    /// The next hint to execute is a host hint with this code. The host is expected to execute
+    /// it before resuming the run, which will continue from the next hint.
+    HintRequested(String),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PauseReason {
+    /// The condition given to `run_until` returned true.
+    Condition,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum PausePoint {
+    Breakpoint,
+    // The index of the requested hint among the hints of the pc
+    HostHint(usize),
 }
 
 impl CairoRunner {
@@ -79,6 +133,9 @@ impl CairoRunner {
             "all" => CairoLayout::all_instance(),
             name => return Err(RunnerError::InvalidLayoutName(name.to_string())),
         };
+        // Lets hints access struct members by name, see `Ids`
+        let mut exec_scopes = ExecutionScopes::new();
+        exec_scopes.insert_value("program_identifiers", Rc::new(program.identifiers.clone()));
         Ok(CairoRunner {
             program: program.clone(),
             layout: cairo_layout,
@@ -94,8 +151,15 @@ impl CairoRunner {
             original_steps: None,
             relocated_memory: Vec::new(),
             relocated_trace: None,
-            exec_scopes: ExecutionScopes::new(),
+            relocation_table: None,
+            hint_stats: None,
+            output_builtin_additional_data: None,
+            exec_scopes,
             execution_public_memory: if proof_mode { Some(Vec::new()) } else { None },
+            breakpoints: HashSet::new(),
+            host_hints: HashSet::new(),
+            paused_at: None,
+            run_until_hint_data: None,
         })
     }
 
@@ -438,31 +502,7 @@ impl CairoRunner {
     }
 
     pub fn get_reference_list(&self) -> HashMap<usize, HintReference> {
-        let mut references = HashMap::<usize, HintReference>::new();
-
-        for (i, reference) in self.program.reference_manager.references.iter().enumerate() {
-            references.insert(
-                i,
-                HintReference {
-                    offset1: reference.value_address.offset1.clone(),
-                    offset2: reference.value_address.offset2.clone(),
-                    dereference: reference.value_address.dereference,
-                    // only store `ap` tracking data if the reference is referred to it
-                    ap_tracking_data: match (
-                        &reference.value_address.offset1,
-                        &reference.value_address.offset2,
-                    ) {
-                        (OffsetValue::Reference(Register::AP, _, _), _)
-                        | (_, OffsetValue::Reference(Register::AP, _, _)) => {
-                            Some(reference.ap_tracking_data.clone())
-                        }
-                        _ => None,
-                    },
-                    cairo_type: Some(reference.value_address.value_type.clone()),
-                },
-            );
-        }
-        references
+        get_program_reference_list(&self.program)
     }
 
     /// Gets the data used by the HintProcessor to execute each hint
@@ -471,22 +511,25 @@ impl CairoRunner {
         references: &HashMap<usize, HintReference>,
         hint_executor: &mut dyn HintProcessor,
     ) -> Result<HashMap<usize, Vec<Box<dyn Any>>>, VirtualMachineError> {
-        let mut hint_data_dictionary = HashMap::<usize, Vec<Box<dyn Any>>>::new();
-        for (hint_index, hints) in self.program.hints.iter() {
-            for hint in hints {
-                let hint_data = hint_executor.compile_hint(
-                    &hint.code,
-                    &hint.flow_tracking_data.ap_tracking,
-                    &hint.flow_tracking_data.reference_ids,
-                    references,
-                );
-                hint_data_dictionary.entry(*hint_index).or_default().push(
-                    hint_data
-                        .map_err(|_| VirtualMachineError::CompileHintFail(hint.code.clone()))?,
-                );
-            }
-        }
-        Ok(hint_data_dictionary)
+        compile_program_hints(
+            &self.program,
+            references,
+            0,
+            hint_executor,
+            &self.host_hints,
+        )
+    }
+
+    // Compiles the hints of the program for a run, see `RunHintData`
+    fn compile_run_hint_data(
+        &self,
+        hint_processor: &mut dyn HintProcessor,
+    ) -> Result<RunHintData, VirtualMachineError> {
+        let references = self.get_reference_list();
+        Ok(RunHintData {
+            hint_data_dictionary: self.get_hint_data_dictionary(&references, hint_processor)?,
+            loaded_programs: LoadedProgramsHintData::default(),
+        })
     }
 
     pub fn get_constants(&self) -> &HashMap<String, Felt> {
@@ -497,21 +540,137 @@ impl CairoRunner {
         &self.program.builtins
     }
 
+    /// Returns the address the program was loaded at, once the segments were initialized.
+    pub fn get_program_base(&self) -> Option<Relocatable> {
+        self.program_base
+    }
+
+    /// Returns the base of the execution segment, once the segments were initialized.
+    pub fn get_execution_base(&self) -> Option<Relocatable> {
+        self.execution_base
+    }
+
+    pub fn get_initial_pc(&self) -> Option<Relocatable> {
+        self.initial_pc
+    }
+
+    pub fn get_initial_ap(&self) -> Option<Relocatable> {
+        self.initial_ap
+    }
+
+    /// Returns the pc at which the run ends, once the entrypoint was initialized.
+    pub fn get_final_pc(&self) -> Option<Relocatable> {
+        self.final_pc
+    }
+
+    pub fn get_layout_name(&self) -> &str {
+        &self.layout._name
+    }
+
+    pub fn is_proof_mode(&self) -> bool {
+        self.proof_mode
+    }
+
+    /// Decodes the value of type `cairo_type` stored at `addr`, using the structs of the
+    /// program. See `ValueInspector` to change how many pointers are followed.
+    pub fn inspect_value(
+        &self,
+        vm: &VirtualMachine,
+        addr: &Relocatable,
+        cairo_type: &str,
+    ) -> Result<CairoValue, TypedValueError> {
+        ValueInspector::new(&self.program.identifiers).inspect(vm, addr, cairo_type)
+    }
+
+    /// Adds variables to the main execution scope, so that hints can read them from the first
+    /// step. Should be called before running the program.
+    pub fn initialize_main_scope(&mut self, variables: HashMap<String, Box<dyn Any>>) {
+        self.exec_scopes.insert_main_scope_values(variables);
+    }
+
+    /// Makes the program input available to hints, as the `program_input` variable of the
+    /// Python VM. See `ExecutionScopes::get_program_input`.
+    pub fn set_program_input(&mut self, program_input: serde_json::Value) {
+        self.initialize_main_scope(HashMap::from([(
+            String::from("program_input"),
+            any_box!(program_input),
+        )]));
+    }
+
+    /// Sets the DictManager used by the dict hints, allowing the host to create dictionaries
+    /// before running the program. Its trackers should point to segments already added to the vm.
+    pub fn set_dict_manager(&mut self, dict_manager: DictManager) {
+        self.initialize_main_scope(HashMap::from([(
+            String::from("dict_manager"),
+            any_box!(Rc::new(RefCell::new(dict_manager))),
+        )]));
+    }
+
+    /// Supplies the initial dict of a dictionary created by the `dict_new` hint without an
+    /// `initial_dict` in scope. Dictionaries take the initial dicts in the order they were added.
+    /// Values that are lists are written to new segments, see `CairoArg`.
+    /// Should be called before running the program, after `set_dict_manager` if it is used.
+    pub fn add_initial_dict(&mut self, initial_dict: Vec<(MaybeRelocatable, CairoArg)>) {
+        match self.exec_scopes.get_dict_manager() {
+            Ok(dict_manager) => dict_manager.borrow_mut().add_initial_dict(initial_dict),
+            Err(_) => {
+                let mut dict_manager = DictManager::new();
+                dict_manager.add_initial_dict(initial_dict);
+                self.set_dict_manager(dict_manager);
+            }
+        }
+    }
+
     pub fn run_until_pc(
         &mut self,
         address: Relocatable,
         vm: &mut VirtualMachine,
         hint_processor: &mut dyn HintProcessor,
     ) -> Result<(), VirtualMachineError> {
-        let references = self.get_reference_list();
-        let hint_data_dictionary = self.get_hint_data_dictionary(&references, hint_processor)?;
+        let mut run_hint_data = self.compile_run_hint_data(hint_processor)?;
         while vm.run_context.pc != address {
+            let (hint_data_dictionary, constants) = run_hint_data.get_for_pc(
+                vm,
+                hint_processor,
+                &self.host_hints,
+                &self.program.constants,
+            )?;
             vm.step(
                 hint_processor,
                 &mut self.exec_scopes,
-                &hint_data_dictionary,
+                hint_data_dictionary,
+                constants,
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Like `run_until_pc`, but hints are executed by an AsyncHintProcessor and awaited, so that
+    /// hints waiting on I/O don't block the thread.
+    /// The VM isn't `Send`, and neither is the returned future, so it should be run on a
+    /// single-threaded executor (ie: inside a tokio `LocalSet`).
+    #[cfg(feature = "async_hints")]
+    pub async fn run_until_pc_async<H: AsyncHintProcessor>(
+        &mut self,
+        address: Relocatable,
+        vm: &mut VirtualMachine,
+        hint_processor: &mut H,
+    ) -> Result<(), VirtualMachineError> {
+        let mut run_hint_data = self.compile_run_hint_data(hint_processor)?;
+        while vm.run_context.pc != address {
+            let (hint_data_dictionary, constants) = run_hint_data.get_for_pc(
+                vm,
+                hint_processor,
+                &self.host_hints,
                 &self.program.constants,
             )?;
+            vm.step_async(
+                hint_processor,
+                &mut self.exec_scopes,
+                hint_data_dictionary,
+                constants,
+            )
+            .await?;
         }
         Ok(())
     }
@@ -523,19 +682,24 @@ impl CairoRunner {
         vm: &mut VirtualMachine,
         hint_processor: &mut dyn HintProcessor,
     ) -> Result<(), VirtualMachineError> {
-        let references = self.get_reference_list();
-        let hint_data_dictionary = self.get_hint_data_dictionary(&references, hint_processor)?;
+        let mut run_hint_data = self.compile_run_hint_data(hint_processor)?;
 
         for remaining_steps in (1..=steps).rev() {
             if self.final_pc.as_ref() == Some(&vm.run_context.pc) {
                 return Err(VirtualMachineError::EndOfProgram(remaining_steps));
             }
 
+            let (hint_data_dictionary, constants) = run_hint_data.get_for_pc(
+                vm,
+                hint_processor,
+                &self.host_hints,
+                &self.program.constants,
+            )?;
             vm.step(
                 hint_processor,
                 &mut self.exec_scopes,
-                &hint_data_dictionary,
-                &self.program.constants,
+                hint_data_dictionary,
+                constants,
             )?;
         }
 
@@ -561,16 +725,154 @@ impl CairoRunner {
         self.run_until_steps(vm.current_step.next_power_of_two(), vm, hint_processor)
     }
 
+    /// Pauses `run_until` when the pc reaches `pc`.
+    pub fn add_breakpoint(&mut self, pc: Relocatable) {
+        self.breakpoints.insert(pc);
+    }
+
+    /// Removes the breakpoint at `pc`, returning false if there was none.
+    pub fn remove_breakpoint(&mut self, pc: &Relocatable) -> bool {
+        self.breakpoints.remove(pc)
+    }
+
+    /// Makes the hints with code `hint_code` host hints: they aren't compiled nor executed by the
+    /// hint processor, instead `run_until` returns `RunStatus::HintRequested` before each of them,
+    /// so that the host executes them.
+    /// Should be called before running the program, as the other run methods fail on host hints.
+    pub fn add_host_hint(&mut self, hint_code: &str) {
+        self.host_hints.insert(hint_code.to_string());
+        // The hints are compiled again, now that they aren't executed by the hint processor
+        self.run_until_hint_data = None;
+    }
+
+    /// Runs the program until it reaches its end, a breakpoint or a host hint, or until
+    /// `condition` returns true after a step, see `RunStatus`.
+    /// Calling `run_until` again resumes the run from where it was paused, the state of the VM can
+    /// be inspected and modified in between.
+    /// The hints are compiled by the first call, so the same hint processor should be given to the
+    /// calls resuming the run.
+    pub fn run_until<F: FnMut(&VirtualMachine) -> bool>(
+        &mut self,
+        vm: &mut VirtualMachine,
+        hint_processor: &mut dyn HintProcessor,
+        condition: F,
+    ) -> Result<RunStatus, VirtualMachineError> {
+        let mut run_hint_data = match self.run_until_hint_data.take() {
+            Some(run_hint_data) => run_hint_data,
+            None => self.compile_run_hint_data(hint_processor)?,
+        };
+        let status = self.run_until_with(vm, hint_processor, &mut run_hint_data, condition);
+        self.run_until_hint_data = Some(run_hint_data);
+        status
+    }
+
+    fn run_until_with<F: FnMut(&VirtualMachine) -> bool>(
+        &mut self,
+        vm: &mut VirtualMachine,
+        hint_processor: &mut dyn HintProcessor,
+        run_hint_data: &mut RunHintData,
+        mut condition: F,
+    ) -> Result<RunStatus, VirtualMachineError> {
+        loop {
+            let pc = vm.run_context.pc;
+            if self.final_pc == Some(pc) {
+                self.paused_at = None;
+                return Ok(RunStatus::Finished);
+            }
+            // The pause is only resumed from if the host didn't move the pc
+            let resumed_from = match self.paused_at.take() {
+                Some((paused_pc, pause_point)) if paused_pc == pc => Some(pause_point),
+                _ => None,
+            };
+            if resumed_from.is_none() && self.breakpoints.contains(&pc) {
+                self.paused_at = Some((pc, PausePoint::Breakpoint));
+                return Ok(RunStatus::BreakpointHit(pc));
+            }
+
+            // Hints before the first hint that wasn't executed yet are skipped
+            let first_hint = match resumed_from {
+                Some(PausePoint::HostHint(hint_index)) => hint_index + 1,
+                _ => 0,
+            };
+            let next_host_hint = if self.host_hints.is_empty() {
+                None
+            } else {
+                self.get_hint_codes(vm, &pc)
+                    .into_iter()
+                    .enumerate()
+                    .skip(first_hint)
+                    .find(|(_, code)| self.host_hints.contains(*code))
+                    .map(|(hint_index, code)| (hint_index, code.to_string()))
+            };
+
+            let (hint_data_dictionary, constants) = run_hint_data.get_for_pc(
+                vm,
+                hint_processor,
+                &self.host_hints,
+                &self.program.constants,
+            )?;
+            let last_hint = next_host_hint
+                .as_ref()
+                .map_or(usize::MAX, |(hint_index, _)| *hint_index);
+            vm.step_hint_range(
+                hint_processor,
+                &mut self.exec_scopes,
+                hint_data_dictionary,
+                constants,
+                first_hint..last_hint,
+            )?;
+            if let Some((hint_index, code)) = next_host_hint {
+                self.paused_at = Some((pc, PausePoint::HostHint(hint_index)));
+                return Ok(RunStatus::HintRequested(code));
+            }
+            vm.step_instruction()?;
+
+            if condition(vm) {
+                return Ok(RunStatus::Paused(PauseReason::Condition));
+            }
+        }
+    }
+
+    // Returns the codes of the hints at `pc`, in the order in which they are executed
+    fn get_hint_codes<'a>(&'a self, vm: &'a VirtualMachine, pc: &Relocatable) -> Vec<&'a str> {
+        let (program, hint_index) = match vm
+            .loaded_programs
+            .iter()
+            .find(|(program_base, _)| program_base.segment_index == pc.segment_index)
+        {
+            Some((program_base, program)) => match pc.offset.checked_sub(program_base.offset) {
+                Some(hint_index) => (program, hint_index),
+                None => return Vec::new(),
+            },
+            None => (&self.program, pc.offset),
+        };
+        program
+            .hints
+            .get(&hint_index)
+            .map_or_else(Vec::new, |hints| {
+                hints.iter().map(|hint| hint.code.as_str()).collect()
+            })
+    }
+
     pub fn get_perm_range_check_limits(
         &self,
         vm: &VirtualMachine,
     ) -> Result<Option<(isize, isize)>, VirtualMachineError> {
-        let limits = get_perm_range_check_limits(
+        let mut limits = get_perm_range_check_limits(
             vm.trace.as_ref().ok_or(VirtualMachineError::TracerError(
                 TraceError::TraceNotEnabled,
             ))?,
             &vm.memory,
         )?;
+        // Entries streamed to disk are no longer in the trace
+        if let Some(streaming_trace) = &vm.streaming_trace {
+            limits = match (limits, streaming_trace.flushed_rc_limits()) {
+                (Some((rc_min, rc_max)), Some((flushed_min, flushed_max))) => {
+                    Some((rc_min.min(flushed_min), rc_max.max(flushed_max)))
+                }
+                (limits, flushed_limits) => limits.or(flushed_limits),
+            };
+        }
 
         match limits {
             Some((mut rc_min, mut rc_max)) => {
@@ -603,8 +905,9 @@ impl CairoRunner {
             rc_units_used_by_builtins += builtin_runner.get_used_perm_range_check_units(vm)?;
         }
 
-        let unused_rc_units =
-            (self.layout.rc_units as usize - 3) * vm.current_step - rc_units_used_by_builtins;
+        let unused_rc_units = ((self.layout.rc_units as usize).saturating_sub(3) * vm.current_step)
+            .checked_sub(rc_units_used_by_builtins)
+            .ok_or(MemoryError::InsufficientAllocatedCells)?;
         if unused_rc_units < (rc_max - rc_min) as usize {
             return Err(MemoryError::InsufficientAllocatedCells.into());
         }
@@ -624,16 +927,23 @@ impl CairoRunner {
             .iter()
             .map(|addr| vm.memory.relocate_value(*addr));
 
-        let builtin_addresses = vm
+        let builtin_segments = vm
             .builtin_runners
             .iter()
-            .map(|(_, runner)| runner.base())
-            .collect::<Vec<_>>()
-            .into_iter()
-            .flat_map(|base| {
-                let size = vm.segments.get_segment_size(base as usize).unwrap();
-                (0..size).map(move |offset| Relocatable::from((base, offset)))
-            });
+            .map(|(_, runner)| {
+                let base = runner.base();
+                let segment_index = base
+                    .try_into()
+                    .map_err(|_| MemoryError::AddressInTemporarySegment(base))?;
+                vm.segments
+                    .get_segment_size(segment_index)
+                    .map(|size| (base, size))
+                    .ok_or(MemoryError::SegmentNotFinalized(segment_index))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let builtin_addresses = builtin_segments.into_iter().flat_map(|(base, size)| {
+            (0..size).map(move |offset| Relocatable::from((base, offset)))
+        });
 
         let addresses = program_addresses
             .chain(accessed_addresses)
@@ -666,7 +976,9 @@ impl CairoRunner {
         }
 
         let diluted_units = diluted_pool_instance.units_per_step as usize * vm.current_step;
-        let unused_diluted_units = diluted_units - used_units_by_builtins;
+        let unused_diluted_units = diluted_units
+            .checked_sub(used_units_by_builtins)
+            .ok_or(MemoryError::InsufficientAllocatedCells)?;
 
         let diluted_usage_upper_bound = 1usize << diluted_pool_instance.n_bits;
         if unused_diluted_units < diluted_usage_upper_bound {
@@ -689,30 +1001,20 @@ impl CairoRunner {
 
         vm.memory.relocate_memory()?;
         vm.end_run(&self.exec_scopes)?;
+        self.output_builtin_additional_data = vm.get_output_builtin_additional_data();
 
         if disable_finalize_all {
+            self.hint_stats = vm.hint_stats.clone();
             return Ok(());
         }
 
         vm.segments.compute_effective_sizes(&vm.memory);
         if self.proof_mode && !disable_trace_padding {
-            self.run_until_next_power_of_2(vm, hint_processor)?;
-            loop {
-                match self.check_used_cells(vm) {
-                    Ok(_) => break,
-                    Err(e) => match e {
-                        VirtualMachineError::MemoryError(
-                            MemoryError::InsufficientAllocatedCells,
-                        ) => {}
-                        e => return Err(e),
-                    },
-                }
-
-                self.run_for_steps(1, vm, hint_processor)?;
-                self.run_until_next_power_of_2(vm, hint_processor)?;
-            }
+            self.pad_proof_mode_run(vm, hint_processor)?;
         }
 
+        // Taken after the padding, whose hints are also counted
+        self.hint_stats = vm.hint_stats.clone();
         self.run_ended = true;
         Ok(())
     }
@@ -738,7 +1040,7 @@ impl CairoRunner {
                             Relocatable::from((index as isize, seg_offset)),
                             relocation_table,
                         )?;
-                        let value = relocate_value(elem.clone(), relocation_table)?;
+                        let value = relocate_value(elem, relocation_table)?;
                         if self.relocated_memory.len() <= relocated_addr {
                             self.relocated_memory.resize(relocated_addr + 1, None);
                         }
@@ -760,6 +1062,9 @@ impl CairoRunner {
         if self.relocated_trace.is_some() {
             return Err(TraceError::AlreadyRelocated);
         }
+        if vm.streaming_trace.is_some() {
+            return Err(TraceError::StreamedTrace);
+        }
 
         let trace = vm.trace.as_ref().ok_or(TraceError::TraceNotEnabled)?.iter();
         let mut relocated_trace = Vec::<RelocatedTraceEntry>::with_capacity(trace.len());
@@ -774,14 +1079,58 @@ impl CairoRunner {
         Ok(())
     }
 
+    /// Returns the relocation table of the memory, along with the destination of the temporary
+    /// segments. The effective sizes of the segments must have been computed.
+    pub fn get_relocation_table(
+        &self,
+        vm: &VirtualMachine,
+    ) -> Result<RelocationTable, MemoryError> {
+        let bases = vm.segments.relocate_segments()?;
+        let mut names = HashMap::new();
+        if let Some(program_base) = self.program_base {
+            names.insert(program_base.segment_index, "program".to_string());
+        }
+        if let Some(execution_base) = self.execution_base {
+            names.insert(execution_base.segment_index, "execution".to_string());
+        }
+        for (name, builtin) in vm.builtin_runners.iter() {
+            names.insert(builtin.base(), name.clone());
+        }
+        let segments = bases
+            .into_iter()
+            .enumerate()
+            .map(|(index, base)| {
+                Ok(RelocatedSegment {
+                    index,
+                    base,
+                    size: vm
+                        .segments
+                        .get_segment_size(index)
+                        .ok_or(MemoryError::SegmentNotFinalized(index))?,
+                    name: names.get(&(index as isize)).cloned(),
+                })
+            })
+            .collect::<Result<_, MemoryError>>()?;
+        let relocation_rules = vm.memory.get_relocation_rules();
+        let temporary_segments = (1..=vm.segments.num_temp_segments as isize)
+            .map(|index| TemporarySegmentRelocation {
+                index: -index,
+                destination: relocation_rules.get(&-index).copied(),
+            })
+            .collect();
+        Ok(RelocationTable {
+            segments,
+            temporary_segments,
+        })
+    }
+
     pub fn relocate(&mut self, vm: &mut VirtualMachine) -> Result<(), TraceError> {
         vm.segments.compute_effective_sizes(&vm.memory);
-        // relocate_segments can fail if compute_effective_sizes is not called before.
-        // The expect should be unreachable.
         let relocation_table = vm
             .segments
             .relocate_segments()
-            .expect("compute_effective_sizes called but relocate_memory still returned error");
+            .map_err(TraceError::MemoryError)?;
+        self.relocation_table = Some(self.get_relocation_table(vm)?);
         if let Err(memory_error) = self.relocate_memory(vm, &relocation_table) {
             return Err(TraceError::MemoryError(memory_error));
         }
@@ -818,7 +1167,7 @@ impl CairoRunner {
     ) -> Result<ExecutionResources, TraceError> {
         let n_steps = match self.original_steps {
             Some(x) => x,
-            None => vm.trace.as_ref().map(|x| x.len()).unwrap_or(0),
+            None => vm.get_trace_len().unwrap_or(0),
         };
         let n_memory_holes = self.get_memory_holes(vm)?;
 
@@ -870,7 +1219,11 @@ impl CairoRunner {
             .try_into()
             .map_err(|_| RunnerError::RunnerInTemporarySegment(base))?;
 
-        for i in 0..segment_used_sizes[segment_index] {
+        let segment_used_size = *segment_used_sizes
+            .get(segment_index)
+            .ok_or(MemoryError::SegmentNotFinalized(segment_index))?;
+
+        for i in 0..segment_used_size {
             let value = vm
                 .memory
                 .get_integer(&(base, i).into())
@@ -882,6 +1235,40 @@ impl CairoRunner {
         Ok(())
     }
 
+    /// Returns the final state of the dictionaries created through the DictManager during the
+    /// run, sorted by segment index. Returns an empty vector if no dictionary was created.
+    pub fn get_dicts(&self) -> Vec<DictSnapshot> {
+        match self.exec_scopes.get_dict_manager() {
+            Ok(dict_manager) => dict_manager.borrow().get_snapshots(),
+            Err(_) => Vec::new(),
+        }
+    }
+
+    /// Writes the final contents of the dictionaries created during the run, one key-value
+    /// pair per line. Does nothing if no dictionary was created.
+    pub fn write_dicts(&self, stdout: &mut dyn io::Write) -> Result<(), RunnerError> {
+        for dict in self.get_dicts() {
+            match dict.default_value {
+                Some(default_value) => writeln!(
+                    stdout,
+                    "Dict at segment {} (default value: {}, accesses: {}):",
+                    dict.segment_index, default_value, dict.n_accesses
+                ),
+                None => writeln!(
+                    stdout,
+                    "Dict at segment {} (accesses: {}):",
+                    dict.segment_index, dict.n_accesses
+                ),
+            }
+            .map_err(|_| RunnerError::WriteFail)?;
+            for (key, value) in dict.data {
+                writeln!(stdout, "  {}: {}", key, value).map_err(|_| RunnerError::WriteFail)?;
+            }
+        }
+
+        Ok(())
+    }
+
     // Finalizes the segments.
     //     Note:
     //     1.  end_run() must precede a call to this method.
@@ -926,8 +1313,17 @@ impl CairoRunner {
             let (_, size) = builtin_runner
                 .get_used_cells_and_allocated_size(vm)
                 .map_err(RunnerError::FinalizeSegements)?;
-            vm.segments
-                .finalize(Some(size), builtin_runner.base() as usize, None)
+            let public_memory = match builtin_runner {
+                BuiltinRunner::Output(output_builtin) => {
+                    Some(output_builtin.get_public_memory(size)?)
+                }
+                _ => None,
+            };
+            vm.segments.finalize(
+                Some(size),
+                builtin_runner.base() as usize,
+                public_memory.as_ref(),
+            )
         }
         self.segments_finalized = true;
         Ok(())
@@ -1022,7 +1418,8 @@ impl CairoRunner {
         let instruction_memory_units = 4 * vm_current_step_u32;
 
         let unused_memory_units = total_memory_units
-            - (public_memory_units + instruction_memory_units + builtins_memory_units);
+            .checked_sub(public_memory_units + instruction_memory_units + builtins_memory_units)
+            .ok_or(MemoryError::InsufficientAllocatedCells)?;
         let memory_address_holes = self.get_memory_holes(vm)?;
         if unused_memory_units < memory_address_holes as u32 {
             Err(MemoryError::InsufficientAllocatedCells)?
@@ -1124,20 +1521,250 @@ pub struct ExecutionResources {
     pub builtin_instance_counter: HashMap<String, usize>,
 }
 
+/// A memory segment, along with the address its first cell is relocated to.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct RelocatedSegment {
+    pub index: usize,
+    pub base: usize,
+    pub size: usize,
+    /// "program", "execution", the name of the builtin that owns the segment, or None.
+    pub name: Option<String>,
+}
+
+/// A temporary segment, along with the address it was relocated to, if any.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct TemporarySegmentRelocation {
+    pub index: isize,
+    #[serde(serialize_with = "serialize_destination")]
+    pub destination: Option<Relocatable>,
+}
+
+fn serialize_destination<S: Serializer>(
+    destination: &Option<Relocatable>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    destination
+        .map(|destination| destination.to_string())
+        .serialize(serializer)
+}
+
+/// Maps the segments of a run to the relocated memory, and temporary segments to the segments
+/// they were merged into.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct RelocationTable {
+    /// Ordered by index, and therefore by base.
+    pub segments: Vec<RelocatedSegment>,
+    /// Ordered from -1 downwards.
+    pub temporary_segments: Vec<TemporarySegmentRelocation>,
+}
+
+impl RelocationTable {
+    /// Returns the relocated address of `address`. Addresses in temporary segments are relocated
+    /// through the address their segment was relocated to.
+    pub fn get_relocated_address(&self, address: &Relocatable) -> Option<usize> {
+        let address = if address.segment_index < 0 {
+            let index = (-address.segment_index - 1) as usize;
+            self.temporary_segments.get(index)?.destination? + address.offset
+        } else {
+            *address
+        };
+        let segment = self.segments.get(address.segment_index as usize)?;
+        Some(segment.base + address.offset)
+    }
+
+    /// Returns the segment and offset a relocated address belongs to, if it is within the
+    /// relocated size of a segment.
+    pub fn get_unrelocated_address(&self, relocated_address: usize) -> Option<Relocatable> {
+        // Empty segments share their base with the following one
+        let next_segment = self
+            .segments
+            .partition_point(|segment| segment.base <= relocated_address);
+        let segment = self.segments[..next_segment]
+            .iter()
+            .rev()
+            .find(|segment| segment.size > 0)?;
+        let offset = relocated_address - segment.base;
+        (offset < segment.size).then(|| (segment.index as isize, offset).into())
+    }
+}
+
+fn get_program_reference_list(program: &Program) -> HashMap<usize, HintReference> {
+    let mut references = HashMap::<usize, HintReference>::new();
+
+    for (i, reference) in program.reference_manager.references.iter().enumerate() {
+        references.insert(
+            i,
+            HintReference {
+                offset1: reference.value_address.offset1.clone(),
+                offset2: reference.value_address.offset2.clone(),
+                dereference: reference.value_address.dereference,
+                // only store `ap` tracking data if the reference is referred to it
+                ap_tracking_data: match (
+                    &reference.value_address.offset1,
+                    &reference.value_address.offset2,
+                ) {
+                    (OffsetValue::Reference(Register::AP, _, _), _)
+                    | (_, OffsetValue::Reference(Register::AP, _, _)) => {
+                        Some(reference.ap_tracking_data.clone())
+                    }
+                    _ => None,
+                },
+                cairo_type: Some(reference.value_address.value_type.clone()),
+            },
+        );
+    }
+    references
+}
+
+// The hint data of host hints, which aren't compiled by the hint processor
+struct HostHint;
+
+// Compiles the hints of the program, indexing them by their pc offset plus `pc_offset`
+fn compile_program_hints(
+    program: &Program,
+    references: &HashMap<usize, HintReference>,
+    pc_offset: usize,
+    hint_executor: &mut dyn HintProcessor,
+    host_hints: &HashSet<String>,
+) -> Result<HashMap<usize, Vec<Box<dyn Any>>>, VirtualMachineError> {
+    let mut hint_data_dictionary = HashMap::<usize, Vec<Box<dyn Any>>>::new();
+    for (hint_index, hints) in program.hints.iter() {
+        for hint in hints {
+            // Host hints are executed by the host instead of the hint processor, see run_until
+            let hint_data = if host_hints.contains(&hint.code) {
+                any_box!(HostHint)
+            } else {
+                hint_executor
+                    .compile_hint(
+                        &hint.code,
+                        &hint.flow_tracking_data.ap_tracking,
+                        &hint.flow_tracking_data.reference_ids,
+                        references,
+                    )
+                    .map_err(|error| match error {
+                        error @ VirtualMachineError::UnknownHint(_) => error,
+                        _ => VirtualMachineError::CompileHintFail(hint.code.clone()),
+                    })?
+            };
+            hint_data_dictionary
+                .entry(hint_index + pc_offset)
+                .or_default()
+                .push(hint_data);
+        }
+    }
+    Ok(hint_data_dictionary)
+}
+
+type HintDataDictionary = HashMap<usize, Vec<Box<dyn Any>>>;
+
+// The hint data dictionary and constants of a program
+type ProgramHintData<'a> = (&'a HintDataDictionary, &'a HashMap<String, Felt>);
+
+// Hint data of the programs loaded during the run (see `VirtualMachine::load_program`),
+// indexed by the segment they were loaded into.
+#[derive(Default)]
+struct LoadedProgramsHintData {
+    n_compiled: usize,
+    hint_data: HashMap<isize, (HintDataDictionary, HashMap<String, Felt>)>,
+}
+
+impl LoadedProgramsHintData {
+    // Compiles the hints of the programs loaded since the last call
+    fn update(
+        &mut self,
+        vm: &VirtualMachine,
+        hint_executor: &mut dyn HintProcessor,
+        host_hints: &HashSet<String>,
+    ) -> Result<(), VirtualMachineError> {
+        for (program_base, program) in vm.loaded_programs.iter().skip(self.n_compiled) {
+            let references = get_program_reference_list(program);
+            let hint_data_dictionary = compile_program_hints(
+                program,
+                &references,
+                program_base.offset,
+                hint_executor,
+                host_hints,
+            )?;
+            self.hint_data.insert(
+                program_base.segment_index,
+                (hint_data_dictionary, program.constants.clone()),
+            );
+        }
+        self.n_compiled = vm.loaded_programs.len();
+        Ok(())
+    }
+
+    // Returns the hint data of the loaded program the pc is in, if any. Hints are only compiled
+    // when a program was loaded since the last call, so that steps don't pay for it otherwise.
+    fn get_for_pc(
+        &mut self,
+        vm: &VirtualMachine,
+        hint_executor: &mut dyn HintProcessor,
+        host_hints: &HashSet<String>,
+    ) -> Result<Option<ProgramHintData>, VirtualMachineError> {
+        if vm.loaded_programs.len() > self.n_compiled {
+            self.update(vm, hint_executor, host_hints)?;
+        }
+        if self.hint_data.is_empty() {
+            return Ok(None);
+        }
+        Ok(self.get(vm.run_context.pc.segment_index))
+    }
+
+    fn get(&self, segment_index: isize) -> Option<ProgramHintData> {
+        self.hint_data
+            .get(&segment_index)
+            .map(|(hint_data_dictionary, constants)| (hint_data_dictionary, constants))
+    }
+}
+
+// The hint data used by the run methods: that of the program, and that of the programs
+// loaded during the run
+struct RunHintData {
+    hint_data_dictionary: HintDataDictionary,
+    loaded_programs: LoadedProgramsHintData,
+}
+
+impl RunHintData {
+    // Returns the hint data dictionary and constants to execute the hints at the pc with
+    fn get_for_pc<'a>(
+        &'a mut self,
+        vm: &VirtualMachine,
+        hint_executor: &mut dyn HintProcessor,
+        host_hints: &HashSet<String>,
+        constants: &'a HashMap<String, Felt>,
+    ) -> Result<ProgramHintData<'a>, VirtualMachineError> {
+        Ok(self
+            .loaded_programs
+            .get_for_pc(vm, hint_executor, host_hints)?
+            .unwrap_or((&self.hint_data_dictionary, constants)))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::{
-        hint_processor::builtin_hint_processor::builtin_hint_processor_definition::BuiltinHintProcessor,
+        hint_processor::builtin_hint_processor::{
+            builtin_hint_processor_definition::{BuiltinHintProcessor, HintProcessorData},
+            hint_code,
+        },
         relocatable,
-        serde::deserialize_program::{Identifier, ReferenceManager},
+        serde::deserialize_program::{
+            ApTracking, FlowTrackingData, HintParams, Identifier, ReferenceManager,
+        },
         types::instance_definitions::bitwise_instance_def::BitwiseInstanceDef,
         utils::test_utils::*,
-        vm::{trace::trace_entry::TraceEntry, vm_memory::memory::Memory},
+        vm::{
+            errors::hint_errors::HintError,
+            trace::trace_entry::TraceEntry,
+            vm_memory::{memory::Memory, segment_storage::MemorySegment},
+        },
     };
     use felt::{felt_str, NewFelt};
     use num_traits::One;
     use std::{
+        cell::Cell,
         collections::{HashMap, HashSet},
         path::Path,
     };
@@ -1769,39 +2396,9 @@ mod tests {
     //Integration tests for initialization + execution phase
 
     #[test]
-    /*Program used:
-    func myfunc(a: felt) -> (r: felt):
-        let b = a * 2
-        return(b)
-    end
-
-    func main():
-        let a = 1
-        let b = myfunc(a)
-        return()
-    end
-
-    main = 3
-    data = [5207990763031199744, 2, 2345108766317314046, 5189976364521848832, 1, 1226245742482522112, 3618502788666131213697322783095070105623107215331596699973092056135872020476, 2345108766317314046]
-    */
     fn initialize_and_run_function_call() {
         //Initialization Phase
-        let program = program!(
-            data = vec_data!(
-                (5207990763031199744_i64),
-                (2),
-                (2345108766317314046_i64),
-                (5189976364521848832_i64),
-                (1),
-                (1226245742482522112_i64),
-                ((
-                    "3618502788666131213697322783095070105623107215331596699973092056135872020476",
-                    10
-                )),
-                (2345108766317314046_i64)
-            ),
-            main = Some(3),
-        );
+        let program = function_call_program(HashMap::new());
         let mut hint_processor = BuiltinHintProcessor::new_empty();
         let mut cairo_runner = cairo_runner!(program);
         let mut vm = vm!(true);
@@ -1837,6 +2434,51 @@ mod tests {
         );
     }
 
+    #[test]
+    fn initialize_and_run_function_call_streaming_trace() {
+        let program = function_call_program(HashMap::new());
+        let path = std::env::temp_dir().join("cairo_vm_initialize_and_run_streaming_trace");
+        let mut hint_processor = BuiltinHintProcessor::new_empty();
+        let mut cairo_runner = cairo_runner!(program);
+        let mut vm = vm!(true);
+        assert_eq!(vm.enable_streaming_trace(&path, 2), Ok(()));
+        let end = cairo_runner.initialize(&mut vm).unwrap();
+        assert_eq!(
+            cairo_runner.run_until_pc(end, &mut vm, &mut hint_processor),
+            Ok(())
+        );
+
+        // Only the entries of the last, incomplete chunk are kept in memory
+        assert_eq!(vm.trace.as_ref().map(|trace| trace.len()), Some(1));
+        assert_eq!(vm.get_trace_len(), Some(5));
+        let rc_limits = cairo_runner.get_perm_range_check_limits(&vm);
+
+        let mut streaming_trace = vm.streaming_trace.take().unwrap();
+        streaming_trace
+            .flush(vm.trace.as_mut().unwrap(), &vm.memory)
+            .unwrap();
+        let trace = streaming_trace
+            .read_entries()
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        trace_check!(
+            trace,
+            [
+                ((0, 3), (1, 2), (1, 2)),
+                ((0, 5), (1, 3), (1, 2)),
+                ((0, 0), (1, 5), (1, 5)),
+                ((0, 2), (1, 6), (1, 5)),
+                ((0, 7), (1, 6), (1, 2))
+            ]
+        );
+        // The limits of the streamed entries are the ones of the whole trace
+        assert_eq!(
+            rc_limits,
+            crate::vm::trace::get_perm_range_check_limits(&trace, &vm.memory)
+        );
+    }
+
     #[test]
     /*Program used:
     %builtins range_check
@@ -2605,6 +3247,85 @@ mod tests {
         );
     }
 
+    #[test]
+    fn get_relocation_table() {
+        let program = program!(builtins = vec![String::from("output")],);
+        let mut cairo_runner = cairo_runner!(program);
+        let mut vm = vm!();
+        cairo_runner.initialize_builtins(&mut vm).unwrap();
+        cairo_runner.initialize_segments(&mut vm, None);
+        vm.segments.add(&mut vm.memory);
+        vm.segments.add_temporary_segment(&mut vm.memory);
+        vm.segments.add_temporary_segment(&mut vm.memory);
+        vm.memory = memory![
+            ((0, 0), 1),
+            ((0, 1), 2),
+            ((1, 0), 3),
+            ((1, 1), 4),
+            ((1, 2), 5),
+            ((2, 0), 6),
+            ((3, 0), 7),
+            ((3, 1), 8)
+        ];
+        vm.memory.temp_data = vec![MemorySegment::new(), MemorySegment::new()];
+        vm.memory
+            .add_relocation_rule((-1, 0).into(), (3, 1).into())
+            .unwrap();
+        vm.memory.relocate_memory().unwrap();
+        vm.segments.compute_effective_sizes(&vm.memory);
+
+        let relocation_table = cairo_runner.get_relocation_table(&vm).unwrap();
+        let segment = |index, base, size, name: Option<&str>| RelocatedSegment {
+            index,
+            base,
+            size,
+            name: name.map(String::from),
+        };
+        assert_eq!(
+            relocation_table,
+            RelocationTable {
+                segments: vec![
+                    segment(0, 1, 2, Some("program")),
+                    segment(1, 3, 3, Some("execution")),
+                    segment(2, 6, 1, Some("output")),
+                    segment(3, 7, 2, None),
+                ],
+                temporary_segments: vec![
+                    TemporarySegmentRelocation {
+                        index: -1,
+                        destination: Some((3, 1).into()),
+                    },
+                    TemporarySegmentRelocation {
+                        index: -2,
+                        destination: None,
+                    },
+                ],
+            }
+        );
+
+        assert_eq!(
+            relocation_table.get_relocated_address(&(-1, 0).into()),
+            Some(8)
+        );
+        assert_eq!(
+            relocation_table.get_relocated_address(&(-2, 0).into()),
+            None
+        );
+        assert_eq!(
+            relocation_table.get_unrelocated_address(6),
+            Some((2, 0).into())
+        );
+        assert_eq!(relocation_table.get_unrelocated_address(0), None);
+        assert_eq!(relocation_table.get_unrelocated_address(9), None);
+        assert_eq!(
+            serde_json::to_value(&relocation_table).unwrap()["temporary_segments"],
+            serde_json::json!([
+                {"index": -1, "destination": "3:1"},
+                {"index": -2, "destination": null}
+            ])
+        );
+    }
+
     #[test]
     fn write_output_from_preset_memory() {
         let program = program!["output"];
@@ -2622,6 +3343,171 @@ mod tests {
         assert_eq!(String::from_utf8(stdout), Ok(String::from("1\n2\n")));
     }
 
+    #[test]
+    fn initialize_main_scope_and_program_input() {
+        let program = program!();
+        let mut cairo_runner = cairo_runner!(program);
+        cairo_runner.exec_scopes.enter_scope(HashMap::new());
+        cairo_runner
+            .initialize_main_scope(HashMap::from([(String::from("a"), any_box!(Felt::new(3)))]));
+        cairo_runner.set_program_input(serde_json::json!({ "n": 7 }));
+
+        assert_eq!(
+            cairo_runner.exec_scopes.get_program_input(),
+            Ok(&serde_json::json!({ "n": 7 }))
+        );
+        cairo_runner.exec_scopes.exit_scope().unwrap();
+        assert_eq!(cairo_runner.exec_scopes.get::<Felt>("a"), Ok(Felt::new(3)));
+    }
+
+    #[test]
+    fn set_dict_manager_used_by_dict_hints() {
+        let program = program!();
+        let mut cairo_runner = cairo_runner!(program);
+        let mut vm = vm!();
+        let mut dict_manager = DictManager::new();
+        dict_manager
+            .new_dict(
+                &mut vm,
+                HashMap::from([(MaybeRelocatable::from(1), MaybeRelocatable::from(4))]),
+            )
+            .unwrap();
+        cairo_runner.set_dict_manager(dict_manager);
+
+        let dict_manager = cairo_runner.exec_scopes.get_dict_manager().unwrap();
+        assert_eq!(
+            dict_manager
+                .borrow_mut()
+                .get_tracker_mut(&relocatable!(0, 0))
+                .unwrap()
+                .get_value(&MaybeRelocatable::from(1)),
+            Ok(&MaybeRelocatable::from(4))
+        );
+    }
+
+    #[test]
+    fn add_initial_dict_used_by_dict_new() {
+        let program = program!();
+        let mut cairo_runner = cairo_runner!(program);
+        let mut vm = vm!();
+        cairo_runner.add_initial_dict(vec![(
+            MaybeRelocatable::from(1),
+            vec![MaybeRelocatable::from(2), MaybeRelocatable::from(3)].into(),
+        )]);
+        cairo_runner.add_initial_dict(vec![]);
+        add_segments!(vm, 2);
+
+        let mut hint_processor = BuiltinHintProcessor::new_empty();
+        let hint_data = any_box!(HintProcessorData::new_default(
+            hint_code::DICT_NEW.to_string(),
+            HashMap::new()
+        ));
+        for _ in 0..2 {
+            hint_processor
+                .execute_hint(
+                    &mut vm,
+                    &mut cairo_runner.exec_scopes,
+                    &hint_data,
+                    &HashMap::new(),
+                )
+                .unwrap();
+            vm.set_ap(vm.get_ap().offset + 1);
+        }
+
+        // The dict segments are followed by the segments of their values
+        check_memory![
+            vm.memory,
+            ((1, 0), (2, 0)),
+            ((1, 1), (4, 0)),
+            ((3, 0), 2),
+            ((3, 1), 3)
+        ];
+        assert_eq!(
+            cairo_runner.get_dicts(),
+            vec![
+                DictSnapshot {
+                    segment_index: 2,
+                    data: vec![(MaybeRelocatable::from(1), MaybeRelocatable::from((3, 0)))],
+                    default_value: None,
+                    n_accesses: 0,
+                },
+                DictSnapshot {
+                    segment_index: 4,
+                    data: vec![],
+                    default_value: None,
+                    n_accesses: 0,
+                },
+            ]
+        );
+        assert_eq!(
+            hint_processor.execute_hint(
+                &mut vm,
+                &mut cairo_runner.exec_scopes,
+                &hint_data,
+                &HashMap::new(),
+            ),
+            Err(HintError::NoInitialDict)
+        );
+    }
+
+    #[test]
+    fn get_dicts_no_dict_manager() {
+        let program = program!();
+        let cairo_runner = cairo_runner!(program);
+        assert_eq!(cairo_runner.get_dicts(), Vec::new());
+    }
+
+    #[test]
+    fn get_and_write_dicts() {
+        let program = program!();
+        let mut cairo_runner = cairo_runner!(program);
+        let mut vm = vm!();
+        let mut dict_manager = DictManager::new();
+        dict_manager
+            .new_dict(
+                &mut vm,
+                HashMap::from([(MaybeRelocatable::from(2), MaybeRelocatable::from(5))]),
+            )
+            .unwrap();
+        dict_manager
+            .new_default_dict(&mut vm, &MaybeRelocatable::from(0), None)
+            .unwrap();
+        dict_manager
+            .trackers
+            .get_mut(&0)
+            .unwrap()
+            .current_ptr
+            .offset = 3;
+        cairo_runner.set_dict_manager(dict_manager);
+
+        assert_eq!(
+            cairo_runner.get_dicts(),
+            vec![
+                DictSnapshot {
+                    segment_index: 0,
+                    data: vec![(MaybeRelocatable::from(2), MaybeRelocatable::from(5))],
+                    default_value: None,
+                    n_accesses: 1,
+                },
+                DictSnapshot {
+                    segment_index: 1,
+                    data: Vec::new(),
+                    default_value: Some(MaybeRelocatable::from(0)),
+                    n_accesses: 0,
+                }
+            ]
+        );
+
+        let mut stdout = Vec::<u8>::new();
+        cairo_runner.write_dicts(&mut stdout).unwrap();
+        assert_eq!(
+            String::from_utf8(stdout),
+            Ok(String::from(
+                "Dict at segment 0 (accesses: 1):\n  2: 5\nDict at segment 1 (default value: 0, accesses: 0):\n"
+            ))
+        );
+    }
+
     #[test]
     /*Program used:
     %builtins output
@@ -3103,6 +3989,26 @@ mod tests {
         assert_eq!(cairo_runner.get_memory_holes(&vm), Ok(0));
     }
 
+    #[test]
+    fn get_memory_holes_builtin_segment_not_finalized() {
+        let program = program!();
+
+        let cairo_runner = cairo_runner!(program);
+        let mut vm = vm!();
+
+        vm.builtin_runners = vec![{
+            let mut builtin_runner: BuiltinRunner = OutputBuiltinRunner::new(true).into();
+            builtin_runner.initialize_segments(&mut vm.segments, &mut vm.memory);
+
+            ("output".to_string(), builtin_runner)
+        }];
+        vm.segments.segment_used_sizes = Some(Vec::new());
+        assert_eq!(
+            cairo_runner.get_memory_holes(&vm),
+            Err(MemoryError::SegmentNotFinalized(0)),
+        );
+    }
+
     #[test]
     fn get_memory_holes() {
         let program = program!();
@@ -3635,7 +4541,8 @@ mod tests {
             Some(Felt::new(0x80FF_8000_0530u64).into()),
             Some(Felt::new(0xBFFF_8000_0620u64).into()),
             Some(Felt::new(0x8FFF_8000_0750u64).into()),
-        ]];
+        ]
+        .into()];
 
         assert_eq!(
             cairo_runner.get_perm_range_check_limits(&vm),
@@ -3657,7 +4564,7 @@ mod tests {
             ap: (0, 0).into(),
             fp: (0, 0).into(),
         }]);
-        vm.memory.data = vec![vec![mayberelocatable!(0x80FF_8000_0530u64).into()]];
+        vm.memory.data = vec![vec![Some(mayberelocatable!(0x80FF_8000_0530u64))].into()];
         vm.builtin_runners = vec![(
             "range_check".to_string(),
             RangeCheckBuiltinRunner::new(12, 5, true).into(),
@@ -3692,7 +4599,7 @@ mod tests {
         let mut vm = vm!();
         vm.builtin_runners = vec![];
         vm.current_step = 10000;
-        vm.memory.data = vec![vec![Some(mayberelocatable!(0x80FF_8000_0530u64))]];
+        vm.memory.data = vec![vec![Some(mayberelocatable!(0x80FF_8000_0530u64))].into()];
         vm.trace = Some(vec![TraceEntry {
             pc: (0, 0).into(),
             ap: (0, 0).into(),
@@ -3714,7 +4621,7 @@ mod tests {
             "range_check".to_string(),
             RangeCheckBuiltinRunner::new(8, 8, true).into(),
         )];
-        vm.memory.data = vec![vec![Some(mayberelocatable!(0x80FF_8000_0530u64))]];
+        vm.memory.data = vec![vec![Some(mayberelocatable!(0x80FF_8000_0530u64))].into()];
         vm.trace = Some(vec![TraceEntry {
             pc: (0, 0).into(),
             ap: (0, 0).into(),
@@ -3776,7 +4683,7 @@ mod tests {
             "range_check".to_string(),
             RangeCheckBuiltinRunner::new(8, 8, true).into(),
         )];
-        vm.memory.data = vec![vec![Some(mayberelocatable!(0x80FF_8000_0530u64))]];
+        vm.memory.data = vec![vec![Some(mayberelocatable!(0x80FF_8000_0530u64))].into()];
         vm.trace = Some(vec![TraceEntry {
             pc: (0, 0).into(),
             ap: (0, 0).into(),
@@ -4267,4 +5174,274 @@ mod tests {
             Ok(()),
         );
     }
+
+    #[test]
+    fn run_until_condition() {
+        let program = function_call_program(HashMap::new());
+        let mut hint_processor = BuiltinHintProcessor::new_empty();
+        let mut cairo_runner = cairo_runner!(program);
+        let mut vm = vm!();
+        let end = cairo_runner.initialize(&mut vm).unwrap();
+
+        assert_eq!(
+            cairo_runner.run_until(&mut vm, &mut hint_processor, |vm| vm.current_step == 2),
+            Ok(RunStatus::Paused(PauseReason::Condition))
+        );
+        assert_eq!(vm.run_context.pc, (0, 0).into());
+        // The condition is only checked after a step
+        assert_eq!(
+            cairo_runner.run_until(&mut vm, &mut hint_processor, |vm| vm.current_step >= 2),
+            Ok(RunStatus::Paused(PauseReason::Condition))
+        );
+        assert_eq!(vm.current_step, 3);
+        assert_eq!(
+            cairo_runner.run_until(&mut vm, &mut hint_processor, |_| false),
+            Ok(RunStatus::Finished)
+        );
+        assert_eq!(vm.run_context.pc, end);
+        assert_eq!(vm.current_step, 5);
+    }
+
+    #[test]
+    fn run_until_breakpoint() {
+        let program = function_call_program(HashMap::new());
+        let mut hint_processor = BuiltinHintProcessor::new_empty();
+        let mut cairo_runner = cairo_runner!(program);
+        let mut vm = vm!();
+        let end = cairo_runner.initialize(&mut vm).unwrap();
+        cairo_runner.add_breakpoint((0, 0).into());
+        cairo_runner.add_breakpoint((0, 7).into());
+
+        assert_eq!(
+            cairo_runner.run_until(&mut vm, &mut hint_processor, |_| false),
+            Ok(RunStatus::BreakpointHit((0, 0).into()))
+        );
+        assert_eq!(vm.current_step, 2);
+        // Resuming executes the instruction at the breakpoint
+        assert!(cairo_runner.remove_breakpoint(&(0, 7).into()));
+        assert!(!cairo_runner.remove_breakpoint(&(0, 7).into()));
+        assert_eq!(
+            cairo_runner.run_until(&mut vm, &mut hint_processor, |_| false),
+            Ok(RunStatus::Finished)
+        );
+        assert_eq!(vm.run_context.pc, end);
+        assert_eq!(vm.current_step, 5);
+    }
+
+    #[test]
+    fn run_until_host_hint() {
+        let hint = |code: &str| HintParams {
+            code: code.to_string(),
+            accessible_scopes: Vec::new(),
+            flow_tracking_data: FlowTrackingData {
+                ap_tracking: ApTracking::new(),
+                reference_ids: HashMap::new(),
+            },
+        };
+        let program = function_call_program(HashMap::from([(
+            0,
+            vec![
+                hint("vm_enter_scope()"),
+                hint("host_hint"),
+                hint("vm_enter_scope()"),
+                hint("host_hint"),
+            ],
+        )]));
+        let mut hint_processor = BuiltinHintProcessor::new_empty();
+        let mut cairo_runner = cairo_runner!(program);
+        cairo_runner.add_host_hint("host_hint");
+        cairo_runner.add_breakpoint((0, 0).into());
+        let mut vm = vm!();
+        cairo_runner.initialize(&mut vm).unwrap();
+
+        assert_eq!(
+            cairo_runner.run_until(&mut vm, &mut hint_processor, |_| false),
+            Ok(RunStatus::BreakpointHit((0, 0).into()))
+        );
+        // The hints before the requested one were executed
+        assert_eq!(
+            cairo_runner.run_until(&mut vm, &mut hint_processor, |_| false),
+            Ok(RunStatus::HintRequested("host_hint".to_string()))
+        );
+        assert_eq!(cairo_runner.exec_scopes.data.len(), 2);
+        assert_eq!(
+            cairo_runner.run_until(&mut vm, &mut hint_processor, |_| false),
+            Ok(RunStatus::HintRequested("host_hint".to_string()))
+        );
+        assert_eq!(cairo_runner.exec_scopes.data.len(), 3);
+        assert_eq!(vm.current_step, 2);
+        assert_eq!(
+            cairo_runner.run_until(&mut vm, &mut hint_processor, |_| false),
+            Ok(RunStatus::Finished)
+        );
+        assert_eq!(cairo_runner.exec_scopes.data.len(), 3);
+        assert_eq!(vm.current_step, 5);
+    }
+
+    #[test]
+    fn run_until_compiles_hints_once() {
+        // Counts the hints compiled by the BuiltinHintProcessor
+        struct CountingHintProcessor {
+            hint_processor: BuiltinHintProcessor,
+            compiled_hints: Cell<usize>,
+        }
+
+        impl HintProcessor for CountingHintProcessor {
+            fn execute_hint(
+                &mut self,
+                vm: &mut VirtualMachine,
+                exec_scopes: &mut ExecutionScopes,
+                hint_data: &Box<dyn Any>,
+                constants: &HashMap<String, Felt>,
+            ) -> Result<(), HintError> {
+                self.hint_processor
+                    .execute_hint(vm, exec_scopes, hint_data, constants)
+            }
+
+            fn compile_hint(
+                &self,
+                hint_code: &str,
+                ap_tracking_data: &ApTracking,
+                reference_ids: &HashMap<String, usize>,
+                references: &HashMap<usize, HintReference>,
+            ) -> Result<Box<dyn Any>, VirtualMachineError> {
+                self.compiled_hints.set(self.compiled_hints.get() + 1);
+                self.hint_processor.compile_hint(
+                    hint_code,
+                    ap_tracking_data,
+                    reference_ids,
+                    references,
+                )
+            }
+        }
+
+        let hint = |code: &str| HintParams {
+            code: code.to_string(),
+            accessible_scopes: Vec::new(),
+            flow_tracking_data: FlowTrackingData {
+                ap_tracking: ApTracking::new(),
+                reference_ids: HashMap::new(),
+            },
+        };
+        let program = function_call_program(HashMap::from([
+            (0, vec![hint("vm_enter_scope()"), hint("host_hint")]),
+            (7, vec![hint("vm_exit_scope()")]),
+        ]));
+        let mut hint_processor = CountingHintProcessor {
+            hint_processor: BuiltinHintProcessor::new_empty(),
+            compiled_hints: Cell::new(0),
+        };
+        let mut cairo_runner = cairo_runner!(program);
+        cairo_runner.add_host_hint("host_hint");
+        cairo_runner.add_breakpoint((0, 7).into());
+        let mut vm = vm!();
+        cairo_runner.initialize(&mut vm).unwrap();
+
+        assert_eq!(
+            cairo_runner.run_until(&mut vm, &mut hint_processor, |vm| vm.current_step == 1),
+            Ok(RunStatus::Paused(PauseReason::Condition))
+        );
+        assert_eq!(hint_processor.compiled_hints.get(), 2);
+        assert_eq!(
+            cairo_runner.run_until(&mut vm, &mut hint_processor, |_| false),
+            Ok(RunStatus::HintRequested("host_hint".to_string()))
+        );
+        assert_eq!(
+            cairo_runner.run_until(&mut vm, &mut hint_processor, |_| false),
+            Ok(RunStatus::BreakpointHit((0, 7).into()))
+        );
+        assert_eq!(hint_processor.compiled_hints.get(), 2);
+
+        // Adding a host hint compiles the other hints again
+        cairo_runner.add_host_hint("vm_exit_scope()");
+        assert_eq!(
+            cairo_runner.run_until(&mut vm, &mut hint_processor, |_| false),
+            Ok(RunStatus::HintRequested("vm_exit_scope()".to_string()))
+        );
+        assert_eq!(hint_processor.compiled_hints.get(), 3);
+        assert_eq!(
+            cairo_runner.run_until(&mut vm, &mut hint_processor, |_| false),
+            Ok(RunStatus::Finished)
+        );
+        assert_eq!(cairo_runner.exec_scopes.data.len(), 2);
+    }
+
+    #[test]
+    fn get_hint_data_dictionary_unknown_hint() {
+        let hint = HintParams {
+            code: "random_invalid_code".to_string(),
+            accessible_scopes: Vec::new(),
+            flow_tracking_data: FlowTrackingData {
+                ap_tracking: ApTracking::new(),
+                reference_ids: HashMap::new(),
+            },
+        };
+        let program = program!(hints = HashMap::from([(1, vec![hint])]),);
+        let cairo_runner = cairo_runner!(program);
+        let mut hint_processor = BuiltinHintProcessor::new_empty();
+        assert_eq!(
+            cairo_runner
+                .get_hint_data_dictionary(&HashMap::new(), &mut hint_processor)
+                .err(),
+            Some(VirtualMachineError::UnknownHint(
+                "random_invalid_code".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn get_hint_data_dictionary_missing_reference() {
+        let hint = HintParams {
+            code: "memory[ap] = segments.add()".to_string(),
+            accessible_scopes: Vec::new(),
+            flow_tracking_data: FlowTrackingData {
+                ap_tracking: ApTracking::new(),
+                reference_ids: HashMap::from([(String::from("__main__.main.x"), 0)]),
+            },
+        };
+        let program = program!(hints = HashMap::from([(1, vec![hint])]),);
+        let cairo_runner = cairo_runner!(program);
+        let mut hint_processor = BuiltinHintProcessor::new_empty();
+        assert_eq!(
+            cairo_runner
+                .get_hint_data_dictionary(&HashMap::new(), &mut hint_processor)
+                .err(),
+            Some(VirtualMachineError::CompileHintFail(
+                "memory[ap] = segments.add()".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn loaded_programs_hint_data_indexed_by_segment() {
+        let hint = HintParams {
+            code: "memory[ap] = segments.add()".to_string(),
+            accessible_scopes: Vec::new(),
+            flow_tracking_data: FlowTrackingData {
+                ap_tracking: ApTracking::new(),
+                reference_ids: HashMap::new(),
+            },
+        };
+        let program = program!(hints = HashMap::from([(1, vec![hint])]),);
+        let mut vm = vm!();
+        vm.load_program(program, (3, 4).into());
+        let mut hint_processor = BuiltinHintProcessor::new_empty();
+
+        let mut loaded_programs = LoadedProgramsHintData::default();
+        assert!(loaded_programs
+            .get_for_pc(&vm, &mut hint_processor, &HashSet::new())
+            .unwrap()
+            .is_none());
+        assert_eq!(loaded_programs.n_compiled, 1);
+
+        vm.run_context.pc = (3, 5).into();
+        let (hint_data_dictionary, _) = loaded_programs
+            .get_for_pc(&vm, &mut hint_processor, &HashSet::new())
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            hint_data_dictionary.keys().collect::<Vec<&usize>>(),
+            vec![&5]
+        );
+    }
 }
@@ -1,2 +1,3 @@
 pub mod builtin_runner;
 pub mod cairo_runner;
+pub mod proof_mode;
@@ -0,0 +1,468 @@
+use crate::{
+    hint_processor::hint_processor_definition::HintProcessor,
+    types::{
+        instruction::{Instruction, Op1Addr, Opcode, PcUpdate, Res},
+        relocatable::MaybeRelocatable,
+    },
+    vm::{
+        decoding::decoder::decode_instruction,
+        errors::{
+            memory_errors::MemoryError, runner_errors::RunnerError, vm_errors::VirtualMachineError,
+        },
+        runners::{builtin_runner::BuiltinRunner, cairo_runner::CairoRunner},
+        vm_core::VirtualMachine,
+    },
+};
+use num_traits::{ToPrimitive, Zero};
+use std::fmt;
+
+// Programs compiled with --proof_mode end with an infinite loop (`jmp rel 0`) at the __end__
+// label. The run is padded by executing it until the amount of steps is a power of two for which
+// every component of the layout was allocated enough cells, as checked by check_used_cells.
+// The usages computed here estimate that amount of steps, and report the components that don't
+// fit.
+
+/// The largest amount of steps a proof mode run is padded to.
+pub const MAX_PROOF_MODE_STEPS: usize = 1 << 30;
+
+/// The usage of a component of the layout (a builtin, or the range check, memory or diluted
+/// units) in a proof mode run of a given amount of steps.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ComponentUsage {
+    pub component: String,
+    pub used: usize,
+    pub available: usize,
+}
+
+impl ComponentUsage {
+    pub fn fits(&self) -> bool {
+        self.used <= self.available
+    }
+}
+
+impl fmt::Display for ComponentUsage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} ({} used, {} available)",
+            self.component, self.used, self.available
+        )
+    }
+}
+
+// The usages of a run that don't depend on its amount of steps
+struct RunUsage {
+    current_step: usize,
+    builtins: Vec<BuiltinUsage>,
+    // (rc_min, rc_max), if the trace used any offset
+    rc_limits: Option<(isize, isize)>,
+    // The offsets used by the end loop, which only count if the run is padded
+    end_loop_rc_limits: Option<(isize, isize)>,
+    rc_units_used_by_builtins: usize,
+    memory_holes: usize,
+}
+
+struct BuiltinUsage {
+    name: String,
+    used_cells: usize,
+    // See BuiltinRunner::get_allocation_params
+    allocation_params: Option<(usize, usize, usize)>,
+    diluted_units: usize,
+}
+
+impl CairoRunner {
+    /// Checks that the program can be run in proof mode: it has __start__ and __end__ labels, and
+    /// the instruction at __end__ is the `jmp rel 0` loop used to pad the run.
+    pub fn validate_proof_mode_program(&self) -> Result<(), RunnerError> {
+        self.program.start.ok_or(RunnerError::NoProgramStart)?;
+        let end = self.program.end.ok_or(RunnerError::NoProgramEnd)?;
+        match self.get_end_loop() {
+            Some(instruction) if is_jmp_rel_0(&instruction) => Ok(()),
+            _ => Err(RunnerError::NoProgramEndLoop(end)),
+        }
+    }
+
+    /// Returns the usage of each component of the layout if the run was padded to `steps` steps.
+    /// Padding only executes the loop at the __end__ label, so the usages are computed from the
+    /// current state of the run, whose segment sizes should be computed.
+    /// `steps` should be at least `ratio * instances_per_component` for every builtin.
+    pub fn get_proof_mode_usages(
+        &self,
+        vm: &VirtualMachine,
+        steps: usize,
+    ) -> Result<Vec<ComponentUsage>, VirtualMachineError> {
+        Ok(self.get_run_usage(vm)?.at_steps(self, steps))
+    }
+
+    /// Returns the smallest power of two, no smaller than the current step, to which the run can
+    /// be padded so that every component of the layout fits in its allocated cells.
+    /// Fails with a report of the components that don't fit if there is none up to
+    /// MAX_PROOF_MODE_STEPS.
+    pub fn get_proof_mode_steps(&self, vm: &VirtualMachine) -> Result<usize, VirtualMachineError> {
+        let run_usage = self.get_run_usage(vm)?;
+        // A builtin is allocated its cells by components of instances_per_component instances
+        let min_steps = vm
+            .builtin_runners
+            .iter()
+            .filter_map(|(_, builtin)| builtin.get_allocation_params())
+            .map(|(ratio, _, instances_per_component)| ratio * instances_per_component)
+            .fold(vm.current_step, usize::max);
+
+        let mut steps = min_steps.next_power_of_two();
+        loop {
+            let usages = run_usage.at_steps(self, steps);
+            if usages.iter().all(ComponentUsage::fits) {
+                return Ok(steps);
+            }
+            if steps >= MAX_PROOF_MODE_STEPS {
+                return Err(RunnerError::InsufficientAllocatedCells(
+                    steps,
+                    usages.into_iter().filter(|usage| !usage.fits()).collect(),
+                )
+                .into());
+            }
+            steps *= 2;
+        }
+    }
+
+    /// Pads the run by executing the loop at the __end__ label until `check_used_cells` passes,
+    /// doubling its steps each time. Padding starts at `get_proof_mode_steps`, so that a run
+    /// usually only needs to be checked once.
+    /// The run should have reached the __end__ label, and its segment sizes should be computed.
+    pub fn pad_proof_mode_run(
+        &mut self,
+        vm: &mut VirtualMachine,
+        hint_processor: &mut dyn HintProcessor,
+    ) -> Result<(), VirtualMachineError> {
+        let steps = match self.get_proof_mode_steps(vm) {
+            Ok(steps) => steps,
+            // The estimate doesn't decide whether the run fits, check_used_cells does
+            Err(VirtualMachineError::RunnerError(RunnerError::InsufficientAllocatedCells(..))) => {
+                vm.current_step.next_power_of_two()
+            }
+            Err(error) => return Err(error),
+        };
+        self.run_until_steps(steps, vm, hint_processor)?;
+        loop {
+            match self.check_used_cells(vm) {
+                Err(VirtualMachineError::MemoryError(MemoryError::InsufficientAllocatedCells)) => {}
+                result => return result,
+            }
+            if vm.current_step >= MAX_PROOF_MODE_STEPS {
+                return Err(self.insufficient_allocated_cells_error(vm));
+            }
+            self.run_for_steps(1, vm, hint_processor)?;
+            self.run_until_next_power_of_2(vm, hint_processor)?;
+        }
+    }
+
+    // Reports the components that don't fit in a run of the current step
+    fn insufficient_allocated_cells_error(&self, vm: &VirtualMachine) -> VirtualMachineError {
+        let overflowed: Vec<_> = match self.get_proof_mode_usages(vm, vm.current_step) {
+            Ok(usages) => usages.into_iter().filter(|usage| !usage.fits()).collect(),
+            Err(error) => return error,
+        };
+        if overflowed.is_empty() {
+            return MemoryError::InsufficientAllocatedCells.into();
+        }
+        RunnerError::InsufficientAllocatedCells(vm.current_step, overflowed).into()
+    }
+
+    // Decodes the instruction at the __end__ label
+    fn get_end_loop(&self) -> Option<Instruction> {
+        let end = self.program.end?;
+        let encoding = match self.program.data.get(end)? {
+            MaybeRelocatable::Int(encoding) => encoding.to_i64()?,
+            _ => return None,
+        };
+        let imm = match self.program.data.get(end + 1) {
+            Some(MaybeRelocatable::Int(imm)) => Some(imm),
+            _ => None,
+        };
+        decode_instruction(encoding, imm).ok()
+    }
+
+    fn get_run_usage(&self, vm: &VirtualMachine) -> Result<RunUsage, VirtualMachineError> {
+        let diluted_pool_instance = self.layout.diluted_pool_instance_def.as_ref();
+        let mut builtins = Vec::new();
+        let mut rc_units_used_by_builtins = 0;
+        for (name, builtin) in &vm.builtin_runners {
+            let used_cells = builtin.get_used_cells(vm)?;
+            if let BuiltinRunner::RangeCheck(range_check) = builtin {
+                rc_units_used_by_builtins += used_cells * range_check.n_parts as usize;
+            }
+            builtins.push(BuiltinUsage {
+                name: name.clone(),
+                used_cells,
+                allocation_params: builtin.get_allocation_params(),
+                diluted_units: diluted_pool_instance.map_or(0, |diluted_pool_instance| {
+                    builtin.get_used_diluted_check_units(
+                        diluted_pool_instance.spacing,
+                        diluted_pool_instance.n_bits,
+                    )
+                }),
+            });
+        }
+
+        let rc_limits = match vm.trace {
+            Some(_) => self.get_perm_range_check_limits(vm)?,
+            None => None,
+        };
+        let end_loop_rc_limits = self.get_end_loop().map(|instruction| {
+            let offsets = [instruction.off0, instruction.off1, instruction.off2];
+            (
+                offsets.iter().copied().min().unwrap_or_default(),
+                offsets.iter().copied().max().unwrap_or_default(),
+            )
+        });
+
+        Ok(RunUsage {
+            current_step: vm.current_step,
+            builtins,
+            rc_limits,
+            end_loop_rc_limits,
+            rc_units_used_by_builtins,
+            memory_holes: self.get_memory_holes(vm)?,
+        })
+    }
+}
+
+impl RunUsage {
+    // Estimates the checks of CairoRunner::check_used_cells
+    fn at_steps(&self, runner: &CairoRunner, steps: usize) -> Vec<ComponentUsage> {
+        let layout = &runner.layout;
+        let mut usages = Vec::new();
+
+        let mut builtins_memory_units = 0;
+        let mut diluted_units_used_by_builtins = 0;
+        for builtin in &self.builtins {
+            let ratio = match builtin.allocation_params {
+                Some((ratio, cells_per_instance, _)) => {
+                    let allocated_cells = cells_per_instance * (steps / ratio);
+                    builtins_memory_units += allocated_cells;
+                    usages.push(ComponentUsage {
+                        component: builtin.name.clone(),
+                        used: builtin.used_cells,
+                        available: allocated_cells,
+                    });
+                    ratio
+                }
+                None => 1,
+            };
+            diluted_units_used_by_builtins += builtin.diluted_units * (steps / ratio);
+        }
+
+        if let Some((rc_min, rc_max)) = self.rc_limits {
+            // The end loop's offsets are only used if the run is padded
+            let (rc_min, rc_max) = match self.end_loop_rc_limits {
+                Some((loop_min, loop_max)) if steps > self.current_step => {
+                    (rc_min.min(loop_min), rc_max.max(loop_max))
+                }
+                _ => (rc_min, rc_max),
+            };
+            usages.push(ComponentUsage {
+                component: "range check units".to_string(),
+                used: (rc_max - rc_min) as usize + self.rc_units_used_by_builtins,
+                available: (layout.rc_units as usize).saturating_sub(3) * steps,
+            });
+        }
+
+        // Out of the memory units available per step, a fraction is used for public memory, and
+        // four are used for the instruction.
+        let total_memory_units = layout._memory_units_per_step as usize * steps;
+        usages.push(ComponentUsage {
+            component: "memory units".to_string(),
+            used: total_memory_units / layout._public_memory_fraction as usize
+                + 4 * steps
+                + builtins_memory_units
+                + self.memory_holes,
+            available: total_memory_units,
+        });
+
+        if let Some(diluted_pool_instance) = &layout.diluted_pool_instance_def {
+            usages.push(ComponentUsage {
+                component: "diluted units".to_string(),
+                used: diluted_units_used_by_builtins + (1 << diluted_pool_instance.n_bits),
+                available: diluted_pool_instance.units_per_step as usize * steps,
+            });
+        }
+
+        usages
+    }
+}
+
+fn is_jmp_rel_0(instruction: &Instruction) -> bool {
+    instruction.opcode == Opcode::NOp
+        && instruction.pc_update == PcUpdate::JumpRel
+        && instruction.res == Res::Op1
+        && instruction.op1_addr == Op1Addr::Imm
+        && instruction.imm.as_ref().map_or(false, Zero::is_zero)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        hint_processor::builtin_hint_processor::builtin_hint_processor_definition::BuiltinHintProcessor,
+        types::program::Program, utils::test_utils::*,
+    };
+    use felt::{Felt, NewFelt};
+
+    // jmp rel 0
+    const JMP_REL_0: i64 = 0x010780017fff7fff;
+    // ret
+    const RET: i64 = 0x208b7fff7fff7ffe;
+
+    // A program whose __start__ is its __end__ loop
+    fn end_loop_program() -> Program {
+        program!(
+            data = vec_data!((JMP_REL_0), (0)),
+            start = Some(0),
+            end = Some(0),
+        )
+    }
+
+    #[test]
+    fn validate_proof_mode_program() {
+        let cairo_runner = cairo_runner!(end_loop_program(), "plain", true);
+        assert_eq!(cairo_runner.validate_proof_mode_program(), Ok(()));
+    }
+
+    #[test]
+    fn validate_proof_mode_program_missing_labels() {
+        let program = program!(data = vec_data!((JMP_REL_0), (0)), end = Some(0),);
+        let cairo_runner = cairo_runner!(program, "plain", true);
+        assert_eq!(
+            cairo_runner.validate_proof_mode_program(),
+            Err(RunnerError::NoProgramStart)
+        );
+
+        let program = program!(data = vec_data!((JMP_REL_0), (0)), start = Some(0),);
+        let cairo_runner = cairo_runner!(program, "plain", true);
+        assert_eq!(
+            cairo_runner.validate_proof_mode_program(),
+            Err(RunnerError::NoProgramEnd)
+        );
+    }
+
+    #[test]
+    fn validate_proof_mode_program_missing_end_loop() {
+        let program = program!(
+            data = vec_data!((RET), (JMP_REL_0), (1)),
+            start = Some(0),
+            end = Some(0),
+        );
+        let cairo_runner = cairo_runner!(program, "plain", true);
+        assert_eq!(
+            cairo_runner.validate_proof_mode_program(),
+            Err(RunnerError::NoProgramEndLoop(0))
+        );
+
+        // jmp rel 1
+        let program = program!(
+            data = vec_data!((JMP_REL_0), (1)),
+            start = Some(0),
+            end = Some(0),
+        );
+        let cairo_runner = cairo_runner!(program, "plain", true);
+        assert_eq!(
+            cairo_runner.validate_proof_mode_program(),
+            Err(RunnerError::NoProgramEndLoop(0))
+        );
+
+        let program = program!(start = Some(0), end = Some(0),);
+        let cairo_runner = cairo_runner!(program, "plain", true);
+        assert_eq!(
+            cairo_runner.validate_proof_mode_program(),
+            Err(RunnerError::NoProgramEndLoop(0))
+        );
+    }
+
+    #[test]
+    fn end_run_pads_to_builtin_ratios() {
+        let mut hint_processor = BuiltinHintProcessor::new_empty();
+        let mut cairo_runner = cairo_runner!(end_loop_program(), "all", true);
+        let mut vm = vm!(true);
+        let end = cairo_runner.initialize(&mut vm).unwrap();
+        cairo_runner
+            .run_until_pc(end, &mut vm, &mut hint_processor)
+            .unwrap();
+        assert_eq!(vm.current_step, 0);
+
+        cairo_runner
+            .end_run(false, false, &mut vm, &mut hint_processor)
+            .unwrap();
+        // The diluted pool of the "all" layout needs 2^16 units besides the ones used by the
+        // bitwise builtin, and 16 are allocated per step
+        assert_eq!(vm.current_step, 1 << 13);
+        assert_eq!(vm.run_context.pc, end);
+    }
+
+    #[test]
+    fn get_proof_mode_steps_covers_memory_holes() {
+        let mut hint_processor = BuiltinHintProcessor::new_empty();
+        let mut cairo_runner = cairo_runner!(end_loop_program(), "plain", true);
+        let mut vm = vm!(true);
+        let end = cairo_runner.initialize(&mut vm).unwrap();
+        cairo_runner
+            .run_until_pc(end, &mut vm, &mut hint_processor)
+            .unwrap();
+        // Only the segments with accessed cells are checked for holes
+        cairo_runner
+            .run_for_steps(1, &mut vm, &mut hint_processor)
+            .unwrap();
+        // Cells of the execution segment that are never accessed are memory holes
+        for offset in 2..102 {
+            vm.insert_value(&(1, offset).into(), Felt::new(offset))
+                .unwrap();
+        }
+        vm.segments.compute_effective_sizes(&vm.memory);
+
+        assert_eq!(
+            cairo_runner.get_proof_mode_usages(&vm, 32),
+            Ok(vec![
+                ComponentUsage {
+                    component: "range check units".to_string(),
+                    used: 2,
+                    available: 13 * 32,
+                },
+                ComponentUsage {
+                    component: "memory units".to_string(),
+                    // Public memory, instructions and holes (all but fp - 1)
+                    used: 2 * 32 + 4 * 32 + 101,
+                    available: 8 * 32,
+                }
+            ])
+        );
+        assert_eq!(cairo_runner.get_proof_mode_steps(&vm), Ok(64));
+
+        cairo_runner
+            .end_run(false, false, &mut vm, &mut hint_processor)
+            .unwrap();
+        assert_eq!(vm.current_step, 64);
+    }
+
+    #[test]
+    fn insufficient_allocated_cells_report() {
+        let error = RunnerError::InsufficientAllocatedCells(
+            32,
+            vec![
+                ComponentUsage {
+                    component: "memory units".to_string(),
+                    used: 300,
+                    available: 256,
+                },
+                ComponentUsage {
+                    component: "range_check".to_string(),
+                    used: 5,
+                    available: 4,
+                },
+            ],
+        );
+        assert_eq!(
+            error.to_string(),
+            "Not enough allocated cells for a run of 32 steps, overflowed components: \
+             memory units (300 used, 256 available), range_check (5 used, 4 available)"
+        );
+    }
+}
@@ -45,11 +45,9 @@ pub fn verify_secure_runner(
         .iter()
         .enumerate()
         .flat_map(|(idx, segment)| {
-            segment.iter().enumerate().filter_map(move |(off, value)| {
-                value
-                    .as_ref()
-                    .map(|val| (Relocatable::from((idx as _, off)), val))
-            })
+            segment
+                .iter_written()
+                .map(move |(off, value)| (Relocatable::from((idx as _, off)), value))
         });
     for (addr, value) in memory_iter {
         // Check builtin segment bounds.
@@ -70,12 +68,11 @@ pub fn verify_secure_runner(
         // is not temporary).
         if !vm
             .segments
-            .is_valid_memory_value(value)
+            .is_valid_memory_value(&value)
             .map_err(RunnerError::FailedMemoryGet)?
         {
             return Err(
-                RunnerError::FailedMemoryGet(MemoryError::InvalidMemoryValue(addr, value.clone()))
-                    .into(),
+                RunnerError::FailedMemoryGet(MemoryError::InvalidMemoryValue(addr, value)).into(),
             );
         }
     }
@@ -132,7 +129,7 @@ mod test {
 
         runner.initialize(&mut vm).unwrap();
 
-        vm.memory.data = vec![vec![Some(relocatable!(0, 1000).into())]];
+        vm.memory.data = vec![vec![Some(relocatable!(0, 1000).into())].into()];
         vm.segments.segment_used_sizes = Some(vec![0, 0, 0, 0]);
 
         assert_eq!(
@@ -163,7 +160,8 @@ mod test {
             Some(relocatable!(2, 1).into()),
             Some(relocatable!(3, 2).into()),
             Some(relocatable!(4, 3).into()),
-        ]];
+        ]
+        .into()];
         vm.segments.segment_used_sizes = Some(vec![5, 1, 2, 3, 4]);
 
         assert_eq!(verify_secure_runner(&runner, true, &mut vm), Ok(()));
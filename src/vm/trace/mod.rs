@@ -7,6 +7,7 @@ use crate::types::relocatable::{MaybeRelocatable, Relocatable};
 use num_traits::ToPrimitive;
 use std::borrow::Cow;
 
+pub mod streaming_trace;
 pub mod trace_entry;
 
 /// Return the minimum and maximum values in the perm_range_check component.
@@ -0,0 +1,199 @@
+use super::{get_perm_range_check_limits, trace_entry::TraceEntry};
+use crate::{
+    types::relocatable::Relocatable,
+    vm::{
+        errors::{trace_errors::TraceError, vm_errors::VirtualMachineError},
+        vm_memory::memory::Memory,
+    },
+};
+use std::{
+    fs::{self, File},
+    io::{BufReader, BufWriter, ErrorKind, Read, Write},
+    path::{Path, PathBuf},
+};
+
+// Each unrelocated register is stored as its segment index (8 bytes) followed by its offset
+// (8 bytes), in little endian, in pc, ap, fp order.
+const REGISTER_SIZE: usize = 16;
+const ENTRY_SIZE: usize = 3 * REGISTER_SIZE;
+
+/// Keeps the trace of a run on disk instead of in memory.
+/// The VM buffers up to `chunk_size` trace entries, which are then appended, still unrelocated,
+/// to the file at `path`. The file can only be relocated once the run is over, as the
+/// relocation table depends on the final size of each segment.
+#[derive(Debug)]
+pub struct StreamingTrace {
+    path: PathBuf,
+    writer: BufWriter<File>,
+    chunk_size: usize,
+    flushed_entries: usize,
+    // The perm range check limits of the entries that were already written to disk, as they
+    // can't be computed from the in-memory trace anymore
+    flushed_rc_limits: Option<(isize, isize)>,
+}
+
+impl StreamingTrace {
+    /// Creates (or truncates) the file at `path`, to which trace entries are written in chunks
+    /// of `chunk_size` entries.
+    pub fn create(path: &Path, chunk_size: usize) -> Result<Self, TraceError> {
+        if chunk_size == 0 {
+            return Err(TraceError::InvalidChunkSize);
+        }
+        let file = File::create(path).map_err(|e| TraceError::WriteFail(e.to_string()))?;
+        Ok(StreamingTrace {
+            path: path.to_path_buf(),
+            writer: BufWriter::new(file),
+            chunk_size,
+            flushed_entries: 0,
+            flushed_rc_limits: None,
+        })
+    }
+
+    pub fn chunk_size(&self) -> usize {
+        self.chunk_size
+    }
+
+    /// Returns the number of entries that were written to disk.
+    pub fn flushed_entries(&self) -> usize {
+        self.flushed_entries
+    }
+
+    /// Returns the perm range check limits of the entries that were written to disk.
+    pub fn flushed_rc_limits(&self) -> Option<(isize, isize)> {
+        self.flushed_rc_limits
+    }
+
+    /// Appends `entries` to the file, and empties them.
+    /// `memory` is used to keep track of the perm range check limits of the written entries.
+    pub fn flush(
+        &mut self,
+        entries: &mut Vec<TraceEntry>,
+        memory: &Memory,
+    ) -> Result<(), VirtualMachineError> {
+        if let Some((min, max)) = get_perm_range_check_limits(entries, memory)? {
+            self.flushed_rc_limits = Some(match self.flushed_rc_limits {
+                Some((flushed_min, flushed_max)) => (flushed_min.min(min), flushed_max.max(max)),
+                None => (min, max),
+            });
+        }
+        for entry in entries.iter() {
+            for register in [&entry.pc, &entry.ap, &entry.fp] {
+                self.writer
+                    .write_all(&(register.segment_index as i64).to_le_bytes())
+                    .and_then(|_| {
+                        self.writer
+                            .write_all(&(register.offset as u64).to_le_bytes())
+                    })
+                    .map_err(|e| TraceError::WriteFail(e.to_string()))?;
+            }
+        }
+        self.flushed_entries += entries.len();
+        entries.clear();
+        Ok(())
+    }
+
+    /// Finishes writing the file, and returns an iterator over the entries written to it.
+    pub fn read_entries(mut self) -> Result<StreamingTraceEntries, TraceError> {
+        self.writer
+            .flush()
+            .map_err(|e| TraceError::WriteFail(e.to_string()))?;
+        let file = File::open(&self.path).map_err(|e| TraceError::ReadFail(e.to_string()))?;
+        Ok(StreamingTraceEntries {
+            path: self.path,
+            reader: BufReader::new(file),
+        })
+    }
+}
+
+/// Iterator over the entries of a `StreamingTrace`. The file is removed once it is dropped.
+pub struct StreamingTraceEntries {
+    path: PathBuf,
+    reader: BufReader<File>,
+}
+
+impl Iterator for StreamingTraceEntries {
+    type Item = Result<TraceEntry, TraceError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut bytes = [0; ENTRY_SIZE];
+        match self.reader.read_exact(&mut bytes) {
+            Ok(()) => (),
+            Err(e) if e.kind() == ErrorKind::UnexpectedEof => return None,
+            Err(e) => return Some(Err(TraceError::ReadFail(e.to_string()))),
+        }
+        let register = |index: usize| {
+            let bytes = &bytes[index * REGISTER_SIZE..(index + 1) * REGISTER_SIZE];
+            let mut segment_index = [0; 8];
+            let mut offset = [0; 8];
+            segment_index.copy_from_slice(&bytes[..8]);
+            offset.copy_from_slice(&bytes[8..]);
+            Relocatable::from((
+                i64::from_le_bytes(segment_index) as isize,
+                u64::from_le_bytes(offset) as usize,
+            ))
+        };
+        Some(Ok(TraceEntry {
+            pc: register(0),
+            ap: register(1),
+            fp: register(2),
+        }))
+    }
+}
+
+impl Drop for StreamingTraceEntries {
+    fn drop(&mut self) {
+        // The file only holds the unrelocated trace, there is nothing to do if it can't be removed
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        types::relocatable::MaybeRelocatable, utils::test_utils::*,
+        vm::errors::memory_errors::MemoryError,
+    };
+
+    fn entry(pc: usize, ap: usize, fp: usize) -> TraceEntry {
+        TraceEntry {
+            pc: (0, pc).into(),
+            ap: (1, ap).into(),
+            fp: (1, fp).into(),
+        }
+    }
+
+    #[test]
+    fn create_with_zero_chunk_size() {
+        let path = std::env::temp_dir().join("cairo_vm_streaming_trace_zero_chunk");
+        assert_eq!(
+            StreamingTrace::create(&path, 0).err(),
+            Some(TraceError::InvalidChunkSize)
+        );
+    }
+
+    #[test]
+    fn flush_and_read_entries() {
+        let path = std::env::temp_dir().join("cairo_vm_streaming_trace_flush_and_read");
+        let memory = memory![
+            ((0, 0), 0x48307ffe7fff8000_i64),
+            ((0, 1), 0x48307ffe7fff8000_i64)
+        ];
+        let mut streaming_trace = StreamingTrace::create(&path, 1).unwrap();
+
+        let mut entries = vec![entry(0, 2, 2)];
+        assert_eq!(streaming_trace.flush(&mut entries, &memory), Ok(()));
+        assert!(entries.is_empty());
+        let mut entries = vec![entry(1, 3, 2)];
+        assert_eq!(streaming_trace.flush(&mut entries, &memory), Ok(()));
+
+        assert_eq!(streaming_trace.flushed_entries(), 2);
+        assert_eq!(streaming_trace.flushed_rc_limits(), Some((-2, 0)));
+        let read_entries = streaming_trace
+            .read_entries()
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>();
+        assert_eq!(read_entries, Ok(vec![entry(0, 2, 2), entry(1, 3, 2)]));
+        assert!(!path.exists());
+    }
+}
@@ -0,0 +1,565 @@
+use crate::{
+    serde::deserialize_program::Identifier,
+    types::relocatable::{MaybeRelocatable, Relocatable},
+    vm::{errors::typed_value_errors::TypedValueError, vm_core::VirtualMachine},
+};
+use std::{collections::HashMap, fmt};
+
+/// The number of pointers followed by default when decoding a value.
+pub const DEFAULT_MAX_DEPTH: usize = 1;
+
+/// A Cairo type, with its struct names resolved to full names.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CairoType {
+    Felt,
+    Pointer(Box<CairoType>),
+    Struct(String),
+    /// The elements of a tuple, with their names for named tuples.
+    Tuple(Vec<(Option<String>, CairoType)>),
+}
+
+/// A value decoded from memory according to its Cairo type.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CairoValue {
+    Value(MaybeRelocatable),
+    /// The pointee is only decoded if the address is relocatable and the depth limit wasn't
+    /// reached.
+    Pointer {
+        address: MaybeRelocatable,
+        pointee: Option<Box<CairoValue>>,
+    },
+    /// A struct with its full name, and its members in memory order.
+    Struct {
+        name: String,
+        members: Vec<(String, CairoValue)>,
+    },
+    Tuple(Vec<(Option<String>, CairoValue)>),
+    /// A memory cell that wasn't written.
+    Unknown,
+}
+
+/// Decodes the values stored in memory using the struct definitions of the program identifiers.
+/// Types are written as in Cairo (`felt`, `Uint256*`, `(felt, MyStruct)`, ...), and structs can
+/// be named by their full name or by a suffix of it, as long as it matches a single struct.
+pub struct ValueInspector<'a> {
+    identifiers: &'a HashMap<String, Identifier>,
+    max_depth: usize,
+}
+
+impl<'a> ValueInspector<'a> {
+    pub fn new(identifiers: &'a HashMap<String, Identifier>) -> ValueInspector<'a> {
+        ValueInspector {
+            identifiers,
+            max_depth: DEFAULT_MAX_DEPTH,
+        }
+    }
+
+    /// Sets the number of nested pointers that are followed, 0 to only decode their addresses.
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Decodes the value of type `cairo_type` stored at `addr`.
+    pub fn inspect(
+        &self,
+        vm: &VirtualMachine,
+        addr: &Relocatable,
+        cairo_type: &str,
+    ) -> Result<CairoValue, TypedValueError> {
+        let cairo_type = self.parse_type(cairo_type)?;
+        self.decode(vm, addr, &cairo_type, 0)
+    }
+
+    pub fn parse_type(&self, cairo_type: &str) -> Result<CairoType, TypedValueError> {
+        let cairo_type = cairo_type.trim();
+        if let Some(pointee) = cairo_type.strip_suffix('*') {
+            return Ok(CairoType::Pointer(Box::new(self.parse_type(pointee)?)));
+        }
+        if let Some(elements) = cairo_type
+            .strip_prefix('(')
+            .and_then(|elements| elements.strip_suffix(')'))
+        {
+            let mut elements = split_top_level(elements, ',')
+                .ok_or_else(|| TypedValueError::InvalidType(cairo_type.to_string()))?;
+            // One-element tuples are written with a trailing comma, as in `(felt,)`
+            if elements
+                .last()
+                .map_or(false, |element| element.trim().is_empty())
+            {
+                elements.pop();
+            }
+            return elements
+                .into_iter()
+                .map(|element| match split_top_level(element, ':').as_deref() {
+                    Some([name, element_type]) => Ok((
+                        Some(name.trim().to_string()),
+                        self.parse_type(element_type)?,
+                    )),
+                    _ => Ok((None, self.parse_type(element)?)),
+                })
+                .collect::<Result<_, _>>()
+                .map(CairoType::Tuple);
+        }
+        match cairo_type {
+            "felt" | "codeoffset" => Ok(CairoType::Felt),
+            name if name.is_empty() || name.contains(|c| "(),:".contains(c)) => {
+                Err(TypedValueError::InvalidType(name.to_string()))
+            }
+            name => self.resolve_struct(name).map(CairoType::Struct),
+        }
+    }
+
+    /// Returns the number of memory cells taken by a value of the given type.
+    pub fn size_of(&self, cairo_type: &CairoType) -> Result<usize, TypedValueError> {
+        match cairo_type {
+            CairoType::Felt | CairoType::Pointer(_) => Ok(1),
+            CairoType::Tuple(elements) => elements
+                .iter()
+                .map(|(_, element_type)| self.size_of(element_type))
+                .sum(),
+            // Members are contiguous, so the struct ends with its last member
+            CairoType::Struct(name) => match self.get_members(name)?.last() {
+                Some((_, offset, member_type)) => Ok(offset + self.size_of(member_type)?),
+                None => Ok(0),
+            },
+        }
+    }
+
+    /// Returns the offset and type of a member of a struct type.
+    pub fn get_member(
+        &self,
+        cairo_type: &CairoType,
+        member_name: &str,
+    ) -> Result<(usize, CairoType), TypedValueError> {
+        let unknown_member =
+            || TypedValueError::UnknownMember(cairo_type.to_string(), member_name.to_string());
+        match cairo_type {
+            CairoType::Struct(name) => self
+                .get_members(name)?
+                .into_iter()
+                .find(|(name, _, _)| name == member_name)
+                .map(|(_, offset, member_type)| (offset, member_type))
+                .ok_or_else(unknown_member),
+            _ => Err(unknown_member()),
+        }
+    }
+
+    fn decode(
+        &self,
+        vm: &VirtualMachine,
+        addr: &Relocatable,
+        cairo_type: &CairoType,
+        depth: usize,
+    ) -> Result<CairoValue, TypedValueError> {
+        Ok(match cairo_type {
+            CairoType::Felt => match vm.get_maybe(addr)? {
+                Some(value) => CairoValue::Value(value),
+                None => CairoValue::Unknown,
+            },
+            CairoType::Pointer(pointee_type) => match vm.get_maybe(addr)? {
+                Some(address) => {
+                    let pointee = match &address {
+                        MaybeRelocatable::RelocatableValue(pointee_addr)
+                            if depth < self.max_depth =>
+                        {
+                            Some(Box::new(self.decode(
+                                vm,
+                                pointee_addr,
+                                pointee_type,
+                                depth + 1,
+                            )?))
+                        }
+                        _ => None,
+                    };
+                    CairoValue::Pointer { address, pointee }
+                }
+                None => CairoValue::Unknown,
+            },
+            CairoType::Struct(name) => {
+                let members = self
+                    .get_members(name)?
+                    .into_iter()
+                    .map(|(member_name, offset, member_type)| {
+                        let value = self.decode(vm, &(*addr + offset), &member_type, depth)?;
+                        Ok((member_name, value))
+                    })
+                    .collect::<Result<_, TypedValueError>>()?;
+                CairoValue::Struct {
+                    name: name.clone(),
+                    members,
+                }
+            }
+            CairoType::Tuple(elements) => {
+                let mut offset = 0;
+                let mut values = Vec::with_capacity(elements.len());
+                for (element_name, element_type) in elements {
+                    let value = self.decode(vm, &(*addr + offset), element_type, depth)?;
+                    values.push((element_name.clone(), value));
+                    offset += self.size_of(element_type)?;
+                }
+                CairoValue::Tuple(values)
+            }
+        })
+    }
+
+    // Looks the struct up by its full name, then in the main scope, then by suffix
+    fn resolve_struct(&self, name: &str) -> Result<String, TypedValueError> {
+        let is_struct = |full_name: &str| {
+            self.identifiers
+                .get(full_name)
+                .map_or(false, |identifier| identifier.members.is_some())
+        };
+        let main_name = format!("__main__.{}", name);
+        for full_name in [name, main_name.as_str()] {
+            if is_struct(full_name) {
+                return Ok(full_name.to_string());
+            }
+        }
+        let suffix = format!(".{}", name);
+        let mut matches = self
+            .identifiers
+            .keys()
+            .filter(|full_name| full_name.ends_with(&suffix) && is_struct(full_name));
+        match (matches.next(), matches.next()) {
+            (Some(full_name), None) => Ok(full_name.clone()),
+            (Some(_), Some(_)) => Err(TypedValueError::AmbiguousType(name.to_string())),
+            (None, _) => Err(TypedValueError::UnknownType(name.to_string())),
+        }
+    }
+
+    // Returns the name, offset and type of each member of the struct, in memory order
+    fn get_members(&self, name: &str) -> Result<Vec<(String, usize, CairoType)>, TypedValueError> {
+        let members = self
+            .identifiers
+            .get(name)
+            .and_then(|identifier| identifier.members.as_ref())
+            .ok_or_else(|| TypedValueError::UnknownType(name.to_string()))?;
+        let mut members = members
+            .iter()
+            .map(|(member_name, member)| {
+                let member_type = self.parse_type(&member.cairo_type)?;
+                Ok((member_name.clone(), member.offset, member_type))
+            })
+            .collect::<Result<Vec<_>, TypedValueError>>()?;
+        members.sort_by_key(|(_, offset, _)| *offset);
+        Ok(members)
+    }
+}
+
+// Splits `s` on the occurrences of `separator` that aren't nested in parentheses.
+// Returns None if the parentheses aren't balanced.
+fn split_top_level(s: &str, separator: char) -> Option<Vec<&str>> {
+    let mut parts = Vec::new();
+    let (mut depth, mut start) = (0_usize, 0);
+    for (i, c) in s.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth = depth.checked_sub(1)?,
+            c if c == separator && depth == 0 => {
+                parts.push(&s[start..i]);
+                start = i + c.len_utf8();
+            }
+            _ => (),
+        }
+    }
+    if depth != 0 {
+        return None;
+    }
+    parts.push(&s[start..]);
+    Some(parts)
+}
+
+impl fmt::Display for CairoType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CairoType::Felt => write!(f, "felt"),
+            CairoType::Pointer(pointee) => write!(f, "{}*", pointee),
+            CairoType::Struct(name) => write!(f, "{}", name),
+            CairoType::Tuple(elements) => {
+                write!(f, "(")?;
+                for (i, (element_name, element_type)) in elements.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    match element_name {
+                        Some(element_name) => write!(f, "{}: {}", element_name, element_type)?,
+                        None => write!(f, "{}", element_type)?,
+                    }
+                }
+                if elements.len() == 1 && elements[0].0.is_none() {
+                    write!(f, ",")?;
+                }
+                write!(f, ")")
+            }
+        }
+    }
+}
+
+// Formats structs as `Name(member=value, ...)` with their short name, pointers as their address
+// followed by `-> pointee` when it was decoded, and unwritten cells as `?`
+impl fmt::Display for CairoValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CairoValue::Value(value) => write!(f, "{}", value),
+            CairoValue::Pointer { address, pointee } => match pointee {
+                Some(pointee) => write!(f, "{} -> {}", address, pointee),
+                None => write!(f, "{}", address),
+            },
+            CairoValue::Struct { name, members } => {
+                let short_name = name.rsplit('.').next().unwrap_or(name);
+                write!(f, "{}(", short_name)?;
+                for (i, (member_name, value)) in members.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}={}", member_name, value)?;
+                }
+                write!(f, ")")
+            }
+            CairoValue::Tuple(elements) => {
+                write!(f, "(")?;
+                for (i, (element_name, value)) in elements.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    match element_name {
+                        Some(element_name) => write!(f, "{}={}", element_name, value)?,
+                        None => write!(f, "{}", value)?,
+                    }
+                }
+                if elements.len() == 1 && elements[0].0.is_none() {
+                    write!(f, ",")?;
+                }
+                write!(f, ")")
+            }
+            CairoValue::Unknown => write!(f, "?"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        utils::test_utils::*,
+        vm::{errors::memory_errors::MemoryError, vm_memory::memory::Memory},
+    };
+
+    fn identifiers() -> HashMap<String, Identifier> {
+        HashMap::from([
+            (
+                String::from("starkware.cairo.common.uint256.Uint256"),
+                struct_identifier(
+                    "starkware.cairo.common.uint256.Uint256",
+                    &[("low", "felt", 0), ("high", "felt", 1)],
+                ),
+            ),
+            (
+                String::from("__main__.Node"),
+                struct_identifier(
+                    "__main__.Node",
+                    &[
+                        ("value", "starkware.cairo.common.uint256.Uint256", 0),
+                        ("next", "__main__.Node*", 2),
+                    ],
+                ),
+            ),
+            (
+                String::from("__main__.Pair"),
+                struct_identifier(
+                    "__main__.Pair",
+                    &[("values", "(a: felt, b: felt*)", 0), ("last", "felt", 2)],
+                ),
+            ),
+            (
+                String::from("a.Point"),
+                struct_identifier("a.Point", &[("x", "felt", 0)]),
+            ),
+            (
+                String::from("b.Point"),
+                struct_identifier("b.Point", &[("x", "felt", 0)]),
+            ),
+        ])
+    }
+
+    #[test]
+    fn parse_types() {
+        let identifiers = identifiers();
+        let inspector = ValueInspector::new(&identifiers);
+        let uint256 = CairoType::Struct(String::from("starkware.cairo.common.uint256.Uint256"));
+        assert_eq!(inspector.parse_type("Uint256"), Ok(uint256.clone()));
+        assert_eq!(
+            inspector.parse_type("felt**"),
+            Ok(CairoType::Pointer(Box::new(CairoType::Pointer(Box::new(
+                CairoType::Felt
+            )))))
+        );
+        assert_eq!(
+            inspector.parse_type("(felt, x: Uint256*)"),
+            Ok(CairoType::Tuple(vec![
+                (None, CairoType::Felt),
+                (
+                    Some(String::from("x")),
+                    CairoType::Pointer(Box::new(uint256))
+                )
+            ]))
+        );
+        assert_eq!(
+            inspector.parse_type("(felt,)"),
+            Ok(CairoType::Tuple(vec![(None, CairoType::Felt)]))
+        );
+        assert_eq!(
+            inspector.parse_type("Node"),
+            Ok(CairoType::Struct(String::from("__main__.Node")))
+        );
+    }
+
+    #[test]
+    fn parse_invalid_types() {
+        let identifiers = identifiers();
+        let inspector = ValueInspector::new(&identifiers);
+        assert_eq!(
+            inspector.parse_type("Unknown"),
+            Err(TypedValueError::UnknownType(String::from("Unknown")))
+        );
+        assert_eq!(
+            inspector.parse_type("Point"),
+            Err(TypedValueError::AmbiguousType(String::from("Point")))
+        );
+        assert_eq!(
+            inspector.parse_type("((felt)"),
+            Err(TypedValueError::InvalidType(String::from("((felt)")))
+        );
+        assert_eq!(
+            inspector.parse_type("*"),
+            Err(TypedValueError::InvalidType(String::new()))
+        );
+    }
+
+    #[test]
+    fn get_members_and_display_types() {
+        let identifiers = identifiers();
+        let inspector = ValueInspector::new(&identifiers);
+        let node = inspector.parse_type("Node").unwrap();
+        let (offset, next_type) = inspector.get_member(&node, "next").unwrap();
+        assert_eq!(offset, 2);
+        assert_eq!(next_type.to_string(), "__main__.Node*");
+        assert_eq!(
+            inspector.get_member(&next_type, "value"),
+            Err(TypedValueError::UnknownMember(
+                String::from("__main__.Node*"),
+                String::from("value")
+            ))
+        );
+        assert_eq!(
+            inspector
+                .parse_type("(a: felt, (felt,))")
+                .unwrap()
+                .to_string(),
+            "(a: felt, (felt,))"
+        );
+    }
+
+    #[test]
+    fn size_of_types() {
+        let identifiers = identifiers();
+        let inspector = ValueInspector::new(&identifiers);
+        let size_of = |cairo_type| inspector.size_of(&inspector.parse_type(cairo_type).unwrap());
+        assert_eq!(size_of("felt"), Ok(1));
+        assert_eq!(size_of("Node*"), Ok(1));
+        assert_eq!(size_of("Node"), Ok(3));
+        assert_eq!(size_of("(Uint256, Pair)"), Ok(5));
+    }
+
+    #[test]
+    fn inspect_struct() {
+        let identifiers = identifiers();
+        let mut vm = vm!();
+        vm.memory = memory![((1, 0), 5), ((1, 1), 7)];
+        let value = ValueInspector::new(&identifiers)
+            .inspect(&vm, &(1, 0).into(), "Uint256")
+            .unwrap();
+        assert_eq!(
+            value,
+            CairoValue::Struct {
+                name: String::from("starkware.cairo.common.uint256.Uint256"),
+                members: vec![
+                    (String::from("low"), CairoValue::Value(5.into())),
+                    (String::from("high"), CairoValue::Value(7.into())),
+                ]
+            }
+        );
+        assert_eq!(value.to_string(), "Uint256(low=5, high=7)");
+    }
+
+    #[test]
+    fn inspect_pointers_up_to_max_depth() {
+        let identifiers = identifiers();
+        let mut vm = vm!();
+        // A linked list of three nodes, the last one pointing to null
+        vm.memory = memory![
+            ((1, 0), 1),
+            ((1, 1), 0),
+            ((1, 2), (1, 3)),
+            ((1, 3), 2),
+            ((1, 4), 0),
+            ((1, 5), (1, 6)),
+            ((1, 6), 3),
+            ((1, 7), 0),
+            ((1, 8), 0),
+            ((1, 9), (1, 0))
+        ];
+        let inspector = ValueInspector::new(&identifiers);
+        assert_eq!(
+            inspector
+                .inspect(&vm, &(1, 9).into(), "Node*")
+                .unwrap()
+                .to_string(),
+            "1:0 -> Node(value=Uint256(low=1, high=0), next=1:3)"
+        );
+        assert_eq!(
+            inspector
+                .with_max_depth(4)
+                .inspect(&vm, &(1, 9).into(), "Node*")
+                .unwrap()
+                .to_string(),
+            "1:0 -> Node(value=Uint256(low=1, high=0), next=1:3 -> \
+             Node(value=Uint256(low=2, high=0), next=1:6 -> \
+             Node(value=Uint256(low=3, high=0), next=0)))"
+        );
+        assert_eq!(
+            ValueInspector::new(&identifiers).with_max_depth(0).inspect(
+                &vm,
+                &(1, 9).into(),
+                "Node*"
+            ),
+            Ok(CairoValue::Pointer {
+                address: MaybeRelocatable::from((1, 0)),
+                pointee: None
+            })
+        );
+    }
+
+    #[test]
+    fn inspect_tuples_and_unknown_cells() {
+        let identifiers = identifiers();
+        let mut vm = vm!();
+        vm.memory = memory![((1, 0), 4), ((1, 1), (2, 0)), ((2, 0), 8)];
+        let inspector = ValueInspector::new(&identifiers);
+        assert_eq!(
+            inspector
+                .inspect(&vm, &(1, 0).into(), "Pair")
+                .unwrap()
+                .to_string(),
+            "Pair(values=(a=4, b=2:0 -> 8), last=?)"
+        );
+        assert_eq!(
+            inspector
+                .inspect(&vm, &(1, 0).into(), "(felt,)")
+                .unwrap()
+                .to_string(),
+            "(4,)"
+        );
+    }
+}
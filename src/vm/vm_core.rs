@@ -6,26 +6,48 @@ use crate::{
         instruction::{
             is_call_instruction, ApUpdate, FpUpdate, Instruction, Opcode, PcUpdate, Res,
         },
+        program::Program,
         relocatable::{MaybeRelocatable, Relocatable},
     },
     vm::{
         context::run_context::RunContext,
-        decoding::decoder::decode_instruction,
+        decoding::{decoder::decode_instruction, instruction_cache::InstructionCache},
         errors::{
-            exec_scope_errors::ExecScopeError, memory_errors::MemoryError,
-            vm_errors::VirtualMachineError,
+            exec_scope_errors::ExecScopeError, hint_errors::HintError, memory_errors::MemoryError,
+            trace_errors::TraceError, vm_errors::VirtualMachineError,
+        },
+        hint_stats::HintStats,
+        inspection::{BuiltinInfo, Registers, SegmentInfo},
+        runners::builtin_runner::{
+            BuiltinRunner, OutputBuiltinAdditionalData, OutputBuiltinRunner,
+            RangeCheckBuiltinRunner, SignatureBuiltinRunner,
+        },
+        trace::{streaming_trace::StreamingTrace, trace_entry::TraceEntry},
+        vm_memory::{
+            memory::Memory,
+            memory_access_log::{AccessSource, MemoryAccessLog},
+            memory_segments::MemorySegmentManager,
         },
-        runners::builtin_runner::{BuiltinRunner, RangeCheckBuiltinRunner, SignatureBuiltinRunner},
-        trace::trace_entry::TraceEntry,
-        vm_memory::{memory::Memory, memory_segments::MemorySegmentManager},
     },
 };
 use felt::Felt;
 use num_traits::{ToPrimitive, Zero};
-use std::{any::Any, borrow::Cow, collections::HashMap};
+use std::{
+    any::Any,
+    borrow::Cow,
+    cell::{Ref, RefCell},
+    collections::HashMap,
+    ops::Range,
+    path::Path,
+    rc::Rc,
+    time::Instant,
+};
 
 use super::vm_memory::memory_segments::gen_typed_args;
 
+#[cfg(feature = "async_hints")]
+use crate::hint_processor::async_hint_processor::AsyncHintProcessor;
+
 const MAX_TRACEBACK_ENTRIES: u32 = 20;
 
 #[derive(PartialEq, Eq, Debug)]
@@ -56,6 +78,12 @@ impl DeducedOperands {
     fn set_op1(&mut self, value: bool) {
         self.0 |= (value as u8) << 2;
     }
+    fn set_op0_by_builtin(&mut self, value: bool) {
+        self.0 |= (value as u8) << 3;
+    }
+    fn set_op1_by_builtin(&mut self, value: bool) {
+        self.0 |= (value as u8) << 4;
+    }
 
     fn was_dest_deducted(&self) -> bool {
         self.0 & 1 != 0
@@ -66,6 +94,12 @@ impl DeducedOperands {
     fn was_op1_deducted(&self) -> bool {
         self.0 & 1 << 2 != 0
     }
+    fn was_op0_deducted_by_builtin(&self) -> bool {
+        self.0 & 1 << 3 != 0
+    }
+    fn was_op1_deducted_by_builtin(&self) -> bool {
+        self.0 & 1 << 4 != 0
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -84,7 +118,15 @@ pub struct VirtualMachine {
     pub(crate) memory: Memory,
     pub(crate) accessed_addresses: Option<Vec<Relocatable>>,
     pub(crate) trace: Option<Vec<TraceEntry>>,
+    // If set, the trace is written to disk in chunks instead of being kept in `trace`
+    pub(crate) streaming_trace: Option<StreamingTrace>,
     pub(crate) current_step: usize,
+    // Programs loaded at runtime (ie: by the bootloader), along with the address they were loaded at
+    pub(crate) loaded_programs: Vec<(Relocatable, Program)>,
+    // Instructions that were already decoded, so that loops don't decode them on each iteration
+    instruction_cache: InstructionCache,
+    // Set by enable_hint_stats
+    pub(crate) hint_stats: Option<HintStats>,
     skip_instruction_execution: bool,
     run_finished: bool,
 }
@@ -126,9 +168,13 @@ impl VirtualMachine {
             // we could not change this value and faced an Error. This is the behaviour that the original VM implements also.
             accessed_addresses: Some(Vec::new()),
             trace,
+            streaming_trace: None,
             current_step: 0,
             skip_instruction_execution: false,
             segments: MemorySegmentManager::new(),
+            loaded_programs: Vec::new(),
+            instruction_cache: InstructionCache::default(),
+            hint_stats: None,
             run_finished: false,
         }
     }
@@ -136,17 +182,22 @@ impl VirtualMachine {
     ///Returns the encoded instruction (the value at pc) and the immediate value (the value at pc + 1, if it exists in the memory).
     fn get_instruction_encoding(
         &self,
-    ) -> Result<(Cow<Felt>, Option<Cow<MaybeRelocatable>>), VirtualMachineError> {
-        let encoding_ref = match self.memory.get(&self.run_context.pc) {
-            Ok(Some(Cow::Owned(MaybeRelocatable::Int(encoding)))) => Cow::Owned(encoding),
-            Ok(Some(Cow::Borrowed(MaybeRelocatable::Int(encoding)))) => Cow::Borrowed(encoding),
-            _ => return Err(VirtualMachineError::InvalidInstructionEncoding),
-        };
+    ) -> Result<(i64, Option<Cow<MaybeRelocatable>>), VirtualMachineError> {
+        // Instructions are small felts, which can be read without building a Felt
+        let encoding = match self.memory.get_u64(&self.run_context.pc) {
+            Some(encoding) => encoding.to_i64(),
+            None => self
+                .memory
+                .get_integer(&self.run_context.pc)
+                .ok()
+                .and_then(|encoding| encoding.to_i64()),
+        }
+        .ok_or(VirtualMachineError::InvalidInstructionEncoding)?;
 
         let imm_addr = &self.run_context.pc + 1_i32;
 
         if let Ok(optional_imm) = self.memory.get(&imm_addr) {
-            Ok((encoding_ref, optional_imm))
+            Ok((encoding, optional_imm))
         } else {
             Err(VirtualMachineError::InvalidInstructionEncoding)
         }
@@ -219,12 +270,12 @@ impl VirtualMachine {
 
     fn update_registers(
         &mut self,
-        instruction: Instruction,
+        instruction: &Instruction,
         operands: Operands,
     ) -> Result<(), VirtualMachineError> {
-        self.update_fp(&instruction, &operands)?;
-        self.update_ap(&instruction, &operands)?;
-        self.update_pc(&instruction, &operands)?;
+        self.update_fp(instruction, &operands)?;
+        self.update_ap(instruction, &operands)?;
+        self.update_pc(instruction, &operands)?;
         Ok(())
     }
 
@@ -425,21 +476,33 @@ impl VirtualMachine {
 
     fn insert_deduced_operands(
         &mut self,
+        instruction: &Instruction,
         deduced_operands: DeducedOperands,
         operands: &Operands,
         operands_addresses: &OperandsAddresses,
     ) -> Result<(), VirtualMachineError> {
         if deduced_operands.was_op0_deducted() {
+            self.log_operand_deduction(
+                instruction,
+                &operands_addresses.op0_addr,
+                deduced_operands.was_op0_deducted_by_builtin(),
+            );
             self.memory
                 .insert(&operands_addresses.op0_addr, &operands.op0)
                 .map_err(VirtualMachineError::MemoryError)?;
         }
         if deduced_operands.was_op1_deducted() {
+            self.log_operand_deduction(
+                instruction,
+                &operands_addresses.op1_addr,
+                deduced_operands.was_op1_deducted_by_builtin(),
+            );
             self.memory
                 .insert(&operands_addresses.op1_addr, &operands.op1)
                 .map_err(VirtualMachineError::MemoryError)?;
         }
         if deduced_operands.was_dest_deducted() {
+            self.set_memory_access_source(Some(AccessSource::Instruction));
             self.memory
                 .insert(&operands_addresses.dst_addr, &operands.dst)
                 .map_err(VirtualMachineError::MemoryError)?;
@@ -448,11 +511,88 @@ impl VirtualMachine {
         Ok(())
     }
 
-    fn run_instruction(&mut self, instruction: Instruction) -> Result<(), VirtualMachineError> {
+    // Sets the source of the following memory accesses, if the memory access log is enabled
+    fn set_memory_access_source(&self, source: Option<AccessSource>) {
+        if let Some(access_log) = &self.memory.access_log {
+            access_log.borrow_mut().source = source;
+        }
+    }
+
+    // Sets the source of the write of a deduced op0 or op1 in the memory access log, if enabled
+    fn log_operand_deduction(
+        &self,
+        instruction: &Instruction,
+        address: &Relocatable,
+        deduced_by_builtin: bool,
+    ) {
+        let access_log = match &self.memory.access_log {
+            Some(access_log) => access_log,
+            None => return,
+        };
+        let source = if instruction.opcode == Opcode::Call {
+            // The return pc pushed by a call is written by the instruction itself
+            AccessSource::Instruction
+        } else if deduced_by_builtin {
+            // The builtin that deduced the operand is the one that owns its segment
+            let builtin = self
+                .builtin_runners
+                .iter()
+                .find(|(_, builtin)| builtin.base() == address.segment_index)
+                .map(|(name, _)| name.clone())
+                .unwrap_or_default();
+            AccessSource::BuiltinDeduction { builtin }
+        } else {
+            AccessSource::AutoDeduction
+        };
+        access_log.borrow_mut().source = Some(source);
+    }
+
+    // Records the reads of the operands that weren't deduced, if the memory access log is enabled
+    fn log_operand_reads(
+        &self,
+        deduced_operands: &DeducedOperands,
+        operands_addresses: &OperandsAddresses,
+    ) {
+        if let Some(access_log) = &self.memory.access_log {
+            let mut access_log = access_log.borrow_mut();
+            let operands = [
+                (
+                    deduced_operands.was_dest_deducted(),
+                    operands_addresses.dst_addr,
+                ),
+                (
+                    deduced_operands.was_op0_deducted(),
+                    operands_addresses.op0_addr,
+                ),
+                (
+                    deduced_operands.was_op1_deducted(),
+                    operands_addresses.op1_addr,
+                ),
+            ];
+            for (index, (was_deduced, address)) in operands.iter().enumerate() {
+                // An address can be used by more than one operand, but it is only read once
+                let already_read = operands[..index]
+                    .iter()
+                    .any(|(was_deduced, other_address)| !was_deduced && other_address == address);
+                if !was_deduced && !already_read {
+                    access_log.record_read(*address, AccessSource::Instruction);
+                }
+            }
+        }
+    }
+
+    fn run_instruction(&mut self, instruction: &Instruction) -> Result<(), VirtualMachineError> {
         let (operands, operands_addresses, deduced_operands) =
-            self.compute_operands(&instruction)?;
-        self.insert_deduced_operands(deduced_operands, &operands, &operands_addresses)?;
-        self.opcode_assertions(&instruction, &operands)?;
+            self.compute_operands(instruction)?;
+        self.log_operand_reads(&deduced_operands, &operands_addresses);
+        self.insert_deduced_operands(
+            instruction,
+            deduced_operands,
+            &operands,
+            &operands_addresses,
+        )?;
+        self.set_memory_access_source(None);
+        self.opcode_assertions(instruction, &operands)?;
 
         if let Some(ref mut trace) = &mut self.trace {
             trace.push(TraceEntry {
@@ -460,6 +600,11 @@ impl VirtualMachine {
                 ap: self.run_context.get_ap(),
                 fp: self.run_context.get_fp(),
             });
+            if let Some(ref mut streaming_trace) = &mut self.streaming_trace {
+                if trace.len() >= streaming_trace.chunk_size() {
+                    streaming_trace.flush(trace, &self.memory)?;
+                }
+            }
         }
 
         if let Some(ref mut accessed_addresses) = self.accessed_addresses {
@@ -473,19 +618,19 @@ impl VirtualMachine {
         Ok(())
     }
 
-    fn decode_current_instruction(&self) -> Result<Instruction, VirtualMachineError> {
-        let (instruction_ref, imm) = self.get_instruction_encoding()?;
-        match instruction_ref.to_i64() {
-            Some(instruction) => {
-                if let Some(MaybeRelocatable::Int(imm_ref)) = imm.as_ref().map(|x| x.as_ref()) {
-                    let decoded_instruction = decode_instruction(instruction, Some(imm_ref))?;
-                    return Ok(decoded_instruction);
-                }
-                let decoded_instruction = decode_instruction(instruction, None)?;
-                Ok(decoded_instruction)
-            }
-            None => Err(VirtualMachineError::InvalidInstructionEncoding),
+    fn decode_current_instruction(&mut self) -> Result<Rc<Instruction>, VirtualMachineError> {
+        let pc = self.run_context.pc;
+        if let Some(instruction) = self.instruction_cache.get(&pc, &self.memory) {
+            return Ok(instruction);
         }
+        let (instruction, imm) = self.get_instruction_encoding()?;
+        let decoded_instruction = Rc::new(match imm.as_ref().map(|x| x.as_ref()) {
+            Some(MaybeRelocatable::Int(imm_ref)) => decode_instruction(instruction, Some(imm_ref))?,
+            _ => decode_instruction(instruction, None)?,
+        });
+        self.instruction_cache
+            .insert(&pc, &self.memory, Rc::clone(&decoded_instruction));
+        Ok(decoded_instruction)
     }
 
     pub fn step_hint(
@@ -495,19 +640,131 @@ impl VirtualMachine {
         hint_data_dictionary: &HashMap<usize, Vec<Box<dyn Any>>>,
         constants: &HashMap<String, Felt>,
     ) -> Result<(), VirtualMachineError> {
-        if let Some(hint_list) = hint_data_dictionary.get(&self.run_context.pc.offset) {
-            for (hint_index, hint_data) in hint_list.iter().enumerate() {
-                hint_executor
-                    .execute_hint(self, exec_scopes, hint_data, constants)
-                    .map_err(|err| VirtualMachineError::Hint(hint_index, Box::new(err)))?
-            }
+        self.step_hint_range(
+            hint_executor,
+            exec_scopes,
+            hint_data_dictionary,
+            constants,
+            0..usize::MAX,
+        )
+    }
+
+    // Executes the hints at the current pc whose index is in `hint_range`
+    pub(crate) fn step_hint_range(
+        &mut self,
+        hint_executor: &mut dyn HintProcessor,
+        exec_scopes: &mut ExecutionScopes,
+        hint_data_dictionary: &HashMap<usize, Vec<Box<dyn Any>>>,
+        constants: &HashMap<String, Felt>,
+        hint_range: Range<usize>,
+    ) -> Result<(), VirtualMachineError> {
+        for (hint_index, hint_data) in self.hints_in_range(hint_data_dictionary, hint_range) {
+            let start = self.before_hint(hint_index);
+            let result = hint_executor.execute_hint(self, exec_scopes, hint_data, constants);
+            self.after_hint(start, hint_index, hint_data.as_ref(), result)?;
+        }
+        Ok(())
+    }
+
+    /// Like `step_hint`, awaiting the hints executed by an AsyncHintProcessor.
+    #[cfg(feature = "async_hints")]
+    pub async fn step_hint_async<H: AsyncHintProcessor>(
+        &mut self,
+        hint_executor: &mut H,
+        exec_scopes: &mut ExecutionScopes,
+        hint_data_dictionary: &HashMap<usize, Vec<Box<dyn Any>>>,
+        constants: &HashMap<String, Felt>,
+    ) -> Result<(), VirtualMachineError> {
+        self.step_hint_range_async(
+            hint_executor,
+            exec_scopes,
+            hint_data_dictionary,
+            constants,
+            0..usize::MAX,
+        )
+        .await
+    }
+
+    // Like `step_hint_range`, awaiting the hints executed by an AsyncHintProcessor
+    #[cfg(feature = "async_hints")]
+    pub(crate) async fn step_hint_range_async<H: AsyncHintProcessor>(
+        &mut self,
+        hint_executor: &mut H,
+        exec_scopes: &mut ExecutionScopes,
+        hint_data_dictionary: &HashMap<usize, Vec<Box<dyn Any>>>,
+        constants: &HashMap<String, Felt>,
+        hint_range: Range<usize>,
+    ) -> Result<(), VirtualMachineError> {
+        for (hint_index, hint_data) in self.hints_in_range(hint_data_dictionary, hint_range) {
+            let start = self.before_hint(hint_index);
+            let result = hint_executor
+                .execute_hint_async(self, exec_scopes, hint_data, constants)
+                .await;
+            self.after_hint(start, hint_index, hint_data.as_ref(), result)?;
         }
         Ok(())
     }
 
+    // Updates the step of the access log and returns the hints at the current pc whose index is in `hint_range`
+    fn hints_in_range<'a>(
+        &self,
+        hint_data_dictionary: &'a HashMap<usize, Vec<Box<dyn Any>>>,
+        hint_range: Range<usize>,
+    ) -> impl Iterator<Item = (usize, &'a Box<dyn Any>)> + 'a {
+        if let Some(access_log) = &self.memory.access_log {
+            access_log.borrow_mut().step = self.current_step;
+        }
+        let hint_list = hint_data_dictionary
+            .get(&self.run_context.pc.offset)
+            .map_or(&[][..], Vec::as_slice);
+        let end = hint_range.end.min(hint_list.len());
+        hint_list
+            .iter()
+            .enumerate()
+            .take(end)
+            .skip(hint_range.start)
+    }
+
+    // Attributes the memory accesses to the hint with index `hint_index` and starts timing it
+    fn before_hint(&mut self, hint_index: usize) -> Option<Instant> {
+        self.set_memory_access_source(Some(AccessSource::Hint {
+            pc: self.run_context.pc,
+            hint_index,
+        }));
+        self.hint_stats.is_some().then(Instant::now)
+    }
+
+    // Records the hint stats, resets the memory access source and maps the hint's error
+    fn after_hint(
+        &mut self,
+        start: Option<Instant>,
+        hint_index: usize,
+        hint_data: &dyn Any,
+        result: Result<(), HintError>,
+    ) -> Result<(), VirtualMachineError> {
+        self.record_hint_stats(start, hint_index, hint_data);
+        self.set_memory_access_source(None);
+        result.map_err(|err| VirtualMachineError::Hint(hint_index, Box::new(err)))
+    }
+
+    // Records the execution of the hint at the current pc, which started at `start`
+    fn record_hint_stats(
+        &mut self,
+        start: Option<Instant>,
+        hint_index: usize,
+        hint_data: &dyn Any,
+    ) {
+        if let (Some(hint_stats), Some(start)) = (&mut self.hint_stats, start) {
+            hint_stats.record(self.run_context.pc, hint_index, hint_data, start.elapsed());
+        }
+    }
+
     pub fn step_instruction(&mut self) -> Result<(), VirtualMachineError> {
+        if let Some(access_log) = &self.memory.access_log {
+            access_log.borrow_mut().step = self.current_step;
+        }
         let instruction = self.decode_current_instruction()?;
-        self.run_instruction(instruction)?;
+        self.run_instruction(&instruction)?;
         self.skip_instruction_execution = false;
         Ok(())
     }
@@ -523,6 +780,20 @@ impl VirtualMachine {
         self.step_instruction()
     }
 
+    /// Like `step`, awaiting the hints executed by an AsyncHintProcessor.
+    #[cfg(feature = "async_hints")]
+    pub async fn step_async<H: AsyncHintProcessor>(
+        &mut self,
+        hint_executor: &mut H,
+        exec_scopes: &mut ExecutionScopes,
+        hint_data_dictionary: &HashMap<usize, Vec<Box<dyn Any>>>,
+        constants: &HashMap<String, Felt>,
+    ) -> Result<(), VirtualMachineError> {
+        self.step_hint_async(hint_executor, exec_scopes, hint_data_dictionary, constants)
+            .await?;
+        self.step_instruction()
+    }
+
     fn compute_op0_deductions(
         &self,
         op0_addr: &Relocatable,
@@ -530,6 +801,7 @@ impl VirtualMachine {
         instruction: &Instruction,
         dst_op: &Option<MaybeRelocatable>,
         op1_op: &Option<MaybeRelocatable>,
+        deduced_operands: &mut DeducedOperands,
     ) -> Result<MaybeRelocatable, VirtualMachineError> {
         let op0_op = match self.deduce_memory_cell(op0_addr)? {
             None => {
@@ -537,7 +809,10 @@ impl VirtualMachine {
                 (op0, *res) = self.deduce_op0(instruction, dst_op.as_ref(), op1_op.as_ref())?;
                 op0
             }
-            deduced_memory_cell => deduced_memory_cell,
+            deduced_memory_cell => {
+                deduced_operands.set_op0_by_builtin(true);
+                deduced_memory_cell
+            }
         };
         let op0 = op0_op.ok_or_else(|| {
             VirtualMachineError::FailedToComputeOperands("op0".to_string(), *op0_addr)
@@ -552,6 +827,7 @@ impl VirtualMachine {
         instruction: &Instruction,
         dst_op: &Option<MaybeRelocatable>,
         op0: &MaybeRelocatable,
+        deduced_operands: &mut DeducedOperands,
     ) -> Result<MaybeRelocatable, VirtualMachineError> {
         let op1_op = match self.deduce_memory_cell(op1_addr)? {
             None => {
@@ -562,7 +838,10 @@ impl VirtualMachine {
                 }
                 op1
             }
-            deduced_memory_cell => deduced_memory_cell,
+            deduced_memory_cell => {
+                deduced_operands.set_op1_by_builtin(true);
+                deduced_memory_cell
+            }
         };
         let op1 = op1_op.ok_or_else(|| {
             VirtualMachineError::FailedToComputeOperands("op1".to_string(), *op1_addr)
@@ -623,7 +902,14 @@ impl VirtualMachine {
             Some(op0) => op0,
             None => {
                 deduced_operands.set_op0(true);
-                self.compute_op0_deductions(&op0_addr, &mut res, instruction, &dst_op, &op1_op)?
+                self.compute_op0_deductions(
+                    &op0_addr,
+                    &mut res,
+                    instruction,
+                    &dst_op,
+                    &op1_op,
+                    &mut deduced_operands,
+                )?
             }
         };
 
@@ -632,7 +918,14 @@ impl VirtualMachine {
             Some(op1) => op1,
             None => {
                 deduced_operands.set_op1(true);
-                self.compute_op1_deductions(&op1_addr, &mut res, instruction, &dst_op, &op0)?
+                self.compute_op1_deductions(
+                    &op1_addr,
+                    &mut res,
+                    instruction,
+                    &dst_op,
+                    &op0,
+                    &mut deduced_operands,
+                )?
             }
         };
 
@@ -668,7 +961,15 @@ impl VirtualMachine {
                 .base()
                 .try_into()
                 .map_err(|_| MemoryError::AddressInTemporarySegment(builtin.base()))?;
-            for (offset, value) in self.memory.data[index].iter().enumerate() {
+            let segment = self
+                .memory
+                .data
+                .get(index)
+                .ok_or(MemoryError::UnallocatedSegment(
+                    index,
+                    self.memory.data.len(),
+                ))?;
+            for (offset, value) in segment.iter().enumerate() {
                 if let Some(deduced_memory_cell) = builtin
                     .deduce_memory_cell(&Relocatable::from((index as isize, offset)), &self.memory)
                     .map_err(VirtualMachineError::RunnerError)?
@@ -677,7 +978,7 @@ impl VirtualMachine {
                         return Err(VirtualMachineError::InconsistentAutoDeduction(
                             name.to_owned(),
                             deduced_memory_cell,
-                            value.to_owned(),
+                            value,
                         ));
                     }
                 }
@@ -911,8 +1212,89 @@ impl VirtualMachine {
 
         Err(VirtualMachineError::NoSignatureBuiltin)
     }
+
+    pub fn get_output_builtin(&mut self) -> Result<&mut OutputBuiltinRunner, VirtualMachineError> {
+        for (name, builtin) in self.get_builtin_runners_as_mut() {
+            if name == &String::from("output") {
+                if let BuiltinRunner::Output(output_builtin) = builtin {
+                    return Ok(output_builtin);
+                };
+            }
+        }
+
+        Err(VirtualMachineError::NoOutputBuiltin)
+    }
+
+    /// Returns the pages and attributes added to the output builtin, as found in the additional
+    /// data of a Cairo PIE. Returns None if the program doesn't use the output builtin.
+    pub fn get_output_builtin_additional_data(&self) -> Option<OutputBuiltinAdditionalData> {
+        self.builtin_runners
+            .iter()
+            .find_map(|(_, builtin_runner)| match builtin_runner {
+                BuiltinRunner::Output(output_builtin) => Some(output_builtin.get_additional_data()),
+                _ => None,
+            })
+    }
+
+    /// Registers the hints of a program whose code was loaded into memory at `program_base`, so
+    /// that they are executed when the pc reaches them. The program's data is not written by
+    /// this method.
+    pub fn load_program(&mut self, program: Program, program_base: Relocatable) {
+        self.loaded_programs.push((program_base, program));
+    }
     pub fn disable_trace(&mut self) {
-        self.trace = None
+        self.trace = None;
+        self.streaming_trace = None;
+    }
+
+    /// Writes the trace to the file at `path` in chunks of `chunk_size` entries while the program
+    /// runs, instead of keeping it in memory. The trace must be enabled.
+    /// The streamed trace can't be relocated by `CairoRunner::relocate`, it is relocated while
+    /// being written by `cairo_run::write_streamed_binary_trace`.
+    pub fn enable_streaming_trace(
+        &mut self,
+        path: &Path,
+        chunk_size: usize,
+    ) -> Result<(), TraceError> {
+        if self.trace.is_none() {
+            return Err(TraceError::TraceNotEnabled);
+        }
+        self.streaming_trace = Some(StreamingTrace::create(path, chunk_size)?);
+        Ok(())
+    }
+
+    /// Starts recording, for each memory cell, the step and source of its first write and of
+    /// its later reads. See `MemoryAccessLog` for the accesses that are recorded.
+    pub fn enable_memory_access_log(&mut self) {
+        self.memory.access_log = Some(RefCell::new(MemoryAccessLog::new()));
+    }
+
+    /// Returns the memory access log, if it was enabled with `enable_memory_access_log`.
+    pub fn get_memory_access_log(&self) -> Option<Ref<MemoryAccessLog>> {
+        self.memory
+            .access_log
+            .as_ref()
+            .map(|access_log| access_log.borrow())
+    }
+
+    /// Starts counting the executions of each hint, and measuring the time they took.
+    /// See `HintStats`.
+    pub fn enable_hint_stats(&mut self) {
+        self.hint_stats = Some(HintStats::new());
+    }
+
+    /// Returns the hint stats, if they were enabled with `enable_hint_stats`.
+    pub fn get_hint_stats(&self) -> Option<&HintStats> {
+        self.hint_stats.as_ref()
+    }
+
+    /// Returns the number of entries in the trace, including the ones streamed to disk.
+    pub fn get_trace_len(&self) -> Option<usize> {
+        let trace_len = self.trace.as_ref()?.len();
+        Some(match &self.streaming_trace {
+            Some(streaming_trace) => streaming_trace.flushed_entries() + trace_len,
+            None => trace_len,
+        })
     }
 
     #[doc(hidden)]
@@ -968,6 +1350,65 @@ impl VirtualMachine {
     pub fn compute_effective_sizes(&mut self) -> &Vec<usize> {
         self.segments.compute_effective_sizes(&self.memory)
     }
+
+    pub fn get_registers(&self) -> Registers {
+        Registers {
+            pc: self.run_context.pc,
+            ap: self.get_ap(),
+            fp: self.get_fp(),
+        }
+    }
+
+    /// Returns the number of steps executed so far.
+    pub fn get_current_step(&self) -> usize {
+        self.current_step
+    }
+
+    /// Returns the segments of the memory, followed by the temporary segments (-1, -2, ...).
+    pub fn get_segments(&self) -> Vec<SegmentInfo> {
+        let segments = self
+            .memory
+            .data
+            .iter()
+            .enumerate()
+            .map(|(index, segment)| SegmentInfo {
+                index: index as isize,
+                len: segment.len(),
+                size: self.segments.get_segment_size(index),
+            });
+        let temporary_segments =
+            self.memory
+                .temp_data
+                .iter()
+                .enumerate()
+                .map(|(index, segment)| SegmentInfo {
+                    index: -(index as isize) - 1,
+                    len: segment.len(),
+                    size: None,
+                });
+        segments.chain(temporary_segments).collect()
+    }
+
+    /// Returns the trace entries kept in memory, or None if the trace is disabled.
+    /// When the trace is streamed to disk, only the entries that weren't flushed yet are kept.
+    pub fn get_trace(&self) -> Option<&[TraceEntry]> {
+        self.trace.as_deref()
+    }
+
+    /// Returns the builtins of the run, in the order their segments were added.
+    pub fn get_builtins(&self) -> Vec<BuiltinInfo> {
+        self.builtin_runners
+            .iter()
+            .map(|(_, builtin)| {
+                let (name, (base, stop_ptr)) = builtin.get_memory_segment_addresses();
+                BuiltinInfo {
+                    name,
+                    base: (base, 0).into(),
+                    stop_ptr: stop_ptr.map(|stop_ptr| (base, stop_ptr).into()),
+                }
+            })
+            .collect()
+    }
 }
 
 #[cfg(test)]
@@ -991,23 +1432,29 @@ mod tests {
         vm::{
             errors::memory_errors::MemoryError,
             runners::{
-                builtin_runner::{BitwiseBuiltinRunner, EcOpBuiltinRunner, HashBuiltinRunner},
+                builtin_runner::{
+                    BitwiseBuiltinRunner, EcOpBuiltinRunner, HashBuiltinRunner, PublicMemoryPage,
+                },
                 cairo_runner::CairoRunner,
             },
+            vm_memory::{
+                memory_access_log::{CellAccesses, MemoryAccess},
+                segment_storage::MemorySegment,
+            },
         },
     };
 
     use felt::{felt_str, NewFelt};
-    use std::{collections::HashSet, path::Path};
+    use std::{
+        collections::{BTreeMap, HashSet},
+        path::Path,
+    };
 
     #[test]
     fn get_instruction_encoding_successful_without_imm() {
         let mut vm = vm!();
         vm.memory = memory![((0, 0), 5)];
-        assert_eq!((Felt::new(5), None), {
-            let value = vm.get_instruction_encoding().unwrap();
-            (value.0.into_owned(), value.1)
-        });
+        assert_eq!((5, None), vm.get_instruction_encoding().unwrap());
     }
 
     #[test]
@@ -1019,7 +1466,7 @@ mod tests {
         let (num, imm) = vm
             .get_instruction_encoding()
             .expect("Unexpected error on get_instruction_encoding");
-        assert_eq!(num.as_ref(), &Felt::new(5));
+        assert_eq!(num, 5);
         assert_eq!(
             imm.map(Cow::into_owned),
             Some(MaybeRelocatable::Int(Felt::new(6)))
@@ -1633,7 +2080,7 @@ mod tests {
         vm.run_context.ap = 5;
         vm.run_context.fp = 6;
 
-        assert_eq!(Ok(()), vm.update_registers(instruction, operands));
+        assert_eq!(Ok(()), vm.update_registers(&instruction, operands));
         assert_eq!(vm.run_context.pc, Relocatable::from((0, 5)));
         assert_eq!(vm.run_context.ap, 5);
         assert_eq!(vm.run_context.fp, 6);
@@ -1666,7 +2113,7 @@ mod tests {
         let mut vm = vm!();
         run_context!(vm, 4, 5, 6);
 
-        assert_eq!(Ok(()), vm.update_registers(instruction, operands));
+        assert_eq!(Ok(()), vm.update_registers(&instruction, operands));
         assert_eq!(vm.run_context.pc, Relocatable::from((0, 12)));
         assert_eq!(vm.run_context.ap, 7);
         assert_eq!(vm.run_context.fp, 11);
@@ -2317,7 +2764,7 @@ mod tests {
             vm.segments.add(&mut vm.memory);
         }
 
-        vm.memory.data.push(Vec::new());
+        vm.memory.data.push(MemorySegment::new());
         let dst_addr = MaybeRelocatable::from((1, 0));
         let dst_addr_value = MaybeRelocatable::Int(Felt::new(5));
         let op0_addr = MaybeRelocatable::from((1, 1));
@@ -2368,7 +2815,7 @@ mod tests {
             vm.segments.add(&mut vm.memory);
         }
         vm.accessed_addresses = Some(Vec::new());
-        vm.memory.data.push(Vec::new());
+        vm.memory.data.push(MemorySegment::new());
         let dst_addr = mayberelocatable!(1, 0);
         let dst_addr_value = mayberelocatable!(6);
         let op0_addr = mayberelocatable!(1, 1);
@@ -3107,6 +3554,21 @@ mod tests {
         let (operands, operands_mem_address, _) = vm.compute_operands(&instruction).unwrap();
         assert_eq!(operands, expected_operands);
         assert_eq!(operands_mem_address, expected_operands_mem_addresses);
+
+        // The result deduced by the bitwise builtin is logged as written by it
+        vm.enable_memory_access_log();
+        vm.run_instruction(&instruction).unwrap();
+        assert_eq!(
+            vm.get_memory_access_log()
+                .unwrap()
+                .get_write(&(2, 2).into()),
+            Some(&MemoryAccess {
+                step: 0,
+                source: AccessSource::BuiltinDeduction {
+                    builtin: String::from("bitwise")
+                }
+            })
+        );
     }
 
     #[test]
@@ -3346,6 +3808,22 @@ mod tests {
         assert_eq!(vm.verify_auto_deductions(), Ok(()));
     }
 
+    #[test]
+    fn verify_auto_deductions_unallocated_builtin_segment() {
+        let mut builtin = BitwiseBuiltinRunner::new(&BitwiseInstanceDef::default(), true);
+        builtin.base = 5;
+        let mut vm = vm!();
+        vm.builtin_runners
+            .push((String::from("bitwise"), builtin.into()));
+        vm.memory = memory![((2, 0), 12), ((2, 1), 10)];
+        assert_eq!(
+            vm.verify_auto_deductions(),
+            Err(VirtualMachineError::MemoryError(
+                MemoryError::UnallocatedSegment(5, 3)
+            ))
+        );
+    }
+
     #[test]
     fn can_get_return_values() {
         let mut vm = vm!();
@@ -3371,7 +3849,7 @@ mod tests {
     }
 
     /*
-    Program used for this test:
+    Program run by the tests below:
     from starkware.cairo.common.alloc import alloc
     func main{}():
         let vec: felt* = alloc()
@@ -3390,18 +3868,8 @@ mod tests {
         RelocatableValue(segment_index=1, offset=0): RelocatableValue(segment_index=2, offset=0),
         RelocatableValue(segment_index=1, offset=1): RelocatableValue(segment_index=3, offset=0)}
      */
-
-    #[test]
-    fn test_step_for_preset_memory_with_alloc_hint() {
+    fn alloc_hint_program_vm() -> VirtualMachine {
         let mut vm = vm!(true);
-        let hint_data_dictionary = HashMap::from([(
-            0_usize,
-            vec![any_box!(HintProcessorData::new_default(
-                "memory[ap] = segments.add()".to_string(),
-                HashMap::new(),
-            ))],
-        )]);
-
         //Initialzie registers
         run_context!(vm, 3, 2, 2);
 
@@ -3410,9 +3878,6 @@ mod tests {
             vm.segments.add(&mut vm.memory);
         }
         //Initialize memory
-
-        let mut hint_processor = BuiltinHintProcessor::new_empty();
-
         vm.memory = memory![
             ((0, 0), 290341444919459839_i64),
             ((0, 1), 1),
@@ -3432,8 +3897,19 @@ mod tests {
             ((1, 0), (2, 0)),
             ((1, 1), (3, 0))
         ];
+        vm
+    }
 
-        //Run Steps
+    // Runs the 6 steps of the program, whose alloc hint is at pc 0
+    fn run_alloc_hint_program(vm: &mut VirtualMachine) {
+        let hint_data_dictionary = HashMap::from([(
+            0_usize,
+            vec![any_box!(HintProcessorData::new_default(
+                "memory[ap] = segments.add()".to_string(),
+                HashMap::new(),
+            ))],
+        )]);
+        let mut hint_processor = BuiltinHintProcessor::new_empty();
         for _ in 0..6 {
             assert_eq!(
                 vm.step(
@@ -3445,6 +3921,14 @@ mod tests {
                 Ok(())
             );
         }
+    }
+
+    #[test]
+    fn test_step_for_preset_memory_with_alloc_hint() {
+        let mut vm = alloc_hint_program_vm();
+
+        //Run Steps
+        run_alloc_hint_program(&mut vm);
         //Compare trace
         let trace = vm.trace.unwrap();
         trace_check!(
@@ -3472,6 +3956,98 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_step_for_preset_memory_with_alloc_hint_access_log() {
+        // Same program as test_step_for_preset_memory_with_alloc_hint
+        let mut vm = alloc_hint_program_vm();
+        vm.enable_memory_access_log();
+
+        run_alloc_hint_program(&mut vm);
+
+        let access_log = vm.get_memory_access_log().unwrap();
+        // The frame pushed by the call at step 0
+        assert_eq!(
+            access_log.get_write(&(1, 2).into()),
+            Some(&MemoryAccess {
+                step: 0,
+                source: AccessSource::Instruction
+            })
+        );
+        assert_eq!(
+            access_log.get_write(&(1, 3).into()),
+            Some(&MemoryAccess {
+                step: 0,
+                source: AccessSource::Instruction
+            })
+        );
+        // The pointer to the new segment is written by the alloc hint, and read by the
+        // instruction that writes to the new segment
+        assert_eq!(
+            access_log.get(&(1, 4).into()),
+            Some(&CellAccesses {
+                write: Some(MemoryAccess {
+                    step: 1,
+                    source: AccessSource::Hint {
+                        pc: (0, 0).into(),
+                        hint_index: 0
+                    }
+                }),
+                reads: vec![MemoryAccess {
+                    step: 4,
+                    source: AccessSource::Instruction
+                }]
+            })
+        );
+        assert_eq!(
+            access_log.get_write(&(2, 0).into()),
+            Some(&MemoryAccess {
+                step: 4,
+                source: AccessSource::AutoDeduction
+            })
+        );
+        // The return pc is read by the instruction at step 3, and once by the ret at step 5,
+        // even if it is both its op0 and its op1
+        assert_eq!(
+            access_log.get(&(1, 1).into()),
+            Some(&CellAccesses {
+                write: None,
+                reads: vec![
+                    MemoryAccess {
+                        step: 3,
+                        source: AccessSource::Instruction
+                    },
+                    MemoryAccess {
+                        step: 5,
+                        source: AccessSource::Instruction
+                    }
+                ]
+            })
+        );
+        // Program cells were written before the log was enabled
+        assert_eq!(access_log.get_write(&(0, 0).into()), None);
+    }
+
+    #[test]
+    fn test_step_for_preset_memory_with_alloc_hint_stats() {
+        // Same program as test_step_for_preset_memory_with_alloc_hint
+        let mut vm = alloc_hint_program_vm();
+        assert!(vm.get_hint_stats().is_none());
+        vm.enable_hint_stats();
+
+        run_alloc_hint_program(&mut vm);
+
+        // The alloc hint is run once, when the pc reaches (0, 0) at step 1
+        let hint_stats = vm.get_hint_stats().unwrap();
+        let location = &hint_stats.by_location()[&((0, 0).into(), 0)];
+        assert_eq!(
+            location.code.as_deref(),
+            Some("memory[ap] = segments.add()")
+        );
+        assert!(!location.extra);
+        assert_eq!(location.timing.calls, 1);
+        assert_eq!(hint_stats.total().calls, 1);
+    }
+
     #[test]
     fn test_get_builtin_runners() {
         let mut vm = vm!();
@@ -3488,6 +4064,24 @@ mod tests {
         assert_eq!(builtins[1].0, "bitwise");
     }
 
+    #[test]
+    fn get_output_builtin_additional_data() {
+        let mut vm = vm!();
+        assert_eq!(vm.get_output_builtin_additional_data(), None);
+
+        let mut output_builtin = OutputBuiltinRunner::new(true);
+        output_builtin.add_page(1, (0, 1).into(), 2).unwrap();
+        vm.builtin_runners
+            .push((String::from("output"), output_builtin.into()));
+        assert_eq!(
+            vm.get_output_builtin_additional_data(),
+            Some(OutputBuiltinAdditionalData {
+                pages: BTreeMap::from([(1, PublicMemoryPage { start: 1, size: 2 })]),
+                attributes: BTreeMap::new(),
+            })
+        );
+    }
+
     #[test]
     fn disable_trace() {
         let mut vm = VirtualMachine::new(true);
@@ -3681,6 +4275,28 @@ mod tests {
         );
     }
 
+    #[test]
+    fn decode_current_instruction_cached() {
+        let mut vm = vm!();
+        vm.memory = memory![((0, 0), 0x480680017fff8000_i64), ((0, 1), 5)];
+        let instruction = vm.decode_current_instruction().unwrap();
+        assert_eq!(instruction.imm, Some(Felt::new(5)));
+        assert!(Rc::ptr_eq(
+            &instruction,
+            &vm.decode_current_instruction().unwrap()
+        ));
+
+        // Replacing the program invalidates the cached instruction
+        vm.memory = memory![((0, 0), 0x480680017fff8000_i64), ((0, 1), 6)];
+        let instruction = vm.decode_current_instruction().unwrap();
+        assert_eq!(instruction.imm, Some(Felt::new(6)));
+        vm.memory = memory![((0, 0), ("112233445566778899", 16))];
+        assert_eq!(
+            vm.decode_current_instruction(),
+            Err(VirtualMachineError::InvalidInstructionEncoding)
+        );
+    }
+
     #[test]
     fn add_relocation_rule_test() {
         let mut vm = vm!();
@@ -1,11 +1,18 @@
 use crate::{
     types::relocatable::{MaybeRelocatable, Relocatable},
     utils::from_relocatable_to_indexes,
-    vm::errors::{memory_errors::MemoryError, vm_errors::VirtualMachineError},
+    vm::{
+        errors::{memory_errors::MemoryError, vm_errors::VirtualMachineError},
+        vm_memory::{
+            memory_access_log::MemoryAccessLog,
+            segment_storage::{MemoryCell, MemorySegment, MemoryValueRef},
+        },
+    },
 };
 use felt::Felt;
 use std::{
     borrow::Cow,
+    cell::RefCell,
     collections::{HashMap, HashSet},
     mem::swap,
 };
@@ -16,27 +23,34 @@ pub struct ValidationRule(
 );
 
 pub struct Memory {
-    pub data: Vec<Vec<Option<MaybeRelocatable>>>,
-    pub temp_data: Vec<Vec<Option<MaybeRelocatable>>>,
+    pub(crate) data: Vec<MemorySegment>,
+    pub(crate) temp_data: Vec<MemorySegment>,
     // relocation_rules's keys map to temp_data's indices and therefore begin at
     // zero; that is, segment_index = -1 maps to key 0, -2 to key 1...
     pub(crate) relocation_rules: HashMap<usize, Relocatable>,
+    // The relocation rules that were already applied by `relocate_memory`, indexed like
+    // `relocation_rules`
+    pub(crate) applied_relocation_rules: HashMap<usize, Relocatable>,
     pub validated_addresses: HashSet<MaybeRelocatable>,
     validation_rules: HashMap<usize, ValidationRule>,
+    // Reads are made through a shared reference, so the log needs interior mutability
+    pub(crate) access_log: Option<RefCell<MemoryAccessLog>>,
 }
 
 impl Memory {
     pub fn new() -> Memory {
         Memory {
-            data: Vec::<Vec<Option<MaybeRelocatable>>>::new(),
-            temp_data: Vec::<Vec<Option<MaybeRelocatable>>>::new(),
+            data: Vec::<MemorySegment>::new(),
+            temp_data: Vec::<MemorySegment>::new(),
             relocation_rules: HashMap::new(),
+            applied_relocation_rules: HashMap::new(),
             validated_addresses: HashSet::<MaybeRelocatable>::new(),
             validation_rules: HashMap::new(),
+            access_log: None,
         }
     }
     ///Inserts an MaybeRelocatable value into an address given by a MaybeRelocatable::Relocatable
-    /// Will panic if the segment index given by the address corresponds to a non-allocated segment
+    /// Will return an UnallocatedSegment error if the segment index given by the address corresponds to a non-allocated segment
     /// If the address isnt contiguous with previously inserted data, memory gaps will be represented by inserting None values
     pub fn insert<'a, K: 'a, V: 'a>(&mut self, key: &'a K, val: &'a V) -> Result<(), MemoryError>
     where
@@ -61,22 +75,21 @@ impl Memory {
             .get_mut(value_index)
             .ok_or(MemoryError::UnallocatedSegment(value_index, data_len))?;
 
-        //Check if the element is inserted next to the last one on the segment
-        //Forgoing this check would allow data to be inserted in a different index
-        if segment.len() <= value_offset {
-            segment.resize(value_offset + 1, None);
-        }
-        // At this point there's *something* in there
-
-        match segment[value_offset] {
-            None => segment[value_offset] = Some(val),
-            Some(ref current_value) => {
-                if current_value != &val {
+        let cell = val.into();
+        match segment.get_cell(value_offset) {
+            None => {
+                segment.set(value_offset, cell);
+                if let Some(access_log) = &self.access_log {
+                    access_log.borrow_mut().record_write(relocatable);
+                }
+            }
+            Some(current_cell) => {
+                if current_cell != &cell {
                     //Existing memory cannot be changed
                     return Err(MemoryError::InconsistentMemory(
                         relocatable.into(),
-                        current_value.to_owned(),
-                        val,
+                        current_cell.to_maybe_relocatable(),
+                        cell.to_maybe_relocatable(),
                     ));
                 }
             }
@@ -96,19 +109,98 @@ impl Memory {
             .try_into()
             .map_err(|_| MemoryError::AddressNotRelocatable)?;
 
-        let data = if relocatable.segment_index.is_negative() {
+        Ok(self.read_cell(&relocatable).map(|cell| match cell {
+            MemoryCell::Relocatable(addr) => Cow::Owned(self.relocate_value(*addr).into()),
+            cell => cell.value(),
+        }))
+    }
+
+    /// Retrieves a value from memory (either normal or temporary) and applies relocation rules,
+    /// like `get`, but borrows it from its cell. Small integers aren't turned into a `Felt`, so
+    /// this is the cheapest way to read values that are expected to be small.
+    #[inline]
+    pub fn get_ref(&self, key: &Relocatable) -> Option<MemoryValueRef> {
+        Some(match self.read_cell(key)? {
+            MemoryCell::Small(value, _) => MemoryValueRef::Small(*value),
+            MemoryCell::Big(value) => match value.as_ref() {
+                MaybeRelocatable::Int(value) => MemoryValueRef::Int(value),
+                MaybeRelocatable::RelocatableValue(addr) => {
+                    MemoryValueRef::Relocatable(self.relocate_value(*addr))
+                }
+            },
+            MemoryCell::Relocatable(addr) => {
+                MemoryValueRef::Relocatable(self.relocate_value(*addr))
+            }
+        })
+    }
+
+    /// Returns the segment with index `segment_index`, negative indexes being temporary segments.
+    pub fn get_segment(&self, segment_index: isize) -> Option<&MemorySegment> {
+        let data = if segment_index.is_negative() {
             &self.temp_data
         } else {
             &self.data
         };
-        let (i, j) = from_relocatable_to_indexes(&relocatable);
-        if data.len() > i && data[i].len() > j {
-            if let Some(ref element) = data[i][j] {
-                return Ok(Some(self.relocate_value(element)));
-            }
+        let (i, _) = from_relocatable_to_indexes(&(segment_index, 0).into());
+        data.get(i)
+    }
+
+    /// Returns the cells of each segment, None being the cells that weren't written, as they
+    /// were stored before segments became `MemorySegment`s. The cells are copied, so prefer
+    /// `get_segment` when reading large segments.
+    pub fn data(&self) -> Vec<Vec<Option<MaybeRelocatable>>> {
+        self.data
+            .iter()
+            .map(|segment| segment.iter().collect())
+            .collect()
+    }
+
+    /// Returns the cells of each temporary segment, like `data`.
+    pub fn temp_data(&self) -> Vec<Vec<Option<MaybeRelocatable>>> {
+        self.temp_data
+            .iter()
+            .map(|segment| segment.iter().collect())
+            .collect()
+    }
+
+    /// Returns the amount of segments, not counting the temporary ones.
+    pub fn num_segments(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn num_temp_segments(&self) -> usize {
+        self.temp_data.len()
+    }
+
+    // Returns the cell at `key` like `get_cell`, recording the read in the access log
+    #[inline]
+    fn read_cell(&self, key: &Relocatable) -> Option<&MemoryCell> {
+        let cell = self.get_cell(key)?;
+        if let Some(access_log) = &self.access_log {
+            access_log.borrow_mut().record_hint_read(*key);
         }
+        Some(cell)
+    }
 
-        Ok(None)
+    /// Returns the cell at `key` as it is stored, without applying relocation rules.
+    #[inline]
+    pub(crate) fn get_cell(&self, key: &Relocatable) -> Option<&MemoryCell> {
+        let data = if key.segment_index.is_negative() {
+            &self.temp_data
+        } else {
+            &self.data
+        };
+        let (i, j) = from_relocatable_to_indexes(key);
+        data.get(i)?.get_cell(j)
+    }
+
+    /// Returns the value at `key` if it's an integer that fits in a u64, without building a `Felt`.
+    /// Relocation rules don't apply, as they only change relocatable values.
+    pub(crate) fn get_u64(&self, key: &Relocatable) -> Option<u64> {
+        match self.get_cell(key)? {
+            MemoryCell::Small(value, _) => Some(*value),
+            _ => None,
+        }
     }
 
     /// Relocates the memory according to the relocation rules and clears `self.relocaction_rules`.
@@ -122,31 +214,30 @@ impl Memory {
         swap(&mut self.data, &mut prev_data);
         swap(&mut self.temp_data, &mut prev_temp_data);
 
-        let data_iter = prev_data
-            .into_iter()
-            .enumerate()
-            .flat_map(|(segment_index, segment_data)| {
-                segment_data
-                    .into_iter()
-                    .enumerate()
-                    .map(move |(cell_offset, cell_data)| {
-                        (
-                            Relocatable::from((segment_index as isize, cell_offset)),
-                            cell_data,
-                        )
-                    })
-            })
-            .chain(prev_temp_data.into_iter().enumerate().flat_map(
-                |(segment_index, segment_data)| {
-                    let segment_index = -(segment_index as isize) - 1;
+        let data_iter =
+            prev_data
+                .iter()
+                .enumerate()
+                .flat_map(|(segment_index, segment_data)| {
                     segment_data
-                        .into_iter()
-                        .enumerate()
+                        .iter_written()
                         .map(move |(cell_offset, cell_data)| {
-                            (Relocatable::from((segment_index, cell_offset)), cell_data)
+                            (
+                                Relocatable::from((segment_index as isize, cell_offset)),
+                                cell_data,
+                            )
                         })
-                },
-            ));
+                })
+                .chain(prev_temp_data.iter().enumerate().flat_map(
+                    |(segment_index, segment_data)| {
+                        let segment_index = -(segment_index as isize) - 1;
+                        segment_data
+                            .iter_written()
+                            .map(move |(cell_offset, cell_data)| {
+                                (Relocatable::from((segment_index, cell_offset)), cell_data)
+                            })
+                    },
+                ));
         for (addr, value) in data_iter {
             // After the following check, addr.segment_index cannot be negative, therefore it is
             // safe to cast to `usize`.
@@ -154,11 +245,6 @@ impl Memory {
                 continue;
             }
 
-            let value = match value {
-                Some(x) => x,
-                None => continue,
-            };
-
             let new_addr: Relocatable = self
                 .relocate_value(&MaybeRelocatable::RelocatableValue(addr))
                 .into_owned()
@@ -167,21 +253,27 @@ impl Memory {
 
             if new_addr.segment_index as usize >= self.data.len() {
                 self.data
-                    .resize(new_addr.segment_index as usize + 1, Vec::new());
+                    .resize(new_addr.segment_index as usize + 1, MemorySegment::new());
             }
 
-            let segment_data = &mut self.data[new_addr.segment_index as usize];
-            if new_addr.offset >= segment_data.len() {
-                segment_data.resize(new_addr.offset + 1, None);
-            }
-
-            segment_data[new_addr.offset] = Some(new_value);
+            self.data[new_addr.segment_index as usize].set(new_addr.offset, new_value.into());
         }
 
-        self.relocation_rules.clear();
+        self.applied_relocation_rules
+            .extend(self.relocation_rules.drain());
         Ok(())
     }
 
+    /// Returns the address each temporary segment was (or will be) relocated to, indexed by
+    /// temporary segment index (-1, -2, ...).
+    pub fn get_relocation_rules(&self) -> HashMap<isize, Relocatable> {
+        self.applied_relocation_rules
+            .iter()
+            .chain(self.relocation_rules.iter())
+            .map(|(key, dst_ptr)| (-(*key as isize) - 1, *dst_ptr))
+            .collect()
+    }
+
     /// Add a new relocation rule.
     ///
     /// Will return an error if any of the following conditions are not met:
@@ -216,20 +308,21 @@ impl Memory {
     //Gets the value from memory address.
     //If the value is an MaybeRelocatable::Int(Bigint) return &Bigint
     //else raises Err
+    #[inline]
     pub fn get_integer(&self, key: &Relocatable) -> Result<Cow<Felt>, VirtualMachineError> {
-        match self.get(key).map_err(VirtualMachineError::MemoryError)? {
+        // Integers are always borrowed, only relocatable values are built by `value`
+        match self.read_cell(key).map(MemoryCell::value) {
             Some(Cow::Borrowed(MaybeRelocatable::Int(int))) => Ok(Cow::Borrowed(int)),
-            Some(Cow::Owned(MaybeRelocatable::Int(int))) => Ok(Cow::Owned(int)),
             _ => Err(VirtualMachineError::ExpectedInteger(
                 MaybeRelocatable::from(key),
             )),
         }
     }
 
+    #[inline]
     pub fn get_relocatable(&self, key: &Relocatable) -> Result<Relocatable, VirtualMachineError> {
-        match self.get(key).map_err(VirtualMachineError::MemoryError)? {
-            Some(Cow::Borrowed(MaybeRelocatable::RelocatableValue(rel))) => Ok(*rel),
-            Some(Cow::Owned(MaybeRelocatable::RelocatableValue(rel))) => Ok(rel),
+        match self.get_ref(key) {
+            Some(MemoryValueRef::Relocatable(rel)) => Ok(rel),
             _ => Err(VirtualMachineError::ExpectedRelocatable(
                 MaybeRelocatable::from(key),
             )),
@@ -388,7 +481,7 @@ mod memory_tests {
     ) -> Result<Memory, MemoryError> {
         let mut memory = Memory::new();
         for _ in 0..num_segements {
-            memory.data.push(Vec::new());
+            memory.data.push(MemorySegment::new());
         }
         for (key, val) in key_val_list.iter() {
             memory.insert(key, val)?;
@@ -401,7 +494,7 @@ mod memory_tests {
         let key = MaybeRelocatable::from((0, 0));
         let val = MaybeRelocatable::from(Felt::new(5));
         let mut memory = Memory::new();
-        memory.data.push(Vec::new());
+        memory.data.push(MemorySegment::new());
         memory.insert(&key, &val).unwrap();
         assert_eq!(
             memory.get(&key).unwrap().unwrap().as_ref(),
@@ -409,10 +502,50 @@ mod memory_tests {
         );
     }
 
+    #[test]
+    fn insert_and_get_at_large_offset() {
+        let key = mayberelocatable!(0, 1 << 32);
+        let mut memory = Memory::new();
+        memory.data.push(MemorySegment::new());
+        memory.insert(&key, &mayberelocatable!(5)).unwrap();
+        assert_eq!(
+            memory.get(&key).unwrap().unwrap().as_ref(),
+            &mayberelocatable!(5)
+        );
+        assert_eq!(memory.get(&mayberelocatable!(0, 1 << 31)).unwrap(), None);
+        assert_eq!(memory.data[0].len(), (1 << 32) + 1);
+    }
+
+    #[test]
+    fn insert_big_felt_inconsistent() {
+        let key = mayberelocatable!(0, 0);
+        let big = felt_str!(
+            "3618502788666131213697322783095070105623107215331596699973092056135872020480"
+        );
+        let mut memory = Memory::new();
+        memory.data.push(MemorySegment::new());
+        memory
+            .insert(&key, &MaybeRelocatable::from(big.clone()))
+            .unwrap();
+        assert_eq!(
+            memory.insert(&key, &MaybeRelocatable::from(big.clone())),
+            Ok(())
+        );
+        assert_eq!(memory.get_integer(&(0, 0).into()).unwrap().as_ref(), &big);
+        assert_eq!(
+            memory.insert(&key, &mayberelocatable!(1)),
+            Err(MemoryError::InconsistentMemory(
+                key,
+                MaybeRelocatable::from(big),
+                mayberelocatable!(1)
+            ))
+        );
+    }
+
     #[test]
     fn get_valuef_from_temp_segment() {
         let mut memory = Memory::new();
-        memory.temp_data = vec![vec![None, None, Some(mayberelocatable!(8))]];
+        memory.temp_data = vec![vec![None, None, Some(mayberelocatable!(8))].into()];
         assert_eq!(
             memory
                 .get(&mayberelocatable!(-1, 2))
@@ -428,10 +561,10 @@ mod memory_tests {
         let key = MaybeRelocatable::from((-1, 3));
         let val = MaybeRelocatable::from(Felt::new(8));
         let mut memory = Memory::new();
-        memory.temp_data.push(Vec::new());
+        memory.temp_data.push(MemorySegment::new());
         memory.insert(&key, &val).unwrap();
         assert_eq!(
-            memory.temp_data[0][3],
+            memory.temp_data[0].get(3),
             Some(MaybeRelocatable::from(Felt::new(8)))
         );
     }
@@ -441,7 +574,7 @@ mod memory_tests {
         let key = MaybeRelocatable::from((-1, 0));
         let val = MaybeRelocatable::from(Felt::new(5));
         let mut memory = Memory::new();
-        memory.temp_data.push(Vec::new());
+        memory.temp_data.push(MemorySegment::new());
         memory.insert(&key, &val).unwrap();
         assert_eq!(
             memory.get(&key).unwrap().unwrap().as_ref(),
@@ -453,7 +586,7 @@ mod memory_tests {
     fn insert_and_get_from_temp_segment_failed() {
         let key = mayberelocatable!(-1, 1);
         let mut memory = Memory::new();
-        memory.temp_data = vec![vec![None, Some(mayberelocatable!(8))]];
+        memory.temp_data = vec![vec![None, Some(mayberelocatable!(8))].into()];
         assert_eq!(
             memory.insert(&key, &mayberelocatable!(5)),
             Err(MemoryError::InconsistentMemory(
@@ -505,7 +638,7 @@ mod memory_tests {
         let val_a = MaybeRelocatable::from(Felt::new(5));
         let val_b = MaybeRelocatable::from(Felt::new(6));
         let mut memory = Memory::new();
-        memory.data.push(Vec::new());
+        memory.data.push(MemorySegment::new());
         memory
             .insert(&key, &val_a)
             .expect("Unexpected memory insert fail");
@@ -535,7 +668,7 @@ mod memory_tests {
         let key_b = MaybeRelocatable::from((0, 2));
         let val = MaybeRelocatable::from(Felt::new(5));
         let mut memory = Memory::new();
-        memory.data.push(Vec::new());
+        memory.data.push(MemorySegment::new());
         memory.insert(&key_a, &val).unwrap();
         memory.insert(&key_b, &val).unwrap();
         assert_eq!(memory.get(&key_b).unwrap().unwrap().as_ref(), &val);
@@ -547,7 +680,7 @@ mod memory_tests {
         let key_b = MaybeRelocatable::from((0, 5));
         let val = MaybeRelocatable::from(Felt::new(5));
         let mut memory = Memory::new();
-        memory.data.push(Vec::new());
+        memory.data.push(MemorySegment::new());
         memory.insert(&key_a, &val).unwrap();
         memory.insert(&key_b, &val).unwrap();
         assert_eq!(memory.get(&key_b).unwrap().unwrap().as_ref(), &val);
@@ -757,7 +890,7 @@ mod memory_tests {
     #[test]
     fn insert_and_get_temporary_succesful() {
         let mut memory = Memory::new();
-        memory.temp_data.push(Vec::new());
+        memory.temp_data.push(MemorySegment::new());
 
         let key = MaybeRelocatable::from((-1, 0));
         let val = MaybeRelocatable::from(Felt::new(5));
@@ -766,6 +899,87 @@ mod memory_tests {
         assert_eq!(memory.get(&key).unwrap().unwrap().as_ref(), &val);
     }
 
+    #[test]
+    fn get_ref_borrows_values() {
+        let big = felt_str!("18446744073709551616");
+        let mut memory = memory![((0, 0), 5), ((0, 1), (-1, 2)), ((0, 3), (1, 0))];
+        memory.insert_value(&(0, 2).into(), big.clone()).unwrap();
+        memory.temp_data.push(MemorySegment::new());
+        memory
+            .add_relocation_rule((-1, 0).into(), (1, 3).into())
+            .unwrap();
+
+        assert_eq!(
+            memory.get_ref(&(0, 0).into()),
+            Some(MemoryValueRef::Small(5))
+        );
+        assert_eq!(memory.get_ref(&(0, 0).into()).unwrap().to_usize(), Some(5));
+        assert_eq!(
+            memory.get_ref(&(0, 1).into()),
+            Some(MemoryValueRef::Relocatable((1, 5).into()))
+        );
+        assert_eq!(
+            memory.get_ref(&(0, 2).into()),
+            Some(MemoryValueRef::Int(&big))
+        );
+        assert_eq!(memory.get_ref(&(0, 2).into()).unwrap().to_u64(), None);
+        assert_eq!(
+            memory
+                .get_ref(&(0, 3).into())
+                .unwrap()
+                .to_maybe_relocatable(),
+            mayberelocatable!(1, 0)
+        );
+        assert_eq!(memory.get_ref(&(0, 4).into()), None);
+        assert_eq!(memory.get_ref(&(1, 0).into()), None);
+    }
+
+    #[test]
+    fn get_segment() {
+        let mut memory = memory![((0, 0), 5), ((1, 1), 6)];
+        memory.temp_data.push(MemorySegment::new());
+
+        assert_eq!(memory.num_segments(), 2);
+        assert_eq!(memory.num_temp_segments(), 1);
+        assert_eq!(
+            memory.get_segment(1),
+            Some(&MemorySegment::from(vec![None, Some(mayberelocatable!(6))]))
+        );
+        assert_eq!(memory.get_segment(-1), Some(&MemorySegment::new()));
+        assert_eq!(memory.get_segment(2), None);
+        assert_eq!(memory.get_segment(-2), None);
+    }
+
+    #[test]
+    fn data_and_temp_data() {
+        let mut memory = memory![((0, 0), 5), ((1, 1), (0, 0))];
+        memory
+            .temp_data
+            .push(MemorySegment::from(vec![None, Some(mayberelocatable!(7))]));
+
+        assert_eq!(
+            memory.data(),
+            vec![
+                vec![Some(mayberelocatable!(5))],
+                vec![None, Some(mayberelocatable!(0, 0))]
+            ]
+        );
+        assert_eq!(
+            memory.temp_data(),
+            vec![vec![None, Some(mayberelocatable!(7))]]
+        );
+    }
+
+    #[test]
+    fn get_integer_borrows_small_values() {
+        let memory = memory![((0, 0), 5)];
+
+        assert!(matches!(
+            memory.get_integer(&(0, 0).into()),
+            Ok(Cow::Borrowed(value)) if value == &Felt::new(5)
+        ));
+    }
+
     #[test]
     fn add_relocation_rule() {
         let mut memory = Memory::new();
@@ -969,10 +1183,11 @@ mod memory_tests {
             ((1, 2), (-1, 2))
         ];
         memory.temp_data = vec![vec![
-            mayberelocatable!(7).into(),
-            mayberelocatable!(8).into(),
-            mayberelocatable!(9).into(),
-        ]];
+            Some(mayberelocatable!(7)),
+            Some(mayberelocatable!(8)),
+            Some(mayberelocatable!(9)),
+        ]
+        .into()];
         memory
             .add_relocation_rule((-1, 0).into(), (2, 1).into())
             .unwrap();
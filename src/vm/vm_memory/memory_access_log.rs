@@ -0,0 +1,248 @@
+use crate::types::relocatable::Relocatable;
+use serde::{ser::SerializeStruct, Serialize, Serializer};
+use std::{collections::HashMap, io};
+
+/// What caused a memory access.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AccessSource {
+    /// The access was made outside of a step, ie: by the runner while initializing the segments.
+    Runner,
+    /// The cell is an operand of the instruction that was run. Written cells are destinations
+    /// deduced from the instruction (ie: `[ap] = [fp - 3] + 1`, or the frame pushed by a call).
+    Instruction,
+    /// The cell is an operand that the VM deduced from the other operands of the instruction
+    /// (ie: `[fp - 3]` in `5 = [fp - 3] + 1`).
+    AutoDeduction,
+    /// The cell is an operand that was deduced by the builtin which owns its segment.
+    BuiltinDeduction { builtin: String },
+    /// The access was made by the `hint_index`-th hint of the instruction at `pc`.
+    Hint { pc: Relocatable, hint_index: usize },
+}
+
+/// A memory access, made during the step `step`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MemoryAccess {
+    pub step: usize,
+    pub source: AccessSource,
+}
+
+/// The accesses made to a memory cell.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CellAccesses {
+    /// The access that first wrote the cell, the cell can't be written with another value.
+    pub write: Option<MemoryAccess>,
+    /// The reads made after the cell was written, in order.
+    pub reads: Vec<MemoryAccess>,
+}
+
+/// Records which step and source wrote each memory cell, and the reads made to it.
+/// Reads are only recorded for the operands of instructions and the accesses made by hints.
+/// Addresses are not relocated, so cells in temporary segments keep their temporary address.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MemoryAccessLog {
+    cells: HashMap<Relocatable, CellAccesses>,
+    // The step and source of the accesses that are being made, None outside of a step
+    pub(crate) step: usize,
+    pub(crate) source: Option<AccessSource>,
+}
+
+impl MemoryAccessLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the accesses made to the cell at `address`, if any.
+    pub fn get(&self, address: &Relocatable) -> Option<&CellAccesses> {
+        self.cells.get(address)
+    }
+
+    /// Returns the access that first wrote the cell at `address`, if it was written.
+    pub fn get_write(&self, address: &Relocatable) -> Option<&MemoryAccess> {
+        self.get(address)?.write.as_ref()
+    }
+
+    /// Returns every logged cell, ordered by address.
+    pub fn iter(&self) -> impl Iterator<Item = (&Relocatable, &CellAccesses)> {
+        let mut cells: Vec<_> = self.cells.iter().collect();
+        cells.sort_by_key(|(address, _)| (address.segment_index, address.offset));
+        cells.into_iter()
+    }
+
+    pub(crate) fn record_write(&mut self, address: Relocatable) {
+        let access = self.current_access();
+        self.cells
+            .entry(address)
+            .or_default()
+            .write
+            .get_or_insert(access);
+    }
+
+    pub(crate) fn record_read(&mut self, address: Relocatable, source: AccessSource) {
+        let access = MemoryAccess {
+            step: self.step,
+            source,
+        };
+        self.cells.entry(address).or_default().reads.push(access);
+    }
+
+    // Records a read made by the current hint, reads made by anything else are recorded
+    // explicitly with `record_read`
+    pub(crate) fn record_hint_read(&mut self, address: Relocatable) {
+        if let Some(source @ AccessSource::Hint { .. }) = &self.source {
+            self.record_read(address, source.clone());
+        }
+    }
+
+    fn current_access(&self) -> MemoryAccess {
+        MemoryAccess {
+            step: self.step,
+            source: self.source.clone().unwrap_or(AccessSource::Runner),
+        }
+    }
+
+    /// Writes the log as a JSON array, with one object per cell ordered by address:
+    /// `{"address": "1:4", "write": {"step": 3, "source": {"type": "Hint", "pc": "0:10",
+    /// "hint_index": 0}}, "reads": [{"step": 4, "source": {"type": "Instruction"}}]}`
+    pub fn write_json(&self, writer: &mut dyn io::Write) -> Result<(), serde_json::Error> {
+        let cells: Vec<_> = self
+            .iter()
+            .map(|(address, accesses)| LoggedCell { address, accesses })
+            .collect();
+        serde_json::to_writer(writer, &cells)
+    }
+}
+
+struct LoggedCell<'a> {
+    address: &'a Relocatable,
+    accesses: &'a CellAccesses,
+}
+
+impl Serialize for LoggedCell<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut cell = serializer.serialize_struct("LoggedCell", 3)?;
+        cell.serialize_field("address", &self.address.to_string())?;
+        cell.serialize_field("write", &self.accesses.write)?;
+        cell.serialize_field("reads", &self.accesses.reads)?;
+        cell.end()
+    }
+}
+
+impl Serialize for MemoryAccess {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut access = serializer.serialize_struct("MemoryAccess", 2)?;
+        access.serialize_field("step", &self.step)?;
+        access.serialize_field("source", &self.source)?;
+        access.end()
+    }
+}
+
+impl Serialize for AccessSource {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            AccessSource::Runner => source_type(serializer, "Runner"),
+            AccessSource::Instruction => source_type(serializer, "Instruction"),
+            AccessSource::AutoDeduction => source_type(serializer, "AutoDeduction"),
+            AccessSource::BuiltinDeduction { builtin } => {
+                let mut source = serializer.serialize_struct("AccessSource", 2)?;
+                source.serialize_field("type", "BuiltinDeduction")?;
+                source.serialize_field("builtin", builtin)?;
+                source.end()
+            }
+            AccessSource::Hint { pc, hint_index } => {
+                let mut source = serializer.serialize_struct("AccessSource", 3)?;
+                source.serialize_field("type", "Hint")?;
+                source.serialize_field("pc", &pc.to_string())?;
+                source.serialize_field("hint_index", hint_index)?;
+                source.end()
+            }
+        }
+    }
+}
+
+fn source_type<S: Serializer>(serializer: S, source_type: &str) -> Result<S::Ok, S::Error> {
+    let mut source = serializer.serialize_struct("AccessSource", 1)?;
+    source.serialize_field("type", source_type)?;
+    source.end()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn record_write_keeps_first_write() {
+        let mut log = MemoryAccessLog::new();
+        log.record_write((1, 0).into());
+        log.step = 3;
+        log.source = Some(AccessSource::Instruction);
+        log.record_write((1, 0).into());
+        assert_eq!(
+            log.get_write(&(1, 0).into()),
+            Some(&MemoryAccess {
+                step: 0,
+                source: AccessSource::Runner
+            })
+        );
+    }
+
+    #[test]
+    fn record_hint_read_outside_hint() {
+        let mut log = MemoryAccessLog::new();
+        log.source = Some(AccessSource::Instruction);
+        log.record_hint_read((1, 0).into());
+        assert_eq!(log.get(&(1, 0).into()), None);
+
+        log.source = Some(AccessSource::Hint {
+            pc: (0, 2).into(),
+            hint_index: 1,
+        });
+        log.record_hint_read((1, 0).into());
+        assert_eq!(
+            log.get(&(1, 0).into()),
+            Some(&CellAccesses {
+                write: None,
+                reads: vec![MemoryAccess {
+                    step: 0,
+                    source: AccessSource::Hint {
+                        pc: (0, 2).into(),
+                        hint_index: 1
+                    }
+                }]
+            })
+        );
+    }
+
+    #[test]
+    fn write_json_ordered_by_address() {
+        let mut log = MemoryAccessLog::new();
+        log.step = 2;
+        log.source = Some(AccessSource::BuiltinDeduction {
+            builtin: "pedersen".to_string(),
+        });
+        log.record_write((2, 1).into());
+        log.source = Some(AccessSource::Hint {
+            pc: (0, 4).into(),
+            hint_index: 0,
+        });
+        log.record_write((1, 3).into());
+        log.record_read((2, 1).into(), AccessSource::Instruction);
+
+        let mut json = Vec::new();
+        log.write_json(&mut json).unwrap();
+        assert_eq!(
+            serde_json::from_slice::<serde_json::Value>(&json).unwrap(),
+            serde_json::json!([
+                {
+                    "address": "1:3",
+                    "write": {"step": 2, "source": {"type": "Hint", "pc": "0:4", "hint_index": 0}},
+                    "reads": []
+                },
+                {
+                    "address": "2:1",
+                    "write": {"step": 2, "source": {"type": "BuiltinDeduction", "builtin": "pedersen"}},
+                    "reads": [{"step": 2, "source": {"type": "Instruction"}}]
+                }
+            ])
+        );
+    }
+}
@@ -1,9 +1,13 @@
 use crate::{
-    types::relocatable::{MaybeRelocatable, Relocatable},
+    types::{
+        cairo_arg::CairoArg,
+        relocatable::{MaybeRelocatable, Relocatable},
+    },
     utils::from_relocatable_to_indexes,
     vm::{
-        errors::memory_errors::MemoryError, errors::vm_errors::VirtualMachineError,
-        vm_memory::memory::Memory,
+        errors::memory_errors::MemoryError,
+        errors::vm_errors::VirtualMachineError,
+        vm_memory::{memory::Memory, segment_storage::MemorySegment},
     },
 };
 
@@ -29,7 +33,7 @@ impl MemorySegmentManager {
     pub fn add(&mut self, memory: &mut Memory) -> Relocatable {
         let segment_index = self.num_segments;
         self.num_segments += 1;
-        memory.data.push(Vec::new());
+        memory.data.push(MemorySegment::new());
         Relocatable {
             segment_index: segment_index as isize,
             offset: 0,
@@ -40,7 +44,7 @@ impl MemorySegmentManager {
     ///Negative segment_index indicates its refer to a temporary segment
     pub fn add_temporary_segment(&mut self, memory: &mut Memory) -> Relocatable {
         self.num_temp_segments += 1;
-        memory.temp_data.push(Vec::new());
+        memory.temp_data.push(MemorySegment::new());
         Relocatable {
             segment_index: -(self.num_temp_segments as isize),
             offset: 0,
@@ -73,7 +77,7 @@ impl MemorySegmentManager {
     /// Calculates the size (number of non-none elements) of each memory segment.
     pub fn compute_effective_sizes(&mut self, memory: &Memory) -> &Vec<usize> {
         self.segment_used_sizes
-            .get_or_insert_with(|| memory.data.iter().map(Vec::len).collect())
+            .get_or_insert_with(|| memory.data.iter().map(MemorySegment::len).collect())
     }
 
     ///Returns the number of used segments when they are already computed.
@@ -125,11 +129,34 @@ impl MemorySegmentManager {
             let base = self.add(memory);
             self.write_arg(memory, &base, value)?;
             Ok(base.into())
+        } else if let Some(value) = arg.downcast_ref::<CairoArg>() {
+            Ok(self.gen_cairo_arg(value, memory)?)
         } else {
             Err(VirtualMachineError::NotImplemented)
         }
     }
 
+    /// Writes the lists of `arg` to new segments, see `CairoArg`.
+    /// A list's segment is added before the segments of the lists it contains.
+    pub fn gen_cairo_arg(
+        &mut self,
+        arg: &CairoArg,
+        memory: &mut Memory,
+    ) -> Result<MaybeRelocatable, MemoryError> {
+        match arg {
+            CairoArg::Single(value) => Ok(value.clone()),
+            CairoArg::Array(args) => {
+                let base = self.add(memory);
+                let values = args
+                    .iter()
+                    .map(|arg| self.gen_cairo_arg(arg, memory))
+                    .collect::<Result<Vec<_>, _>>()?;
+                self.load_data(memory, &base.into(), &values)?;
+                Ok(base.into())
+            }
+        }
+    }
+
     pub fn write_arg(
         &mut self,
         memory: &mut Memory,
@@ -196,7 +223,7 @@ impl MemorySegmentManager {
                         .ok_or(MemoryError::CantGetMutAccessedOffset)?
                 }
             };
-            if offset > *segment_size {
+            if offset >= *segment_size {
                 return Err(MemoryError::NumOutOfBounds);
             }
 
@@ -624,6 +651,18 @@ mod tests {
         );
     }
 
+    #[test]
+    fn get_memory_holes_offset_equal_to_size() {
+        let mut memory_segment_manager = MemorySegmentManager::new();
+        memory_segment_manager.segment_used_sizes = Some(vec![2]);
+
+        let accessed_addresses = vec![(0, 0).into(), (0, 1).into(), (0, 2).into()];
+        assert_eq!(
+            memory_segment_manager.get_memory_holes(accessed_addresses.into_iter()),
+            Err(MemoryError::NumOutOfBounds),
+        );
+    }
+
     #[test]
     fn get_memory_holes_empty() {
         let mut memory_segment_manager = MemorySegmentManager::new();
@@ -795,6 +834,40 @@ mod tests {
         );
     }
 
+    /// Test that the call to .gen_arg() with a nested CairoArg writes each list
+    /// to its own segment.
+    #[test]
+    fn gen_arg_cairo_arg() {
+        let mut memory_segment_manager = MemorySegmentManager::new();
+        let mut vm = vm!();
+
+        let arg = CairoArg::Array(vec![
+            mayberelocatable!(1).into(),
+            vec![mayberelocatable!(2), mayberelocatable!(3)].into(),
+            CairoArg::Array(vec![]),
+            mayberelocatable!(4, 5).into(),
+        ]);
+        assert_eq!(
+            memory_segment_manager.gen_arg(&arg, &mut vm.memory),
+            Ok(mayberelocatable!(0, 0)),
+        );
+        assert_eq!(memory_segment_manager.num_segments, 3);
+        check_memory![
+            vm.memory,
+            ((0, 0), 1),
+            ((0, 1), (1, 0)),
+            ((0, 2), (2, 0)),
+            ((0, 3), (4, 5)),
+            ((1, 0), 2),
+            ((1, 1), 3)
+        ];
+        assert!(vm.memory.data[2].is_empty());
+        assert_eq!(
+            memory_segment_manager.gen_arg(&CairoArg::from(mayberelocatable!(7)), &mut vm.memory),
+            Ok(mayberelocatable!(7)),
+        );
+    }
+
     /// Test that the call to .gen_arg() with any other argument returns a not
     /// implemented error.
     #[test]
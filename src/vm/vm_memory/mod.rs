@@ -1,2 +1,4 @@
 pub mod memory;
+pub mod memory_access_log;
 pub mod memory_segments;
+pub mod segment_storage;
@@ -0,0 +1,359 @@
+use crate::types::relocatable::{MaybeRelocatable, Relocatable};
+use felt::{Felt, NewFelt};
+use num_traits::ToPrimitive;
+use once_cell::unsync::OnceCell;
+use std::{borrow::Cow, fmt, iter};
+
+// Cells are stored in pages of PAGE_SIZE cells, so that writing to a large offset only
+// allocates the page that holds it
+const PAGE_BITS: usize = 12;
+const PAGE_SIZE: usize = 1 << PAGE_BITS;
+
+/// The value held by a memory cell.
+/// Felts that fit in a u64 are stored inline, as most of the values found in a run
+/// (instructions, counters, offsets) are small, and a `Felt` always allocates its digits.
+/// Bigger felts are boxed as a `MaybeRelocatable::Int`, so that they can be borrowed.
+/// Small felts are boxed the first time they are borrowed, and kept so that later reads don't
+/// build them again.
+#[derive(Clone, Debug)]
+pub(crate) enum MemoryCell {
+    Small(u64, OnceCell<Box<MaybeRelocatable>>),
+    Big(Box<MaybeRelocatable>),
+    Relocatable(Relocatable),
+}
+
+impl MemoryCell {
+    pub(crate) fn small(value: u64) -> MemoryCell {
+        MemoryCell::Small(value, OnceCell::new())
+    }
+
+    /// Returns the value of the cell, borrowing it unless it's relocatable. Small felts are
+    /// boxed the first time.
+    #[inline]
+    pub(crate) fn value(&self) -> Cow<MaybeRelocatable> {
+        match self {
+            MemoryCell::Small(value, boxed) => Cow::Borrowed(
+                boxed.get_or_init(|| Box::new(MaybeRelocatable::Int(Felt::new(*value)))),
+            ),
+            MemoryCell::Big(value) => Cow::Borrowed(value),
+            MemoryCell::Relocatable(addr) => Cow::Owned(MaybeRelocatable::RelocatableValue(*addr)),
+        }
+    }
+
+    pub(crate) fn to_maybe_relocatable(&self) -> MaybeRelocatable {
+        match self {
+            MemoryCell::Small(value, _) => MaybeRelocatable::Int(Felt::new(*value)),
+            MemoryCell::Big(value) => value.as_ref().clone(),
+            MemoryCell::Relocatable(addr) => MaybeRelocatable::RelocatableValue(*addr),
+        }
+    }
+}
+
+// The boxed value of a small felt is left out, as it's only a copy of the felt
+impl PartialEq for MemoryCell {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (MemoryCell::Small(a, _), MemoryCell::Small(b, _)) => a == b,
+            (MemoryCell::Big(a), MemoryCell::Big(b)) => a == b,
+            (MemoryCell::Relocatable(a), MemoryCell::Relocatable(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+impl Eq for MemoryCell {}
+
+/// A memory value borrowed from its cell, see `Memory::get_ref`.
+/// Small integers are given as they are stored, so reading them doesn't build a `Felt`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MemoryValueRef<'a> {
+    /// An integer that fits in a u64.
+    Small(u64),
+    /// An integer that doesn't fit in a u64.
+    Int(&'a Felt),
+    Relocatable(Relocatable),
+}
+
+impl MemoryValueRef<'_> {
+    /// Returns the value if it's an integer that fits in a u64.
+    pub fn to_u64(&self) -> Option<u64> {
+        match self {
+            MemoryValueRef::Small(value) => Some(*value),
+            MemoryValueRef::Int(_) | MemoryValueRef::Relocatable(_) => None,
+        }
+    }
+
+    /// Returns the value if it's an integer that fits in a usize.
+    pub fn to_usize(&self) -> Option<usize> {
+        self.to_u64()?.to_usize()
+    }
+
+    pub fn to_maybe_relocatable(&self) -> MaybeRelocatable {
+        match self {
+            MemoryValueRef::Small(value) => MaybeRelocatable::Int(Felt::new(*value)),
+            MemoryValueRef::Int(value) => MaybeRelocatable::Int((*value).clone()),
+            MemoryValueRef::Relocatable(addr) => MaybeRelocatable::RelocatableValue(*addr),
+        }
+    }
+}
+
+impl From<MaybeRelocatable> for MemoryCell {
+    fn from(value: MaybeRelocatable) -> Self {
+        match value {
+            MaybeRelocatable::Int(ref int) => match int.to_u64() {
+                Some(small) => MemoryCell::small(small),
+                None => MemoryCell::Big(Box::new(value)),
+            },
+            MaybeRelocatable::RelocatableValue(addr) => MemoryCell::Relocatable(addr),
+        }
+    }
+}
+
+/// The cells of a memory segment.
+/// Behaves like a `Vec<Option<MaybeRelocatable>>` whose length is one past the highest written
+/// offset, but only allocates the pages that hold written cells. Unwritten pages still take an
+/// empty entry in the page list, so writing at offset `n` costs about `n / 170` bytes instead of
+/// the `32 * n` bytes a vector of cells would.
+#[derive(Clone, Default)]
+pub struct MemorySegment {
+    // The last page may hold less than PAGE_SIZE cells, and pages that were never written to
+    // are left empty
+    pages: Vec<Vec<Option<MemoryCell>>>,
+    len: usize,
+}
+
+impl MemorySegment {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns one past the highest written offset.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the value at `offset`, or None if it wasn't written.
+    pub fn get(&self, offset: usize) -> Option<MaybeRelocatable> {
+        self.get_cell(offset).map(MemoryCell::to_maybe_relocatable)
+    }
+
+    #[inline]
+    pub(crate) fn get_cell(&self, offset: usize) -> Option<&MemoryCell> {
+        self.pages
+            .get(offset >> PAGE_BITS)?
+            .get(offset & (PAGE_SIZE - 1))?
+            .as_ref()
+    }
+
+    /// Writes `value` at `offset`, replacing the previous value if any.
+    pub(crate) fn set(&mut self, offset: usize, value: MemoryCell) {
+        let page_index = offset >> PAGE_BITS;
+        if self.pages.len() <= page_index {
+            self.pages.resize_with(page_index + 1, Vec::new);
+        }
+        let page = &mut self.pages[page_index];
+        let cell_index = offset & (PAGE_SIZE - 1);
+        if page.len() <= cell_index {
+            page.resize(cell_index + 1, None);
+        }
+        page[cell_index] = Some(value);
+        self.len = self.len.max(offset + 1);
+    }
+
+    /// Iterates over every offset of the segment, yielding None for the cells that weren't
+    /// written.
+    pub fn iter(&self) -> impl Iterator<Item = Option<MaybeRelocatable>> + '_ {
+        self.cells()
+            .map(|cell| cell.map(MemoryCell::to_maybe_relocatable))
+    }
+
+    // Iterates over every offset of the segment, walking the pages instead of looking up each
+    // offset. Pages that were never written to, and the unwritten end of the pages, are gaps.
+    fn cells(&self) -> impl Iterator<Item = Option<&MemoryCell>> + '_ {
+        let allocated_len = self.pages.len() << PAGE_BITS;
+        self.pages
+            .iter()
+            .flat_map(|page| {
+                page.iter()
+                    .map(Option::as_ref)
+                    .chain(iter::repeat(None).take(PAGE_SIZE - page.len()))
+            })
+            .chain(iter::repeat(None).take(self.len.saturating_sub(allocated_len)))
+            .take(self.len)
+    }
+
+    /// Iterates over the written cells of the segment and their offsets, skipping the pages
+    /// that were never written to.
+    pub fn iter_written(&self) -> impl Iterator<Item = (usize, MaybeRelocatable)> + '_ {
+        self.pages
+            .iter()
+            .enumerate()
+            .flat_map(|(page_index, page)| {
+                page.iter()
+                    .enumerate()
+                    .filter_map(move |(cell_index, cell)| {
+                        Some((
+                            (page_index << PAGE_BITS) + cell_index,
+                            cell.as_ref()?.to_maybe_relocatable(),
+                        ))
+                    })
+            })
+    }
+}
+
+impl From<Vec<Option<MaybeRelocatable>>> for MemorySegment {
+    fn from(values: Vec<Option<MaybeRelocatable>>) -> Self {
+        let mut segment = MemorySegment::new();
+        let len = values.len();
+        for (offset, value) in values.into_iter().enumerate() {
+            if let Some(value) = value {
+                segment.set(offset, value.into());
+            }
+        }
+        // Trailing gaps are kept, as they are part of the vector's length
+        segment.len = len;
+        segment
+    }
+}
+
+// Two segments holding the same cells may not have allocated the same pages
+impl PartialEq for MemorySegment {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.cells().eq(other.cells())
+    }
+}
+
+impl Eq for MemorySegment {}
+
+impl PartialEq<Vec<Option<MaybeRelocatable>>> for MemorySegment {
+    fn eq(&self, other: &Vec<Option<MaybeRelocatable>>) -> bool {
+        self.len == other.len()
+            && self
+                .iter()
+                .zip(other.iter())
+                .all(|(a, b)| a.as_ref() == b.as_ref())
+    }
+}
+
+impl fmt::Debug for MemorySegment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_utils::mayberelocatable;
+    use felt::felt_str;
+
+    #[test]
+    fn memory_cell_small_and_big_values() {
+        assert_eq!(
+            MemoryCell::from(mayberelocatable!(u64::MAX)),
+            MemoryCell::small(u64::MAX)
+        );
+        let big = felt_str!("18446744073709551616");
+        assert_eq!(
+            MemoryCell::from(MaybeRelocatable::Int(big.clone())),
+            MemoryCell::Big(Box::new(MaybeRelocatable::Int(big.clone())))
+        );
+        assert_eq!(
+            MemoryCell::Big(Box::new(MaybeRelocatable::Int(big.clone()))).to_maybe_relocatable(),
+            MaybeRelocatable::Int(big)
+        );
+        assert_eq!(
+            MemoryCell::from(mayberelocatable!(2, 3)).to_maybe_relocatable(),
+            mayberelocatable!(2, 3)
+        );
+    }
+
+    #[test]
+    fn small_cell_is_boxed_once() {
+        let cell = MemoryCell::small(7);
+        let value = cell.value();
+        assert_eq!(value, Cow::Owned::<MaybeRelocatable>(mayberelocatable!(7)));
+        assert!(
+            matches!((value, cell.value()), (Cow::Borrowed(a), Cow::Borrowed(b)) if std::ptr::eq(a, b))
+        );
+        assert_eq!(cell, MemoryCell::small(7));
+    }
+
+    #[test]
+    fn memory_cell_size() {
+        assert_eq!(std::mem::size_of::<Option<MemoryCell>>(), 24);
+        assert_eq!(std::mem::size_of::<Option<MaybeRelocatable>>(), 32);
+    }
+
+    #[test]
+    fn set_and_get_sparse_offsets() {
+        let mut segment = MemorySegment::new();
+        segment.set(3, MemoryCell::small(7));
+        segment.set(5 * PAGE_SIZE + 1, MemoryCell::small(8));
+
+        assert_eq!(segment.len(), 5 * PAGE_SIZE + 2);
+        assert_eq!(segment.get(3), Some(mayberelocatable!(7)));
+        assert_eq!(segment.get(5 * PAGE_SIZE + 1), Some(mayberelocatable!(8)));
+        assert_eq!(segment.get(2 * PAGE_SIZE), None);
+        assert_eq!(segment.get(10 * PAGE_SIZE), None);
+        // Only the pages holding the written cells are allocated
+        assert_eq!(
+            segment.pages.iter().map(Vec::len).collect::<Vec<_>>(),
+            vec![4, 0, 0, 0, 0, 2]
+        );
+    }
+
+    #[test]
+    fn iter_written_skips_gaps() {
+        let mut segment = MemorySegment::new();
+        segment.set(PAGE_SIZE * 3, MemoryCell::small(1));
+        segment.set(1, MemoryCell::Relocatable((1, 0).into()));
+
+        assert_eq!(
+            segment.iter_written().collect::<Vec<_>>(),
+            vec![
+                (1, mayberelocatable!(1, 0)),
+                (PAGE_SIZE * 3, mayberelocatable!(1))
+            ]
+        );
+        assert_eq!(segment.iter().filter(Option::is_some).count(), 2);
+    }
+
+    #[test]
+    fn iter_walks_every_offset() {
+        let mut segment = MemorySegment::from(vec![None; PAGE_SIZE * 2 + 3]);
+        segment.set(1, MemoryCell::small(1));
+        segment.set(PAGE_SIZE + 1, MemoryCell::small(2));
+
+        let cells: Vec<_> = segment.iter().collect();
+        assert_eq!(cells.len(), PAGE_SIZE * 2 + 3);
+        assert_eq!(cells[1], Some(mayberelocatable!(1)));
+        assert_eq!(cells[PAGE_SIZE + 1], Some(mayberelocatable!(2)));
+        assert_eq!(cells.iter().filter(|cell| cell.is_some()).count(), 2);
+    }
+
+    #[test]
+    fn from_vec_keeps_gaps() {
+        let segment = MemorySegment::from(vec![None, Some(mayberelocatable!(5)), None]);
+        assert_eq!(segment.len(), 3);
+        assert_eq!(segment, vec![None, Some(mayberelocatable!(5)), None]);
+        assert_ne!(segment, vec![None, Some(mayberelocatable!(5))]);
+    }
+
+    #[test]
+    fn eq_ignores_allocated_pages() {
+        let segment = MemorySegment {
+            pages: vec![vec![None, None], vec![Some(MemoryCell::small(1))]],
+            len: PAGE_SIZE + 1,
+        };
+        let mut other = MemorySegment::new();
+        other.set(PAGE_SIZE, MemoryCell::small(1));
+        assert_eq!(segment, other);
+        other.set(0, MemoryCell::small(2));
+        assert_ne!(segment, other);
+    }
+}
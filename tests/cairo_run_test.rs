@@ -1,6 +1,26 @@
 use cairo_vm::cairo_run;
-use cairo_vm::hint_processor::builtin_hint_processor::builtin_hint_processor_definition::BuiltinHintProcessor;
+use cairo_vm::hint_processor::builtin_hint_processor::bootloader::objects::{
+    FactTopology, SimpleBootloaderInput, Task,
+};
+use cairo_vm::hint_processor::builtin_hint_processor::bootloader::utils::compute_program_hash_chain;
+use cairo_vm::hint_processor::builtin_hint_processor::builtin_hint_processor_definition::{
+    BuiltinHintProcessor, HintFunc,
+};
+use cairo_vm::hint_processor::builtin_hint_processor::dict_manager::DictSnapshot;
+use cairo_vm::hint_processor::builtin_hint_processor::hint_utils::insert_value_from_var_name;
+use cairo_vm::types::cairo_arg::CairoArg;
+use cairo_vm::types::program::Program;
+use cairo_vm::types::relocatable::MaybeRelocatable;
+use cairo_vm::vm::errors::hint_errors::HintError;
+use cairo_vm::vm::runners::builtin_runner::{OutputBuiltinAdditionalData, PublicMemoryPage};
+use cairo_vm::vm::runners::cairo_runner::CairoRunner;
+use cairo_vm::vm::vm_core::VirtualMachine;
+use felt::{Felt, NewFelt};
+use num_traits::Zero;
+use std::any::Any;
+use std::collections::{BTreeMap, HashMap};
 use std::path::Path;
+use std::rc::Rc;
 
 #[test]
 fn cairo_run_test() {
@@ -707,6 +727,113 @@ fn cairo_run_dict_update() {
     .expect("Couldn't run program");
 }
 
+#[test]
+fn cairo_run_dict_update_get_dicts() {
+    let mut hint_executor = BuiltinHintProcessor::new_empty();
+    let cairo_runner = cairo_run::cairo_run(
+        Path::new("cairo_programs/dict_update.json"),
+        "main",
+        false,
+        false,
+        "all",
+        false,
+        &mut hint_executor,
+    )
+    .expect("Couldn't run program");
+    assert_eq!(
+        cairo_runner.get_dicts(),
+        vec![DictSnapshot {
+            segment_index: 2,
+            data: vec![
+                (
+                    MaybeRelocatable::from(Felt::new(10)),
+                    MaybeRelocatable::from(Felt::zero() - Felt::new(8))
+                ),
+                (
+                    MaybeRelocatable::from(Felt::new(12)),
+                    MaybeRelocatable::from(Felt::new(49))
+                ),
+            ],
+            default_value: Some(MaybeRelocatable::from(Felt::new(17))),
+            n_accesses: 8,
+        }]
+    );
+}
+
+#[test]
+fn cairo_run_program_input() {
+    let mut hint_executor = BuiltinHintProcessor::new_empty();
+    hint_executor.add_hint(
+        String::from("ids.n = program_input['n']"),
+        Rc::new(HintFunc(Box::new(
+            |vm, exec_scopes, ids_data, ap_tracking, _constants| {
+                let n = exec_scopes.get_program_input()?["n"]
+                    .as_u64()
+                    .ok_or_else(|| HintError::CustomHint(String::from("n should be an integer")))?;
+                insert_value_from_var_name("n", Felt::new(n), vm, ids_data, ap_tracking)
+            },
+        ))),
+    );
+    let program = Program::from_file(Path::new("cairo_programs/program_input.json"), Some("main"))
+        .expect("Couldn't load program");
+    let cairo_runner = cairo_run::cairo_run_with_initial_scope(
+        &program,
+        &cairo_run::CairoRunConfig {
+            layout: "all",
+            ..Default::default()
+        },
+        &mut hint_executor,
+        HashMap::from([(
+            String::from("program_input"),
+            Box::new(serde_json::json!({ "n": 42 })) as Box<dyn Any>,
+        )]),
+    )
+    .expect("Couldn't run program");
+    assert_eq!(
+        cairo_runner.exec_scopes.get_program_input(),
+        Ok(&serde_json::json!({ "n": 42 }))
+    );
+}
+
+#[test]
+fn cairo_run_hint_stats() {
+    let mut hint_executor = BuiltinHintProcessor::new_empty();
+    hint_executor.add_hint(
+        String::from("ids.n = program_input['n']"),
+        Rc::new(HintFunc(Box::new(
+            |vm, exec_scopes, ids_data, ap_tracking, _constants| {
+                let n = exec_scopes.get_program_input()?["n"]
+                    .as_u64()
+                    .ok_or_else(|| HintError::CustomHint(String::from("n should be an integer")))?;
+                insert_value_from_var_name("n", Felt::new(n), vm, ids_data, ap_tracking)
+            },
+        ))),
+    );
+    let program = Program::from_file(Path::new("cairo_programs/program_input.json"), Some("main"))
+        .expect("Couldn't load program");
+    let cairo_runner = cairo_run::cairo_run_with_initial_scope(
+        &program,
+        &cairo_run::CairoRunConfig {
+            layout: "all",
+            hint_stats: true,
+            ..Default::default()
+        },
+        &mut hint_executor,
+        HashMap::from([(
+            String::from("program_input"),
+            Box::new(serde_json::json!({ "n": 42 })) as Box<dyn Any>,
+        )]),
+    )
+    .expect("Couldn't run program");
+    let hint_stats = cairo_runner
+        .hint_stats
+        .expect("Hint stats weren't recorded");
+    let extra_hints = hint_stats.extra_hints();
+    assert_eq!(extra_hints.len(), 1);
+    assert_eq!(extra_hints[0].code, "ids.n = program_input['n']");
+    assert_eq!(extra_hints[0].timing.calls, 1);
+}
+
 #[test]
 fn cairo_run_uint256() {
     let mut hint_executor = BuiltinHintProcessor::new_empty();
@@ -845,6 +972,40 @@ fn cairo_run_dict_update_bad() {
     ));
 }
 
+#[test]
+fn cairo_run_squash_dict_bad() {
+    let mut hint_executor = BuiltinHintProcessor::new_empty();
+    let err = cairo_run::cairo_run(
+        Path::new("cairo_programs/bad_programs/bad_squash_dict.json"),
+        "main",
+        false,
+        false,
+        "all",
+        false,
+        &mut hint_executor,
+    )
+    .err();
+    assert!(err
+        .unwrap()
+        .to_string()
+        .contains("squash_dict_inner fail: No keys left but remaining_accesses > 0"));
+}
+
+#[test]
+fn cairo_run_pointer_difference() {
+    let mut hint_executor = BuiltinHintProcessor::new_empty();
+    cairo_run::cairo_run(
+        Path::new("cairo_programs/pointer_difference.json"),
+        "main",
+        false,
+        false,
+        "all",
+        false,
+        &mut hint_executor,
+    )
+    .expect("Couldn't run program");
+}
+
 #[test]
 fn cairo_run_squash_dict() {
     let mut hint_executor = BuiltinHintProcessor::new_empty();
@@ -1174,6 +1335,57 @@ fn cairo_run_dict_integration() {
     .expect("Couldn't run program");
 }
 
+#[test]
+fn cairo_run_dict_initial_dict() {
+    let program = Program::from_file(
+        Path::new("cairo_programs/dict_initial_dict.json"),
+        Some("main"),
+    )
+    .expect("Couldn't read program");
+    let mut hint_executor = BuiltinHintProcessor::new_empty();
+    let mut cairo_runner = CairoRunner::new(&program, "all", false).unwrap();
+    let mut vm = VirtualMachine::new(false);
+    cairo_runner.add_initial_dict(vec![
+        (
+            MaybeRelocatable::from(Felt::new(1)),
+            MaybeRelocatable::from(Felt::new(10)).into(),
+        ),
+        (
+            MaybeRelocatable::from(Felt::new(2)),
+            vec![
+                MaybeRelocatable::from(Felt::new(3)),
+                MaybeRelocatable::from(Felt::new(4)),
+            ]
+            .into(),
+        ),
+        (
+            MaybeRelocatable::from(Felt::new(3)),
+            CairoArg::Array(vec![
+                MaybeRelocatable::from(Felt::new(5)).into(),
+                vec![
+                    MaybeRelocatable::from(Felt::new(6)),
+                    MaybeRelocatable::from(Felt::new(7)),
+                ]
+                .into(),
+            ]),
+        ),
+    ]);
+    let end = cairo_runner.initialize(&mut vm).unwrap();
+    cairo_runner
+        .run_until_pc(end, &mut vm, &mut hint_executor)
+        .expect("Couldn't run program");
+
+    let dicts = cairo_runner.get_dicts();
+    assert_eq!(dicts.len(), 1);
+    assert_eq!(
+        dicts[0].data[0],
+        (
+            MaybeRelocatable::from(Felt::new(1)),
+            MaybeRelocatable::from(Felt::new(11))
+        )
+    );
+}
+
 #[test]
 fn cairo_run_secp_integration() {
     let mut hint_executor = BuiltinHintProcessor::new_empty();
@@ -1189,6 +1401,36 @@ fn cairo_run_secp_integration() {
     .expect("Couldn't run program");
 }
 
+#[test]
+fn cairo_run_secp256r1_ec() {
+    let mut hint_executor = BuiltinHintProcessor::new_empty();
+    cairo_run::cairo_run(
+        Path::new("cairo_programs/secp256r1_ec.json"),
+        "main",
+        false,
+        false,
+        "all",
+        false,
+        &mut hint_executor,
+    )
+    .expect("Couldn't run program");
+}
+
+#[test]
+fn cairo_run_secp256r1_signature() {
+    let mut hint_executor = BuiltinHintProcessor::new_empty();
+    cairo_run::cairo_run(
+        Path::new("cairo_programs/secp256r1_signature.json"),
+        "main",
+        false,
+        false,
+        "all",
+        false,
+        &mut hint_executor,
+    )
+    .expect("Couldn't run program");
+}
+
 #[test]
 fn cairo_run_keccak_integration() {
     let mut hint_executor = BuiltinHintProcessor::new_empty();
@@ -1267,7 +1509,7 @@ fn cairo_run_error_msg_attr_ap_based_reference() {
     .err()
     .unwrap();
 
-    assert_eq!(err.to_string(), String::from("Error message: SafeUint256: addition overflow: {x} (Cannot evaluate ap-based or complex references: ['x'])\ncairo_programs/bad_programs/error_msg_attr_tempvar.cairo:4:9: Error at pc=0:2:\nAn ASSERT_EQ instruction failed: 3 != 2.\n        assert x = 2;\n        ^***********^\n"));
+    assert_eq!(err.to_string(), String::from("Error message: SafeUint256: addition overflow: 3\ncairo_programs/bad_programs/error_msg_attr_tempvar.cairo:4:9: Error at pc=0:2:\nAn ASSERT_EQ instruction failed: 3 != 2.\n        assert x = 2;\n        ^***********^\n"));
 }
 
 #[test]
@@ -1284,7 +1526,9 @@ fn cairo_run_error_msg_attr_complex_reference() {
     )
     .err()
     .unwrap();
-    assert!(err.to_string().contains("Error message: Cats cannot have more than nine lives: {cat} (Cannot evaluate ap-based or complex references: ['cat'])"))
+    assert!(err
+        .to_string()
+        .contains("Error message: Cats cannot have more than nine lives: Cat(paws=2, lives=10)"))
 }
 
 #[test]
@@ -1301,3 +1545,76 @@ fn cairo_run_dict_store_cast_pointer() {
     )
     .expect("Couldn't run program");
 }
+
+#[test]
+fn cairo_run_output_builtin_pages() {
+    let mut hint_executor = BuiltinHintProcessor::new_empty();
+    let cairo_runner = cairo_run::cairo_run(
+        Path::new("cairo_programs/output_builtin_pages.json"),
+        "main",
+        false,
+        false,
+        "all",
+        false,
+        &mut hint_executor,
+    )
+    .expect("Couldn't run program");
+    assert_eq!(
+        cairo_runner.output_builtin_additional_data,
+        Some(OutputBuiltinAdditionalData {
+            pages: BTreeMap::from([(1, PublicMemoryPage { start: 1, size: 2 })]),
+            attributes: BTreeMap::from([(String::from("gps_fact_topology"), vec![2, 1, 0, 2])]),
+        })
+    );
+}
+
+#[test]
+fn cairo_run_simple_bootloader_run_program_task() {
+    let bootloader_program = Program::from_file(
+        Path::new("cairo_programs/bootloader/simple_bootloader.json"),
+        Some("main"),
+    )
+    .expect("Couldn't load the simple bootloader");
+    let task_program = Program::from_file(Path::new("cairo_programs/array_sum.json"), Some("main"))
+        .expect("Couldn't load the task program");
+    let program_hash = compute_program_hash_chain(&task_program, 0).unwrap();
+    let input = SimpleBootloaderInput::new(vec![Task::RunProgram {
+        program: task_program,
+        program_input: None,
+    }]);
+
+    let mut hint_executor = BuiltinHintProcessor::new_empty();
+    let mut run = cairo_run::cairo_run_simple_bootloader(
+        &bootloader_program,
+        input,
+        "all",
+        false,
+        &mut hint_executor,
+    )
+    .expect("Couldn't run the simple bootloader");
+
+    // The number of tasks, followed by the output size, the program hash and the output of the task
+    let output_base = run.vm.get_output_builtin().unwrap().base();
+    let output: Vec<Felt> = run
+        .vm
+        .get_integer_range(&(output_base, 0).into(), 4)
+        .unwrap()
+        .into_iter()
+        .map(|value| value.into_owned())
+        .collect();
+    assert_eq!(
+        output,
+        vec![Felt::new(1), Felt::new(3), program_hash, Felt::new(34)]
+    );
+    assert_eq!(
+        run.fact_topologies,
+        vec![FactTopology {
+            tree_structure: vec![1, 0],
+            page_sizes: vec![1],
+        }]
+    );
+    assert_eq!(
+        run.output_builtin_additional_data.pages,
+        BTreeMap::from([(1, PublicMemoryPage { start: 3, size: 1 })])
+    );
+}